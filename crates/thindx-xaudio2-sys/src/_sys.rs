@@ -17,6 +17,9 @@
 #[path = "sys27.rs"] pub mod xaudio2_7;
 #[path = "sys28.rs"] pub mod xaudio2_8;
 #[path = "sys29.rs"] pub mod xaudio2_9;
+#[path = "hrtfapo.rs"] pub mod hrtfapo;
+#[path = "xapo.rs"] pub mod xapo;
+#[path = "x3daudio.rs"] pub mod x3daudio;
 
 const fn guid(data1: u32, data2: u16, data3: u16, data4: u64) -> winapi::shared::guiddef::GUID {
     winapi::shared::guiddef::GUID { Data1: data1, Data2: data2, Data3: data3, Data4: data4.to_be_bytes() }
@@ -0,0 +1,184 @@
+//! ✔️ HRTF APO - `hrtfapoapi.h`, exported by `hrtfapo.dll` (Windows 10+)
+//!
+//! A head-related transfer function [XAPO](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xapo-overview) for spatializing mono sources over headphones.
+//! Requires 48 kHz mono float input, and an XAudio2 engine created with [XAUDIO2_1024_QUANTUM](crate::xaudio2_9::XAUDIO2_1024_QUANTUM).
+//!
+//! ### References
+//! *   [hrtfapoapi.h header](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/)
+//! *   [How to: Use HRTF audio](https://learn.microsoft.com/en-us/windows/win32/xaudio2/how-to--use-hrtf-audio)
+
+use abistr::*;
+use bytemuck::{Pod, Zeroable};
+use winapi::shared::guiddef::GUID;
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winresult::*;
+
+
+
+#[doc = "`\"hrtfapo.dll\"`"] pub const HRTFAPO_DLL      : &'static str              =           "hrtfapo.dll";
+#[doc = "`\"hrtfapo.dll\"`"] pub const HRTFAPO_DLL_A    : CStrNonNull<'static, u8 > = cstr8!(   "hrtfapo.dll");
+#[doc = "`\"hrtfapo.dll\"`"] pub const HRTFAPO_DLL_W    : CStrNonNull<'static, u16> = cstr16!(  "hrtfapo.dll");
+
+#[doc = "`15b3cd66-e9de-4464-b6e6-2bc3cf63d455`"] pub const IID_IXAPOHrtfParameters : GUID = super::guid(0x15b3cd66, 0xe9de, 0x4464, 0xb6e62bc3cf63d455);
+
+
+
+/// Maximum gain limit, in dB, for [HrtfDistanceDecay::maxGain]
+pub const HRTF_MAX_GAIN_LIMIT : f32 = 12.0;
+
+/// Minimum gain limit, in dB, for [HrtfDistanceDecay::minGain]
+pub const HRTF_MIN_GAIN_LIMIT : f32 = -96.0;
+
+/// Minimum [HrtfDistanceDecay::unityGainDistance], in meters
+pub const HRTF_MIN_UNITY_GAIN_DISTANCE : f32 = 0.05;
+
+/// Default [HrtfDistanceDecay::unityGainDistance], in meters
+pub const HRTF_DEFAULT_UNITY_GAIN_DISTANCE : f32 = 1.0;
+
+/// Default [HrtfDistanceDecay::cutoffDistance], in meters (`FLT_MAX`)
+pub const HRTF_DEFAULT_CUTOFF_DISTANCE : f32 = f32::MAX;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ns-hrtfapoapi-hrtfposition)\]
+/// Position of a sound source, in meters, relative to the listener: +x right, +y up, -z forward.
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)] #[repr(C)] pub struct HrtfPosition {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ns-hrtfapoapi-hrtforientation)\]
+/// Orientation of a sound source, as a row-major 3x3 rotation matrix.
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)] #[repr(C)] pub struct HrtfOrientation {
+    pub element: [f32; 9],
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ne-hrtfapoapi-hrtfdirectivitytype)\]
+/// Used in [HrtfDirectivity]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)] pub struct HrtfDirectivityType(u32);
+
+/// The sound emission is the same in all directions.
+pub const OmniDirectional : HrtfDirectivityType = HrtfDirectivityType(0);
+
+/// The sound emission is cardioid shaped ([HrtfDirectivityCardioid].)
+pub const Cardioid : HrtfDirectivityType = HrtfDirectivityType(1);
+
+/// The sound emission is cone shaped ([HrtfDirectivityCone].)
+pub const Cone : HrtfDirectivityType = HrtfDirectivityType(2);
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ne-hrtfapoapi-hrtfenvironment)\]
+/// Acoustic environment, for [IXAPOHrtfParameters::SetEnvironment]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)] pub struct HrtfEnvironment(u32);
+
+/// A small room.
+pub const Small : HrtfEnvironment = HrtfEnvironment(0);
+
+/// A medium-sized room.
+pub const Medium : HrtfEnvironment = HrtfEnvironment(1);
+
+/// A large enclosed space.
+pub const Large : HrtfEnvironment = HrtfEnvironment(2);
+
+/// An outdoor space.
+pub const Outdoors : HrtfEnvironment = HrtfEnvironment(3);
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ne-hrtfapoapi-hrtfdistancedecaytype)\]
+/// Used in [HrtfDistanceDecay]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)] pub struct HrtfDistanceDecayType(u32);
+
+/// Simulates natural decay with distance, as constrained by the min/max gain and cutoff distance.
+pub const NaturalDecay : HrtfDistanceDecayType = HrtfDistanceDecayType(0);
+
+/// No decay is applied by the APO: the app is responsible for distance attenuation (e.g. via [IXAPOHrtfParameters::SetSourceGain].)
+pub const CustomDecay : HrtfDistanceDecayType = HrtfDistanceDecayType(1);
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ns-hrtfapoapi-hrtfdirectivity)\]
+/// Base directivity pattern, and the first member of [HrtfDirectivityCardioid] / [HrtfDirectivityCone].
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)] #[repr(C)] pub struct HrtfDirectivity {
+    /// [OmniDirectional], [Cardioid], or [Cone]
+    pub r#type:     HrtfDirectivityType,
+    /// Blend between the pattern (`1.0`) and omnidirectional (`0.0`)
+    pub scaling:    f32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ns-hrtfapoapi-hrtfdirectivitycardioid)\]
+/// Cardioid directivity pattern (`directivity.type` must be [Cardioid].)
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)] #[repr(C)] pub struct HrtfDirectivityCardioid {
+    pub directivity:    HrtfDirectivity,
+    /// Order controlling the shape of the cardioid: `(0, 32]`
+    pub order:          f32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ns-hrtfapoapi-hrtfdirectivitycone)\]
+/// Cone directivity pattern (`directivity.type` must be [Cone].)
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)] #[repr(C)] pub struct HrtfDirectivityCone {
+    pub directivity:    HrtfDirectivity,
+    /// Inner cone angle, in radians: `[0, 2π]`
+    pub innerAngle:     f32,
+    /// Outer cone angle, in radians: `[innerAngle, 2π]`
+    pub outerAngle:     f32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ns-hrtfapoapi-hrtfdistancedecay)\]
+/// Distance decay behavior of a sound source.
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)] #[repr(C)] pub struct HrtfDistanceDecay {
+    /// [NaturalDecay] or [CustomDecay]
+    pub r#type:             HrtfDistanceDecayType,
+    /// Maximum gain, in dB: `[HRTF_MIN_GAIN_LIMIT, HRTF_MAX_GAIN_LIMIT]`
+    pub maxGain:            f32,
+    /// Minimum gain, in dB: `[HRTF_MIN_GAIN_LIMIT, maxGain]`
+    pub minGain:            f32,
+    /// Distance, in meters, at which gain is 0 dB: `[HRTF_MIN_UNITY_GAIN_DISTANCE, cutoffDistance]`
+    pub unityGainDistance:  f32,
+    /// Distance, in meters, beyond which the source is silent
+    pub cutoffDistance:     f32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ns-hrtfapoapi-hrtfapoinit)\]
+/// Initialization parameters for [CreateHrtfApoFn].
+///
+/// Null pointers select defaults: [NaturalDecay] with the `HRTF_*` limits/defaults, and [OmniDirectional] respectively.
+#[derive(Clone, Copy, Debug, Zeroable)] #[repr(C)] pub struct HrtfApoInit {
+    pub distanceDecay:  *mut HrtfDistanceDecay,
+    /// Points to a [HrtfDirectivity], [HrtfDirectivityCardioid], or [HrtfDirectivityCone] (per `type`)
+    pub directivity:    *mut HrtfDirectivity,
+}
+
+impl Default for HrtfApoInit { fn default() -> Self { Self::zeroed() } }
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-createhrtfapo)\]
+/// `CreateHrtfApo`: creates an HRTF XAPO (as `xApo`), which also implements [IXAPOHrtfParameters].
+pub type CreateHrtfApoFn = unsafe extern "system" fn(init: *const HrtfApoInit, xApo: *mut *mut IUnknown) -> HResult;
+
+
+
+interfaces! {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nn-hrtfapoapi-ixapohrtfparameters)\]
+    /// Per-source parameters of an HRTF XAPO.
+    ///
+    /// Use [IUnknown]::[QueryInterface](IUnknown::QueryInterface) on the XAPO created by [CreateHrtfApoFn] to obtain a pointer to this interface.
+    #[iid = IID_IXAPOHrtfParameters]
+    pub interface IXAPOHrtfParameters(IXAPOHrtfParametersVtbl) => unsafe IUnknown(IUnknownVtbl) {
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-ixapohrtfparameters-setsourceposition)\]
+        /// Sets the position of the source, in meters, relative to the listener.
+        pub unsafe fn SetSourcePosition(&self, position: *const HrtfPosition) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-ixapohrtfparameters-setsourceorientation)\]
+        /// Sets the rotation matrix of the source, relative to the listener.
+        pub unsafe fn SetSourceOrientation(&self, orientation: *const HrtfOrientation) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-ixapohrtfparameters-setsourcegain)\]
+        /// Sets the custom gain of the source, in dB: `[HRTF_MIN_GAIN_LIMIT, HRTF_MAX_GAIN_LIMIT]`.
+        pub unsafe fn SetSourceGain(&self, gain: f32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-ixapohrtfparameters-setenvironment)\]
+        /// Selects the acoustic environment to simulate.
+        pub unsafe fn SetEnvironment(&self, environment: HrtfEnvironment) -> HResult;
+    }
+}
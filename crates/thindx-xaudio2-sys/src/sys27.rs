@@ -1,7 +1,11 @@
-//! 🚫 XAudio 2.7 - Windows XP? via redist
+//! ✔️ XAudio 2.7 - Windows XP? via redist
 //!
 //! Introduced in the legacy [DirectX SDK (June 2010)]((https://www.microsoft.com/en-us/download/details.aspx?id=6812))
 //!
+//! Unlike XAudio 2.8+, XAudio 2.7 isn't created via an exported `XAudio2Create` function.
+//! Instead, [CLSID_XAudio2] (or [CLSID_XAudio2_Debug]) is instantiated via `CoCreateInstance`, and then [IXAudio2::Initialize]d.
+//! Devices are enumerated with [IXAudio2::GetDeviceCount] / [IXAudio2::GetDeviceDetails], and selected by index.
+//!
 //! ### References
 //! *   [XAudio2 Versions: XAudio 2.7 and earlier (Windows 7)](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-versions#xaudio-27-and-earlier-windows-7)
 //! *   [XAudio does not ship with Windows 7](https://stackoverflow.com/a/49524446/953531)
@@ -9,12 +13,668 @@
 //! *   [The Zombie DirectX SDK](https://walbourn.github.io/the-zombie-directx-sdk/)
 //! *   [Not So Direct Setup](https://walbourn.github.io/not-so-direct-setup/)
 
-#![deprecated = "Not currently implemented.  Prefer XAudio 2.9, which has redists backported to Windows 7 SP 1."]
-#![allow(deprecated)]
-
-use super::xaudio2_6 as prev;
+// Most plain data is unchanged in XAudio 2.8, so reuse it.  Interfaces and anything referencing them are redefined below.
+use super::xaudio2_8 as next;
+use abibool::bool32;
+use abistr::*;
+use bytemuck::Zeroable;
 use winapi::shared::guiddef::GUID;
+use winapi::shared::mmreg::{WAVEFORMATEX, WAVEFORMATEXTENSIBLE};
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winresult::*;
+use core::ffi::c_void;
+
+#[doc = "`\"xaudio2_7.dll\"`"] pub const XAUDIO2_DLL        : &'static str              =           "xaudio2_7.dll";
+#[doc = "`\"xaudio2_7.dll\"`"] pub const XAUDIO2_DLL_A      : CStrNonNull<'static, u8 > = cstr8!(   "xaudio2_7.dll");
+#[doc = "`\"xaudio2_7.dll\"`"] pub const XAUDIO2_DLL_W      : CStrNonNull<'static, u16> = cstr16!(  "xaudio2_7.dll");
+
+#[doc = "`\"xaudiod2_7.dll\"`"] pub const XAUDIO2D_DLL     : &'static str              =           "xaudiod2_7.dll";
+#[doc = "`\"xaudiod2_7.dll\"`"] pub const XAUDIO2D_DLL_A   : CStrNonNull<'static, u8 > = cstr8!(   "xaudiod2_7.dll");
+#[doc = "`\"xaudiod2_7.dll\"`"] pub const XAUDIO2D_DLL_W   : CStrNonNull<'static, u16> = cstr16!(  "xaudiod2_7.dll");
+
+#[doc = "`5a508685-a254-4fba-9b82-9a24b00306af`"] pub const CLSID_XAudio2         : GUID = super::guid(0x5a508685, 0xa254, 0x4fba, 0x9b829a24b00306af);
+#[doc = "`db05ea35-0329-4d4b-a53a-6dead03d3852`"] pub const CLSID_XAudio2_Debug   : GUID = super::guid(0xdb05ea35, 0x0329, 0x4d4b, 0xa53a6dead03d3852);
+#[doc = "`8bcf1f58-9fe7-4583-8ac6-e2adc465c8bb`"] pub const IID_IXAudio2          : GUID = super::guid(0x8bcf1f58, 0x9fe7, 0x4583, 0x8ac6e2adc465c8bb);
+
+// Numeric boundary values
+pub use next::{
+    XAUDIO2_MAX_BUFFER_BYTES,
+    XAUDIO2_MAX_QUEUED_BUFFERS,
+    XAUDIO2_MAX_BUFFERS_SYSTEM,
+    XAUDIO2_MAX_AUDIO_CHANNELS,
+    XAUDIO2_MIN_SAMPLE_RATE,
+    XAUDIO2_MAX_SAMPLE_RATE,
+    XAUDIO2_MAX_VOLUME_LEVEL,
+    XAUDIO2_MIN_FREQ_RATIO,
+    XAUDIO2_MAX_FREQ_RATIO,
+    XAUDIO2_DEFAULT_FREQ_RATIO,
+    XAUDIO2_MAX_FILTER_ONEOVERQ,
+    XAUDIO2_MAX_FILTER_FREQUENCY,
+    XAUDIO2_MAX_LOOP_COUNT,
+    XAUDIO2_MAX_INSTANCES,
+};
+
+pub use next::{
+    XAUDIO2_MAX_RATIO_TIMES_RATE_XMA_MONO,
+    XAUDIO2_MAX_RATIO_TIMES_RATE_XMA_MULTICHANNEL,
+};
+
+// Numeric values with special meanings
+pub use next::{
+    XAUDIO2_COMMIT_NOW,
+    XAUDIO2_COMMIT_ALL,
+    XAUDIO2_INVALID_OPSET,
+    XAUDIO2_NO_LOOP_REGION,
+    XAUDIO2_LOOP_INFINITE,
+    XAUDIO2_DEFAULT_CHANNELS,
+    XAUDIO2_DEFAULT_SAMPLERATE,
+};
+
+
+
+// Flags
+
+/// Used in [IXAudio2::Initialize] (selects [CLSID_XAudio2_Debug] when creating the engine)
+pub const XAUDIO2_DEBUG_ENGINE : u32 = 0x0001;
+
+pub use next::{
+    XAUDIO2_VOICE_NOPITCH,
+    XAUDIO2_VOICE_NOSRC,
+    XAUDIO2_VOICE_USEFILTER,
+};
+
+/// Used in [IXAudio2::CreateSourceVoice]
+pub const XAUDIO2_VOICE_MUSIC : u32 = 0x0010;
+
+pub use next::{
+    XAUDIO2_PLAY_TAILS,
+    XAUDIO2_END_OF_STREAM,
+    XAUDIO2_SEND_USEFILTER,
+};
+
+
+
+// Default parameters for the built-in filter
+pub use next::{
+    XAUDIO2_DEFAULT_FILTER_TYPE,
+    XAUDIO2_DEFAULT_FILTER_FREQUENCY,
+    XAUDIO2_DEFAULT_FILTER_ONEOVERQ,
+};
+
+// Internal XAudio2 constants
+pub use next::{
+    XAUDIO2_QUANTUM_NUMERATOR,
+    XAUDIO2_QUANTUM_DENOMINATOR,
+    XAUDIO2_QUANTUM_MS,
+};
+
+// XAudio2 error codes
+pub use next::{
+    FACILITY_XAUDIO2,
+    XAUDIO2_E_INVALID_CALL,
+    XAUDIO2_E_XMA_DECODER_ERROR,
+    XAUDIO2_E_XAPO_CREATION_FAILED,
+    XAUDIO2_E_DEVICE_INVALIDATED,
+};
+
+
+
+// Used in IXAudio2::Initialize, specifies which CPU(s) to use.
+pub use next::{
+    XAUDIO2_PROCESSOR,
+    Processor1,
+    Processor2,
+    Processor3,
+    Processor4,
+    Processor5,
+    Processor6,
+    Processor7,
+    Processor8,
+    Processor9,
+    Processor10,
+    Processor11,
+    Processor12,
+    Processor13,
+    Processor14,
+    Processor15,
+    Processor16,
+    Processor17,
+    Processor18,
+    Processor19,
+    Processor20,
+    Processor21,
+    Processor22,
+    Processor23,
+    Processor24,
+    Processor25,
+    Processor26,
+    Processor27,
+    Processor28,
+    Processor29,
+    Processor30,
+    Processor31,
+    Processor32,
+    XAUDIO2_ANY_PROCESSOR,
+};
+
+/// The "default" processor as of XAudio 2.7 (every processor - XAudio 2.8 changed this to [Processor1].)
+pub const XAUDIO2_DEFAULT_PROCESSOR : u32 = XAUDIO2_ANY_PROCESSOR;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ne-xaudio2-xaudio2_device_role)\]
+/// Used in [XAUDIO2_DEVICE_DETAILS::Role]: which default device role(s) a device fills.
+pub type XAUDIO2_DEVICE_ROLE            = u32;
+#[doc = "Not the default device for any role"]      pub const NotDefaultDevice              : u32 = 0x0;
+#[doc = "Default console device"]                   pub const DefaultConsoleDevice          : u32 = 0x1;
+#[doc = "Default multimedia device"]                pub const DefaultMultimediaDevice       : u32 = 0x2;
+#[doc = "Default communications device"]            pub const DefaultCommunicationsDevice   : u32 = 0x4;
+#[doc = "Default game device"]                      pub const DefaultGameDevice             : u32 = 0x8;
+#[doc = "Default device for every role"]            pub const GlobalDefaultDevice           : u32 = 0xF;
+#[doc = "Invalid role"]                             pub const InvalidDeviceRole             : u32 = !GlobalDefaultDevice;
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_device_details)\]
+/// Returned by [IXAudio2::GetDeviceDetails]
+#[derive(Clone, Copy)] #[repr(C, packed(1))] pub struct XAUDIO2_DEVICE_DETAILS {
+    /// String identifier for the audio device (NUL terminated.)
+    pub DeviceID: [u16; 256],
+
+    /// Friendly name suitable for display to a human (NUL terminated.)
+    pub DisplayName: [u16; 256],
+
+    /// Roles that the device should be used for.
+    pub Role: XAUDIO2_DEVICE_ROLE,
+
+    /// The device's native PCM audio output format.
+    pub OutputFormat: WAVEFORMATEXTENSIBLE,
+}
+
+impl Default for XAUDIO2_DEVICE_DETAILS { fn default() -> Self { unsafe { core::mem::zeroed() } } }
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_voice_details)\]
+/// [IXAudio2Voice::GetVoiceDetails] returned voice metadata (XAudio 2.8 added `ActiveFlags`)
+#[derive(Clone, Copy, Debug, Default, Zeroable)] #[repr(C, packed(1))] pub struct XAUDIO2_VOICE_DETAILS {
+    /// Flags the voice was created with.
+    pub CreationFlags: u32,
+
+    /// Channels in the voice's input audio.
+    pub InputChannels: u32,
+
+    /// Sample rate of the voice's input audio.
+    pub InputSampleRate: u32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_send_descriptor)\]
+/// (Flags, [IXAudio2Voice]) to output to
+#[repr(C, packed(1))] pub struct XAUDIO2_SEND_DESCRIPTOR {
+    pub Flags:          u32,
+    pub pOutputVoice:   *const IXAudio2Voice,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_voice_sends)\]
+/// &'_ \[[XAUDIO2_SEND_DESCRIPTOR]\]
+#[repr(C, packed(1))] pub struct XAUDIO2_VOICE_SENDS {
+    pub SendCount:      u32,
+    pub pSends:         *mut XAUDIO2_SEND_DESCRIPTOR,
+}
+
+pub use next::{
+    XAUDIO2_EFFECT_DESCRIPTOR,
+    XAUDIO2_EFFECT_CHAIN,
+    XAUDIO2_FILTER_TYPE,
+    LowPassFilter,
+    BandPassFilter,
+    HighPassFilter,
+    NotchFilter,
+    XAUDIO2_FILTER_PARAMETERS,
+    XAUDIO2_BUFFER,
+    XAUDIO2_BUFFER_WMA,
+    XAUDIO2_VOICE_STATE,
+    XAUDIO2_PERFORMANCE_DATA,
+    XAUDIO2_DEBUG_CONFIGURATION,
+};
+
+pub use next::{
+    XAUDIO2_LOG_ERRORS,
+    XAUDIO2_LOG_WARNINGS,
+    XAUDIO2_LOG_INFO,
+    XAUDIO2_LOG_DETAIL,
+    XAUDIO2_LOG_API_CALLS,
+    XAUDIO2_LOG_FUNC_CALLS,
+    XAUDIO2_LOG_TIMING,
+    XAUDIO2_LOG_LOCKS,
+    XAUDIO2_LOG_MEMORY,
+    XAUDIO2_LOG_STREAMING,
+};
+
+pub use next::{
+    IXAudio2EngineCallback, // Unchanged
+    IXAudio2EngineCallbackVtbl,
+    IXAudio2VoiceCallback,  // Unchanged
+    IXAudio2VoiceCallbackVtbl,
+};
+
+
+
+interfaces! {
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2)\]
+    /// Top-level XAudio2 COM interface.
+    ///
+    /// Unlike XAudio 2.8+, this is created via `CoCreateInstance(`[CLSID_XAudio2]`, ...)` and must be [Initialize](Self::Initialize)d before use.
+    #[iid = IID_IXAudio2]
+    pub interface IXAudio2(IXAudio2Vtbl) => unsafe IUnknown(IUnknownVtbl) {
+        /// \[<strike>microsoft.com</strike>\]
+        /// Returns the number of audio output devices available.
+        ///
+        /// ### Arguments
+        /// * `pCount` - Returns the device count.
+        pub unsafe fn GetDeviceCount(&self, pCount: *mut u32) -> HResult;
+
+        /// \[<strike>microsoft.com</strike>\]
+        /// Returns information about the device with the given index.
+        ///
+        /// ### Arguments
+        /// * `Index`           - Index of the device to be queried (`0 .. GetDeviceCount`).
+        /// * `pDeviceDetails`  - Returns the device details.
+        pub unsafe fn GetDeviceDetails(&self, Index: u32, pDeviceDetails: *mut XAUDIO2_DEVICE_DETAILS) -> HResult;
+
+        /// \[<strike>microsoft.com</strike>\]
+        /// Sets global XAudio2 parameters and prepares it for use.
+        ///
+        /// ### Arguments
+        /// * `Flags`               - Flags specifying the XAudio2 object's behavior (e.g. [XAUDIO2_DEBUG_ENGINE].)
+        /// * `XAudio2Processor`    - An [XAUDIO2_PROCESSOR] value that specifies the hardware threads to use.
+        pub unsafe fn Initialize(&self, Flags: u32, XAudio2Processor: XAUDIO2_PROCESSOR) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-registerforcallbacks)\]
+        /// Adds a new client to receive XAudio2's engine callbacks.
+        ///
+        /// ### Arguments
+        /// * `pCallback` - Callback interface to be called during each processing pass.
+        pub unsafe fn RegisterForCallbacks(&self, pCallback: *const IXAudio2EngineCallback) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-unregisterforcallbacks)\]
+        /// Removes an existing receiver of XAudio2 engine callbacks.
+        ///
+        /// ### Arguments
+        /// * `pCallback` - Previously registered callback interface to be removed.
+        pub unsafe fn UnregisterForCallbacks(&self, pCallback: *const IXAudio2EngineCallback) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+        /// Creates and configures a source voice.
+        ///
+        /// ### Arguments
+        /// * `ppSourceVoice`       - Returns the new object's [IXAudio2SourceVoice] interface.
+        /// * `pSourceFormat`       - Format of the audio that will be fed to the voice.
+        /// * `Flags`               - VOICE_\* flags specifying the source voice's behavior.
+        /// * `MaxFrequencyRatio`   - Maximum [IXAudio2SourceVoice::SetFrequencyRatio] argument to be allowed.
+        /// * `pCallback`           - Optional pointer to a client-provided callback interface.
+        /// * `pSendList`           - Optional list of voices this voice should send audio to.
+        /// * `pEffectChain`        - Optional list of effects to apply to the audio data.
+        pub unsafe fn CreateSourceVoice(
+            &self,
+            ppSourceVoice:      *mut *mut IXAudio2SourceVoice,
+            pSourceFormat:      *const WAVEFORMATEX,
+            Flags:              u32,
+            MaxFrequencyRatio:  f32,
+            pCallback:          *const IXAudio2VoiceCallback,
+            pSendList:          *const XAUDIO2_VOICE_SENDS,
+            pEffectChain:       *const XAUDIO2_EFFECT_CHAIN,
+        ) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsubmixvoice)\]
+        /// Creates and configures a submix voice.
+        ///
+        /// ### Arguments
+        /// * `ppSubmixVoice`   - Returns the new object's [IXAudio2SubmixVoice] interface.
+        /// * `InputChannels`   - Number of channels in this voice's input audio data.
+        /// * `InputSampleRate` - Sample rate of this voice's input audio data.
+        /// * `Flags`           - VOICE_\* flags specifying the submix voice's behavior.
+        /// * `ProcessingStage` - Arbitrary number that determines the processing order.
+        /// * `pSendList`       - Optional list of voices this voice should send audio to.
+        /// * `pEffectChain`    - Optional list of effects to apply to the audio data.
+        pub unsafe fn CreateSubmixVoice(
+            &self,
+            ppSubmixVoice:      *mut *mut IXAudio2SubmixVoice,
+            InputChannels:      u32,
+            InputSampleRate:    u32,
+            Flags:              u32,
+            ProcessingStage:    u32,
+            pSendList:          *const XAUDIO2_VOICE_SENDS,
+            pEffectChain:       *const XAUDIO2_EFFECT_CHAIN,
+        ) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createmasteringvoice)\]
+        /// Creates and configures a mastering voice.
+        ///
+        /// ### Arguments
+        /// * `ppMasteringVoice`    - Returns the new object's [IXAudio2MasteringVoice] interface.
+        /// * `InputChannels`       - Number of channels in this voice's input audio data.
+        /// * `InputSampleRate`     - Sample rate of this voice's input audio data.
+        /// * `Flags`               - VOICE_\* flags specifying the mastering voice's behavior.
+        /// * `DeviceIndex`         - Index of the device to receive the output audio (see [GetDeviceDetails](Self::GetDeviceDetails).)
+        /// * `pEffectChain`        - Optional list of effects to apply to the audio data.
+        pub unsafe fn CreateMasteringVoice(
+            &self,
+            ppMasteringVoice:   *mut *mut IXAudio2MasteringVoice,
+            InputChannels:      u32,
+            InputSampleRate:    u32,
+            Flags:              u32,
+            DeviceIndex:        u32,
+            pEffectChain:       *const XAUDIO2_EFFECT_CHAIN,
+        ) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-startengine)\]
+        /// Creates and starts the audio processing thread.
+        pub unsafe fn StartEngine(&self) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-stopengine)\]
+        /// Stops and destroys the audio processing thread.
+        pub unsafe fn StopEngine(&self) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-commitchanges)\]
+        /// Atomically applies a set of operations previously tagged with a given identifier.
+        ///
+        /// ### Arguments
+        /// * `OperationSet` - Identifier of the set of operations to be applied.
+        pub unsafe fn CommitChanges(&self, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-getperformancedata)\]
+        /// Returns current resource usage details: memory, CPU, etc.
+        ///
+        /// ### Arguments
+        /// * `pPerfData` - Returns the performance data structure.
+        pub unsafe fn GetPerformanceData(&self, pPerfData: *mut XAUDIO2_PERFORMANCE_DATA) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-setdebugconfiguration)\]
+        /// Configures XAudio2's debug output (in debug builds only).
+        ///
+        /// ### Arguments
+        /// * `pDebugConfiguration` - Structure describing the debug output behavior.
+        /// * `pReserved`           - Optional parameter; must be NULL.
+        pub unsafe fn SetDebugConfiguration(&self, pDebugConfiguration: *const XAUDIO2_DEBUG_CONFIGURATION, pReserved: *const c_void) -> ();
+    }
+
+
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2voice)\]
+    /// Base voice management interface.
+    ///
+    /// Same methods as XAudio 2.8's voices, but [GetVoiceDetails](Self::GetVoiceDetails) returns a smaller [XAUDIO2_VOICE_DETAILS].
+    pub interface IXAudio2Voice(IXAudio2VoiceVtbl) {
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-getvoicedetails)\]
+        /// Returns the basic characteristics of this voice.
+        ///
+        /// ### Arguments
+        /// * `pVoiceDetails` - Returns the voice's details.
+        pub unsafe fn GetVoiceDetails(&self, pVoiceDetails: *mut XAUDIO2_VOICE_DETAILS) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputvoices)\]
+        /// Replaces the set of submix/mastering voices that receive this voice's output.
+        ///
+        /// ### Arguments
+        /// * `pSendList` - Optional list of voices this voice should send audio to.
+        pub unsafe fn SetOutputVoices(&self, pSendList: *const XAUDIO2_VOICE_SENDS) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-seteffectchain)\]
+        /// Replaces this voice's current effect chain with a new one.
+        ///
+        /// ### Arguments
+        /// * `pEffectChain` - Structure describing the new effect chain to be used.
+        pub unsafe fn SetEffectChain(&self, pEffectChain: *const XAUDIO2_EFFECT_CHAIN) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-enableeffect)\]
+        /// Enables an effect in this voice's effect chain.
+        ///
+        /// ### Arguments
+        /// * `EffectIndex`     - Index of an effect within this voice's effect chain.
+        /// * `OperationSet`    - Used to identify this call as part of a deferred batch.
+        pub unsafe fn EnableEffect(&self, EffectIndex: u32, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-disableeffect)\]
+        /// Disables an effect in this voice's effect chain.
+        ///
+        /// ### Arguments
+        /// * `EffectIndex`     - Index of an effect within this voice's effect chain.
+        /// * `OperationSet`    - Used to identify this call as part of a deferred batch.
+        pub unsafe fn DisableEffect(&self, EffectIndex: u32, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-geteffectstate)\]
+        /// Returns the running state of an effect.
+        ///
+        /// ### Arguments
+        /// * `EffectIndex` - Index of an effect within this voice's effect chain.
+        /// * `pEnabled`    - Returns the enabled/disabled state of the given effect.
+        pub unsafe fn GetEffectState(&self, EffectIndex: u32, pEnabled: *mut bool32) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-seteffectparameters)\]
+        /// Sets effect-specific parameters.
+        ///
+        /// ### Arguments
+        /// * `EffectIndex`         - Index of an effect within this voice's effect chain.
+        /// * `pParameters`         - Pointer to an effect-specific parameters block.
+        /// * `ParametersByteSize`  - Size of the pParameters array  in bytes.
+        /// * `OperationSet`        - Used to identify this call as part of a deferred batch.
+        pub unsafe fn SetEffectParameters(&self, EffectIndex: u32, pParameters: *const u8, ParametersByteSize: u32, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-geteffectparameters)\]
+        /// Obtains the current effect-specific parameters.
+        ///
+        /// ### Arguments
+        /// * `EffectIndex`         - Index of an effect within this voice's effect chain.
+        /// * `pParameters`         - Returns the current values of the effect-specific parameters.
+        /// * `ParametersByteSize`  - Size of the pParameters array in bytes.
+        pub unsafe fn GetEffectParameters(&self, EffectIndex: u32, pParameters: *mut u8, ParametersByteSize: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setfilterparameters)\]
+        /// Sets this voice's filter parameters.
+        ///
+        /// ### Arguments
+        /// * `pParameters`     - Pointer to the filter's parameter structure.
+        /// * `OperationSet`    - Used to identify this call as part of a deferred batch.
+        pub unsafe fn SetFilterParameters(&self, pParameters: *const XAUDIO2_FILTER_PARAMETERS, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-getfilterparameters)\]
+        /// Returns this voice's current filter parameters.
+        ///
+        /// ### Arguments
+        /// * `pParameters` - Returns the filter parameters.
+        pub unsafe fn GetFilterParameters(&self, pParameters: *mut XAUDIO2_FILTER_PARAMETERS) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputfilterparameters)\]
+        /// Sets the filter parameters on one of this voice's sends.
+        ///
+        /// ### Arguments
+        /// * `pDestinationVoice`   - Destination voice of the send whose filter parameters will be set.
+        /// * `pParameters`         - Pointer to the filter's parameter structure.
+        /// * `OperationSet`        - Used to identify this call as part of a deferred batch.
+        pub unsafe fn SetOutputFilterParameters(&self, pDestinationVoice: *const IXAudio2Voice, pParameters: *const XAUDIO2_FILTER_PARAMETERS, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-getoutputfilterparameters)\]
+        /// Returns the filter parameters from one of this voice's sends.
+        ///
+        /// ### Arguments
+        /// * `pDestinationVoice`   - Destination voice of the send whose filter parameters will be read.
+        /// * `pParameters`         - Returns the filter parameters.
+        pub unsafe fn GetOutputFilterParameters(&self, pDestinationVoice: *const IXAudio2Voice, pParameters: *mut XAUDIO2_FILTER_PARAMETERS) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setvolume)\]
+        /// Sets this voice's overall volume level.
+        ///
+        /// ### Arguments
+        /// * `Volume`          - New overall volume level to be used, as an amplitude factor.
+        /// * `OperationSet`    - Used to identify this call as part of a deferred batch.
+        pub unsafe fn SetVolume(&self, Volume: f32, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-getvolume)\]
+        /// Obtains this voice's current overall volume level.
+        ///
+        /// ### Arguments
+        /// * `pVolume` - Returns the voice's current overall volume level.
+        pub unsafe fn GetVolume(&self, pVolume: *mut f32) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setchannelvolumes)\]
+        /// Sets this voice's per-channel volume levels.
+        ///
+        /// ### Arguments
+        /// * `Channels`        - Used to confirm the voice's channel count.
+        /// * `pVolumes`        - Array of per-channel volume levels to be used.
+        /// * `OperationSet`    - Used to identify this call as part of a deferred batch.
+        pub unsafe fn SetChannelVolumes(&self, Channels: u32, pVolumes: *const f32, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-getchannelvolumes)\]
+        /// Returns this voice's current per-channel volume levels.
+        ///
+        /// ### Arguments
+        /// * `Channels` - Used to confirm the voice's channel count.
+        /// * `pVolumes` - Returns an array of the current per-channel volume levels.
+        pub unsafe fn GetChannelVolumes(&self, Channels: u32, pVolumes: *mut f32) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputmatrix)\]
+        /// Sets the volume levels used to mix from each channel of this
+        /// voice's output audio to each channel of a given destination
+        /// voice's input audio.
+        ///
+        /// ### Arguments
+        /// * `pDestinationVoice`   - The destination voice whose mix matrix to change.
+        /// * `SourceChannels`      - Used to confirm this voice's output channel count (the number of channels produced by the last effect in the chain).
+        /// * `DestinationChannels` - Confirms the destination voice's input channels.
+        /// * `pLevelMatrix`        - Array of \[SourceChannels * DestinationChannels\] send levels.  The level used to send from source channel S to destination channel D should be in pLevelMatrix\[S + SourceChannels * D\].
+        /// * `OperationSet`        - Used to identify this call as part of a deferred batch.
+        pub unsafe fn SetOutputMatrix(
+            &self,
+            pDestinationVoice:      *const IXAudio2Voice,
+            SourceChannels:         u32,
+            DestinationChannels:    u32,
+            pLevelMatrix:           *const f32,
+            OperationSet:           u32,
+        ) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-getoutputmatrix)\]
+        /// Obtains the volume levels used to send each channel of this
+        /// voice's output audio to each channel of a given destination
+        /// voice's input audio.
+        ///
+        /// ### Arguments
+        /// * `pDestinationVoice`   - The destination voice whose mix matrix to obtain.
+        /// * `SourceChannels`      - Used to confirm this voice's output channel count (the number of channels produced by the last effect in the chain).
+        /// * `DestinationChannels` - Confirms the destination voice's input channels.
+        /// * `pLevelMatrix`        - Array of send levels, as above.
+        pub unsafe fn GetOutputMatrix(
+            &self,
+            pDestinationVoice:      *const IXAudio2Voice,
+            SourceChannels:         u32,
+            DestinationChannels:    u32,
+            pLevelMatrix:           *mut f32,
+        ) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-destroyvoice)\]
+        /// Destroys this voice, stopping it if necessary and removing it from the XAudio2 graph.
+        pub unsafe fn DestroyVoice(&self) -> ();
+    }
+
+
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2sourcevoice)\]
+    /// Source voice management interface.
+    ///
+    /// Unlike XAudio 2.8+, [GetState](Self::GetState) takes no `Flags`.
+    pub interface IXAudio2SourceVoice(IXAudio2SourceVoiceVtbl) => unsafe IXAudio2Voice(IXAudio2VoiceVtbl) {
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-start)\]
+        /// Makes this voice start consuming and processing audio.
+        ///
+        /// ### Arguments
+        /// * `Flags`           - Flags controlling how the voice should be started.
+        /// * `OperationSet`    - Used to identify this call as part of a deferred batch.
+        pub unsafe fn Start(&self, Flags: u32, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-stop)\]
+        /// Makes this voice stop consuming audio.
+        ///
+        /// ### Arguments
+        /// * `Flags`           - Flags controlling how the voice should be stopped (e.g. [XAUDIO2_PLAY_TAILS].)
+        /// * `OperationSet`    - Used to identify this call as part of a deferred batch.
+        pub unsafe fn Stop(&self, Flags: u32, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-submitsourcebuffer)\]
+        /// Adds a new audio buffer to this voice's input queue.
+        ///
+        /// ### Arguments
+        /// * `pBuffer`     - Pointer to the buffer structure to be queued.
+        /// * `pBufferWMA`  - Additional structure used only when submitting XWMA data.
+        pub unsafe fn SubmitSourceBuffer(&self, pBuffer: *const XAUDIO2_BUFFER, pBufferWMA: *const XAUDIO2_BUFFER_WMA) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-flushsourcebuffers)\]
+        /// Removes all pending audio buffers from this voice's queue.
+        pub unsafe fn FlushSourceBuffers(&self) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-discontinuity)\]
+        /// Notifies the voice of an intentional break in the stream of audio buffers (e.g. the end of a sound), to prevent XAudio2 from interpreting an empty buffer queue as a glitch.
+        pub unsafe fn Discontinuity(&self) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-exitloop)\]
+        /// Breaks out of the current loop when its end is reached.
+        ///
+        /// ### Arguments
+        /// * `OperationSet` - Used to identify this call as part of a deferred batch.
+        pub unsafe fn ExitLoop(&self, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getstate)\]
+        /// Returns the number of buffers currently queued on this voice, the pContext value associated with the currently processing buffer (if any), and other voice state information.
+        ///
+        /// ### Arguments
+        /// * `pVoiceState` - Returns the state information.
+        pub unsafe fn GetState(&self, pVoiceState: *mut XAUDIO2_VOICE_STATE) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setfrequencyratio)\]
+        /// Sets this voice's frequency adjustment, i.e. its pitch.
+        ///
+        /// ### Arguments
+        /// * `Ratio`           - Frequency change, expressed as source frequency / target frequency.
+        /// * `OperationSet`    - Used to identify this call as part of a deferred batch.
+        pub unsafe fn SetFrequencyRatio(&self, Ratio: f32, OperationSet: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getfrequencyratio)\]
+        /// Returns this voice's current frequency adjustment ratio.
+        ///
+        /// ### Arguments
+        /// * `pRatio` - Returns the frequency adjustment.
+        pub unsafe fn GetFrequencyRatio(&self, pRatio: *mut f32) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setsourcesamplerate)\]
+        /// Reconfigures this voice to treat its source data as being at a different sample rate than the original one specified in CreateSourceVoice's pSourceFormat argument.
+        ///
+        /// ### Arguments
+        /// * `NewSourceSampleRate` - The intended sample rate of further submitted XAudio2 buffers.
+        pub unsafe fn SetSourceSampleRate(&self, NewSourceSampleRate: u32) -> HResult;
+    }
+
+
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2submixvoice)\]
+    /// Submixing voice management interface.
+    pub interface IXAudio2SubmixVoice(IXAudio2SubmixVoiceVtbl) => unsafe IXAudio2Voice(IXAudio2VoiceVtbl) {
+        // There are currently no methods specific to submix voices.
+    }
+
+
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2masteringvoice)\]
+    /// Mastering voice management interface.
+    ///
+    /// XAudio 2.7 has no `GetChannelMask`: use [XAUDIO2_DEVICE_DETAILS::OutputFormat]'s `dwChannelMask` instead.
+    pub interface IXAudio2MasteringVoice(IXAudio2MasteringVoiceVtbl) => unsafe IXAudio2Voice(IXAudio2VoiceVtbl) {
+        // There are currently no methods specific to mastering voices.
+    }
+
+}
 
-pub const CLSID_XAudio2         : GUID = super::guid(0x5a508685, 0xa254, 0x4fba, 0x9b829a24b00306af);
-pub const CLSID_XAudio2_Debug   : GUID = super::guid(0xdb05ea35, 0x0329, 0x4d4b, 0xa53a6dead03d3852);
-pub const IID_IXAudio2          : GUID = prev::IID_IXAudio2; // Might be a different interface despite reusing the GUID
+#[cfg(feature = "helper-functions")] pub use next::{
+    XAudio2DecibelsToAmplitudeRatio,
+    XAudio2AmplitudeRatioToDecibels,
+    XAudio2SemitonesToFrequencyRatio,
+    XAudio2FrequencyRatioToSemitones,
+    XAudio2CutoffFrequencyToRadians,
+    XAudio2RadiansToCutoffFrequency,
+};
@@ -0,0 +1,278 @@
+//! ✔️ X3DAudio - exported by `xaudio2_8.dll` and `xaudio2_9.dll`
+//!
+//! ### References
+//! *   [X3DAudio](https://learn.microsoft.com/en-us/windows/win32/xaudio2/x3daudio)
+//! *   [x3daudio.h header](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/)
+
+use bytemuck::{Pod, Zeroable};
+
+
+
+/// Size of an [X3DAUDIO_HANDLE], in bytes
+pub const X3DAUDIO_HANDLE_BYTESIZE : usize = 20;
+
+/// `π`, as defined by `x3daudio.h`
+#[allow(clippy::approx_constant)] // mirror x3daudio.h's literals exactly, rather than core::f32::consts
+pub const X3DAUDIO_PI : f32 = 3.141592654;
+
+/// `2π`, as defined by `x3daudio.h`
+#[allow(clippy::approx_constant)]
+pub const X3DAUDIO_2PI : f32 = 6.283185307;
+
+/// Speed of sound in meters per second (dry air at approximately 20°C), for use with [X3DAudioInitialize](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudioinitialize)
+pub const X3DAUDIO_SPEED_OF_SOUND : f32 = 343.5;
+
+
+
+// Calculation control flags, used when calling X3DAudioCalculate
+
+/// Enable matrix coefficient table calculation
+pub const X3DAUDIO_CALCULATE_MATRIX : u32 = 0x00000001;
+
+/// Enable delay time array calculation (stereo final mix only)
+pub const X3DAUDIO_CALCULATE_DELAY : u32 = 0x00000002;
+
+/// Enable LPF direct-path coefficient calculation
+pub const X3DAUDIO_CALCULATE_LPF_DIRECT : u32 = 0x00000004;
+
+/// Enable LPF reverb-path coefficient calculation
+pub const X3DAUDIO_CALCULATE_LPF_REVERB : u32 = 0x00000008;
+
+/// Enable reverb send level calculation
+pub const X3DAUDIO_CALCULATE_REVERB : u32 = 0x00000010;
+
+/// Enable doppler shift factor calculation
+pub const X3DAUDIO_CALCULATE_DOPPLER : u32 = 0x00000020;
+
+/// Enable emitter-to-listener interior angle calculation
+pub const X3DAUDIO_CALCULATE_EMITTER_ANGLE : u32 = 0x00000040;
+
+/// Do not position to front center speaker, signal positioned to remaining speakers instead, front center destination channel will be zero in returned matrix coefficient table, valid only for matrix calculations with final mix formats that have a front center channel
+pub const X3DAUDIO_CALCULATE_ZEROCENTER : u32 = 0x00010000;
+
+/// Apply equal mix of all source channels to LFE destination channel, valid only for matrix calculations with sources that have no LFE channel and final mix formats that have an LFE channel
+pub const X3DAUDIO_CALCULATE_REDIRECT_TO_LFE : u32 = 0x00020000;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudioinitialize)\]
+/// Opaque instance handle, initialized by `X3DAudioInitialize` and read by `X3DAudioCalculate`
+pub type X3DAUDIO_HANDLE = [u8; X3DAUDIO_HANDLE_BYTESIZE];
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/d3d9types/ns-d3d9types-d3dvector)\]
+/// `D3DVECTOR`: a 3D vector in a left-handed cartesian coordinate system
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)] #[repr(C, packed(1))] pub struct X3DAUDIO_VECTOR {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_distance_curve_point)\]
+/// A (distance, DSP setting) pair of an [X3DAUDIO_DISTANCE_CURVE]
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)] #[repr(C, packed(1))] pub struct X3DAUDIO_DISTANCE_CURVE_POINT {
+    /// Normalized distance, must be within 0 ..= 1
+    pub Distance: f32,
+
+    /// DSP control setting
+    pub DSPSetting: f32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_distance_curve)\]
+/// A piecewise linear curve of [X3DAUDIO_DISTANCE_CURVE_POINT]s, sorted by ascending distance, covering 0 ..= 1
+#[derive(Clone, Copy, Debug, Zeroable)] #[repr(C, packed(1))] pub struct X3DAUDIO_DISTANCE_CURVE {
+    /// Distance curve point array, must have at least `PointCount` elements with no duplicates, sorted in ascending [Distance](X3DAUDIO_DISTANCE_CURVE_POINT::Distance) order
+    pub pPoints: *mut X3DAUDIO_DISTANCE_CURVE_POINT,
+
+    /// Number of distance curve points, must be `>= 2` as all distance curves must have at least two endpoints, defining DSP settings at 0.0 and 1.0 normalized distance
+    pub PointCount: u32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_cone)\]
+/// Sound cone, used by [X3DAUDIO_LISTENER] and [X3DAUDIO_EMITTER] to scale DSP settings by direction
+///
+/// All angles are in radians, must be within 0 ..= [X3DAUDIO_2PI], with `InnerAngle <= OuterAngle`.
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)] #[repr(C, packed(1))] pub struct X3DAUDIO_CONE {
+    /// Inner cone angle in radians
+    pub InnerAngle: f32,
+
+    /// Outer cone angle in radians
+    pub OuterAngle: f32,
+
+    /// Volume level scaler on/within inner cone, used only for matrix calculations, must be within 0 ..= 2
+    pub InnerVolume: f32,
+
+    /// Volume level scaler on/beyond outer cone, used only for matrix calculations, must be within 0 ..= 2
+    pub OuterVolume: f32,
+
+    /// LPF (both direct and reverb paths) coefficient subtrahend on/within inner cone, used only for LPF (both direct and reverb paths) calculations, must be within 0 ..= 1
+    pub InnerLPF: f32,
+
+    /// LPF (both direct and reverb paths) coefficient subtrahend on/beyond outer cone, used only for LPF (both direct and reverb paths) calculations, must be within 0 ..= 1
+    pub OuterLPF: f32,
+
+    /// Reverb send level scaler on/within inner cone, used only for reverb calculations, must be within 0 ..= 2
+    pub InnerReverb: f32,
+
+    /// Reverb send level scaler on/beyond outer cone, used only for reverb calculations, must be within 0 ..= 2
+    pub OuterReverb: f32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_listener)\]
+/// The point of reception
+///
+/// `OrientFront` and `OrientTop` must be orthonormal.
+#[derive(Clone, Copy, Debug, Zeroable)] #[repr(C, packed(1))] pub struct X3DAUDIO_LISTENER {
+    /// Orientation of front direction, used only for matrix and delay calculations or listeners with cones for matrix, LPF (both direct and reverb paths), and reverb calculations, must be normalized when used
+    pub OrientFront: X3DAUDIO_VECTOR,
+
+    /// Orientation of top direction, used only for matrix and delay calculations, must be orthonormal with `OrientFront` when used
+    pub OrientTop: X3DAUDIO_VECTOR,
+
+    /// Position in user-defined world units, does not affect `Velocity`
+    pub Position: X3DAUDIO_VECTOR,
+
+    /// Velocity vector in user-defined world units/second, used only for doppler calculations, does not affect `Position`
+    pub Velocity: X3DAUDIO_VECTOR,
+
+    /// Sound cone, used only for matrix, LPF (both direct and reverb paths), and reverb calculations, null specifies omnidirectionality
+    pub pCone: *mut X3DAUDIO_CONE,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_emitter)\]
+/// A single-point or multi-point 3D audio source
+///
+/// Null curves select their defaults, documented per field.
+#[derive(Clone, Copy, Debug, Zeroable)] #[repr(C, packed(1))] pub struct X3DAUDIO_EMITTER {
+    /// Sound cone, used only with single-channel emitters for matrix, LPF (both direct and reverb paths), and reverb calculations, null specifies omnidirectionality
+    pub pCone: *mut X3DAUDIO_CONE,
+
+    /// Orientation of front direction, used only for emitter angle calculations or with multi-channel emitters for matrix calculations or single-channel emitters with cones for matrix, LPF (both direct and reverb paths), and reverb calculations, must be normalized when used
+    pub OrientFront: X3DAUDIO_VECTOR,
+
+    /// Orientation of top direction, used only with multi-channel emitters for matrix calculations, must be orthonormal with `OrientFront` when used
+    pub OrientTop: X3DAUDIO_VECTOR,
+
+    /// Position in user-defined world units, does not affect `Velocity`
+    pub Position: X3DAUDIO_VECTOR,
+
+    /// Velocity vector in user-defined world units/second, used only for doppler calculations, does not affect `Position`
+    pub Velocity: X3DAUDIO_VECTOR,
+
+    /// Inner radius, must be within 0 ..= [f32::MAX]
+    pub InnerRadius: f32,
+
+    /// Inner radius angle, must be within 0 ..= [X3DAUDIO_PI]/4
+    pub InnerRadiusAngle: f32,
+
+    /// Number of sound channels, must be > 0
+    pub ChannelCount: u32,
+
+    /// Channel radius, used only with multi-channel emitters for matrix calculations, must be >= 0 when used
+    pub ChannelRadius: f32,
+
+    /// Channel azimuth array, used only with multi-channel emitters for matrix calculations, contains positions of each channel expressed in radians along the channel radius with respect to the front orientation vector in the plane orthogonal to the top orientation vector, or [X3DAUDIO_2PI] to specify an LFE channel, must have at least `ChannelCount` elements, all within 0 ..= [X3DAUDIO_2PI] when used
+    pub pChannelAzimuths: *mut f32,
+
+    /// Volume-level distance curve, used only for matrix calculations, null specifies a default curve that conforms to the inverse square law, calculated in user-defined world units with distances <= `CurveDistanceScaler` clamped to no attenuation
+    pub pVolumeCurve: *mut X3DAUDIO_DISTANCE_CURVE,
+
+    /// LFE roll-off distance curve, or null to use default curve: `[0.0, CurveDistanceScaler], [CurveDistanceScaler, 0.0]`, null specifies a default curve that conforms to the inverse square law with distances <= `CurveDistanceScaler` clamped to no attenuation
+    pub pLFECurve: *mut X3DAUDIO_DISTANCE_CURVE,
+
+    /// Low-pass filter (LPF) direct-path coefficient distance curve, or null to use default curve: `[0.0, 1.0], [1.0, 0.75]`
+    pub pLPFDirectCurve: *mut X3DAUDIO_DISTANCE_CURVE,
+
+    /// LPF reverb-path coefficient distance curve, or null to use default curve: `[0.0, 0.75], [1.0, 0.75]`
+    pub pLPFReverbCurve: *mut X3DAUDIO_DISTANCE_CURVE,
+
+    /// Reverb send level distance curve, or null to use default curve: `[0.0, 1.0], [1.0, 0.0]`
+    pub pReverbCurve: *mut X3DAUDIO_DISTANCE_CURVE,
+
+    /// Curve distance scaler, used to scale normalized distance curves to user-defined world units and/or exaggerate their effect, used only for matrix, LPF (both direct and reverb paths), and reverb calculations, must be within [f32::MIN_POSITIVE] ..= [f32::MAX] when used
+    pub CurveDistanceScaler: f32,
+
+    /// Doppler shift scaler, used to exaggerate doppler shift effect, used only for doppler calculations, must be within 0 ..= [f32::MAX] when used
+    pub DopplerScaler: f32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_dsp_settings)\]
+/// Receives the results of `X3DAudioCalculate`
+///
+/// The caller allocates `pMatrixCoefficients` and `pDelayTimes`, and fills in `SrcChannelCount` and `DstChannelCount`.
+#[derive(Clone, Copy, Debug, Zeroable)] #[repr(C, packed(1))] pub struct X3DAUDIO_DSP_SETTINGS {
+    /// \[in\] matrix coefficient table, receives an array representing the volume level used to send from source channel S to destination channel D, stored as `pMatrixCoefficients[SrcChannelCount * D + S]`, must have at least `SrcChannelCount*DstChannelCount` elements
+    pub pMatrixCoefficients: *mut f32,
+
+    /// \[in\] delay time array, receives delays for each destination channel in milliseconds, must have at least `DstChannelCount` elements (stereo final mix only)
+    pub pDelayTimes: *mut f32,
+
+    /// \[in\] number of source channels, must equal number of channels in respective emitter
+    pub SrcChannelCount: u32,
+
+    /// \[in\] number of destination channels, must equal number of channels of the final mix
+    pub DstChannelCount: u32,
+
+    /// \[out\] LPF direct-path coefficient
+    pub LPFDirectCoefficient: f32,
+
+    /// \[out\] LPF reverb-path coefficient
+    pub LPFReverbCoefficient: f32,
+
+    /// \[out\] reverb send level
+    pub ReverbLevel: f32,
+
+    /// \[out\] doppler shift factor, scales resampler ratio for doppler shift effect, where the effective frequency = DopplerFactor * original frequency
+    pub DopplerFactor: f32,
+
+    /// \[out\] emitter-to-listener interior angle, expressed in radians with respect to the emitter's front orientation
+    pub EmitterToListenerAngle: f32,
+
+    /// \[out\] distance in user-defined world units from the emitter base to listener position, always calculated
+    pub EmitterToListenerDistance: f32,
+
+    /// \[out\] component of emitter velocity vector projected onto emitter->listener vector in user-defined world units/second, calculated only for doppler
+    pub EmitterVelocityComponent: f32,
+
+    /// \[out\] component of listener velocity vector projected onto emitter->listener vector in user-defined world units/second, calculated only for doppler
+    pub ListenerVelocityComponent: f32,
+}
+
+
+
+/// Linear volume curve: full volume at distance 0, silence at distance 1
+pub const X3DAudioDefault_LinearCurvePoints : [X3DAUDIO_DISTANCE_CURVE_POINT; 2] = [
+    X3DAUDIO_DISTANCE_CURVE_POINT { Distance: 0.0, DSPSetting: 1.0 },
+    X3DAUDIO_DISTANCE_CURVE_POINT { Distance: 1.0, DSPSetting: 0.0 },
+];
+
+/// [X3DAUDIO_DISTANCE_CURVE] referencing [X3DAudioDefault_LinearCurvePoints].
+///
+/// N.B. `pPoints` is `*mut` to match the C struct, but X3DAudio never writes through it.
+pub const X3DAudioDefault_LinearCurve : X3DAUDIO_DISTANCE_CURVE = X3DAUDIO_DISTANCE_CURVE {
+    pPoints:    &X3DAudioDefault_LinearCurvePoints as *const [X3DAUDIO_DISTANCE_CURVE_POINT; 2] as *mut X3DAUDIO_DISTANCE_CURVE_POINT,
+    PointCount: 2,
+};
+
+/// Directional cone: full volume within 90°, 70.8% volume (-3 dB) beyond 180°, with more filtering and reverb outside the cone
+pub const X3DAudioDefault_DirectionalCone : X3DAUDIO_CONE = X3DAUDIO_CONE {
+    InnerAngle:     X3DAUDIO_PI/2.0,
+    OuterAngle:     X3DAUDIO_PI,
+    InnerVolume:    1.0,
+    OuterVolume:    0.708,
+    InnerLPF:       0.0,
+    OuterLPF:       0.25,
+    InnerReverb:    0.708,
+    OuterReverb:    1.0,
+};
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudioinitialize)\]
+/// `X3DAudioInitialize`: initializes an [X3DAUDIO_HANDLE] for `SpeakerChannelMask` speakers (must be nonzero), with `SpeedOfSound` in world units per second.
+pub type X3DAudioInitializeFn = unsafe extern "cdecl" fn(SpeakerChannelMask: u32, SpeedOfSound: f32, Instance: *mut X3DAUDIO_HANDLE) -> winresult::HResult;
+// X3DAUDIO_API_ -> STDAPIVCALLTYPE -> __cdecl
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudiocalculate)\]
+/// `X3DAudioCalculate`: calculates DSP settings for `pEmitter` as heard by `pListener`, for the `X3DAUDIO_CALCULATE_*` `Flags` requested.
+pub type X3DAudioCalculateFn = unsafe extern "cdecl" fn(Instance: *const X3DAUDIO_HANDLE, pListener: *const X3DAUDIO_LISTENER, pEmitter: *const X3DAUDIO_EMITTER, Flags: u32, pDSPSettings: *mut X3DAUDIO_DSP_SETTINGS);
+// X3DAUDIO_API_ -> STDAPIVCALLTYPE -> __cdecl
@@ -0,0 +1,186 @@
+//! ✔️ XAPO - `xapo.h` (XAudio 2.8+ interface IDs)
+//!
+//! Interfaces for authoring custom audio processing objects (effects) to insert into XAudio2 effect chains.
+//!
+//! ### References
+//! *   [XAPO Overview](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xapo-overview)
+//! *   [xapo.h header](https://learn.microsoft.com/en-us/windows/win32/api/xapo/)
+
+use abibool::bool32;
+use bytemuck::Zeroable;
+use winapi::shared::guiddef::GUID;
+use winapi::shared::mmreg::WAVEFORMATEX;
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winresult::*;
+use core::ffi::c_void;
+
+
+
+#[doc = "`a410b984-9839-4819-a0be-2856ae6b3adb`"] pub const IID_IXAPO           : GUID = super::guid(0xa410b984, 0x9839, 0x4819, 0xa0be2856ae6b3adb);
+#[doc = "`26d95c66-80f2-499a-ad54-5ae7f01c6d98`"] pub const IID_IXAPOParameters : GUID = super::guid(0x26d95c66, 0x80f2, 0x499a, 0xad545ae7f01c6d98);
+
+
+
+/// Minimum number of channels supported by XAPOs
+pub const XAPO_MIN_CHANNELS : u32 = 1;
+
+/// Maximum number of channels supported by XAPOs
+pub const XAPO_MAX_CHANNELS : u32 = 64;
+
+/// Minimum framerate supported by XAPOs
+pub const XAPO_MIN_FRAMERATE : u32 = 1000;
+
+/// Maximum framerate supported by XAPOs
+pub const XAPO_MAX_FRAMERATE : u32 = 200000;
+
+/// Length, in characters (including the terminating nul), of [XAPO_REGISTRATION_PROPERTIES]'s strings
+pub const XAPO_REGISTRATION_STRING_LENGTH : usize = 256;
+
+
+
+// XAPO property flags, used in XAPO_REGISTRATION_PROPERTIES::Flags
+
+/// Number of channels of input and output buffers must match (applies to [XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS::pFormat])
+pub const XAPO_FLAG_CHANNELS_MUST_MATCH : u32 = 0x00000001;
+
+/// Framerate of input and output buffers must match (applies to [XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS::pFormat])
+pub const XAPO_FLAG_FRAMERATE_MUST_MATCH : u32 = 0x00000002;
+
+/// Bit depth of input and output buffers must match (applies to [XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS::pFormat])
+pub const XAPO_FLAG_BITSPERSAMPLE_MUST_MATCH : u32 = 0x00000004;
+
+/// Number of input and output buffers must match (applies to [XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS])
+pub const XAPO_FLAG_BUFFERCOUNT_MUST_MATCH : u32 = 0x00000008;
+
+/// XAPO must be run in-place
+pub const XAPO_FLAG_INPLACE_REQUIRED : u32 = 0x00000020;
+
+/// XAPO may be run in-place
+pub const XAPO_FLAG_INPLACE_SUPPORTED : u32 = 0x00000010;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xapo-error-codes)\]
+/// Requested audio format unsupported
+pub const XAPO_E_FORMAT_UNSUPPORTED : HResult = HResult::from_constant(0x88970001);
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/ns-xapo-xapo_registration_properties)\]
+/// Describes general properties of an XAPO.
+///
+/// Returned by [IXAPO::GetRegistrationProperties], allocated with `XAPOAlloc` (`CoTaskMemAlloc`.)
+#[derive(Clone, Copy, Zeroable)] #[repr(C, packed(1))] pub struct XAPO_REGISTRATION_PROPERTIES {
+    /// COM class ID of the XAPO
+    pub clsid:                  GUID,
+    /// Friendly name (nul terminated UTF-16)
+    pub FriendlyName:           [u16; XAPO_REGISTRATION_STRING_LENGTH],
+    /// Copyright information (nul terminated UTF-16)
+    pub CopyrightInfo:          [u16; XAPO_REGISTRATION_STRING_LENGTH],
+    pub MajorVersion:           u32,
+    pub MinorVersion:           u32,
+    /// `XAPO_FLAG_*`
+    pub Flags:                  u32,
+    pub MinInputBufferCount:    u32,
+    pub MaxInputBufferCount:    u32,
+    pub MinOutputBufferCount:   u32,
+    pub MaxOutputBufferCount:   u32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/ns-xapo-xapo_lockforprocess_buffer_parameters)\]
+/// Defines stream buffer parameters that remain constant while an XAPO is locked.
+#[derive(Clone, Copy, Zeroable)] #[repr(C, packed(1))] pub struct XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS {
+    /// Buffer format
+    pub pFormat:        *const WAVEFORMATEX,
+    /// Maximum number of frames in respective buffer that [IXAPO::Process] would have to handle, irrespective of dynamic variable rate resampling.
+    pub MaxFrameCount:  u32,
+}
+
+/// Alias of [XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS]
+pub type XAPO_LOCKFORPROCESS_PARAMETERS = XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS;
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/ne-xapo-xapo_buffer_flags)\]
+/// Used in [XAPO_PROCESS_BUFFER_PARAMETERS]
+#[derive(Clone, Copy, Debug, Default, Zeroable, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)] pub struct XAPO_BUFFER_FLAGS(u32);
+
+/// Audio data is considered all zeros (the buffer's contents are undefined)
+pub const XAPO_BUFFER_SILENT : XAPO_BUFFER_FLAGS = XAPO_BUFFER_FLAGS(0);
+
+/// Data is valid
+pub const XAPO_BUFFER_VALID : XAPO_BUFFER_FLAGS = XAPO_BUFFER_FLAGS(1);
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/ns-xapo-xapo_process_buffer_parameters)\]
+/// Defines stream buffer parameters that may change from one call to the next of [IXAPO::Process].
+#[derive(Clone, Copy, Zeroable)] #[repr(C, packed(1))] pub struct XAPO_PROCESS_BUFFER_PARAMETERS {
+    /// Audio data buffer (of the format negotiated in [IXAPO::LockForProcess])
+    pub pBuffer:            *mut c_void,
+    /// Describes the contents of the buffer
+    pub BufferFlags:        XAPO_BUFFER_FLAGS,
+    /// Number of valid frames within the buffer
+    pub ValidFrameCount:    u32,
+}
+
+
+
+interfaces! {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapo)\]
+    /// The interface for an Audio Processing Object which can be used in an XAudio2 effect chain.
+    #[iid = IID_IXAPO]
+    pub interface IXAPO(IXAPOVtbl) => unsafe IUnknown(IUnknownVtbl) {
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-getregistrationproperties)\]
+        /// Returns the registration properties of an XAPO (to be freed with `XAPOFree` / `CoTaskMemFree`.)
+        pub unsafe fn GetRegistrationProperties(&self, ppRegistrationProperties: *mut *mut XAPO_REGISTRATION_PROPERTIES) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-isinputformatsupported)\]
+        /// Queries if a specific input format is supported for a given output format.
+        /// On [XAPO_E_FORMAT_UNSUPPORTED], `*ppSupportedInputFormat` (if requested) receives the nearest supported format (to be freed with `XAPOFree`.)
+        pub unsafe fn IsInputFormatSupported(&self, pOutputFormat: *const WAVEFORMATEX, pRequestedInputFormat: *const WAVEFORMATEX, ppSupportedInputFormat: *mut *mut WAVEFORMATEX) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-isoutputformatsupported)\]
+        /// Queries if a specific output format is supported for a given input format.
+        /// On [XAPO_E_FORMAT_UNSUPPORTED], `*ppSupportedOutputFormat` (if requested) receives the nearest supported format (to be freed with `XAPOFree`.)
+        pub unsafe fn IsOutputFormatSupported(&self, pInputFormat: *const WAVEFORMATEX, pRequestedOutputFormat: *const WAVEFORMATEX, ppSupportedOutputFormat: *mut *mut WAVEFORMATEX) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-initialize)\]
+        /// Performs any effect-specific initialization.
+        pub unsafe fn Initialize(&self, pData: *const c_void, DataByteSize: u32) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-reset)\]
+        /// Resets variables dependent on frame history (e.g. delay lines), without affecting allocations or parameters.
+        pub unsafe fn Reset(&self) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-lockforprocess)\]
+        /// Called by XAudio2 to lock the input and output configurations of an XAPO, allowing it to allocate buffers and precalculate parameters.
+        pub unsafe fn LockForProcess(&self, InputLockedParameterCount: u32, pInputLockedParameters: *const XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS, OutputLockedParameterCount: u32, pOutputLockedParameters: *const XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS) -> HResult;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-unlockforprocess)\]
+        /// Deallocates variables that were allocated with [LockForProcess](Self::LockForProcess).
+        pub unsafe fn UnlockForProcess(&self) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-process)\]
+        /// Runs the XAPO's digital signal processing (DSP) code on the given input and output buffers (on the realtime audio thread.)
+        pub unsafe fn Process(&self, InputProcessParameterCount: u32, pInputProcessParameters: *const XAPO_PROCESS_BUFFER_PARAMETERS, OutputProcessParameterCount: u32, pOutputProcessParameters: *mut XAPO_PROCESS_BUFFER_PARAMETERS, IsEnabled: bool32) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-calcinputframes)\]
+        /// Returns the number of input frames required to generate the given number of output frames.
+        pub unsafe fn CalcInputFrames(&self, OutputFrameCount: u32) -> u32;
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-calcoutputframes)\]
+        /// Returns the number of output frames that will be generated from the given number of input frames.
+        pub unsafe fn CalcOutputFrames(&self, InputFrameCount: u32) -> u32;
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapoparameters)\]
+    /// An optional interface that allows an XAPO to use effect-specific parameters.
+    #[iid = IID_IXAPOParameters]
+    pub interface IXAPOParameters(IXAPOParametersVtbl) => unsafe IUnknown(IUnknownVtbl) {
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapoparameters-setparameters)\]
+        /// Sets effect-specific parameters.
+        pub unsafe fn SetParameters(&self, pParameters: *const c_void, ParameterByteSize: u32) -> ();
+
+        /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapoparameters-getparameters)\]
+        /// Gets the current values for any effect-specific parameters.
+        pub unsafe fn GetParameters(&self, pParameters: *mut c_void, ParameterByteSize: u32) -> ();
+    }
+}
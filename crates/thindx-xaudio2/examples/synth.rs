@@ -0,0 +1,40 @@
+//! Continuously synthesize a tone on demand, sweeping its pitch from the main thread.
+
+use thindx_xaudio2::xaudio2_9::*;
+use core::f32::consts::TAU;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering::Relaxed};
+
+
+
+fn main() {
+    mcom::init::mta().expect("mcom::init::mta");
+    let xaudio2 = unsafe { xaudio2::create(None, None) }.expect("xaudio2::create");
+
+    let _master = xaudio2.create_mastering_voice(
+        xaudio2::DEFAULT_CHANNELS, xaudio2::DEFAULT_SAMPLERATE,
+        0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY
+    ).expect("create_mastering_voice");
+
+    let hz = 44100;
+    let pitch = Arc::new(AtomicU32::new(220.0_f32.to_bits()));
+
+    let format = xaudio2::TypedSourceFormat::<[f32; 2]>::pcm(hz);
+    let mut phase = 0.0_f32;
+    let renderer_pitch = Arc::clone(&pitch);
+    let synth = xaudio2.create_render_source_voice(&format, 0, xaudio2::DEFAULT_FREQ_RATIO, move |frames: &mut [[f32; 2]]| {
+        let step = f32::from_bits(renderer_pitch.load(Relaxed)) / (hz as f32);
+        for frame in frames {
+            phase = (phase + step).fract();
+            let s = f32::sin(phase * TAU);
+            *frame = [s, s];
+        }
+    }, None /* defaults to master */, None).expect("synth");
+
+    synth.set_volume(0.2, xaudio2::COMMIT_NOW).unwrap(); // 20% pure tone is plenty loud IMO
+    synth.start(xaudio2::COMMIT_NOW).expect("synth.start()");
+    for step in 0 ..= 100 {
+        pitch.store((220.0 * 2.0_f32.powf(step as f32 / 50.0)).to_bits(), Relaxed); // two octaves up
+        std::thread::sleep(std::time::Duration::from_millis(30));
+    }
+}
@@ -1,22 +1,26 @@
 #![doc = include_str!("../Readme.md")]
-#![cfg(windows)]
 #![forbid(unsafe_op_in_unsafe_fn)]
 #![no_std]
 
 extern crate alloc;
 extern crate std;
 
-#[cfg(doc)] #[path = "../doc/_doc.rs"] pub mod _doc;
-pub use thindx_xaudio2_sys as sys;
+#[cfg(all(windows, doc))] #[path = "../doc/_doc.rs"] pub mod _doc;
+#[cfg(windows)] pub use thindx_xaudio2_sys as sys;
 
-#[path = "util/_util.rs"] mod util; pub use util::*;
-#[path = "xa20.rs"] pub mod xaudio2_0;
-#[path = "xa21.rs"] pub mod xaudio2_1;
-#[path = "xa22.rs"] pub mod xaudio2_2;
-#[path = "xa23.rs"] pub mod xaudio2_3;
-#[path = "xa24.rs"] pub mod xaudio2_4;
-#[path = "xa25.rs"] pub mod xaudio2_5;
-#[path = "xa26.rs"] pub mod xaudio2_6;
-#[path = "xa27.rs"] pub mod xaudio2_7;
-#[path = "xa28/_xa28.rs"] pub mod xaudio2_8;
-#[path = "xa29/_xa29.rs"] pub mod xaudio2_9;
+#[path = "portable/_portable.rs"] pub mod portable;
+
+#[cfg(windows)] #[path = "any/_any.rs"] pub mod any;
+#[cfg(windows)] #[path = "mock/_mock.rs"] pub mod mock;
+#[cfg(windows)] #[path = "util/_util.rs"] mod util;
+#[cfg(windows)] pub use util::*;
+#[cfg(windows)] #[path = "xa20.rs"] pub mod xaudio2_0;
+#[cfg(windows)] #[path = "xa21.rs"] pub mod xaudio2_1;
+#[cfg(windows)] #[path = "xa22.rs"] pub mod xaudio2_2;
+#[cfg(windows)] #[path = "xa23.rs"] pub mod xaudio2_3;
+#[cfg(windows)] #[path = "xa24.rs"] pub mod xaudio2_4;
+#[cfg(windows)] #[path = "xa25.rs"] pub mod xaudio2_5;
+#[cfg(windows)] #[path = "xa26.rs"] pub mod xaudio2_6;
+#[cfg(windows)] #[path = "xa27/_xa27.rs"] pub mod xaudio2_7;
+#[cfg(windows)] #[path = "xa28/_xa28.rs"] pub mod xaudio2_8;
+#[cfg(windows)] #[path = "xa29/_xa29.rs"] pub mod xaudio2_9;
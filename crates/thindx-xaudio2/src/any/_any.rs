@@ -0,0 +1,91 @@
+//! ✔️ Version-agnostic facade over [xaudio2_7](crate::xaudio2_7) .. [xaudio2_9](crate::xaudio2_9), picked at runtime
+//!
+//! Use [create_best_available] to load whichever XAudio2 is present, then drive it through the [XAudio2], [Voice], and [SourceVoice] traits.
+//! Only the API common to every supported version is exposed here:
+//! for callbacks, sends, effect chains, device selection, etc. reach for the version specific types via
+//! [XAudio2::as_xaudio2_8] / [XAudio2::as_xaudio2_7].
+//!
+//! ### Example
+//! ```
+//! use thindx_xaudio2::any;
+//!
+//! mcom::init::mta().unwrap();
+//! let best = unsafe { any::create_best_available(any::DEFAULT_ORDER) }.unwrap();
+//! for skipped in best.failures.iter() { eprintln!("skipped {}: {:?}", skipped.version, skipped.error) }
+//! println!("loaded {}", best.version);
+//!
+//! let xaudio2 = best.xaudio2;
+//! let _master = xaudio2.create_mastering_voice(2, 48000, 0).unwrap();
+//! ```
+
+use core::fmt::{self, Display, Formatter};
+
+mod create;                         pub use create::*;
+mod ixaudio2;                       pub use ixaudio2::*;
+mod voices;                         pub use voices::*;
+
+#[doc(no_inline)] pub use crate::portable::SourceFormat;
+#[doc(no_inline)] pub use crate::xaudio2_8::xaudio2::{
+    FilterParameters,
+    PerformanceData,
+    VoiceState,
+};
+#[doc(no_inline)] pub use crate::xaudio2_8::xaudio2::sys::XAUDIO2_BUFFER as Buffer;
+
+/// XAudio 2.7's voice details are the common subset of every version (XAudio 2.8 added `ActiveFlags`.)
+#[doc(no_inline)] pub use crate::xaudio2_7::xaudio2::VoiceDetails;
+
+
+
+/// An XAudio2 version (or distribution) that [create_best_available] can try to load.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive] pub enum Version {
+    /// XAudio 2.9 via [`xaudio2_9redist.dll`](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-redistributable) (NuGet redist, Windows 7 SP1+), shipped alongside your executable.
+    V2_9Redist,
+    /// XAudio 2.9 via `xaudio2_9.dll` (Windows 10+)
+    V2_9,
+    /// XAudio 2.8 via `xaudio2_8.dll` (Windows 8+)
+    V2_8,
+    /// XAudio 2.7 via the registered COM classes of the June 2010 DirectX redist
+    V2_7,
+}
+
+impl Version {
+    /// Every version, newest first.
+    pub const ALL : &'static [Version] = &[Version::V2_9Redist, Version::V2_9, Version::V2_8, Version::V2_7];
+
+    /// The DLL this version is loaded from.
+    pub fn dll(self) -> &'static str {
+        match self {
+            Version::V2_9Redist => REDIST_DLL,
+            Version::V2_9       => crate::xaudio2_9::xaudio2::DLL,
+            Version::V2_8       => crate::xaudio2_8::xaudio2::DLL,
+            Version::V2_7       => crate::xaudio2_7::xaudio2::DLL,
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            Version::V2_9Redist => write!(fmt, "XAudio 2.9 redist ({})", self.dll()),
+            Version::V2_9       => write!(fmt, "XAudio 2.9 ({})", self.dll()),
+            Version::V2_8       => write!(fmt, "XAudio 2.8 ({})", self.dll()),
+            Version::V2_7       => write!(fmt, "XAudio 2.7 ({})", self.dll()),
+        }
+    }
+}
+
+/// `"xaudio2_9redist.dll"` - the DLL found in the [Microsoft.XAudio2.Redist](https://www.nuget.org/packages/Microsoft.XAudio2.Redist/) NuGet package.
+pub const REDIST_DLL : &'static str = "xaudio2_9redist.dll";
+
+/// Prefer the OS's own XAudio 2.9, then a bundled redist, then older OS / DirectX SDK versions.
+pub const DEFAULT_ORDER : &'static [Version] = &[Version::V2_9, Version::V2_9Redist, Version::V2_8, Version::V2_7];
+
+
+
+#[test] fn version_display() {
+    assert_eq!(alloc::format!("{}", Version::V2_9Redist), "XAudio 2.9 redist (xaudio2_9redist.dll)");
+    assert_eq!(alloc::format!("{}", Version::V2_8), "XAudio 2.8 (xaudio2_8.dll)");
+    for v in DEFAULT_ORDER { assert!(Version::ALL.contains(v)) }
+}
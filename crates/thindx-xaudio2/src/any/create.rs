@@ -0,0 +1,116 @@
+use super::*;
+use crate::{xaudio2_7, xaudio2_8, xaudio2_9};
+
+use winresult::*;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use core::fmt::{self, Debug, Display, Formatter};
+
+
+
+/// Why a [Version] couldn't be used by [create_best_available].
+#[derive(Clone, Copy, Debug)] pub struct Failure {
+    pub version:    Version,
+    pub error:      HResultError,
+}
+
+/// The result of a successful [create_best_available].
+pub struct BestAvailable {
+    /// The created engine.
+    pub xaudio2:    Box<dyn XAudio2>,
+    /// Which [Version] `xaudio2` is (same as `xaudio2.version()`.)
+    pub version:    Version,
+    /// The versions tried before `version`, and why each of them failed.
+    pub failures:   Vec<Failure>,
+}
+
+impl Debug for BestAvailable {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("BestAvailable").field("version", &self.version).field("failures", &self.failures).finish_non_exhaustive()
+    }
+}
+
+/// Every [Version] passed to [create_best_available] failed.
+#[derive(Clone, Debug)] pub struct CreateError {
+    /// Every version tried, in order, and why each of them failed.  Empty if no versions were requested.
+    pub failures:   Vec<Failure>,
+}
+
+impl Display for CreateError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        if self.failures.is_empty() { return write!(fmt, "unable to create XAudio2: no versions were requested") }
+        write!(fmt, "unable to create XAudio2:")?;
+        for failure in self.failures.iter() { write!(fmt, "\n    {}: {:?}", failure.version, failure.error)?; }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CreateError {}
+
+/// Returns the last failure's error, or [E::INVALIDARG] if no versions were requested.
+impl From<CreateError> for HResultError { fn from(err: CreateError) -> Self { err.failures.last().map_or(E::INVALIDARG, |f| f.error) } }
+
+
+
+/// Try to create each [Version] in `order`, returning the first that succeeds (along with why any earlier versions failed.)
+///
+/// Initialize COM (see e.g. [mcom::init::mta]) before calling this function:
+/// XAudio 2.7 is created via `CoCreateInstance` and will fail outright without it.
+///
+/// ### Safety
+/// See [xaudio2_9::xaudio2::create] - the same caveats apply to every version.
+///
+/// ### Arguments
+/// *   `order` - The versions to try, in order of preference (e.g. [DEFAULT_ORDER].)
+///
+/// ### Example
+/// ```
+/// use thindx_xaudio2::any::{self, Version};
+///
+/// mcom::init::mta().unwrap();
+/// let best = unsafe { any::create_best_available(&[Version::V2_9Redist, Version::V2_9, Version::V2_8]) };
+/// match best {
+///     Ok(best) => println!("loaded {} (after {} failures)", best.version, best.failures.len()),
+///     Err(err) => println!("{err}"),
+/// }
+/// ```
+///
+/// ### Errors
+/// *   [CreateError]   - if every version in `order` failed (see [CreateError::failures] for per-version details, e.g. [ERROR::MOD_NOT_FOUND])
+pub unsafe fn create_best_available(order: &[Version]) -> Result<BestAvailable, CreateError> {
+    let mut failures = Vec::new();
+    for &version in order {
+        match unsafe { create(version) } {
+            Ok(xaudio2) => return Ok(BestAvailable { xaudio2, version, failures }),
+            Err(error)  => failures.push(Failure { version, error }),
+        }
+    }
+    Err(CreateError { failures })
+}
+
+/// Create a specific [Version] of XAudio2 with default flags and processor.
+///
+/// ### Safety
+/// See [xaudio2_9::xaudio2::create] - the same caveats apply to every version.
+///
+/// ### Errors
+/// *   [HResultError::from_win32]\([ERROR::MOD_NOT_FOUND])     - if the version's DLL could not be found
+/// *   [HResultError::from_win32]\([ERROR::PROC_NOT_FOUND])    - if the version's DLL didn't export `XAudio2Create`
+/// *   `REGDB_E_CLASSNOTREG`                                   - if XAudio 2.7 isn't installed (no DirectX redist)
+/// *   ...
+///
+/// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
+pub unsafe fn create(version: Version) -> Result<Box<dyn XAudio2>, HResultError> {
+    let xaudio2 : Box<dyn XAudio2> = match version {
+        Version::V2_9Redist => {
+            let exports = xaudio2_9::xaudio2::Exports::from_path(REDIST_DLL).map_err(|err| xaudio2_8::io_error_to_hresult(&err))?;
+            Box::new(Modern { version, xaudio2: unsafe { xaudio2_9::xaudio2::create_with_exports(&exports, 0, None, xaudio2_9::xaudio2::NTDDI_VERSION) }? })
+        },
+        Version::V2_9 => Box::new(Modern { version, xaudio2: unsafe { xaudio2_9::xaudio2::create(None, None) }? }),
+        Version::V2_8 => Box::new(Modern { version, xaudio2: unsafe { xaudio2_8::xaudio2::create(None, None) }? }),
+        Version::V2_7 => Box::new(Legacy(unsafe { xaudio2_7::xaudio2::create(0, None) }?)),
+    };
+    Ok(xaudio2)
+}
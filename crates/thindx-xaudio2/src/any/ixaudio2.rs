@@ -0,0 +1,111 @@
+use super::*;
+use crate::{xaudio2_7, xaudio2_8};
+
+use winresult::*;
+
+use alloc::boxed::Box;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2)\]
+/// IXAudio2
+/// &mdash; The subset of the XAudio2 engine API common to XAudio 2.7 .. 2.9.
+///
+/// Created by [create_best_available] or [create].
+pub trait XAudio2 {
+    /// Which [Version] of XAudio2 this is.
+    fn version(&self) -> Version;
+
+    /// The underlying XAudio 2.8+ engine (also used for XAudio 2.9), if this is XAudio 2.8+.
+    fn as_xaudio2_8(&self) -> Option<&xaudio2_8::XAudio2> { None }
+
+    /// The underlying XAudio 2.7 engine, if this is XAudio 2.7.
+    fn as_xaudio2_7(&self) -> Option<&xaudio2_7::xaudio2::XAudio2> { None }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// Creates and configures a source voice without callbacks, sending to the mastering voice.
+    fn create_source_voice(&self, format: &SourceFormat, flags: u32, max_frequency_ratio: f32) -> Result<Box<dyn SourceVoice + '_>, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsubmixvoice)\]
+    /// Creates and configures a submix voice, sending to the mastering voice.
+    fn create_submix_voice(&self, input_channels: u32, input_sample_rate: u32, flags: u32, processing_stage: u32) -> Result<Box<dyn Voice + '_>, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createmasteringvoice)\]
+    /// Creates and configures a mastering voice on the default audio device.
+    fn create_mastering_voice(&self, input_channels: u32, input_sample_rate: u32, flags: u32) -> Result<Box<dyn Voice + '_>, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-startengine)\]
+    /// Creates and starts the audio processing thread.
+    fn start_engine(&self) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-stopengine)\]
+    /// Stops and destroys the audio processing thread.
+    fn stop_engine(&self);
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-commitchanges)\]
+    /// Atomically applies a set of operations previously tagged with a given identifier.
+    fn commit_changes(&self, operation_set: u32) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-getperformancedata)\]
+    /// Returns current resource usage details: memory, CPU, etc.
+    fn get_performance_data(&self) -> PerformanceData;
+}
+
+
+
+/// XAudio 2.8 or 2.9 (they share an ABI, and thus [xaudio2_8::XAudio2].)
+pub(crate) struct Modern {
+    pub version:    Version,
+    pub xaudio2:    xaudio2_8::XAudio2,
+}
+
+impl XAudio2 for Modern {
+    fn version(&self) -> Version { self.version }
+    fn as_xaudio2_8(&self) -> Option<&xaudio2_8::XAudio2> { Some(&self.xaudio2) }
+
+    fn create_source_voice(&self, format: &SourceFormat, flags: u32, max_frequency_ratio: f32) -> Result<Box<dyn SourceVoice + '_>, HResultError> {
+        // SAFETY: ✔️ no callback, so there are no demands on pContext
+        Ok(Box::new(unsafe { self.xaudio2.create_source_voice_unchecked(format, flags, max_frequency_ratio, None, None, None) }?))
+    }
+
+    fn create_submix_voice(&self, input_channels: u32, input_sample_rate: u32, flags: u32, processing_stage: u32) -> Result<Box<dyn Voice + '_>, HResultError> {
+        Ok(Box::new(self.xaudio2.create_submix_voice(input_channels, input_sample_rate, flags, processing_stage, None, None)?))
+    }
+
+    fn create_mastering_voice(&self, input_channels: u32, input_sample_rate: u32, flags: u32) -> Result<Box<dyn Voice + '_>, HResultError> {
+        Ok(Box::new(self.xaudio2.create_mastering_voice(input_channels, input_sample_rate, flags, (), None, xaudio2_8::xaudio2::DEFAULT_AUDIO_CATEGORY)?))
+    }
+
+    fn start_engine(&self) -> Result<HResultSuccess, HResultError> { self.xaudio2.start_engine() }
+    fn stop_engine(&self) { self.xaudio2.stop_engine() }
+    fn commit_changes(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> { self.xaudio2.commit_changes(operation_set) }
+    fn get_performance_data(&self) -> PerformanceData { self.xaudio2.get_performance_data() }
+}
+
+
+
+/// XAudio 2.7
+pub(crate) struct Legacy(pub xaudio2_7::xaudio2::XAudio2);
+
+impl XAudio2 for Legacy {
+    fn version(&self) -> Version { Version::V2_7 }
+    fn as_xaudio2_7(&self) -> Option<&xaudio2_7::xaudio2::XAudio2> { Some(&self.0) }
+
+    fn create_source_voice(&self, format: &SourceFormat, flags: u32, max_frequency_ratio: f32) -> Result<Box<dyn SourceVoice + '_>, HResultError> {
+        // SAFETY: ✔️ no callback, so there are no demands on pContext
+        Ok(Box::new(unsafe { self.0.create_source_voice_unchecked(format, flags, max_frequency_ratio, None, None, None) }?))
+    }
+
+    fn create_submix_voice(&self, input_channels: u32, input_sample_rate: u32, flags: u32, processing_stage: u32) -> Result<Box<dyn Voice + '_>, HResultError> {
+        Ok(Box::new(self.0.create_submix_voice(input_channels, input_sample_rate, flags, processing_stage, None, None)?))
+    }
+
+    fn create_mastering_voice(&self, input_channels: u32, input_sample_rate: u32, flags: u32) -> Result<Box<dyn Voice + '_>, HResultError> {
+        Ok(Box::new(self.0.create_mastering_voice(input_channels, input_sample_rate, flags, 0, None)?))
+    }
+
+    fn start_engine(&self) -> Result<HResultSuccess, HResultError> { self.0.start_engine() }
+    fn stop_engine(&self) { self.0.stop_engine() }
+    fn commit_changes(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> { self.0.commit_changes(operation_set) }
+    fn get_performance_data(&self) -> PerformanceData { self.0.get_performance_data() }
+}
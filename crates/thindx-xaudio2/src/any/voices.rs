@@ -0,0 +1,168 @@
+use super::*;
+use crate::{xaudio2_7, xaudio2_8};
+
+use winresult::*;
+
+use core::ptr::null;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2voice)\]
+/// IXAudio2Voice
+/// &mdash; The subset of the voice API common to XAudio 2.7 .. 2.9.
+///
+/// The voice is destroyed when dropped.
+pub trait Voice {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getvoicedetails)\]
+    /// Returns information about the creation flags, input channels, and sample rate of this voice.
+    fn get_voice_details(&self) -> VoiceDetails;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-enableeffect)\]
+    /// Enables the effect at a given position in this voice's effect chain.
+    fn enable_effect(&self, effect_index: u32, operation_set: u32) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-disableeffect)\]
+    /// Disables the effect at a given position in this voice's effect chain.
+    fn disable_effect(&self, effect_index: u32, operation_set: u32) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-geteffectstate)\]
+    /// Returns the running state of the effect at a specified position in this voice's effect chain.
+    fn get_effect_state(&self, effect_index: u32) -> bool;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setfilterparameters)\]
+    /// Sets this voice's filter parameters (requires [VOICE_USEFILTER](crate::xaudio2_8::xaudio2::VOICE_USEFILTER).)
+    fn set_filter_parameters(&self, parameters: &FilterParameters, operation_set: u32) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getfilterparameters)\]
+    /// Gets this voice's filter parameters.
+    fn get_filter_parameters(&self) -> FilterParameters;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setvolume)\]
+    /// Sets the overall volume level for this voice.
+    fn set_volume(&self, volume: f32, operation_set: u32) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getvolume)\]
+    /// Gets this voice's current overall volume level.
+    fn get_volume(&self) -> f32;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setchannelvolumes)\]
+    /// Sets the volume levels for this voice's individual channels.
+    fn set_channel_volumes(&self, volumes: &[f32], operation_set: u32) -> Result<HResultSuccess, HResultError>;
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2sourcevoice)\]
+/// IXAudio2SourceVoice
+/// &mdash; The subset of the source voice API common to XAudio 2.7 .. 2.9.
+pub trait SourceVoice : Voice {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-start)\]
+    /// Starts consumption and processing of audio by the voice.
+    fn start(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-stop)\]
+    /// Stops consumption of audio by the current voice.
+    fn stop(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-submitsourcebuffer)\]
+    /// Adds a new audio buffer to this voice's input queue.
+    ///
+    /// ### Safety
+    /// *   `buffer.pAudioData` must remain valid until XAudio2 is done with it
+    ///     (e.g. until [flush_source_buffers](Self::flush_source_buffers) + [stop](Self::stop), or the voice is destroyed.)
+    unsafe fn submit_source_buffer_unchecked(&self, buffer: &Buffer) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-flushsourcebuffers)\]
+    /// Removes all pending audio buffers from this voice's queue.
+    fn flush_source_buffers(&self) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-discontinuity)\]
+    /// Notifies the voice of an intentional break in the stream of audio buffers.
+    fn discontinuity(&self) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-exitloop)\]
+    /// Breaks out of the current loop when its end is reached.
+    fn exit_loop(&self, operation_set: u32) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getstate)\]
+    /// Returns the number of buffers queued, the pContext of the current buffer, and the samples played.
+    fn get_state(&self) -> VoiceState;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setfrequencyratio)\]
+    /// Sets this voice's frequency adjustment, i.e. its pitch.
+    fn set_frequency_ratio(&self, ratio: f32, operation_set: u32) -> Result<HResultSuccess, HResultError>;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getfrequencyratio)\]
+    /// Returns this voice's current frequency adjustment ratio.
+    fn get_frequency_ratio(&self) -> f32;
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setsourcesamplerate)\]
+    /// Reconfigures this voice to treat its source data as being at a different sample rate.
+    fn set_source_sample_rate(&self, new_source_sample_rate: u32) -> Result<HResultSuccess, HResultError>;
+}
+
+
+
+macro_rules! voices {
+    ( $base:ident : $($voice:ident),+ $(,)? ) => {$(
+        impl Voice for $voice<'_> {
+            fn get_voice_details(&self) -> VoiceDetails { details($base::get_voice_details(self)) }
+            fn enable_effect(&self, effect_index: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> { $base::enable_effect(self, effect_index, operation_set) }
+            fn disable_effect(&self, effect_index: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> { $base::disable_effect(self, effect_index, operation_set) }
+            fn get_effect_state(&self, effect_index: u32) -> bool { $base::get_effect_state(self, effect_index) }
+            fn set_filter_parameters(&self, parameters: &FilterParameters, operation_set: u32) -> Result<HResultSuccess, HResultError> { $base::set_filter_parameters(self, parameters, operation_set) }
+            fn get_filter_parameters(&self) -> FilterParameters { $base::get_filter_parameters(self) }
+            fn set_volume(&self, volume: f32, operation_set: u32) -> Result<HResultSuccess, HResultError> { $base::set_volume(self, volume, operation_set) }
+            fn get_volume(&self) -> f32 { $base::get_volume(self) }
+            fn set_channel_volumes(&self, volumes: &[f32], operation_set: u32) -> Result<HResultSuccess, HResultError> { $base::set_channel_volumes(self, volumes, operation_set) }
+        }
+    )+};
+}
+
+mod xa27 {
+    use super::*;
+    use xaudio2_7::xaudio2::{Voice as Base, MasteringVoice, SubmixVoice, SourceVoice as Source};
+
+    fn details(details: VoiceDetails) -> VoiceDetails { details }
+
+    voices!(Base: MasteringVoice, SubmixVoice, Source);
+
+    impl SourceVoice for Source<'_> {
+        fn start(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> { Source::start(self, flags, operation_set) }
+        fn stop(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> { Source::stop(self, flags, operation_set) }
+        unsafe fn submit_source_buffer_unchecked(&self, buffer: &Buffer) -> Result<HResultSuccess, HResultError> { unsafe { Source::submit_source_buffer_unchecked(self, buffer, None) } }
+        fn flush_source_buffers(&self) -> Result<HResultSuccess, HResultError> { Source::flush_source_buffers(self) }
+        fn discontinuity(&self) -> Result<HResultSuccess, HResultError> { Source::discontinuity(self) }
+        fn exit_loop(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> { Source::exit_loop(self, operation_set) }
+        fn get_state(&self) -> VoiceState { Source::get_state(self) }
+        fn set_frequency_ratio(&self, ratio: f32, operation_set: u32) -> Result<HResultSuccess, HResultError> { Source::set_frequency_ratio(self, ratio, operation_set) }
+        fn get_frequency_ratio(&self) -> f32 { Source::get_frequency_ratio(self) }
+        fn set_source_sample_rate(&self, new_source_sample_rate: u32) -> Result<HResultSuccess, HResultError> { Source::set_source_sample_rate(self, new_source_sample_rate) }
+    }
+}
+
+mod xa28 {
+    use super::*;
+    use xaudio2_8::xaudio2::{Voice as Base, MasteringVoice, SubmixVoice, SourceVoiceUntyped as Source};
+
+    fn details(details: xaudio2_8::xaudio2::VoiceDetails) -> VoiceDetails {
+        VoiceDetails {
+            CreationFlags:      details.CreationFlags,
+            InputChannels:      details.InputChannels,
+            InputSampleRate:    details.InputSampleRate,
+        }
+    }
+
+    voices!(Base: MasteringVoice, SubmixVoice, Source);
+
+    impl SourceVoice for Source<'_> {
+        fn start(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> { Source::start(self, flags, operation_set) }
+        fn stop(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> { Source::stop(self, flags, operation_set) }
+        unsafe fn submit_source_buffer_unchecked(&self, buffer: &Buffer) -> Result<HResultSuccess, HResultError> { unsafe { (*self.as_raw()).SubmitSourceBuffer(buffer, null()) }.succeeded() }
+        fn flush_source_buffers(&self) -> Result<HResultSuccess, HResultError> { Source::flush_source_buffers(self) }
+        fn discontinuity(&self) -> Result<HResultSuccess, HResultError> { Source::discontinuity(self) }
+        fn exit_loop(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> { Source::exit_loop(self, operation_set) }
+        fn get_state(&self) -> VoiceState { Source::get_state(self, 0) }
+        fn set_frequency_ratio(&self, ratio: f32, operation_set: u32) -> Result<HResultSuccess, HResultError> { Source::set_frequency_ratio(self, ratio, operation_set) }
+        fn get_frequency_ratio(&self) -> f32 { Source::get_frequency_ratio(self) }
+        fn set_source_sample_rate(&self, new_source_sample_rate: u32) -> Result<HResultSuccess, HResultError> { Source::set_source_sample_rate(self, new_source_sample_rate) }
+    }
+}
@@ -0,0 +1,418 @@
+//! 🧪 In-process fake XAudio2 engine for tests - no DLLs, devices, or threads required
+//!
+//! [`Engine`] implements [IXAudio2] (2.8/2.9 ABI) and its voices in Rust, through the same
+//! [`FromVtable`](thindx_xaudio2_sys::FromVtable) machinery used for callbacks.
+//! Nothing happens in the background: audio is only processed when you call [`Engine::step`], which runs a single
+//! [`QUANTUM_MS`](crate::xaudio2_8::xaudio2::QUANTUM_MS) processing pass and fires engine + voice callbacks on the calling thread, in a deterministic order.
+//!
+//! ### What's modeled
+//! *   Voice graph validation (sends, processing stages, output matrix / channel counts, destroy order)
+//! *   Source buffer queues: [`MAX_QUEUED_BUFFERS`](crate::xaudio2_8::xaudio2::MAX_QUEUED_BUFFERS), play/loop regions, [`END_OF_STREAM`](crate::xaudio2_8::xaudio2::END_OF_STREAM), flushing, `SamplesPlayed`
+//! *   Deferred operation sets via `CommitChanges`
+//! *   Audio output, via [`portable::render`](crate::portable::render): read it back with [`Engine::take_output`]
+//! *   Every call is recorded, and arbitrary calls can be made to fail via [`Engine::fail_next`]
+//!
+//! ### What isn't
+//! *   Effects beyond reference counting and parameter bookkeeping
+//!
+//! ### Example
+//! ```
+//! # use thindx_xaudio2::mock;
+//! # use thindx_xaudio2::xaudio2_9::*;
+//! let engine  = mock::Engine::new();
+//! let xaudio2 = engine.xaudio2();
+//! let _master = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+//! engine.step();
+//! assert_eq!(engine.passes(), 1);
+//! ```
+
+use crate::xaudio2_8::XAudio2;
+use crate::xaudio2_8::xaudio2::sys::*;
+
+use winresult::*;
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+mod ixaudio2;
+mod ixaudio2voice;
+mod state;
+
+use ixaudio2::Inner;
+use crate::portable::render::{BufferEvent, VoiceHandle};
+
+
+
+/// A fake [IXAudio2] engine.  Cheap to [Clone] (all clones share the same engine.)
+#[derive(Clone)] pub struct Engine(Arc<Inner>);
+
+/// Identifies a voice created by an [`Engine`].  Ids are never reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)] pub struct VoiceId(u32);
+
+/// The flavor of a mock voice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)] pub enum VoiceKind { Source, Submix, Mastering }
+
+/// A recorded call into the mock engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)] pub struct Call {
+    /// The voice the method was called on, or [None] for [IXAudio2] methods.
+    pub voice:  Option<VoiceId>,
+
+    /// The COM method name, e.g. `"SubmitSourceBuffer"`.
+    pub method: &'static str,
+}
+
+/// A snapshot of a mock voice's state, as it currently affects processing (uncommitted operation sets are excluded.)
+#[derive(Clone, Debug)] #[non_exhaustive] pub struct VoiceInfo {
+    pub kind:               VoiceKind,
+    pub input_channels:     u32,
+    pub input_sample_rate:  u32,
+    pub sends:              Vec<VoiceId>,
+    pub volume:             f32,
+    pub frequency_ratio:    f32,
+    /// Source voices only: has [`start`](crate::xaudio2_8::SourceVoiceUntyped::start) taken effect?
+    pub started:            bool,
+    /// Source voices only: buffers submitted but not yet finished.
+    pub buffers_queued:     usize,
+    /// Source voices only: is the last queued buffer flagged [`END_OF_STREAM`](crate::xaudio2_8::xaudio2::END_OF_STREAM)?
+    pub end_of_stream:      bool,
+    /// Source voices only: frames consumed so far.
+    pub samples_played:     u64,
+}
+
+impl Default for Engine { fn default() -> Self { Self::new() } }
+
+impl Engine {
+    /// Create a new mock engine.  Like real XAudio2, the engine starts out started.
+    pub fn new() -> Self { Self(Inner::new()) }
+
+    /// Get a new [XAudio2] reference to this engine.
+    pub fn xaudio2(&self) -> XAudio2 { unsafe { XAudio2::from_raw(Inner::into_raw(self.0.clone())) } }
+
+    /// Run a single processing pass, if the engine is started:
+    ///
+    /// 1.  `OnProcessingPassStart` for every registered engine callback
+    /// 2.  For each started source voice, in creation order:
+    ///     `OnVoiceProcessingPassStart`, then `OnBufferStart` / `OnLoopEnd` / `OnBufferEnd` / `OnStreamEnd` as buffers are consumed, then `OnVoiceProcessingPassEnd`
+    /// 3.  Submix voices, then the mastering voice, are mixed - appending the pass's output to [`Engine::take_output`]
+    /// 4.  `OnProcessingPassEnd` for every registered engine callback
+    ///
+    /// Buffers removed by `FlushSourceBuffers` receive their `OnBufferEnd` during the next pass, even if the voice is stopped.
+    /// No lock is held while callbacks run, so callbacks may freely submit more buffers.
+    pub fn step(&self) {
+        let (engine_callbacks, voices) = {
+            let mut state = self.0.lock();
+            if !state.engine_started { return }
+            state.passes += 1;
+            state.renderer.begin_pass();
+            let voices = state.voices.iter().filter(|(_, v)| v.kind == VoiceKind::Source).map(|(id, v)| (*id, v.handle, v.callback, state.renderer.is_started(v.handle))).collect::<Vec<_>>();
+            (state.engine_callbacks.clone(), voices)
+        };
+
+        for cb in engine_callbacks.iter() { unsafe { (**cb).OnProcessingPassStart() } }
+        for (voice, handle, callback, started) in voices { self.step_voice(voice, handle, unsafe { callback.as_ref() }, started) }
+        {
+            let mut state = self.0.lock();
+            let output = state.renderer.end_pass();
+            state.output.extend(output);
+        }
+        for cb in engine_callbacks.iter() { unsafe { (**cb).OnProcessingPassEnd() } }
+    }
+
+    fn step_voice(&self, voice: VoiceId, handle: VoiceHandle, callback: Option<&IXAudio2VoiceCallback>, started: bool) {
+        let bytes_required = {
+            let state = self.0.lock();
+            if !state.voices.contains_key(&voice) { return } // destroyed by an earlier callback
+            state.renderer.bytes_required(handle)
+        };
+
+        if let (Some(cb), true) = (callback, started) { unsafe { cb.OnVoiceProcessingPassStart(bytes_required) } }
+
+        let events = self.0.lock().renderer.process_voice(handle);
+
+        if let Some(cb) = callback {
+            for event in events {
+                match event {
+                    BufferEvent::BufferStart(context)   => unsafe { cb.OnBufferStart(context) },
+                    BufferEvent::BufferEnd(context)     => unsafe { cb.OnBufferEnd(context) },
+                    BufferEvent::LoopEnd(context)       => unsafe { cb.OnLoopEnd(context) },
+                    BufferEvent::StreamEnd              => unsafe { cb.OnStreamEnd() },
+                }
+            }
+            if started { unsafe { cb.OnVoiceProcessingPassEnd() } }
+        }
+    }
+
+    /// Take the mastering voice's interleaved output from every [step](Self::step) since the last call.
+    ///
+    /// Each pass appends `input_channels * input_sample_rate / 100` samples (rates that aren't a multiple of 100 alternate between frame counts.)
+    pub fn take_output(&self) -> Vec<f32> { core::mem::take(&mut self.0.lock().output) }
+
+    /// Number of processing passes run by [Engine::step] so far.
+    pub fn passes(&self) -> u64 { self.0.lock().passes }
+
+    /// Is the engine currently started?  (See `StartEngine` / `StopEngine`.)
+    pub fn is_started(&self) -> bool { self.0.lock().engine_started }
+
+    /// All calls made into the engine or its voices so far, in order.
+    pub fn calls(&self) -> Vec<Call> { self.0.lock().calls.clone() }
+
+    /// Forget all previously recorded calls.
+    pub fn clear_calls(&self) { self.0.lock().calls.clear() }
+
+    /// Misuse detected by the engine that real XAudio2 would only report through its debug runtime, if at all.
+    /// (e.g. destroying a voice that's still the destination of another voice, or destroying a voice with buffers still queued.)
+    pub fn warnings(&self) -> Vec<String> { self.0.lock().warnings.clone() }
+
+    /// Make the next call to `method` (e.g. `"CreateSourceVoice"`) fail with `error` instead of doing anything.
+    ///
+    /// Only methods that return an `HRESULT` can fail.
+    pub fn fail_next(&self, method: &'static str, error: impl Into<HResult>) { self.0.lock().failures.push((method, error.into())) }
+
+    /// Fire `OnCriticalError` on every registered engine callback.
+    pub fn critical_error(&self, error: impl Into<HResult>) {
+        let error = error.into();
+        let engine_callbacks = self.0.lock().engine_callbacks.clone();
+        for cb in engine_callbacks.iter() { unsafe { (**cb).OnCriticalError(error) } }
+    }
+
+    /// Fire `OnVoiceError` on `voice`'s callback, reporting the buffer currently being processed.
+    pub fn voice_error(&self, voice: VoiceId, error: impl Into<HResult>) {
+        let (callback, context) = {
+            let state = self.0.lock();
+            match state.voices.get(&voice) { Some(v) => (v.callback, state.renderer.current_context(v.handle).unwrap_or(core::ptr::null_mut())), None => return }
+        };
+        if let Some(cb) = unsafe { callback.as_ref() } { unsafe { cb.OnVoiceError(context, error.into()) } }
+    }
+
+    /// Identify a voice created by this engine from its raw interface pointer (e.g. [`SourceVoiceUntyped::as_raw`](crate::xaudio2_8::SourceVoiceUntyped::as_raw)).
+    pub fn voice_id<I>(&self, voice: *const I) -> Option<VoiceId> { self.0.lock().voice_at(voice.cast()) }
+
+    /// All voices that haven't been destroyed yet, in creation order.
+    pub fn voices(&self) -> Vec<VoiceId> { self.0.lock().voices.keys().copied().collect() }
+
+    /// Inspect a voice, or [None] if it has been destroyed.
+    pub fn voice(&self, voice: VoiceId) -> Option<VoiceInfo> {
+        let state = self.0.lock();
+        let v = state.voices.get(&voice)?;
+        Some(VoiceInfo {
+            kind:               v.kind,
+            input_channels:     v.input_channels,
+            input_sample_rate:  v.input_sample_rate,
+            sends:              v.sends.iter().map(|s| s.1).collect(),
+            volume:             state.renderer.get_volume(v.handle).unwrap_or(1.0),
+            frequency_ratio:    state.renderer.get_frequency_ratio(v.handle).unwrap_or(1.0),
+            started:            state.renderer.is_started(v.handle),
+            buffers_queued:     state.renderer.buffers_queued(v.handle),
+            end_of_stream:      state.renderer.end_of_stream_queued(v.handle),
+            samples_played:     if v.flags & XAUDIO2_VOICE_NOSAMPLESPLAYED == 0 { state.renderer.samples_played(v.handle) } else { 0 },
+        })
+    }
+}
+
+
+
+#[cfg(test)] #[derive(Default)] struct Log(std::sync::Mutex<Vec<String>>);
+#[cfg(test)] impl Log {
+    fn push(&self, s: String) { self.0.lock().unwrap().push(s) }
+    fn take(&self) -> Vec<String> { core::mem::take(&mut *self.0.lock().unwrap()) }
+}
+
+#[cfg(test)] struct VC(Arc<Log>);
+#[cfg(test)] impl crate::xaudio2_9::xaudio2::VoiceCallback for VC {
+    type BufferContext = (u32, Arc<()>);
+    fn on_voice_processing_pass_start(&self, bytes_required: u32) { self.0.push(alloc::format!("pass start {bytes_required}")) }
+    fn on_voice_processing_pass_end(&self) { self.0.push("pass end".into()) }
+    fn on_stream_end(&self) { self.0.push("stream end".into()) }
+    fn on_buffer_start(&self, c: &Self::BufferContext) { self.0.push(alloc::format!("buffer start {}", c.0)) }
+    fn on_buffer_end(&self, c: Self::BufferContext) { self.0.push(alloc::format!("buffer end {}", c.0)) }
+    fn on_loop_end(&self, c: &Self::BufferContext) { self.0.push(alloc::format!("loop end {}", c.0)) }
+    fn on_voice_error(&self, c: &Self::BufferContext, error: HResult) { self.0.push(alloc::format!("voice error {} {error:?}", c.0)) }
+}
+
+#[test] fn queue_and_callbacks() {
+    use crate::xaudio2_9::*;
+
+    let engine  = Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let log     = Arc::new(Log::default());
+    let vc      = xaudio2::VoiceCallbackWrapper::new(VC(log.clone()));
+    let keep    = Arc::new(());
+
+    let _master = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+    let voice   = xaudio2.create_source_voice_typed_callback(&xaudio2::TypedSourceFormat::<[i16; 2]>::pcm(48000), 0, 1.0, &vc, None, None).unwrap();
+    let id      = engine.voice_id(voice.as_raw()).unwrap();
+
+    voice.submit_source_buffer(0, alloc::vec![[0i16; 2]; 720], .., None, None, (1, keep.clone())).unwrap();
+    voice.submit_source_buffer(xaudio2::END_OF_STREAM, alloc::vec![[0i16; 2]; 240], .., None, None, (2, keep.clone())).unwrap();
+    assert_eq!(engine.voice(id).unwrap().buffers_queued, 2);
+
+    engine.step(); // not started: nothing consumed
+    assert!(log.take().is_empty());
+
+    voice.start(0, xaudio2::COMMIT_NOW).unwrap();
+    engine.step(); // 480 frames per 10ms pass @ 48 kHz
+    assert_eq!(log.take(), ["pass start 0", "buffer start 1", "pass end"]);
+    engine.step();
+    assert_eq!(log.take(), ["pass start 0", "buffer end 1", "buffer start 2", "buffer end 2", "stream end", "pass end"]);
+    engine.step();
+    assert_eq!(log.take(), [alloc::format!("pass start {}", 480 * 4), "pass end".into()]);
+
+    let info = engine.voice(id).unwrap();
+    assert_eq!(info.buffers_queued, 0);
+    assert_eq!(info.samples_played, 960);
+    assert_eq!(Arc::strong_count(&keep), 1, "contexts should have been dropped by on_buffer_end");
+    assert!(engine.warnings().is_empty());
+}
+
+#[test] fn max_queued_buffers() {
+    use crate::xaudio2_9::*;
+
+    let engine  = Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let vc      = xaudio2::VoiceCallbackWrapper::new(VC(Default::default()));
+    let _master = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+    let voice   = xaudio2.create_source_voice_typed_callback(&xaudio2::TypedSourceFormat::<[f32; 1]>::pcm(44100), 0, 1.0, &vc, None, None).unwrap();
+    let keep    = Arc::new(());
+    for i in 0 .. xaudio2::MAX_QUEUED_BUFFERS {
+        voice.submit_source_buffer(0, alloc::vec![[0.0f32]; 16], .., None, None, (i, keep.clone())).unwrap();
+    }
+    let err = voice.submit_source_buffer(0, alloc::vec![[0.0f32]; 16], .., None, None, (99, keep.clone())).unwrap_err();
+    assert_eq!(xaudio2::HResult::from(err), xaudio2::E_INVALID_CALL);
+
+    voice.flush_source_buffers().unwrap();
+    engine.step(); // stopped, but flushed buffers still get OnBufferEnd
+    assert_eq!(Arc::strong_count(&keep), 1);
+}
+
+#[test] fn loops() {
+    use crate::xaudio2_9::*;
+
+    let engine  = Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let log     = Arc::new(Log::default());
+    let vc      = xaudio2::VoiceCallbackWrapper::new(VC(log.clone()));
+    let _master = xaudio2.create_mastering_voice(1, 1000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+    let voice   = xaudio2.create_source_voice_typed_callback(&xaudio2::TypedSourceFormat::<[u8; 1]>::pcm(1000), 0, 1.0, &vc, None, None).unwrap();
+    voice.submit_source_buffer(0, alloc::vec![[0u8]; 25], .., xaudio2::SampleRange::try_from(5_u32 .. 15).unwrap(), xaudio2::LoopCount::try_from(2_u8).unwrap(), (7, Arc::new(()))).unwrap();
+    voice.start(0, xaudio2::COMMIT_NOW).unwrap();
+
+    // 10 frames per pass @ 1 kHz: 0..5 + 5..15 (loop) + 5..15 (loop) + 5..25
+    for _ in 0 .. 5 { engine.step() }
+    let log = log.take().into_iter().filter(|l| !l.starts_with("pass")).collect::<Vec<_>>();
+    assert_eq!(log, ["buffer start 7", "loop end 7", "loop end 7", "buffer end 7"]);
+}
+
+#[test] fn operation_sets() {
+    use crate::xaudio2_9::*;
+
+    let engine  = Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let master  = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+    let id      = engine.voice_id(master.as_raw()).unwrap();
+
+    master.set_volume(0.5, 42).unwrap();
+    assert_eq!(master.get_volume(), 0.5, "getters report the most recently requested value");
+    assert_eq!(engine.voice(id).unwrap().volume, 1.0, "but it hasn't been applied yet");
+    xaudio2.commit_changes(1).unwrap();
+    assert_eq!(engine.voice(id).unwrap().volume, 1.0);
+    xaudio2.commit_changes(42).unwrap();
+    assert_eq!(engine.voice(id).unwrap().volume, 0.5);
+}
+
+#[test] fn graph() {
+    use crate::xaudio2_9::*;
+
+    let engine  = Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let vc      = xaudio2::VoiceCallbackWrapper::new(VC(Default::default()));
+    let format  = xaudio2::TypedSourceFormat::<[f32; 1]>::pcm(48000);
+
+    assert!(xaudio2.create_source_voice_typed_callback(&format, 0, 1.0, &vc, None, None).is_err(), "no mastering voice to send to");
+    let master  = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+    assert!(xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).is_err(), "only one mastering voice");
+
+    let submix  = xaudio2.create_submix_voice(1, 48000, 0, 0, None, None).unwrap();
+    let sends   = [xaudio2::SendDescriptor { flags: 0, output_voice: submix.as_ref() }];
+    let voice   = xaudio2.create_source_voice_typed_callback(&format, 0, 1.0, &vc, Some(&sends[..]), None).unwrap();
+    let (master_id, submix_id, voice_id) = (engine.voice_id(master.as_raw()).unwrap(), engine.voice_id(submix.as_raw()).unwrap(), engine.voice_id(voice.as_raw()).unwrap());
+    assert_eq!(engine.voice(voice_id).unwrap().sends, [submix_id]);
+    assert_eq!(engine.voice(submix_id).unwrap().sends, [master_id]);
+
+    let mut matrix = [9.0; 2];
+    submix.get_output_matrix(master.as_ref(), 1, 2, &mut matrix);
+    assert_eq!(matrix, [1.0, 1.0]);
+    assert!(submix.set_output_matrix(master.as_ref(), 2, 2, &[1.0; 4], 0).is_err(), "wrong source channel count");
+
+    engine.fail_next("SetVolume", E::OUTOFMEMORY);
+    assert!(voice.set_volume(0.5, 0).is_err());
+    assert!(voice.set_volume(0.5, 0).is_ok());
+
+    drop(submix); // still the destination of `voice`
+    assert_eq!(engine.warnings().len(), 1);
+    assert!(engine.voice(submix_id).is_some());
+    drop(voice);
+    drop(master); // still the destination of `submix`
+    assert_eq!(engine.warnings().len(), 2);
+    assert_eq!(engine.voices(), [master_id, submix_id]); // leaked, as real XAudio2 would
+}
+
+#[test] fn engine_callbacks() {
+    use crate::xaudio2_9::*;
+
+    struct EC(Arc<Log>);
+    impl xaudio2::EngineCallback for EC {
+        fn on_processing_pass_start(&self) { self.0.push("start".into()) }
+        fn on_processing_pass_end(&self) { self.0.push("end".into()) }
+        fn on_critical_error(&self, error: xaudio2::HResult) { self.0.push(alloc::format!("{error:?}")) }
+    }
+
+    let engine  = Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let log     = Arc::new(Log::default());
+    let ec      = xaudio2.register_for_callbacks_leak(EC(log.clone())).unwrap();
+    xaudio2.register_for_callbacks(ec).unwrap(); // duplicate registrations are ignored
+
+    engine.step();
+    assert_eq!(log.take(), ["start", "end"]);
+
+    xaudio2.stop_engine();
+    engine.step();
+    assert!(log.take().is_empty());
+
+    xaudio2.start_engine().unwrap();
+    xaudio2.unregister_for_callbacks(ec);
+    engine.step();
+    assert!(log.take().is_empty());
+    assert_eq!(engine.passes(), 2);
+}
+
+#[test] fn render() {
+    use crate::xaudio2_9::*;
+
+    let engine  = Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let vc      = xaudio2::VoiceCallbackWrapper::new(VC(Default::default()));
+    let master  = xaudio2.create_mastering_voice(2, 1000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+    let submix  = xaudio2.create_submix_voice(1, 1000, 0, 0, None, None).unwrap();
+    let sends   = [xaudio2::SendDescriptor { flags: 0, output_voice: submix.as_ref() }];
+    let voice   = xaudio2.create_source_voice_typed_callback(&xaudio2::TypedSourceFormat::<[i16; 1]>::pcm(1000), 0, 2.0, &vc, Some(&sends[..]), None).unwrap();
+
+    voice.submit_source_buffer(0, alloc::vec![[16384i16]; 30], .., None, None, (0, Arc::new(()))).unwrap();
+    voice.set_volume(0.5, 0).unwrap();
+    submix.set_output_matrix(master.as_ref(), 1, 2, &[1.0, 0.25], 0).unwrap();
+    voice.start(0, xaudio2::COMMIT_NOW).unwrap();
+
+    engine.step(); // 10 frames @ 1 kHz
+    assert_eq!(engine.take_output(), [0.25, 0.0625].repeat(10));
+
+    voice.set_frequency_ratio(2.0, xaudio2::COMMIT_NOW).unwrap();
+    voice.set_volume(1.0, 7).unwrap(); // deferred
+    engine.step(); // 20 source frames squeezed into 10 output frames
+    assert_eq!(engine.take_output(), [0.25, 0.0625].repeat(10));
+    assert_eq!(engine.voice(engine.voice_id(voice.as_raw()).unwrap()).unwrap().samples_played, 30);
+
+    xaudio2.commit_changes(7).unwrap();
+    engine.step(); // starved
+    assert_eq!(engine.take_output(), [0.0; 20]);
+}
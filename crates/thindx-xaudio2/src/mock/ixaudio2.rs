@@ -0,0 +1,273 @@
+use super::*;
+use super::state::*;
+use super::ixaudio2voice::MockVoice;
+use crate::portable::{SourceFormat, WaveFormatEx, WAVE_FORMAT_PCM, WAVE_FORMAT_IEEE_FLOAT};
+use crate::xaudio2_8::xaudio2::sys::*;
+
+use thindx_xaudio2_sys::FromVtable;
+use winapi::Interface;
+use winapi::shared::guiddef::{IsEqualGUID, REFIID};
+use winapi::shared::minwindef::ULONG;
+use winapi::shared::winerror::{E_NOINTERFACE, E_POINTER, HRESULT, S_OK};
+use winapi::um::audiosessiontypes::AUDIO_STREAM_CATEGORY;
+use winapi::shared::mmreg::WAVEFORMATEX;
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winresult::*;
+
+use alloc::sync::Arc;
+use core::ffi::c_void;
+use core::mem::{ManuallyDrop, size_of};
+use std::sync::{Mutex, MutexGuard};
+
+
+
+/// The object behind the mock [IXAudio2] pointer.  Reference counted via [Arc].
+#[repr(C)] pub(super) struct Inner {
+    #[allow(dead_code)] interface: IXAudio2, // must be first: `this` pointers are cast back to `Inner`
+    state:      Mutex<State>,
+}
+
+// SAFETY: `interface` only references a `'static` vtable, and all mutable state lives behind `state`'s lock.
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+impl Inner {
+    pub(super) fn new() -> Arc<Self> {
+        let state = State { engine_started: true, .. Default::default() };
+        Arc::new(Self { interface: unsafe { IXAudio2::from_vtable(&VTBL) }, state: Mutex::new(state) })
+    }
+
+    /// Lock the engine state.  Poisoning is ignored: a panicking test shouldn't cascade into unrelated `Drop`s.
+    pub(super) fn lock(&self) -> MutexGuard<State> { self.state.lock().unwrap_or_else(|poison| poison.into_inner()) }
+
+    /// Hand out a new reference as a raw [IXAudio2] pointer.
+    pub(super) fn into_raw(this: Arc<Self>) -> *mut IXAudio2 { Arc::into_raw(this) as *mut IXAudio2 }
+
+    unsafe fn from_this<'a>(this: *const IXAudio2) -> &'a Self { unsafe { &*this.cast() } }
+}
+
+static VTBL : IXAudio2Vtbl = IXAudio2Vtbl {
+    base: IUnknownVtbl {
+        QueryInterface:         query_interface,
+        AddRef:                 add_ref,
+        Release:                release,
+    },
+    RegisterForCallbacks:       register_for_callbacks,
+    UnregisterForCallbacks:     unregister_for_callbacks,
+    CreateSourceVoice:          create_source_voice,
+    CreateSubmixVoice:          create_submix_voice,
+    CreateMasteringVoice:       create_mastering_voice,
+    StartEngine:                start_engine,
+    StopEngine:                 stop_engine,
+    CommitChanges:              commit_changes,
+    GetPerformanceData:         get_performance_data,
+    SetDebugConfiguration:      set_debug_configuration,
+};
+
+unsafe extern "system" fn query_interface(this: *mut IUnknown, riid: REFIID, ppv: *mut *mut c_void) -> HRESULT {
+    if ppv.is_null() { return E_POINTER }
+    let riid = unsafe { &*riid };
+    let known = [IUnknown::uuidof(), thindx_xaudio2_sys::xaudio2_8::IID_IXAudio2, thindx_xaudio2_sys::xaudio2_9::IID_IXAudio2];
+    if known.iter().any(|iid| IsEqualGUID(iid, riid)) {
+        unsafe { add_ref(this) };
+        unsafe { *ppv = this.cast() };
+        S_OK
+    } else {
+        unsafe { *ppv = core::ptr::null_mut() };
+        E_NOINTERFACE
+    }
+}
+
+unsafe extern "system" fn add_ref(this: *mut IUnknown) -> ULONG {
+    let this = this as *const Inner;
+    unsafe { Arc::increment_strong_count(this) };
+    let arc = ManuallyDrop::new(unsafe { Arc::from_raw(this) });
+    Arc::strong_count(&arc) as ULONG
+}
+
+unsafe extern "system" fn release(this: *mut IUnknown) -> ULONG {
+    let arc = unsafe { Arc::from_raw(this as *const Inner) };
+    let remaining = Arc::strong_count(&arc) - 1;
+    drop(arc);
+    remaining as ULONG
+}
+
+unsafe extern "system" fn register_for_callbacks(this: *const IXAudio2, callback: *const IXAudio2EngineCallback) -> HResult {
+    let mut state = unsafe { Inner::from_this(this) }.lock();
+    state.record(None, "RegisterForCallbacks");
+    if let Some(hr) = state.injected("RegisterForCallbacks") { return hr }
+    if callback.is_null() { return E::INVALIDARG.into() }
+    if !state.engine_callbacks.contains(&callback) { state.engine_callbacks.push(callback) }
+    S::OK.into()
+}
+
+unsafe extern "system" fn unregister_for_callbacks(this: *const IXAudio2, callback: *const IXAudio2EngineCallback) {
+    let mut state = unsafe { Inner::from_this(this) }.lock();
+    state.record(None, "UnregisterForCallbacks");
+    state.engine_callbacks.retain(|cb| *cb != callback);
+}
+
+unsafe extern "system" fn create_source_voice(
+    this:               *const IXAudio2,
+    pp_source_voice:    *mut *mut IXAudio2SourceVoice,
+    source_format:      *const WAVEFORMATEX,
+    flags:              u32,
+    max_frequency_ratio:f32,
+    callback:           *const IXAudio2VoiceCallback,
+    send_list:          *const XAUDIO2_VOICE_SENDS,
+    effect_chain:       *const XAUDIO2_EFFECT_CHAIN,
+) -> HResult {
+    let inner = unsafe { Inner::from_this(this) };
+    let mut state = inner.lock();
+    state.record(None, "CreateSourceVoice");
+    if let Some(hr) = state.injected("CreateSourceVoice") { return hr }
+    if pp_source_voice.is_null() || source_format.is_null() { return E::INVALIDARG.into() }
+
+    let format = WaveFormatEx::from(unsafe { *source_format });
+    let source_format = match format.wFormatTag {
+        WAVE_FORMAT_PCM | WAVE_FORMAT_IEEE_FLOAT    => unsafe { SourceFormat::from_wave_format(WaveFormatEx { cbSize: 0, .. format }) }, // cbSize is ignored
+        _                                           => match unsafe { SourceFormat::from_wave_format_bytes(core::slice::from_raw_parts(source_format.cast(), size_of::<WaveFormatEx>() + usize::from(format.cbSize))) } {
+            Ok(f)   => f,
+            Err(_)  => return E::INVALIDARG.into(),
+        },
+    };
+    let (channels, rate, block_align) = (format.nChannels, format.nSamplesPerSec, format.nBlockAlign);
+    if channels == 0 || u32::from(channels) > XAUDIO2_MAX_AUDIO_CHANNELS                    { return XAUDIO2_E_INVALID_CALL }
+    if !(XAUDIO2_MIN_SAMPLE_RATE ..= XAUDIO2_MAX_SAMPLE_RATE).contains(&rate)               { return XAUDIO2_E_INVALID_CALL }
+    if block_align == 0                                                                     { return XAUDIO2_E_INVALID_CALL }
+    if !(XAUDIO2_MIN_FREQ_RATIO ..= XAUDIO2_MAX_FREQ_RATIO).contains(&max_frequency_ratio)  { return E::INVALIDARG.into() }
+
+    let sends = match unsafe { state.sends(VoiceKind::Source, 0, send_list) } { Ok(s) => s, Err(hr) => return hr };
+    let dests = state.handles(&sends);
+    let handle = match state.renderer.create_source_voice(&source_format, max_frequency_ratio, Some(&dests)) { Ok(h) => h, Err(_) => return XAUDIO2_E_INVALID_CALL };
+    let (effects, enabled) = match unsafe { State::effects(effect_chain) } { Ok(e) => e, Err(hr) => { let _ = state.renderer.destroy_voice(handle); return hr } };
+
+    let mut voice = VoiceState::new(handle, VoiceKind::Source, flags, channels.into(), rate);
+    voice.format                = format;
+    voice.callback              = callback;
+    voice.max_frequency_ratio   = max_frequency_ratio;
+    voice.sends                 = sends;
+    voice.set_effects(effects, enabled);
+
+    let raw = MockVoice::new(this.cast(), VoiceKind::Source);
+    let id = state.add_voice(raw as usize, voice);
+    unsafe { (*raw).id = id };
+    unsafe { *pp_source_voice = raw.cast() };
+    S::OK.into()
+}
+
+unsafe extern "system" fn create_submix_voice(
+    this:               *const IXAudio2,
+    pp_submix_voice:    *mut *mut IXAudio2SubmixVoice,
+    input_channels:     u32,
+    input_sample_rate:  u32,
+    flags:              u32,
+    processing_stage:   u32,
+    send_list:          *const XAUDIO2_VOICE_SENDS,
+    effect_chain:       *const XAUDIO2_EFFECT_CHAIN,
+) -> HResult {
+    let inner = unsafe { Inner::from_this(this) };
+    let mut state = inner.lock();
+    state.record(None, "CreateSubmixVoice");
+    if let Some(hr) = state.injected("CreateSubmixVoice") { return hr }
+    if pp_submix_voice.is_null() { return E::INVALIDARG.into() }
+    if input_channels == 0 || input_channels > XAUDIO2_MAX_AUDIO_CHANNELS                   { return XAUDIO2_E_INVALID_CALL }
+    if !(XAUDIO2_MIN_SAMPLE_RATE ..= XAUDIO2_MAX_SAMPLE_RATE).contains(&input_sample_rate)  { return XAUDIO2_E_INVALID_CALL }
+
+    let sends = match unsafe { state.sends(VoiceKind::Submix, processing_stage, send_list) } { Ok(s) => s, Err(hr) => return hr };
+    let dests = state.handles(&sends);
+    let handle = match state.renderer.create_submix_voice(input_channels, input_sample_rate, processing_stage, Some(&dests)) { Ok(h) => h, Err(_) => return XAUDIO2_E_INVALID_CALL };
+    let (effects, enabled) = match unsafe { State::effects(effect_chain) } { Ok(e) => e, Err(hr) => { let _ = state.renderer.destroy_voice(handle); return hr } };
+
+    let mut voice = VoiceState::new(handle, VoiceKind::Submix, flags, input_channels, input_sample_rate);
+    voice.processing_stage  = processing_stage;
+    voice.sends             = sends;
+    voice.set_effects(effects, enabled);
+
+    let raw = MockVoice::new(this.cast(), VoiceKind::Submix);
+    let id = state.add_voice(raw as usize, voice);
+    unsafe { (*raw).id = id };
+    unsafe { *pp_submix_voice = raw.cast() };
+    S::OK.into()
+}
+
+unsafe extern "system" fn create_mastering_voice(
+    this:               *const IXAudio2,
+    pp_mastering_voice: *mut *mut IXAudio2MasteringVoice,
+    input_channels:     u32,
+    input_sample_rate:  u32,
+    flags:              u32,
+    _device_id:         *const u16,
+    effect_chain:       *const XAUDIO2_EFFECT_CHAIN,
+    _stream_category:   AUDIO_STREAM_CATEGORY,
+) -> HResult {
+    let inner = unsafe { Inner::from_this(this) };
+    let mut state = inner.lock();
+    state.record(None, "CreateMasteringVoice");
+    if let Some(hr) = state.injected("CreateMasteringVoice") { return hr }
+    if pp_mastering_voice.is_null() { return E::INVALIDARG.into() }
+    if state.voices.values().any(|v| v.kind == VoiceKind::Mastering) { return XAUDIO2_E_INVALID_CALL }
+
+    let input_channels      = if input_channels     == XAUDIO2_DEFAULT_CHANNELS     { 2     } else { input_channels     };
+    let input_sample_rate   = if input_sample_rate  == XAUDIO2_DEFAULT_SAMPLERATE   { 48000 } else { input_sample_rate  };
+    if input_channels > XAUDIO2_MAX_AUDIO_CHANNELS                                          { return XAUDIO2_E_INVALID_CALL }
+    if !(XAUDIO2_MIN_SAMPLE_RATE ..= XAUDIO2_MAX_SAMPLE_RATE).contains(&input_sample_rate)  { return XAUDIO2_E_INVALID_CALL }
+
+    let handle = match state.renderer.create_mastering_voice(input_channels, input_sample_rate) { Ok(h) => h, Err(_) => return XAUDIO2_E_INVALID_CALL };
+    let (effects, enabled) = match unsafe { State::effects(effect_chain) } { Ok(e) => e, Err(hr) => { let _ = state.renderer.destroy_voice(handle); return hr } };
+
+    let mut voice = VoiceState::new(handle, VoiceKind::Mastering, flags, input_channels, input_sample_rate);
+    voice.set_effects(effects, enabled);
+
+    let raw = MockVoice::new(this.cast(), VoiceKind::Mastering);
+    let id = state.add_voice(raw as usize, voice);
+    unsafe { (*raw).id = id };
+    unsafe { *pp_mastering_voice = raw.cast() };
+    S::OK.into()
+}
+
+unsafe extern "system" fn start_engine(this: *const IXAudio2) -> HResult {
+    let mut state = unsafe { Inner::from_this(this) }.lock();
+    state.record(None, "StartEngine");
+    if let Some(hr) = state.injected("StartEngine") { return hr }
+    state.engine_started = true;
+    S::OK.into()
+}
+
+unsafe extern "system" fn stop_engine(this: *const IXAudio2) {
+    let mut state = unsafe { Inner::from_this(this) }.lock();
+    state.record(None, "StopEngine");
+    state.engine_started = false;
+}
+
+unsafe extern "system" fn commit_changes(this: *const IXAudio2, operation_set: u32) -> HResult {
+    let mut state = unsafe { Inner::from_this(this) }.lock();
+    state.record(None, "CommitChanges");
+    if let Some(hr) = state.injected("CommitChanges") { return hr }
+    if operation_set == XAUDIO2_INVALID_OPSET { return XAUDIO2_E_INVALID_CALL }
+    state.commit(operation_set);
+    S::OK.into()
+}
+
+unsafe extern "system" fn get_performance_data(this: *const IXAudio2, perf_data: *mut XAUDIO2_PERFORMANCE_DATA) {
+    let mut state = unsafe { Inner::from_this(this) }.lock();
+    state.record(None, "GetPerformanceData");
+    if perf_data.is_null() { return }
+    let mut data = XAUDIO2_PERFORMANCE_DATA::default();
+    for v in state.voices.values() {
+        match v.kind {
+            VoiceKind::Source       => {
+                data.TotalSourceVoiceCount  += 1;
+                if state.renderer.is_started(v.handle) { data.ActiveSourceVoiceCount += 1 }
+            },
+            VoiceKind::Submix       => data.ActiveSubmixVoiceCount += 1,
+            VoiceKind::Mastering    => {},
+        }
+    }
+    unsafe { *perf_data = data };
+}
+
+unsafe extern "system" fn set_debug_configuration(this: *const IXAudio2, _debug_configuration: *const XAUDIO2_DEBUG_CONFIGURATION, _reserved: *const c_void) {
+    let mut state = unsafe { Inner::from_this(this) }.lock();
+    state.record(None, "SetDebugConfiguration");
+}
@@ -0,0 +1,464 @@
+use super::*;
+use super::ixaudio2::Inner;
+use super::state::*;
+use crate::portable::{SPEAKER_MONO, SPEAKER_STEREO, SPEAKER_QUAD, SPEAKER_5POINT1, SPEAKER_7POINT1_SURROUND, WAVE_FORMAT_ADPCM, WAVE_FORMAT_WMAUDIO2, WAVE_FORMAT_WMAUDIO3};
+use crate::portable::render::Buffer;
+use crate::xaudio2_8::xaudio2::sys::*;
+
+use abibool::bool32;
+use thindx_xaudio2_sys::FromVtable;
+use winresult::*;
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ptr::null_mut;
+
+
+
+/// The object behind mock [IXAudio2SourceVoice] / [IXAudio2SubmixVoice] / [IXAudio2MasteringVoice] pointers.
+///
+/// Voices aren't reference counted - they live until [IXAudio2Voice::DestroyVoice] - but do keep the engine alive.
+#[repr(C)] pub(super) struct MockVoice {
+    #[allow(dead_code)] interface: IXAudio2Voice, // must be first: `this` pointers are cast back to `MockVoice`
+    engine:         Arc<Inner>,
+    pub(super) id:  VoiceId,
+}
+
+impl MockVoice {
+    /// Allocate a new voice.  The caller is expected to register it with the engine and fill in `id`.
+    ///
+    /// ### Safety
+    /// *   `engine` must have come from [Inner::into_raw] (and still be alive.)
+    pub(super) unsafe fn new(engine: *const Inner, kind: VoiceKind) -> *mut Self {
+        let vtable : *const IXAudio2VoiceVtbl = match kind {
+            VoiceKind::Source       => &SOURCE_VTBL.base,
+            VoiceKind::Submix       => &SUBMIX_VTBL.base,
+            VoiceKind::Mastering    => &MASTERING_VTBL.base,
+        };
+        let engine = unsafe { Arc::increment_strong_count(engine); Arc::from_raw(engine) };
+        Box::into_raw(Box::new(Self {
+            interface:  unsafe { IXAudio2Voice::from_vtable_unbounded(vtable) },
+            engine,
+            id:         VoiceId(0),
+        }))
+    }
+
+    unsafe fn from_this<'a, I>(this: *const I) -> &'a Self { unsafe { &*this.cast() } }
+
+    /// Lock the engine, record `method`, and run `f` against this voice's state (if it's still registered.)
+    fn with<R>(&self, method: &'static str, f: impl FnOnce(&mut State, VoiceId) -> R) -> Option<R> {
+        let mut state = self.engine.lock();
+        state.record(Some(self.id), method);
+        if !state.voices.contains_key(&self.id) { return None }
+        Some(f(&mut state, self.id))
+    }
+
+    /// [Self::with], but honoring failures injected by [Engine::fail_next].
+    fn try_with(&self, method: &'static str, f: impl FnOnce(&mut State, VoiceId) -> HResult) -> HResult {
+        self.with(method, |state, id| match state.injected(method) {
+            Some(hr)    => hr,
+            None        => f(state, id),
+        }).unwrap_or(XAUDIO2_E_INVALID_CALL)
+    }
+}
+
+const BASE_VTBL : IXAudio2VoiceVtbl = IXAudio2VoiceVtbl {
+    GetVoiceDetails:            get_voice_details,
+    SetOutputVoices:            set_output_voices,
+    SetEffectChain:             set_effect_chain,
+    EnableEffect:               enable_effect,
+    DisableEffect:              disable_effect,
+    GetEffectState:             get_effect_state,
+    SetEffectParameters:        set_effect_parameters,
+    GetEffectParameters:        get_effect_parameters,
+    SetFilterParameters:        set_filter_parameters,
+    GetFilterParameters:        get_filter_parameters,
+    SetOutputFilterParameters:  set_output_filter_parameters,
+    GetOutputFilterParameters:  get_output_filter_parameters,
+    SetVolume:                  set_volume,
+    GetVolume:                  get_volume,
+    SetChannelVolumes:          set_channel_volumes,
+    GetChannelVolumes:          get_channel_volumes,
+    SetOutputMatrix:            set_output_matrix,
+    GetOutputMatrix:            get_output_matrix,
+    DestroyVoice:               destroy_voice,
+};
+
+static SOURCE_VTBL : IXAudio2SourceVoiceVtbl = IXAudio2SourceVoiceVtbl {
+    base:                       BASE_VTBL,
+    Start:                      start,
+    Stop:                       stop,
+    SubmitSourceBuffer:         submit_source_buffer,
+    FlushSourceBuffers:         flush_source_buffers,
+    Discontinuity:              discontinuity,
+    ExitLoop:                   exit_loop,
+    GetState:                   get_state,
+    SetFrequencyRatio:          set_frequency_ratio,
+    GetFrequencyRatio:          get_frequency_ratio,
+    SetSourceSampleRate:        set_source_sample_rate,
+};
+
+static SUBMIX_VTBL : IXAudio2SubmixVoiceVtbl = IXAudio2SubmixVoiceVtbl {
+    base:                       BASE_VTBL,
+};
+
+static MASTERING_VTBL : IXAudio2MasteringVoiceVtbl = IXAudio2MasteringVoiceVtbl {
+    base:                       BASE_VTBL,
+    GetChannelMask:             get_channel_mask,
+};
+
+/// Resolve `dest` to one of `voice`'s sends.  Like XAudio2, `null` is accepted when there's exactly one send.
+fn send_to(state: &State, voice: VoiceId, dest: *const IXAudio2Voice) -> Option<VoiceId> {
+    let sends = &state.voices[&voice].sends;
+    if dest.is_null() {
+        match sends[..] { [(_, only)] => Some(only), _ => None }
+    } else {
+        let dest = state.voice_at(dest)?;
+        sends.iter().any(|s| s.1 == dest).then(|| dest)
+    }
+}
+
+
+
+// IXAudio2Voice
+
+unsafe extern "system" fn get_voice_details(this: *const IXAudio2Voice, details: *mut XAUDIO2_VOICE_DETAILS) {
+    unsafe { MockVoice::from_this(this) }.with("GetVoiceDetails", |state, id| {
+        let v = &state.voices[&id];
+        if details.is_null() { return }
+        unsafe { *details = XAUDIO2_VOICE_DETAILS {
+            CreationFlags:      v.flags,
+            ActiveFlags:        v.flags,
+            InputChannels:      v.input_channels,
+            InputSampleRate:    v.input_sample_rate,
+        }};
+    });
+}
+
+unsafe extern "system" fn set_output_voices(this: *const IXAudio2Voice, send_list: *const XAUDIO2_VOICE_SENDS) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetOutputVoices", |state, id| {
+        let (kind, stage) = (state.voices[&id].kind, state.voices[&id].processing_stage);
+        if kind == VoiceKind::Mastering { return XAUDIO2_E_INVALID_CALL }
+        let sends = match unsafe { state.sends(kind, stage, send_list) } { Ok(s) => s, Err(hr) => return hr };
+        if sends.iter().any(|s| s.1 == id) { return XAUDIO2_E_INVALID_CALL }
+        state.set_sends(id, sends);
+        S::OK.into()
+    })
+}
+
+unsafe extern "system" fn set_effect_chain(this: *const IXAudio2Voice, effect_chain: *const XAUDIO2_EFFECT_CHAIN) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetEffectChain", |state, id| {
+        let (effects, enabled) = match unsafe { State::effects(effect_chain) } { Ok(e) => e, Err(hr) => return hr };
+        let v = state.voices.get_mut(&id).unwrap();
+        let before = v.output_channels();
+        v.set_effects(effects, enabled);
+        let (handle, after) = (v.handle, v.output_channels());
+        if after != before {
+            // output matrices no longer fit - reconnect every send with default matrices
+            let sends = core::mem::take(&mut v.sends);
+            let _ = state.renderer.set_output_channels(handle, after);
+            state.set_sends(id, sends);
+        }
+        S::OK.into()
+    })
+}
+
+unsafe extern "system" fn enable_effect(this: *const IXAudio2Voice, effect_index: u32, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("EnableEffect", |state, id| {
+        if effect_index as usize >= state.voices[&id].effects.len() { return E::INVALIDARG.into() }
+        state.deferrable(id, operation_set, Op::EnableEffect(effect_index, true))
+    })
+}
+
+unsafe extern "system" fn disable_effect(this: *const IXAudio2Voice, effect_index: u32, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("DisableEffect", |state, id| {
+        if effect_index as usize >= state.voices[&id].effects.len() { return E::INVALIDARG.into() }
+        state.deferrable(id, operation_set, Op::EnableEffect(effect_index, false))
+    })
+}
+
+unsafe extern "system" fn get_effect_state(this: *const IXAudio2Voice, effect_index: u32, enabled: *mut bool32) {
+    unsafe { MockVoice::from_this(this) }.with("GetEffectState", |state, id| {
+        let e = state.voices[&id].latest.effects_enabled.get(effect_index as usize).copied();
+        if let (Some(e), false) = (e, enabled.is_null()) { unsafe { *enabled = bool32::from(e) } }
+    });
+}
+
+unsafe extern "system" fn set_effect_parameters(this: *const IXAudio2Voice, effect_index: u32, parameters: *const u8, parameters_byte_size: u32, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetEffectParameters", |state, id| {
+        if effect_index as usize >= state.voices[&id].effects.len() { return E::INVALIDARG.into() }
+        if parameters.is_null() && parameters_byte_size != 0 { return E::INVALIDARG.into() }
+        let bytes = if parameters_byte_size == 0 { Vec::new() } else { unsafe { core::slice::from_raw_parts(parameters, parameters_byte_size as usize) }.to_vec() };
+        state.deferrable(id, operation_set, Op::EffectParameters(effect_index, bytes))
+    })
+}
+
+unsafe extern "system" fn get_effect_parameters(this: *const IXAudio2Voice, effect_index: u32, parameters: *mut u8, parameters_byte_size: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("GetEffectParameters", |state, id| {
+        let bytes = match state.voices[&id].latest.effect_parameters.get(effect_index as usize) { Some(b) => b, None => return E::INVALIDARG.into() };
+        if parameters.is_null() { return E::INVALIDARG.into() }
+        let out = unsafe { core::slice::from_raw_parts_mut(parameters, parameters_byte_size as usize) };
+        if bytes.is_empty() {
+            out.fill(0); // never set: a real effect would report its defaults
+        } else if bytes.len() == out.len() {
+            out.copy_from_slice(bytes);
+        } else {
+            return E::INVALIDARG.into();
+        }
+        S::OK.into()
+    })
+}
+
+unsafe extern "system" fn set_filter_parameters(this: *const IXAudio2Voice, parameters: *const XAUDIO2_FILTER_PARAMETERS, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetFilterParameters", |state, id| {
+        if state.voices[&id].flags & XAUDIO2_VOICE_USEFILTER == 0 { return XAUDIO2_E_INVALID_CALL }
+        if parameters.is_null() { return E::INVALIDARG.into() }
+        state.deferrable(id, operation_set, Op::Filter(unsafe { *parameters }))
+    })
+}
+
+unsafe extern "system" fn get_filter_parameters(this: *const IXAudio2Voice, parameters: *mut XAUDIO2_FILTER_PARAMETERS) {
+    unsafe { MockVoice::from_this(this) }.with("GetFilterParameters", |state, id| {
+        if !parameters.is_null() { unsafe { *parameters = state.voices[&id].latest.filter } }
+    });
+}
+
+unsafe extern "system" fn set_output_filter_parameters(this: *const IXAudio2Voice, destination_voice: *const IXAudio2Voice, parameters: *const XAUDIO2_FILTER_PARAMETERS, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetOutputFilterParameters", |state, id| {
+        let dest = match send_to(state, id, destination_voice) { Some(d) => d, None => return XAUDIO2_E_INVALID_CALL };
+        let send_flags = state.voices[&id].sends.iter().find(|s| s.1 == dest).map_or(0, |s| s.0);
+        if send_flags & XAUDIO2_SEND_USEFILTER == 0 { return XAUDIO2_E_INVALID_CALL }
+        if parameters.is_null() { return E::INVALIDARG.into() }
+        state.deferrable(id, operation_set, Op::OutputFilter(dest, unsafe { *parameters }))
+    })
+}
+
+unsafe extern "system" fn get_output_filter_parameters(this: *const IXAudio2Voice, destination_voice: *const IXAudio2Voice, parameters: *mut XAUDIO2_FILTER_PARAMETERS) {
+    unsafe { MockVoice::from_this(this) }.with("GetOutputFilterParameters", |state, id| {
+        let dest = match send_to(state, id, destination_voice) { Some(d) => d, None => return };
+        let filter = match state.voices[&id].latest.output_filters.get(&dest).copied() { Some(f) => f, None => return };
+        if !parameters.is_null() { unsafe { *parameters = filter } }
+    });
+}
+
+unsafe extern "system" fn set_volume(this: *const IXAudio2Voice, volume: f32, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetVolume", |state, id| {
+        if !(-XAUDIO2_MAX_VOLUME_LEVEL ..= XAUDIO2_MAX_VOLUME_LEVEL).contains(&volume) { return E::INVALIDARG.into() }
+        state.deferrable(id, operation_set, Op::Volume(volume))
+    })
+}
+
+unsafe extern "system" fn get_volume(this: *const IXAudio2Voice, volume: *mut f32) {
+    unsafe { MockVoice::from_this(this) }.with("GetVolume", |state, id| {
+        if !volume.is_null() { unsafe { *volume = state.voices[&id].latest.volume } }
+    });
+}
+
+unsafe extern "system" fn set_channel_volumes(this: *const IXAudio2Voice, channels: u32, volumes: *const f32, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetChannelVolumes", |state, id| {
+        if channels != state.voices[&id].output_channels() || volumes.is_null() { return E::INVALIDARG.into() }
+        let volumes = unsafe { core::slice::from_raw_parts(volumes, channels as usize) };
+        if volumes.iter().any(|v| !(-XAUDIO2_MAX_VOLUME_LEVEL ..= XAUDIO2_MAX_VOLUME_LEVEL).contains(v)) { return E::INVALIDARG.into() }
+        state.deferrable(id, operation_set, Op::ChannelVolumes(volumes.to_vec()))
+    })
+}
+
+unsafe extern "system" fn get_channel_volumes(this: *const IXAudio2Voice, channels: u32, volumes: *mut f32) {
+    unsafe { MockVoice::from_this(this) }.with("GetChannelVolumes", |state, id| {
+        let v = &state.voices[&id].latest.channel_volumes;
+        if channels as usize != v.len() || volumes.is_null() { return }
+        unsafe { core::slice::from_raw_parts_mut(volumes, v.len()) }.copy_from_slice(v);
+    });
+}
+
+unsafe extern "system" fn set_output_matrix(this: *const IXAudio2Voice, destination_voice: *const IXAudio2Voice, source_channels: u32, destination_channels: u32, level_matrix: *const f32, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetOutputMatrix", |state, id| {
+        let dest = match send_to(state, id, destination_voice) { Some(d) => d, None => return E::INVALIDARG.into() };
+        if source_channels      != state.voices[&id  ].output_channels()    { return E::INVALIDARG.into() }
+        if destination_channels != state.voices[&dest].input_channels       { return E::INVALIDARG.into() }
+        if level_matrix.is_null()                                           { return E::INVALIDARG.into() }
+        let matrix = unsafe { core::slice::from_raw_parts(level_matrix, (source_channels * destination_channels) as usize) }.to_vec();
+        state.deferrable(id, operation_set, Op::OutputMatrix(dest, matrix))
+    })
+}
+
+unsafe extern "system" fn get_output_matrix(this: *const IXAudio2Voice, destination_voice: *const IXAudio2Voice, source_channels: u32, destination_channels: u32, level_matrix: *mut f32) {
+    unsafe { MockVoice::from_this(this) }.with("GetOutputMatrix", |state, id| {
+        let dest = match send_to(state, id, destination_voice) { Some(d) => d, None => return };
+        let matrix = match state.voices[&id].latest.output_matrices.get(&dest) { Some(m) => m, None => return };
+        if (source_channels * destination_channels) as usize != matrix.len() || level_matrix.is_null() { return }
+        unsafe { core::slice::from_raw_parts_mut(level_matrix, matrix.len()) }.copy_from_slice(matrix);
+    });
+}
+
+unsafe extern "system" fn destroy_voice(this: *const IXAudio2Voice) {
+    let voice = unsafe { MockVoice::from_this(this) };
+    let removed = voice.with("DestroyVoice", |state, id| {
+        if state.has_senders(id) {
+            // XAudio2 refuses (with a debug-runtime error) to destroy voices that are still the destination of other voices.
+            state.warnings.push(format!("DestroyVoice: {id:?} is still the destination of another voice"));
+            return false;
+        }
+        let queued = state.renderer.buffers_queued(state.voices[&id].handle);
+        let v = state.remove_voice(id).unwrap();
+        if queued != 0 {
+            state.warnings.push(format!("DestroyVoice: {id:?} destroyed with {queued} buffer(s) still queued"));
+        }
+        let mut v = v;
+        v.set_effects(Vec::new(), Vec::new());
+        true
+    }).unwrap_or(false);
+    if removed { drop(unsafe { Box::from_raw(this as *mut MockVoice) }) }
+}
+
+
+
+// IXAudio2SourceVoice
+
+unsafe extern "system" fn start(this: *const IXAudio2SourceVoice, flags: u32, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("Start", |state, id| {
+        if flags != 0 { return E::INVALIDARG.into() }
+        state.deferrable(id, operation_set, Op::Start)
+    })
+}
+
+unsafe extern "system" fn stop(this: *const IXAudio2SourceVoice, flags: u32, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("Stop", |state, id| {
+        if flags & !XAUDIO2_PLAY_TAILS != 0 { return E::INVALIDARG.into() }
+        state.deferrable(id, operation_set, Op::Stop)
+    })
+}
+
+unsafe extern "system" fn submit_source_buffer(this: *const IXAudio2SourceVoice, buffer: *const XAUDIO2_BUFFER, buffer_wma: *const XAUDIO2_BUFFER_WMA) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SubmitSourceBuffer", |state, id| {
+        let v = &state.voices[&id];
+        if buffer.is_null() { return E::INVALIDARG.into() }
+        let buffer = unsafe { *buffer };
+        let (flags, bytes, data) = (buffer.Flags, buffer.AudioBytes, buffer.pAudioData);
+        let (play_begin, play_length) = (buffer.PlayBegin, buffer.PlayLength);
+        let (loop_begin, loop_length, loop_count) = (buffer.LoopBegin, buffer.LoopLength, buffer.LoopCount);
+        let block_align = u32::from(v.format.nBlockAlign);
+        let xwma = matches!(v.format.wFormatTag, WAVE_FORMAT_WMAUDIO2 | WAVE_FORMAT_WMAUDIO3);
+
+        if state.renderer.buffers_queued(v.handle) >= XAUDIO2_MAX_QUEUED_BUFFERS as usize { return XAUDIO2_E_INVALID_CALL }
+        if flags & !XAUDIO2_END_OF_STREAM != 0                                          { return E::INVALIDARG.into() }
+        if bytes == 0 || bytes > XAUDIO2_MAX_BUFFER_BYTES || data.is_null()             { return E::INVALIDARG.into() }
+        if bytes % block_align != 0                                                     { return E::INVALIDARG.into() }
+        if loop_count > XAUDIO2_MAX_LOOP_COUNT && loop_count != XAUDIO2_LOOP_INFINITE   { return E::INVALIDARG.into() }
+        if xwma && buffer_wma.is_null()                                                 { return E::INVALIDARG.into() }
+        if v.format.wFormatTag != WAVE_FORMAT_ADPCM && !xwma { // MS-ADPCM / xWMA regions are in decoded samples: left to the renderer, which knows wSamplesPerBlock / the packet table
+            let frames = bytes / block_align;
+            let play_end = if play_length == 0 { frames } else { play_begin.saturating_add(play_length) };
+            if play_begin >= frames || play_end > frames                                    { return E::INVALIDARG.into() }
+            if loop_count != 0 {
+                let loop_end = if loop_length == 0 { play_end } else { loop_begin.saturating_add(loop_length) };
+                if loop_begin < play_begin || loop_begin >= loop_end || loop_end > play_end { return E::INVALIDARG.into() }
+            }
+        }
+
+        // copied, so processing never reads memory the caller has since freed
+        let audio_data : Arc<[u8]> = unsafe { core::slice::from_raw_parts(data, bytes as usize) }.into();
+        let handle = v.handle;
+        let buffer = Buffer {
+            end_of_stream: flags & XAUDIO2_END_OF_STREAM != 0, audio_data,
+            play_begin, play_length, loop_begin, loop_length, loop_count,
+            context: buffer.pContext,
+        };
+        let result = if xwma {
+            let wma = unsafe { *buffer_wma };
+            let (dpds, packets) = (wma.pDecodedPacketCumulativeBytes, wma.PacketCount);
+            if dpds.is_null() || packets == 0 || bytes % packets != 0 { return E::INVALIDARG.into() }
+            let dpds = unsafe { core::slice::from_raw_parts(dpds, packets as usize) };
+            state.renderer.submit_xwma_buffer_raw(handle, buffer, dpds)
+        } else {
+            state.renderer.submit_source_buffer_raw(handle, buffer)
+        };
+        match result {
+            Ok(())  => S::OK.into(),
+            Err(_)  => E::INVALIDARG.into(),
+        }
+    })
+}
+
+unsafe extern "system" fn flush_source_buffers(this: *const IXAudio2SourceVoice) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("FlushSourceBuffers", |state, id| {
+        let _ = state.renderer.flush_source_buffers(state.voices[&id].handle);
+        S::OK.into()
+    })
+}
+
+unsafe extern "system" fn discontinuity(this: *const IXAudio2SourceVoice) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("Discontinuity", |state, id| {
+        // equivalent to flagging the last queued buffer with XAUDIO2_END_OF_STREAM
+        let _ = state.renderer.discontinuity(state.voices[&id].handle);
+        S::OK.into()
+    })
+}
+
+unsafe extern "system" fn exit_loop(this: *const IXAudio2SourceVoice, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("ExitLoop", |state, id| state.deferrable(id, operation_set, Op::ExitLoop))
+}
+
+unsafe extern "system" fn get_state(this: *const IXAudio2SourceVoice, voice_state: *mut XAUDIO2_VOICE_STATE, flags: u32) {
+    unsafe { MockVoice::from_this(this) }.with("GetState", |state, id| {
+        let v = &state.voices[&id];
+        if voice_state.is_null() { return }
+        let no_samples_played = (flags | v.flags) & XAUDIO2_VOICE_NOSAMPLESPLAYED != 0;
+        unsafe { *voice_state = XAUDIO2_VOICE_STATE {
+            pCurrentBufferContext:  state.renderer.current_context(v.handle).unwrap_or(null_mut()),
+            BuffersQueued:          state.renderer.buffers_queued(v.handle) as u32,
+            SamplesPlayed:          if no_samples_played { 0 } else { state.renderer.samples_played(v.handle) },
+        }};
+    });
+}
+
+unsafe extern "system" fn set_frequency_ratio(this: *const IXAudio2SourceVoice, ratio: f32, operation_set: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetFrequencyRatio", |state, id| {
+        let v = &state.voices[&id];
+        if v.flags & XAUDIO2_VOICE_NOPITCH != 0 { return XAUDIO2_E_INVALID_CALL }
+        if ratio.is_nan() { return E::INVALIDARG.into() }
+        let ratio = ratio.max(XAUDIO2_MIN_FREQ_RATIO).min(v.max_frequency_ratio); // XAudio2 clamps rather than failing
+        state.deferrable(id, operation_set, Op::FrequencyRatio(ratio))
+    })
+}
+
+unsafe extern "system" fn get_frequency_ratio(this: *const IXAudio2SourceVoice, ratio: *mut f32) {
+    unsafe { MockVoice::from_this(this) }.with("GetFrequencyRatio", |state, id| {
+        if !ratio.is_null() { unsafe { *ratio = state.voices[&id].latest.frequency_ratio } }
+    });
+}
+
+unsafe extern "system" fn set_source_sample_rate(this: *const IXAudio2SourceVoice, new_source_sample_rate: u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("SetSourceSampleRate", |state, id| {
+        let handle = state.voices[&id].handle;
+        if state.renderer.buffers_queued(handle) != 0 { return XAUDIO2_E_INVALID_CALL }
+        if !(XAUDIO2_MIN_SAMPLE_RATE ..= XAUDIO2_MAX_SAMPLE_RATE).contains(&new_source_sample_rate) { return E::INVALIDARG.into() }
+        let _ = state.renderer.set_source_sample_rate(handle, new_source_sample_rate);
+        let v = state.voices.get_mut(&id).unwrap();
+        v.input_sample_rate         = new_source_sample_rate;
+        v.format.nSamplesPerSec     = new_source_sample_rate;
+        S::OK.into()
+    })
+}
+
+
+
+// IXAudio2MasteringVoice
+
+unsafe extern "system" fn get_channel_mask(this: *const IXAudio2MasteringVoice, channel_mask: *mut u32) -> HResult {
+    unsafe { MockVoice::from_this(this) }.try_with("GetChannelMask", |state, id| {
+        if channel_mask.is_null() { return E::INVALIDARG.into() }
+        let mask = match state.voices[&id].input_channels {
+            1 => SPEAKER_MONO,
+            2 => SPEAKER_STEREO,
+            4 => SPEAKER_QUAD,
+            6 => SPEAKER_5POINT1,
+            8 => SPEAKER_7POINT1_SURROUND,
+            _ => 0,
+        };
+        unsafe { *channel_mask = mask };
+        S::OK.into()
+    })
+}
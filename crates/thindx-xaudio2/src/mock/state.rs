@@ -0,0 +1,305 @@
+use super::*;
+use crate::portable::WaveFormatEx;
+use crate::portable::render::{FilterParameters, Renderer, VoiceHandle, default_matrix};
+use crate::xaudio2_8::xaudio2::sys::*;
+
+use winapi::um::unknwnbase::IUnknown;
+use winresult::*;
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::c_void;
+
+
+
+/// Everything the mock engine knows, guarded by [`Inner::state`](super::ixaudio2::Inner).
+///
+/// Raw pointers (callbacks, contexts, effects) are only ever stored and handed back to the caller - never dereferenced while locked.
+#[derive(Default)] pub(super) struct State {
+    pub(super) engine_started:      bool,
+    pub(super) engine_callbacks:    Vec<*const IXAudio2EngineCallback>,
+    pub(super) next_voice:          u32,
+    pub(super) voices:              BTreeMap<VoiceId, VoiceState>,
+    pub(super) by_address:          BTreeMap<usize, VoiceId>,
+    pub(super) pending:             Vec<Pending>,
+    pub(super) calls:               Vec<Call>,
+    pub(super) failures:            Vec<(&'static str, HResult)>,
+    pub(super) warnings:            Vec<String>,
+    pub(super) passes:              u64,
+    /// Parameters actually in effect (deferred operation sets not yet committed are excluded), buffer queues, and audio processing
+    pub(super) renderer:            Renderer<*mut c_void>,
+    pub(super) output:              Vec<f32>,
+}
+
+pub(super) struct VoiceState {
+    pub(super) handle:              VoiceHandle,
+    pub(super) kind:                VoiceKind,
+    pub(super) flags:               u32,
+    pub(super) input_channels:      u32,
+    pub(super) input_sample_rate:   u32,
+    pub(super) processing_stage:    u32,
+    pub(super) sends:               Vec<(u32, VoiceId)>,
+    pub(super) effects:             Vec<Effect>,
+    /// Most recently requested parameters (what `Get*` methods report)
+    pub(super) latest:              VoiceParams,
+
+    // source voices only
+    pub(super) format:              WaveFormatEx,
+    pub(super) callback:            *const IXAudio2VoiceCallback,
+    pub(super) max_frequency_ratio: f32,
+}
+
+pub(super) struct Effect {
+    pub(super) unknown:             *const IUnknown,
+    pub(super) output_channels:     u32,
+}
+
+pub(super) struct VoiceParams {
+    pub(super) volume:              f32,
+    pub(super) channel_volumes:     Vec<f32>,
+    pub(super) output_matrices:     BTreeMap<VoiceId, Vec<f32>>,
+    pub(super) filter:              XAUDIO2_FILTER_PARAMETERS,
+    pub(super) output_filters:      BTreeMap<VoiceId, XAUDIO2_FILTER_PARAMETERS>,
+    pub(super) frequency_ratio:     f32,
+    pub(super) effects_enabled:     Vec<bool>,
+    pub(super) effect_parameters:   Vec<Vec<u8>>,
+}
+
+pub(super) struct Pending {
+    pub(super) operation_set:       u32,
+    pub(super) voice:               VoiceId,
+    pub(super) op:                  Op,
+}
+
+pub(super) enum Op {
+    Start,
+    Stop,
+    ExitLoop,
+    Volume(f32),
+    ChannelVolumes(Vec<f32>),
+    OutputMatrix(VoiceId, Vec<f32>),
+    Filter(XAUDIO2_FILTER_PARAMETERS),
+    OutputFilter(VoiceId, XAUDIO2_FILTER_PARAMETERS),
+    FrequencyRatio(f32),
+    EnableEffect(u32, bool),
+    EffectParameters(u32, Vec<u8>),
+}
+
+pub(super) const DEFAULT_FILTER : XAUDIO2_FILTER_PARAMETERS = XAUDIO2_FILTER_PARAMETERS {
+    Type:       XAUDIO2_DEFAULT_FILTER_TYPE,
+    Frequency:  XAUDIO2_DEFAULT_FILTER_FREQUENCY,
+    OneOverQ:   XAUDIO2_DEFAULT_FILTER_ONEOVERQ,
+};
+
+impl State {
+    pub(super) fn record(&mut self, voice: Option<VoiceId>, method: &'static str) { self.calls.push(Call { voice, method }) }
+
+    /// Consume a failure previously injected via [Engine::fail_next], if any.
+    pub(super) fn injected(&mut self, method: &'static str) -> Option<HResult> {
+        let i = self.failures.iter().position(|f| f.0 == method)?;
+        Some(self.failures.remove(i).1)
+    }
+
+    pub(super) fn voice_at(&self, address: *const IXAudio2Voice) -> Option<VoiceId> { self.by_address.get(&(address as usize)).copied() }
+
+    /// Resolve and validate a send list, returning `(flags, destination)` pairs.
+    ///
+    /// A null send list means "send to the mastering voice" for source and submix voices.
+    /// Like XAudio2, all destinations must share the same input sample rate.
+    pub(super) unsafe fn sends(&self, kind: VoiceKind, stage: u32, send_list: *const XAUDIO2_VOICE_SENDS) -> Result<Vec<(u32, VoiceId)>, HResult> {
+        let mut sends = Vec::new();
+        if kind == VoiceKind::Mastering { return Ok(sends) }
+        if send_list.is_null() {
+            let master = self.voices.iter().find(|(_, v)| v.kind == VoiceKind::Mastering).map(|(id, _)| *id).ok_or(XAUDIO2_E_INVALID_CALL)?;
+            sends.push((0, master));
+            return Ok(sends);
+        }
+        let send_list = unsafe { &*send_list };
+        let (count, descs) = (send_list.SendCount, send_list.pSends);
+        if count != 0 && descs.is_null() { return Err(E::INVALIDARG.into()) }
+        for i in 0 .. count as usize {
+            let desc = unsafe { &*descs.add(i) };
+            let (flags, output) = (desc.Flags, desc.pOutputVoice);
+            let dest = self.voice_at(output).ok_or(HResult::from(E::INVALIDARG))?;
+            match self.voices[&dest].kind {
+                VoiceKind::Source                                                   => return Err(XAUDIO2_E_INVALID_CALL),
+                VoiceKind::Submix if kind == VoiceKind::Submix && self.voices[&dest].processing_stage <= stage => return Err(XAUDIO2_E_INVALID_CALL),
+                _                                                                   => {},
+            }
+            if sends.iter().any(|s: &(u32, VoiceId)| s.1 == dest) { return Err(E::INVALIDARG.into()) }
+            if sends.first().map_or(false, |s| self.voices[&s.1].input_sample_rate != self.voices[&dest].input_sample_rate) { return Err(XAUDIO2_E_INVALID_CALL) }
+            sends.push((flags, dest));
+        }
+        Ok(sends)
+    }
+
+    /// Resolve an effect chain, [AddRef](IUnknown::AddRef)ing each effect like XAudio2 does.
+    pub(super) unsafe fn effects(effect_chain: *const XAUDIO2_EFFECT_CHAIN) -> Result<(Vec<Effect>, Vec<bool>), HResult> {
+        let (mut effects, mut enabled) = (Vec::new(), Vec::new());
+        if effect_chain.is_null() { return Ok((effects, enabled)) }
+        let chain = unsafe { &*effect_chain };
+        let (count, descs) = (chain.EffectCount, chain.pEffectDescriptors);
+        if count != 0 && descs.is_null() { return Err(E::INVALIDARG.into()) }
+        for i in 0 .. count as usize {
+            let desc = unsafe { &*descs.add(i) };
+            let (unknown, initial_state, output_channels) = (desc.pEffect, desc.InitialState, desc.OutputChannels);
+            if unknown.is_null() || output_channels == 0 { return Err(E::INVALIDARG.into()) }
+            effects.push(Effect { unknown, output_channels });
+            enabled.push(initial_state.into());
+        }
+        for effect in effects.iter() { unsafe { (*effect.unknown).AddRef() }; }
+        Ok((effects, enabled))
+    }
+
+    /// The renderer voices corresponding to `sends`.
+    pub(super) fn handles(&self, sends: &[(u32, VoiceId)]) -> Vec<VoiceHandle> { sends.iter().map(|s| self.voices[&s.1].handle).collect() }
+
+    /// Register `voice`, whose renderer voice ([VoiceState::handle]) was already created with `voice.sends`.
+    pub(super) fn add_voice(&mut self, address: usize, mut voice: VoiceState) -> VoiceId {
+        self.next_voice += 1;
+        let id = VoiceId(self.next_voice);
+        let (handle, flags, out_channels, sends) = (voice.handle, voice.flags, voice.output_channels(), core::mem::take(&mut voice.sends));
+        self.voices.insert(id, voice);
+        self.by_address.insert(address, id);
+        let _ = self.renderer.set_output_channels(handle, out_channels);
+        if flags & XAUDIO2_VOICE_USEFILTER != 0 { let _ = self.renderer.set_filter_parameters(handle, DEFAULT_FILTER.into()); }
+        self.set_sends(id, sends);
+        id
+    }
+
+    /// Give `source` a default output matrix + filter for `dest`.
+    fn connect(&mut self, source: VoiceId, source_channels: u32, dest: VoiceId) {
+        let matrix = default_matrix(source_channels, self.voices[&dest].input_channels);
+        let params = &mut self.voices.get_mut(&source).unwrap().latest;
+        params.output_matrices.insert(dest, matrix);
+        params.output_filters.insert(dest, DEFAULT_FILTER);
+    }
+
+    pub(super) fn set_sends(&mut self, voice: VoiceId, sends: Vec<(u32, VoiceId)>) {
+        let handles = self.handles(&sends);
+        let v = self.voices.get_mut(&voice).unwrap();
+        let params = &mut v.latest;
+        params.output_matrices.retain(|dest, _| sends.iter().any(|s| s.1 == *dest));
+        params.output_filters .retain(|dest, _| sends.iter().any(|s| s.1 == *dest));
+        let added = sends.iter().zip(handles.iter()).filter(|(s, _)| !v.sends.iter().any(|o| o.1 == s.1)).map(|(s, h)| (s.0, s.1, *h)).collect::<Vec<_>>();
+        let (handle, out_channels) = (v.handle, v.output_channels());
+        v.sends = sends;
+
+        let _ = self.renderer.set_output_voices(handle, Some(&handles));
+        for (flags, dest, dest_handle) in added {
+            self.connect(voice, out_channels, dest);
+            if flags & XAUDIO2_SEND_USEFILTER != 0 { let _ = self.renderer.set_output_filter_parameters(handle, dest_handle, DEFAULT_FILTER.into()); }
+        }
+    }
+
+    /// Does any other voice still send to `voice`?
+    pub(super) fn has_senders(&self, voice: VoiceId) -> bool {
+        self.voices.values().any(|v| v.sends.iter().any(|s| s.1 == voice))
+    }
+
+    pub(super) fn remove_voice(&mut self, voice: VoiceId) -> Option<VoiceState> {
+        let v = self.voices.remove(&voice)?;
+        let _ = self.renderer.destroy_voice(v.handle);
+        self.by_address.retain(|_, id| *id != voice);
+        self.pending.retain(|p| p.voice != voice);
+        Some(v)
+    }
+
+    /// Apply `op` to `voice` immediately, or defer it until [IXAudio2::CommitChanges] is called for `operation_set`.
+    pub(super) fn deferrable(&mut self, voice: VoiceId, operation_set: u32, op: Op) -> HResult {
+        if operation_set == XAUDIO2_INVALID_OPSET { return XAUDIO2_E_INVALID_CALL }
+        let v = match self.voices.get_mut(&voice) { Some(v) => v, None => return XAUDIO2_E_INVALID_CALL };
+        v.latest.apply(&op);
+        if operation_set == XAUDIO2_COMMIT_NOW {
+            self.apply(voice, &op);
+        } else {
+            self.pending.push(Pending { operation_set, voice, op });
+        }
+        S::OK.into()
+    }
+
+    pub(super) fn commit(&mut self, operation_set: u32) {
+        let pending = core::mem::take(&mut self.pending);
+        for p in pending {
+            if operation_set == XAUDIO2_COMMIT_ALL || p.operation_set == operation_set {
+                self.apply(p.voice, &p.op);
+            } else {
+                self.pending.push(p);
+            }
+        }
+    }
+
+    /// Put `op` into effect for processing.
+    fn apply(&mut self, voice: VoiceId, op: &Op) {
+        let handle = match self.voices.get(&voice) { Some(v) => v.handle, None => return };
+        let dest = |dest: &VoiceId| self.voices.get(dest).map(|d| (d.handle, d.input_channels));
+        let r = &mut self.renderer;
+        let _ = match op {
+            Op::Start                   => r.start(handle),
+            Op::Stop                    => r.stop(handle),
+            Op::ExitLoop                => r.exit_loop(handle),
+            Op::Volume(v)               => r.set_volume(handle, *v),
+            Op::ChannelVolumes(v)       => r.set_channel_volumes(handle, v),
+            Op::OutputMatrix(d, m)      => match dest(d) { Some((d, channels)) => r.set_output_matrix(handle, d, m.len() as u32 / channels, channels, m), None => Ok(()) },
+            Op::Filter(f)               => r.set_filter_parameters(handle, FilterParameters::from(*f)),
+            Op::OutputFilter(d, f)      => match dest(d) { Some((d, _)) => r.set_output_filter_parameters(handle, d, FilterParameters::from(*f)), None => Ok(()) },
+            Op::FrequencyRatio(ratio)   => r.set_frequency_ratio(handle, *ratio),
+            Op::EnableEffect(..)        => Ok(()), // effects aren't processed
+            Op::EffectParameters(..)    => Ok(()),
+        };
+    }
+}
+
+impl VoiceState {
+    pub(super) fn new(handle: VoiceHandle, kind: VoiceKind, flags: u32, input_channels: u32, input_sample_rate: u32) -> Self {
+        Self {
+            handle, kind, flags, input_channels, input_sample_rate,
+            processing_stage:       0,
+            sends:                  Vec::new(),
+            effects:                Vec::new(),
+            latest:                 VoiceParams {
+                volume:             1.0,
+                channel_volumes:    alloc::vec![1.0; input_channels as usize],
+                output_matrices:    Default::default(),
+                filter:             DEFAULT_FILTER,
+                output_filters:     Default::default(),
+                frequency_ratio:    1.0,
+                effects_enabled:    Vec::new(),
+                effect_parameters:  Vec::new(),
+            },
+            format:                 Default::default(),
+            callback:               core::ptr::null(),
+            max_frequency_ratio:    1.0,
+        }
+    }
+
+    /// The number of channels produced by the last effect in the chain (or the input channel count without effects.)
+    pub(super) fn output_channels(&self) -> u32 { self.effects.last().map_or(self.input_channels, |e| e.output_channels) }
+
+    pub(super) fn set_effects(&mut self, effects: Vec<Effect>, enabled: Vec<bool>) {
+        let old = core::mem::replace(&mut self.effects, effects);
+        for effect in old { unsafe { (*effect.unknown).Release() }; }
+        let out = self.output_channels() as usize;
+        let params = &mut self.latest;
+        params.effect_parameters    = alloc::vec![Vec::new(); enabled.len()];
+        params.effects_enabled      = enabled;
+        params.channel_volumes      .resize(out, 1.0);
+    }
+}
+
+impl VoiceParams {
+    fn apply(&mut self, op: &Op) {
+        match op {
+            Op::Start | Op::Stop | Op::ExitLoop => {},
+            Op::Volume(v)                       => self.volume = *v,
+            Op::ChannelVolumes(v)               => self.channel_volumes = v.clone(),
+            Op::OutputMatrix(dest, m)           => { self.output_matrices.insert(*dest, m.clone()); },
+            Op::Filter(f)                       => self.filter = *f,
+            Op::OutputFilter(dest, f)           => { self.output_filters.insert(*dest, *f); },
+            Op::FrequencyRatio(r)               => self.frequency_ratio = *r,
+            Op::EnableEffect(i, e)              => if let Some(s) = self.effects_enabled.get_mut(*i as usize) { *s = *e },
+            Op::EffectParameters(i, p)          => if let Some(s) = self.effect_parameters.get_mut(*i as usize) { *s = p.clone() },
+        }
+    }
+}
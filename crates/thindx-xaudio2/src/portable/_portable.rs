@@ -0,0 +1,22 @@
+//! ✔️ Platform independent types - usable without `cfg(windows)`
+//!
+//! Plain value types that don't touch COM or load any DLLs.
+//! These are re-exported by the per-version `xaudio2` modules (e.g. `xaudio2_9::xaudio2`) on Windows,
+//! but remain available here on every platform for build tools, asset pipelines, tests, etc.
+
+mod adpcm;                          pub use adpcm::*;
+mod context;                        pub use context::*;
+mod loop_count;                     pub use loop_count::*;
+mod parameter_channel;              pub use parameter_channel::*;
+mod reverb;                         pub use reverb::*;
+mod sample_range;                   pub use sample_range::*;
+mod source_format;                  pub use source_format::*;
+mod wave_format;                    pub use wave_format::*;
+mod wave_format_extensible;         pub use wave_format_extensible::*;
+mod xapofx;                         pub use xapofx::*;
+mod xwma;                           pub use xwma::*;
+
+pub mod wav;
+
+#[path = "render/_render.rs"] pub mod render;
+#[path = "x3daudio/_x3daudio.rs"] pub mod x3daudio;
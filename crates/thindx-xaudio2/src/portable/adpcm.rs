@@ -0,0 +1,177 @@
+use super::*;
+
+use bytemuck::{Pod, Zeroable};
+use winresult::*;
+
+use core::mem::size_of;
+use core::ops::Deref;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-adpcmcoefset)\]
+/// ADPCMCOEFSET: a pair of MS-ADPCM predictor coefficients.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, Hash)]
+#[repr(C)] pub struct AdpcmCoefSet {
+    pub iCoef1: i16,
+    pub iCoef2: i16,
+}
+
+/// The 7 standard MS-ADPCM coefficient pairs.  XAudio2 doesn't support custom coefficient tables.
+pub const ADPCM_COEFFICIENTS : [AdpcmCoefSet; 7] = [
+    AdpcmCoefSet { iCoef1: 256, iCoef2:    0 },
+    AdpcmCoefSet { iCoef1: 512, iCoef2: -256 },
+    AdpcmCoefSet { iCoef1:   0, iCoef2:    0 },
+    AdpcmCoefSet { iCoef1: 192, iCoef2:   64 },
+    AdpcmCoefSet { iCoef1: 240, iCoef2:    0 },
+    AdpcmCoefSet { iCoef1: 460, iCoef2: -208 },
+    AdpcmCoefSet { iCoef1: 392, iCoef2: -232 },
+];
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-adpcmwaveformat)\]
+/// ADPCMWAVEFORMAT (with the standard 7 coefficients): platform independent equivalent with an identical (packed, 50 byte) layout.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, Hash)]
+#[repr(C, packed(1))] pub struct AdpcmWaveFormat {
+    /// `wFormatTag` must be [WAVE_FORMAT_ADPCM], `wBitsPerSample` must be `4`, and `cbSize` must be `32`.
+    pub wfx:                WaveFormatEx,
+
+    /// Decoded samples (frames) per `nBlockAlign` byte block.
+    pub wSamplesPerBlock:   u16,
+
+    /// Must be `7`.
+    pub wNumCoef:           u16,
+
+    /// Must be [ADPCM_COEFFICIENTS].
+    pub aCoef:              [AdpcmCoefSet; 7],
+}
+
+const _ : () = assert!(size_of::<AdpcmWaveFormat>() == 50);
+
+/// Bytes of per-channel block header: predictor index (1), initial delta (2), and 2 initial samples (2 each.)
+const HEADER_BYTES_PER_CHANNEL : u16 = 7;
+
+
+
+/// A validated [WAVE_FORMAT_ADPCM] [SourceFormat].
+///
+/// [SourceVoiceDynamic::submit_adpcm_buffer_unchecked](crate::xaudio2_9::SourceVoiceDynamic::submit_adpcm_buffer_unchecked) uses this to validate buffers:
+/// *   Audio data must be a whole number of `block_align` byte blocks.
+/// *   [SampleRange]s are measured in decoded samples, and must begin on a block boundary
+///     (and end on one too, unless they end at the end of the buffer.)
+#[derive(Clone)] pub struct AdpcmSourceFormat(SourceFormat);
+
+impl AsRef<SourceFormat> for AdpcmSourceFormat { fn as_ref(&self) -> &SourceFormat { &self.0 } }
+impl Deref for AdpcmSourceFormat { fn deref(&self) -> &Self::Target { &self.0 } type Target = SourceFormat; }
+impl From<AdpcmSourceFormat> for SourceFormat { fn from(f: AdpcmSourceFormat) -> Self { f.0 } }
+
+impl AdpcmSourceFormat {
+    /// MS-ADPCM with the standard coefficients, `channels` (1 or 2), and a block size derived from `samples_per_block`.
+    ///
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if `channels` isn't 1 or 2,
+    /// or if `samples_per_block` doesn't correspond to a whole number of bytes per block (`(samples_per_block - 2) * channels` must be even, and `samples_per_block >= 2`.)
+    pub fn new(channels: u16, hz: u32, samples_per_block: u16) -> Result<Self, HResultError> {
+        if !matches!(channels, 1 | 2) { return Err(E::INVALIDARG) }
+        let nibbles = u32::from(samples_per_block.checked_sub(2).ok_or(E::INVALIDARG)?) * u32::from(channels);
+        if nibbles % 2 != 0 { return Err(E::INVALIDARG) }
+        let block_align = u16::try_from(u32::from(HEADER_BYTES_PER_CHANNEL * channels) + nibbles / 2).map_err(|_| E::INVALIDARG)?;
+        let blocks_per_sec = hz / u32::from(samples_per_block);
+        let format = AdpcmWaveFormat {
+            wfx: WaveFormatEx {
+                wFormatTag:         WAVE_FORMAT_ADPCM,
+                nChannels:          channels,
+                nSamplesPerSec:     hz,
+                nAvgBytesPerSec:    blocks_per_sec * u32::from(block_align),
+                nBlockAlign:        block_align,
+                wBitsPerSample:     4,
+                cbSize:             (size_of::<AdpcmWaveFormat>() - size_of::<WaveFormatEx>()) as u16,
+            },
+            wSamplesPerBlock:   samples_per_block,
+            wNumCoef:           7,
+            aCoef:              ADPCM_COEFFICIENTS,
+        };
+        Self::from_adpcm_wave_format(format)
+    }
+
+    /// Validate a complete [AdpcmWaveFormat].
+    ///
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> unless:
+    /// *   `wFormatTag` is [WAVE_FORMAT_ADPCM], `wBitsPerSample` is 4, and `cbSize` is 32
+    /// *   `nChannels` is 1 or 2
+    /// *   `nBlockAlign` matches `wSamplesPerBlock` and `nChannels`
+    /// *   The coefficient table is [ADPCM_COEFFICIENTS]
+    pub fn from_adpcm_wave_format(format: AdpcmWaveFormat) -> Result<Self, HResultError> {
+        let AdpcmWaveFormat { wfx, wSamplesPerBlock: samples_per_block, wNumCoef: num_coef, aCoef: coef } = format;
+        let WaveFormatEx { wFormatTag: tag, nChannels: channels, nBlockAlign: block_align, wBitsPerSample: bits, cbSize: cb_size, .. } = wfx;
+        if tag != WAVE_FORMAT_ADPCM || bits != 4 || usize::from(cb_size) != size_of::<AdpcmWaveFormat>() - size_of::<WaveFormatEx>() { return Err(E::INVALIDARG) }
+        if !matches!(channels, 1 | 2) || num_coef != 7 || coef != ADPCM_COEFFICIENTS { return Err(E::INVALIDARG) }
+        let header_bytes = HEADER_BYTES_PER_CHANNEL * channels;
+        let nibbles = u32::from(block_align.checked_sub(header_bytes).ok_or(E::INVALIDARG)?) * 2;
+        if samples_per_block < 2 || nibbles != u32::from(samples_per_block - 2) * u32::from(channels) { return Err(E::INVALIDARG) }
+        Ok(Self(unsafe { SourceFormat::from_wave_format_bytes(bytemuck::bytes_of(&format)) }?))
+    }
+
+    /// Validate a [SourceFormat] as per [from_adpcm_wave_format](Self::from_adpcm_wave_format).
+    pub fn from_source_format(format: &SourceFormat) -> Result<Self, HResultError> {
+        let bytes = format.as_bytes();
+        if bytes.len() != size_of::<AdpcmWaveFormat>() { return Err(E::INVALIDARG) }
+        Self::from_adpcm_wave_format(bytemuck::pod_read_unaligned(bytes))
+    }
+
+    /// The underlying [AdpcmWaveFormat] header.
+    pub fn adpcm_wave_format(&self) -> AdpcmWaveFormat { bytemuck::pod_read_unaligned(self.0.as_bytes()) }
+
+    /// Bytes per block (`nBlockAlign`.)
+    pub fn block_align(&self) -> u16 { self.wave_format().nBlockAlign }
+
+    /// Decoded samples (frames) per block (`wSamplesPerBlock`.)
+    pub fn samples_per_block(&self) -> u16 { self.adpcm_wave_format().wSamplesPerBlock }
+
+    /// The number of decoded samples (frames) in `bytes` of audio data.
+    ///
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if `bytes` isn't a whole number of blocks, or the result would overflow.
+    pub fn samples(&self, bytes: usize) -> Result<u32, HResultError> {
+        let block_align = usize::from(self.block_align());
+        if bytes % block_align != 0 { return Err(E::INVALIDARG) }
+        u32::try_from(bytes / block_align).ok().and_then(|blocks| blocks.checked_mul(self.samples_per_block().into())).ok_or(E::INVALIDARG)
+    }
+
+    /// Validate an `XAUDIO2_BUFFER`-style `(begin, length)` region of a buffer `samples` long.
+    /// `length == 0` means "to the end of the buffer".
+    pub(crate) fn check_region(&self, samples: u32, begin: u32, length: u32) -> Result<(), HResultError> {
+        let spb = u32::from(self.samples_per_block());
+        let end = if length == 0 { samples } else { begin.checked_add(length).ok_or(E::INVALIDARG)? };
+        if begin % spb != 0 || end > samples || (end % spb != 0 && end != samples) { return Err(E::INVALIDARG) }
+        Ok(())
+    }
+}
+
+
+
+#[test] fn adpcm() {
+    let mono = AdpcmSourceFormat::new(1, 22050, 512).unwrap();
+    let f = mono.adpcm_wave_format();
+    assert_eq!({f.wfx.wFormatTag},      WAVE_FORMAT_ADPCM);
+    assert_eq!({f.wfx.nBlockAlign},     7 + 255);
+    assert_eq!({f.wfx.cbSize},          32);
+    assert_eq!(mono.as_bytes().len(),   50);
+    assert_eq!(mono.samples(262 * 3),   Ok(512 * 3));
+    assert!(mono.samples(262 * 3 + 1).is_err());
+
+    let stereo = AdpcmSourceFormat::new(2, 44100, 33).unwrap();
+    assert_eq!(stereo.block_align(),    14 + 31);
+    assert!(AdpcmSourceFormat::new(1, 44100, 33).is_err(), "half a byte per block");
+    assert!(AdpcmSourceFormat::new(3, 44100, 32).is_err(), "too many channels");
+    assert!(AdpcmSourceFormat::from_source_format(&stereo).is_ok());
+
+    let mut bad = stereo.adpcm_wave_format();
+    bad.aCoef[6].iCoef2 = 0;
+    assert!(AdpcmSourceFormat::from_adpcm_wave_format(bad).is_err(), "non-standard coefficients");
+
+    assert!(mono.check_region(1024, 0, 0).is_ok());
+    assert!(mono.check_region(1024, 512, 512).is_ok());
+    assert!(mono.check_region(1000, 512, 0).is_ok(), "may end at the end of the buffer");
+    assert!(mono.check_region(1024, 1, 511).is_err(), "must begin on a block");
+    assert!(mono.check_region(1024, 0, 500).is_err(), "must end on a block");
+    assert!(mono.check_region(1024, 512, 1024).is_err(), "out of bounds");
+}
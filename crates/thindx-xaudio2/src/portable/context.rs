@@ -1,6 +1,3 @@
-#[allow(unused_imports)] use super::*;
-#[allow(unused_imports)] use super::xaudio2::sys::*;
-
 use alloc::boxed::Box;
 use alloc::sync::Arc;
 
@@ -9,18 +6,18 @@ use core::ops::Deref;
 
 
 
-/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_buffer)\] [XAUDIO2_BUFFER::pContext]-compatible type.
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_buffer)\] `XAUDIO2_BUFFER::pContext`-compatible type.
 ///
 /// In general, the lifecycle of a [Context] instance goes something like this:
 /// -   User creates a [Context] with audio data / context.
-/// -   User submits the [Context] to [IXAudio2SourceVoice::SubmitSourceBuffer] via [XAUDIO2_BUFFER::pContext].
+/// -   User submits the [Context] to `IXAudio2SourceVoice::SubmitSourceBuffer` via `XAUDIO2_BUFFER::pContext`.
 /// -   Ownership is [Send]t to an XAudio2 thread or thread pool.
-/// -   Various [IXAudio2VoiceCallback] callbacks allow access to the [Deref]erenced object.
-/// -   [IXAudio2VoiceCallback::OnBufferEnd] is the final use, and [Drop]s the [Context].
+/// -   Various `IXAudio2VoiceCallback` callbacks allow access to the [Deref]erenced object.
+/// -   `IXAudio2VoiceCallback::OnBufferEnd` is the final use, and [Drop]s the [Context].
 ///
 /// A typical impl of [Context] will keep alive the data referenced by:
-/// *   [XAUDIO2_BUFFER::pAudioData]
-/// *   [XAUDIO2_BUFFER_WMA::pDecodedPacketCumulativeBytes] (if any)
+/// *   `XAUDIO2_BUFFER::pAudioData`
+/// *   `XAUDIO2_BUFFER_WMA::pDecodedPacketCumulativeBytes` (if any)
 pub trait Context
     : Deref     // Context should dereference to *something*
     + Sized     // XAUDIO2_BUFFER::pContext                 is a narrow pointer, can't support DSTs
@@ -28,17 +25,17 @@ pub trait Context
     + 'static   // IXAudio2SourceVoice::SubmitSourceBuffer  is asyncronous, Self will outlive the call
     where Self::Target : Sized
 {
-    /// Transfer ownership of `Self` into a [XAUDIO2_BUFFER::pContext] pointer.
+    /// Transfer ownership of `Self` into a `XAUDIO2_BUFFER::pContext` pointer.
     fn into_pcontext(self) -> *mut c_void;
 
-    /// Transfer ownership of a [XAUDIO2_BUFFER::pContext] pointer back into `Self`.
+    /// Transfer ownership of a `XAUDIO2_BUFFER::pContext` pointer back into `Self`.
     ///
     /// ### Safety
     /// *   `pcontext` must be a valid (properly aligned, non-dangling) pointer to a Self raw instance.
     /// *   `pcontext` should no longer be used after calling this function, as ownership has been transfered from it.
     unsafe fn from_pcontext(pcontext: *mut c_void) -> Self;
 
-    /// Borrow a reference to `Self` from a [XAUDIO2_BUFFER::pContext] pointer.
+    /// Borrow a reference to `Self` from a `XAUDIO2_BUFFER::pContext` pointer.
     ///
     /// ### Safety
     /// *   `pcontext` must be a valid (properly aligned, non-dangling) pointer to a Self raw instance.
@@ -70,3 +67,20 @@ impl<T: Sized + Sync + 'static> Context for &'static T {
     unsafe fn from_pcontext(pcontext: *mut c_void) -> Self { unsafe { &*pcontext.cast() } }
     unsafe fn borrow_pcontext(pcontext: &*mut c_void) -> &Self::Target { unsafe { &*(*pcontext).cast() } }
 }
+
+#[test] fn round_trip() {
+    let boxed = Box::new(42_u32).into_pcontext();
+    assert_eq!(*unsafe { <Box<u32> as Context>::borrow_pcontext(&boxed) }, 42);
+    assert_eq!(*unsafe { <Box<u32> as Context>::from_pcontext(boxed) }, 42);
+
+    let arc = Arc::new(13_u32);
+    let shared = Arc::clone(&arc).into_pcontext();
+    assert_eq!(Arc::strong_count(&arc), 2);
+    assert_eq!(*unsafe { <Arc<u32> as Context>::borrow_pcontext(&shared) }, 13);
+    drop(unsafe { <Arc<u32> as Context>::from_pcontext(shared) });
+    assert_eq!(Arc::strong_count(&arc), 1);
+
+    static VALUE : u32 = 7;
+    let static_ref = (&VALUE).into_pcontext();
+    assert_eq!(*unsafe { <&'static u32 as Context>::from_pcontext(static_ref) }, 7);
+}
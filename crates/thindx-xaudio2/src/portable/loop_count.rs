@@ -1,5 +1,3 @@
-use super::xaudio2::sys::*;
-
 use bytemuck::*;
 use nonmax::NonMaxU8;
 use winresult::*;
@@ -13,8 +11,8 @@ use core::ops::*;
 /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_buffer)\]
 /// `0 ..= 254` \| `∞`
 ///
-/// Differences from [XAUDIO2_BUFFER::LoopCount]:
-/// *   Internally [u8], not [u32] ([XAUDIO2_MAX_LOOP_COUNT] is 254, [XAUDIO2_LOOP_INFINITE] is 255, and above is invalid.)
+/// Differences from `XAUDIO2_BUFFER::LoopCount`:
+/// *   Internally [u8], not [u32] (`XAUDIO2_MAX_LOOP_COUNT` is 254, `XAUDIO2_LOOP_INFINITE` is 255, and above is invalid.)
 /// *   Attempts to prevent accidental construction of a 255 ("infinite") loop count.
 ///     *   [new](Self::new) requires [NonMaxU8].
 ///     *   [TryFrom]<[u8]> errors on 255.
@@ -28,13 +26,17 @@ pub const MAX_LOOP_COUNT    : LoopCount = LoopCount(254);
 /// Loop infinitely / forever.
 pub const LOOP_INFINITE     : LoopCount = LoopCount(255);
 
-const _ : () = assert!((NO_LOOP_REGION.0 as u32) == XAUDIO2_NO_LOOP_REGION);
-const _ : () = assert!((MAX_LOOP_COUNT.0 as u32) == XAUDIO2_MAX_LOOP_COUNT);
-const _ : () = assert!((LOOP_INFINITE .0 as u32) == XAUDIO2_LOOP_INFINITE );
+#[cfg(windows)] const _ : () = {
+    use thindx_xaudio2_sys::xaudio2_8::*;
+
+    assert!((NO_LOOP_REGION.0 as u32) == XAUDIO2_NO_LOOP_REGION);
+    assert!((MAX_LOOP_COUNT.0 as u32) == XAUDIO2_MAX_LOOP_COUNT);
+    assert!((LOOP_INFINITE .0 as u32) == XAUDIO2_LOOP_INFINITE );
 
-const _ : () = assert!((LoopCount::NO_LOOP .0 as u32) == XAUDIO2_NO_LOOP_REGION);
-const _ : () = assert!((LoopCount::MAX     .0 as u32) == XAUDIO2_MAX_LOOP_COUNT);
-const _ : () = assert!((LoopCount::INFINITE.0 as u32) == XAUDIO2_LOOP_INFINITE );
+    assert!((LoopCount::NO_LOOP .0 as u32) == XAUDIO2_NO_LOOP_REGION);
+    assert!((LoopCount::MAX     .0 as u32) == XAUDIO2_MAX_LOOP_COUNT);
+    assert!((LoopCount::INFINITE.0 as u32) == XAUDIO2_LOOP_INFINITE );
+};
 
 impl LoopCount {
     /// No looping.
@@ -66,3 +68,20 @@ impl TryFrom<u8> for LoopCount {
         else            { Ok(Self(value)) }
     }
 }
+
+#[test] fn conversions() {
+    assert_eq!(LoopCount::try_from(0_u8  ), Ok(NO_LOOP_REGION));
+    assert_eq!(LoopCount::try_from(254_u8), Ok(MAX_LOOP_COUNT));
+    assert!(LoopCount::try_from(255_u8).is_err());
+
+    assert_eq!(LoopCount::from(..),     LOOP_INFINITE);
+    assert_eq!(LoopCount::from(None),   NO_LOOP_REGION);
+
+    assert!( MAX_LOOP_COUNT.is_finite());
+    assert!( LOOP_INFINITE .is_infinite());
+    assert_eq!(MAX_LOOP_COUNT.finite().map(|n| n.get()), Some(254));
+    assert_eq!(LOOP_INFINITE .finite(), None);
+
+    assert_eq!(alloc::format!("{}",   LOOP_INFINITE ), "∞");
+    assert_eq!(alloc::format!("{:?}", MAX_LOOP_COUNT), "254");
+}
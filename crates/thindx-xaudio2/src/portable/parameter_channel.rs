@@ -0,0 +1,314 @@
+use sync::*;
+
+use core::fmt::{self, Debug, Formatter};
+use core::ops::Deref;
+use core::sync::atomic::Ordering::*;
+
+
+
+/// Triple buffered effect parameters: a lock-free, allocation-free equivalent of [CXAPOParametersBase]'s parameter handling.
+///
+/// *   [set_parameters](Self::set_parameters) / [get_parameters](Self::get_parameters) may be called from any thread (concurrent setters briefly spin on each other, never on processing.)
+/// *   [begin_process](Self::begin_process) returns the most recently set parameters, which remain stable until [end_process](Processing::end_process).
+///
+/// Processing never blocks, allocates, or observes torn parameters, making this suitable for XAudio2's realtime audio thread.
+/// Share it (e.g. via [Arc](alloc::sync::Arc)) between an XAPO and whatever sets its parameters.
+///
+/// ### Example
+/// ```
+/// # use thindx_xaudio2::portable::*;
+/// #[derive(Clone, Copy)] struct Gain { gain: f32 }
+/// let parameters = ParameterChannel::new(Gain { gain: 1.0 });
+/// parameters.set_parameters(Gain { gain: 0.5 });          // e.g. from the game thread
+///
+/// let processing = parameters.begin_process();            // e.g. from IXAPO::Process
+/// assert!(processing.parameters_changed());
+/// assert_eq!(processing.gain, 0.5);
+/// processing.end_process();
+/// ```
+///
+/// [CXAPOParametersBase]:  https://learn.microsoft.com/en-us/windows/win32/api/xapobase/nl-xapobase-cxapoparametersbase
+pub struct ParameterChannel<P: Copy> {
+    buffers:    [UnsafeCell<P>; 3],
+
+    /// Index of the buffer most recently published by a setter, or'ed with [DIRTY] if processing hasn't acquired it yet.
+    middle:     AtomicU8,
+
+    /// Setter state, guarded by `setting`.
+    back:       UnsafeCell<u8>,
+    latest:     UnsafeCell<u8>,
+    setting:    AtomicBool,
+
+    /// Processing state, guarded by `processing`.
+    front:      UnsafeCell<u8>,
+    processing: AtomicBool,
+}
+
+const DIRTY : u8 = 0x80;
+const INDEX : u8 = 0x03;
+
+// SAFETY: buffers are only written through `back` (exclusive to the current setter), and only read through `front` (by processing) or `latest` (by setters.)
+// `back` is never `front` nor `latest`: buffers only change hands through `middle`.
+unsafe impl<P: Copy + Send       > Send for ParameterChannel<P> {}
+unsafe impl<P: Copy + Send + Sync> Sync for ParameterChannel<P> {}
+
+impl<P: Copy> ParameterChannel<P> {
+    /// Create a new channel, with `initial` parameters.
+    pub fn new(initial: P) -> Self {
+        Self {
+            buffers:    [UnsafeCell::new(initial), UnsafeCell::new(initial), UnsafeCell::new(initial)],
+            middle:     AtomicU8::new(1),
+            back:       UnsafeCell::new(2),
+            latest:     UnsafeCell::new(1),
+            setting:    AtomicBool::new(false),
+            front:      UnsafeCell::new(0),
+            processing: AtomicBool::new(false),
+        }
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapobase/nf-xapobase-cxapoparametersbase-setparameters)\]
+    /// Publish new parameters, to be picked up by the next [begin_process](Self::begin_process).
+    pub fn set_parameters(&self, parameters: P) {
+        self.set(|_, back| *back = parameters)
+    }
+
+    /// Publish new parameters derived from the most recently set ones (e.g. to update a single field), without racing other setters.
+    ///
+    /// `update` must not call back into this channel's setters (it would spin forever.)
+    pub fn update_parameters(&self, update: impl FnOnce(&mut P)) {
+        self.set(|latest, back| { *back = *latest; update(back) })
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapobase/nf-xapobase-cxapoparametersbase-getparameters)\]
+    /// The most recently set parameters (which processing may not have picked up yet.)
+    pub fn get_parameters(&self) -> P {
+        let setting = self.lock_setters();
+        // SAFETY: `latest` is guarded by `setting`, and its buffer is only ever read while published.
+        let parameters = unsafe { *self.buffers[usize::from(*self.latest.get().deref())].get().deref() };
+        drop(setting); // N.B. after the above temporaries: a tail expression's would outlive the lock
+        parameters
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapobase/nf-xapobase-cxapoparametersbase-beginprocess)\]
+    /// Acquire the most recently set parameters for processing.
+    ///
+    /// ### Panics
+    /// *   If already processing (every [begin_process](Self::begin_process) must be paired with an [end_process](Processing::end_process) first.)
+    pub fn begin_process(&self) -> Processing<'_, P> {
+        let already = self.processing.swap(true, Acquire);
+        assert!(!already, "ParameterChannel::begin_process: already processing");
+
+        let front = self.front.get_mut();
+        // SAFETY: `front` is guarded by `processing`
+        let front = unsafe { front.deref() };
+        let changed = self.middle.load(Relaxed) & DIRTY != 0;
+        if changed {
+            // Acquire the setter's writes to the published buffer, Release our reads of the old front buffer.
+            *front = self.middle.swap(*front, AcqRel) & INDEX;
+        }
+        // N.B. setters never write to `front`
+        let parameters = self.buffers[usize::from(*front)].get();
+        Processing { channel: self, parameters, changed }
+    }
+
+    fn set(&self, write: impl FnOnce(&P, &mut P)) {
+        let _setting = self.lock_setters();
+        let (back, latest) = (self.back.get_mut(), self.latest.get_mut());
+        // SAFETY: `back` and `latest` are guarded by `setting`.  `back`'s buffer isn't published, so nothing else accesses it.
+        unsafe {
+            let back    = back.deref();
+            let latest  = latest.deref();
+            {
+                let (src, dst) = (self.buffers[usize::from(*latest)].get(), self.buffers[usize::from(*back)].get_mut());
+                write(src.deref(), dst.deref());
+            } // N.B. finish accessing the buffers before publishing them
+            *latest = *back;
+            // Release our writes to the published buffer, Acquire processing's reads of whatever buffer we get back.
+            *back = self.middle.swap(*back | DIRTY, AcqRel) & INDEX;
+        }
+    }
+
+    fn lock_setters(&self) -> SetterLock<'_> {
+        while self.setting.compare_exchange_weak(false, true, Acquire, Relaxed).is_err() { spin_loop() }
+        SetterLock(&self.setting)
+    }
+}
+
+impl<P: Copy + Default> Default for ParameterChannel<P> { fn default() -> Self { Self::new(P::default()) } }
+impl<P: Copy + Debug> Debug for ParameterChannel<P> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result { fmt.debug_struct("ParameterChannel").field("parameters", &self.get_parameters()).finish() }
+}
+
+struct SetterLock<'a>(&'a AtomicBool);
+impl Drop for SetterLock<'_> { fn drop(&mut self) { self.0.store(false, Release) } }
+
+
+
+/// Parameters acquired by [ParameterChannel::begin_process], which remain stable until [end_process](Self::end_process) (or drop.)
+pub struct Processing<'a, P: Copy> {
+    channel:    &'a ParameterChannel<P>,
+    parameters: ConstPtr<P>,
+    changed:    bool,
+}
+
+impl<P: Copy> Processing<'_, P> {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapobase/nf-xapobase-cxapoparametersbase-parameterschanged)\]
+    /// `true` if these parameters were set since the previous [begin_process](ParameterChannel::begin_process).
+    pub fn parameters_changed(&self) -> bool { self.changed }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapobase/nf-xapobase-cxapoparametersbase-endprocess)\]
+    /// Release the parameters, allowing the next [begin_process](ParameterChannel::begin_process) to pick up newer ones.
+    pub fn end_process(self) {}
+}
+
+impl<P: Copy> Deref for Processing<'_, P> {
+    type Target = P;
+    // SAFETY: setters never write to the front buffer, which stays `front` until `self` is dropped.
+    fn deref(&self) -> &P { unsafe { self.parameters.deref() } }
+}
+
+impl<P: Copy> Drop for Processing<'_, P> {
+    fn drop(&mut self) { self.channel.processing.store(false, Release) }
+}
+
+
+
+/// [loom](https://docs.rs/loom)'s `UnsafeCell` and atomics when model checking (`RUSTFLAGS="--cfg loom"`), thin wrappers around `core`'s otherwise.
+#[cfg(loom)] mod sync {
+    pub(super) use loom::cell::{ConstPtr, UnsafeCell};
+    pub(super) use loom::hint::spin_loop;
+    pub(super) use loom::sync::atomic::{AtomicBool, AtomicU8};
+}
+
+#[cfg(not(loom))] mod sync {
+    pub(super) use core::hint::spin_loop;
+    pub(super) use core::sync::atomic::{AtomicBool, AtomicU8};
+
+    /// The subset of `loom::cell::UnsafeCell` used by [ParameterChannel](super::ParameterChannel).
+    pub(super) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+    pub(super) struct ConstPtr<T>(*const T);
+    pub(super) struct MutPtr<T>(*mut T);
+
+    // SAFETY: ✔️ equivalent to `&T`
+    unsafe impl<T: Sync> Send for ConstPtr<T> {}
+    unsafe impl<T: Sync> Sync for ConstPtr<T> {}
+
+    impl<T> UnsafeCell<T> {
+        pub fn new(value: T) -> Self { Self(core::cell::UnsafeCell::new(value)) }
+        pub fn get(&self) -> ConstPtr<T> { ConstPtr(self.0.get()) }
+        pub fn get_mut(&self) -> MutPtr<T> { MutPtr(self.0.get()) }
+    }
+
+    impl<T> ConstPtr<T> { pub unsafe fn deref(&self) -> &T { unsafe { &*self.0 } } }
+    #[allow(clippy::mut_from_ref)] impl<T> MutPtr<T> { pub unsafe fn deref(&self) -> &mut T { unsafe { &mut *self.0 } } }
+}
+
+
+
+#[cfg(not(loom))] #[test] fn parameter_channel() {
+    let channel = ParameterChannel::new(1);
+    assert_eq!(*channel.begin_process(), 1);
+    assert!(!channel.begin_process().parameters_changed());
+
+    channel.set_parameters(2);
+    channel.set_parameters(3);
+    channel.update_parameters(|p| *p += 1);
+    assert_eq!(channel.get_parameters(), 4);
+
+    let processing = channel.begin_process();
+    assert!(processing.parameters_changed());
+    assert_eq!(*processing, 4);
+    channel.set_parameters(5);
+    assert_eq!(*processing, 4, "stable until end_process");
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| channel.begin_process())).is_err(), "already processing");
+    processing.end_process();
+    assert_eq!(*channel.begin_process(), 5);
+}
+
+#[cfg(not(loom))] #[test] fn parameter_channel_threads() {
+    use std::sync::atomic::AtomicUsize;
+    use std::thread;
+
+    // Every field must match: any mismatch is a torn read.
+    const WRITERS : usize = 3;
+    const WRITES  : usize = 20000;
+    let channel = ParameterChannel::new([0usize; 8]);
+    let done    = AtomicUsize::new(0);
+
+    thread::scope(|s| {
+        for w in 0 .. WRITERS {
+            let (channel, done) = (&channel, &done);
+            s.spawn(move || {
+                for i in 1 ..= WRITES {
+                    if i % 2 == 0 { channel.set_parameters([i * WRITERS + w; 8]) }
+                    else          { channel.update_parameters(|p| *p = [i * WRITERS + w; 8]) }
+                    let p = channel.get_parameters();
+                    assert!(p.iter().all(|v| *v == p[0]), "torn get_parameters: {:?}", p);
+                }
+                done.fetch_add(1, SeqCst);
+            });
+        }
+
+        // Processing: never torn, and each writer's values only move forwards.
+        let mut last = [0usize; WRITERS];
+        while done.load(SeqCst) < WRITERS {
+            let processing = channel.begin_process();
+            let p = *processing;
+            assert!(p.iter().all(|v| *v == p[0]), "torn begin_process: {:?}", p);
+            if p[0] != 0 {
+                let w = p[0] % WRITERS;
+                assert!(last[w] <= p[0], "writer {} went backwards: {} -> {}", w, last[w], p[0]);
+                last[w] = p[0];
+            }
+            processing.end_process();
+        }
+    });
+
+    let last = channel.get_parameters();
+    assert_eq!(*channel.begin_process(), last, "final parameters propagate");
+}
+
+/// Exhaustively model checks the set / begin / end protocol: `RUSTFLAGS="--cfg loom" cargo test -p thindx-xaudio2 --lib --release loom`
+#[cfg(loom)] #[test] fn parameter_channel_loom_process() {
+    loom::model(|| {
+        let channel = loom::sync::Arc::new(ParameterChannel::new([0u32; 2]));
+        let setter = {
+            let channel = channel.clone();
+            loom::thread::spawn(move || {
+                channel.set_parameters([1; 2]);
+                channel.update_parameters(|p| *p = [p[0] + 1; 2]);
+            })
+        };
+
+        let mut last = 0;
+        for _ in 0 .. 2 {
+            let processing = channel.begin_process();
+            let p = *processing;
+            assert_eq!(p[0], p[1], "torn begin_process");
+            assert!(last <= p[0], "went backwards: {} -> {}", last, p[0]);
+            last = p[0];
+            processing.end_process();
+        }
+
+        setter.join().unwrap();
+        assert_eq!(*channel.begin_process(), [2; 2], "final parameters propagate");
+    });
+}
+
+#[cfg(loom)] #[test] fn parameter_channel_loom_setters() {
+    loom::model(|| {
+        let channel = loom::sync::Arc::new(ParameterChannel::new([0u32; 2]));
+        let setters = [(); 2].map(|_| {
+            let channel = channel.clone();
+            loom::thread::spawn(move || channel.update_parameters(|p| *p = [p[0] + 1; 2]))
+        });
+
+        let processing = channel.begin_process();
+        assert_eq!(processing[0], processing[1], "torn begin_process");
+        processing.end_process();
+
+        for setter in setters { setter.join().unwrap() }
+        assert_eq!(channel.get_parameters(), [2; 2], "updates aren't lost");
+        assert_eq!(*channel.begin_process(), [2; 2], "final parameters propagate");
+    });
+}
@@ -0,0 +1,17 @@
+//! ✔️ Offline software rendering of XAudio2 voice graphs - no devices, DLLs, or `cfg(windows)` required
+//!
+//! [Renderer] emulates XAudio2's processing (sample rate conversion, frequency ratios, submix stages, output matrices,
+//! volumes, and filters) entirely in Rust, producing interleaved [f32] output deterministically.
+//! Useful for golden-file tests, Linux CI, and rendering audio offline.
+//!
+//! [Engine] and its voices wrap a [Renderer] in the same method surface as `IXAudio2` and its voices,
+//! so mix graph code written against XAudio2 can be rendered offline too.
+//!
+//! The building blocks ([Filter], [Resampler], [decode], [decode_adpcm]) are exposed for reuse on their own.
+
+mod adpcm;                          pub use adpcm::*;
+mod filter;                         pub use filter::*;
+mod pcm;                            pub use pcm::*;
+mod renderer;                       pub use renderer::*;
+mod resampler;                      pub use resampler::*;
+mod voices;                         pub use voices::*;
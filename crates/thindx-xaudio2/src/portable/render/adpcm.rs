@@ -0,0 +1,79 @@
+use crate::portable::*;
+
+use alloc::vec::Vec;
+
+
+
+/// MS-ADPCM step size adaptation table, indexed by nibble.
+const ADAPTATION : [i32; 16] = [230, 230, 230, 230, 307, 409, 512, 614, 768, 614, 512, 409, 307, 230, 230, 230];
+
+/// Decode whole blocks of MS-ADPCM `bytes` (in `format`) into interleaved [f32] samples, appended to `out`.
+///
+/// Each block produces [samples_per_block](AdpcmSourceFormat::samples_per_block) frames.
+/// Blocks with an out of range predictor index decode as silence, so the frame count is still honored.
+pub fn decode_adpcm(format: &AdpcmSourceFormat, bytes: &[u8], out: &mut Vec<f32>) {
+    let channels    = usize::from(format.wave_format().nChannels);
+    let block_align = usize::from(format.block_align());
+    let spb         = usize::from(format.samples_per_block());
+    out.reserve(bytes.len() / block_align * spb * channels);
+
+    for block in bytes.chunks_exact(block_align) {
+        let (header, data) = block.split_at(7 * channels);
+        let i16_at = |field: usize, ch: usize| i32::from(i16::from_le_bytes([header[channels + 2 * (field * channels + ch)], header[channels + 2 * (field * channels + ch) + 1]]));
+
+        let mut states = [Channel::default(); 2];
+        let mut valid = true;
+        for (ch, state) in states.iter_mut().enumerate().take(channels) {
+            let coef = ADPCM_COEFFICIENTS.get(usize::from(header[ch]));
+            valid &= coef.is_some();
+            let coef = coef.copied().unwrap_or_default();
+            *state = Channel { coef1: coef.iCoef1.into(), coef2: coef.iCoef2.into(), delta: i16_at(0, ch), sample1: i16_at(1, ch), sample2: i16_at(2, ch) };
+        }
+        if !valid {
+            out.resize(out.len() + spb * channels, 0.0);
+            continue;
+        }
+
+        for state in &states[..channels] { out.push(state.sample2 as f32 / 32768.0) }
+        for state in &states[..channels] { out.push(state.sample1 as f32 / 32768.0) }
+        let nibbles = data.iter().flat_map(|b| [b >> 4, b & 0xF]);
+        for (nibble, ch) in nibbles.zip((0 .. channels).cycle()).take((spb - 2) * channels) {
+            out.push(states[ch].next(nibble) as f32 / 32768.0);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)] struct Channel {
+    coef1:      i32,
+    coef2:      i32,
+    delta:      i32,
+    sample1:    i32,
+    sample2:    i32,
+}
+
+impl Channel {
+    fn next(&mut self, nibble: u8) -> i32 {
+        let signed = i32::from(nibble as i8) << 28 >> 28;
+        let predicted = (self.sample1 * self.coef1 + self.sample2 * self.coef2) >> 8;
+        let sample = (predicted + signed * self.delta).clamp(i16::MIN.into(), i16::MAX.into());
+        self.sample2 = self.sample1;
+        self.sample1 = sample;
+        self.delta = ((ADAPTATION[usize::from(nibble)] * self.delta) >> 8).max(16);
+        sample
+    }
+}
+
+
+
+#[test] fn decode_adpcm_mono() {
+    let format = AdpcmSourceFormat::new(1, 8000, 4).unwrap();
+    let block = [0, 16, 0, 100, 0, 50, 0, 0x12];
+    let mut out = Vec::new();
+    decode_adpcm(&format, &[block, block].concat(), &mut out);
+    let expected = [50.0, 100.0, 116.0, 148.0].map(|s: f32| s / 32768.0);
+    assert_eq!(out, [expected, expected].concat());
+
+    let mut out = Vec::new();
+    decode_adpcm(&format, &[7, 16, 0, 100, 0, 50, 0, 0x12], &mut out);
+    assert_eq!(out, [0.0; 4], "invalid predictor");
+}
@@ -0,0 +1,175 @@
+use alloc::vec::Vec;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ne-xaudio2-xaudio2_filter_type)\]
+/// XAUDIO2_FILTER_TYPE
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)] pub enum FilterType {
+    /// Attenuates frequencies above the cutoff frequency (state-variable filter.)
+    #[default] LowPass,
+    /// Attenuates frequencies outside a given range (state-variable filter.)
+    BandPass,
+    /// Attenuates frequencies below the cutoff frequency (state-variable filter.)
+    HighPass,
+    /// Attenuates frequencies inside a given range (state-variable filter.)
+    Notch,
+    /// Attenuates frequencies above the cutoff frequency (one-pole filter, [FilterParameters::one_over_q] has no effect.)
+    LowPassOnePole,
+    /// Attenuates frequencies below the cutoff frequency (one-pole filter, [FilterParameters::one_over_q] has no effect.)
+    HighPassOnePole,
+}
+
+impl FilterType {
+    /// Convert from a raw `XAUDIO2_FILTER_TYPE` value.
+    pub fn from_raw(raw: u32) -> Option<Self> {
+        match raw {
+            0 => Some(FilterType::LowPass),
+            1 => Some(FilterType::BandPass),
+            2 => Some(FilterType::HighPass),
+            3 => Some(FilterType::Notch),
+            4 => Some(FilterType::LowPassOnePole),
+            5 => Some(FilterType::HighPassOnePole),
+            _ => None,
+        }
+    }
+
+    /// Convert into a raw `XAUDIO2_FILTER_TYPE` value.
+    pub fn into_raw(self) -> u32 { self as u32 }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-filter-parameters)\]
+/// XAUDIO2_FILTER_PARAMETERS
+#[derive(Clone, Copy, Debug, PartialEq)] pub struct FilterParameters {
+    /// The type of filter.
+    pub filter_type:    FilterType,
+
+    /// Filter radian frequency (see [cutoff_frequency_to_radians] / [cutoff_frequency_to_one_pole_coefficient].)
+    /// Ranges from `0.0 ..= `[MAX_FILTER_FREQUENCY].
+    pub frequency:      f32,
+
+    /// Reciprocal of the filter's Q factor, for state-variable filters.
+    /// Ranges from `0.0 ..= `[MAX_FILTER_ONEOVERQ].
+    pub one_over_q:     f32,
+}
+
+impl Default for FilterParameters {
+    /// XAudio2's defaults: a [FilterType::LowPass] with [MAX_FILTER_FREQUENCY] and a `one_over_q` of `1.0`.
+    fn default() -> Self { Self { filter_type: FilterType::LowPass, frequency: MAX_FILTER_FREQUENCY, one_over_q: 1.0 } }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-constants)\] XAUDIO2_MAX_FILTER_FREQUENCY
+pub const MAX_FILTER_FREQUENCY  : f32 = 1.0;
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-constants)\] XAUDIO2_MAX_FILTER_ONEOVERQ
+pub const MAX_FILTER_ONEOVERQ   : f32 = 1.5;
+
+#[cfg(windows)] const _ : () = {
+    use thindx_xaudio2_sys::xaudio2_8::*;
+    assert!(MAX_FILTER_FREQUENCY    == XAUDIO2_MAX_FILTER_FREQUENCY);
+    assert!(MAX_FILTER_ONEOVERQ     == XAUDIO2_MAX_FILTER_ONEOVERQ);
+};
+
+#[cfg(windows)] impl From<thindx_xaudio2_sys::xaudio2_8::XAUDIO2_FILTER_PARAMETERS> for FilterParameters {
+    fn from(p: thindx_xaudio2_sys::xaudio2_8::XAUDIO2_FILTER_PARAMETERS) -> Self {
+        use thindx_xaudio2_sys::xaudio2_8::*;
+        let filter_type = p.Type;
+        let filter_type = [LowPassFilter, BandPassFilter, HighPassFilter, NotchFilter, LowPassOnePoleFilter, HighPassOnePoleFilter].iter()
+            .position(|t| *t == filter_type).and_then(|t| FilterType::from_raw(t as u32)).unwrap_or_default();
+        Self { filter_type, frequency: p.Frequency, one_over_q: p.OneOverQ }
+    }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-xaudio2cutofffrequencytoradians)\]
+/// XAudio2CutoffFrequencyToRadians: convert a cutoff frequency in hertz to a state-variable [FilterParameters::frequency].
+pub fn cutoff_frequency_to_radians(cutoff_frequency: f32, sample_rate: u32) -> f32 {
+    if (cutoff_frequency * 6.0) as u32 >= sample_rate { return MAX_FILTER_FREQUENCY }
+    2.0 * (core::f32::consts::PI * cutoff_frequency / sample_rate as f32).sin()
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-xaudio2cutofffrequencytoonepolecoefficient)\]
+/// XAudio2CutoffFrequencyToOnePoleCoefficient: convert a cutoff frequency in hertz to a one-pole [FilterParameters::frequency].
+pub fn cutoff_frequency_to_one_pole_coefficient(cutoff_frequency: f32, sample_rate: u32) -> f32 {
+    if cutoff_frequency as u32 >= sample_rate { return MAX_FILTER_FREQUENCY }
+    1.0 - (1.0 - 2.0 * cutoff_frequency / sample_rate as f32).powi(2)
+}
+
+
+
+/// Per-channel state for a [FilterParameters] filter.
+///
+/// State-variable filters use the same Chamberlin topology as XAudio2 (per sample: `lp += f*bp; hp = x - lp - q*bp; bp += f*hp; notch = hp + lp`.)
+#[derive(Clone, Debug, Default)] pub struct Filter {
+    state: Vec<[f32; 4]>, // [low, band, high, notch] per channel
+}
+
+impl Filter {
+    /// Create a new filter with zeroed state.
+    pub fn new() -> Self { Self::default() }
+
+    /// Reset all filter state to zero.
+    pub fn reset(&mut self) { self.state.clear() }
+
+    /// Filter interleaved `samples` of `channels` channels in place.
+    pub fn process(&mut self, parameters: &FilterParameters, samples: &mut [f32], channels: usize) {
+        if channels == 0 { return }
+        if self.state.len() != channels { self.state.resize(channels, [0.0; 4]) }
+        let FilterParameters { filter_type, frequency: f, one_over_q: q } = *parameters;
+
+        for frame in samples.chunks_exact_mut(channels) {
+            for (x, [lp, bp, hp, notch]) in frame.iter_mut().zip(self.state.iter_mut()) {
+                match filter_type {
+                    FilterType::LowPassOnePole | FilterType::HighPassOnePole => {
+                        *lp += f * (*x - *lp);
+                        *hp = *x - *lp;
+                        *x = if filter_type == FilterType::LowPassOnePole { *lp } else { *hp };
+                    },
+                    _ => {
+                        *lp     += f * *bp;
+                        *hp     = *x - *lp - q * *bp;
+                        *bp     += f * *hp;
+                        *notch  = *hp + *lp;
+                        *x = match filter_type {
+                            FilterType::LowPass     => *lp,
+                            FilterType::BandPass    => *bp,
+                            FilterType::HighPass    => *hp,
+                            _                       => *notch,
+                        };
+                    },
+                }
+            }
+        }
+    }
+}
+
+
+
+#[test] fn one_pole() {
+    let lp = FilterParameters { filter_type: FilterType::LowPassOnePole, frequency: 0.5, one_over_q: 1.0 };
+    let mut dc = [1.0; 32];
+    Filter::new().process(&lp, &mut dc, 1);
+    assert_eq!(dc[0], 0.5);
+    assert!((dc[31] - 1.0).abs() < 1e-6, "low pass should converge on DC");
+
+    let hp = FilterParameters { filter_type: FilterType::HighPassOnePole, .. lp };
+    let mut dc = [1.0; 32];
+    Filter::new().process(&hp, &mut dc, 1);
+    assert!(dc[31].abs() < 1e-6, "high pass should reject DC");
+}
+
+#[test] fn state_variable() {
+    let f = cutoff_frequency_to_radians(1000.0, 48000);
+    assert!((f - 2.0 * (core::f32::consts::PI / 48.0).sin()).abs() < 1e-6);
+    assert_eq!(cutoff_frequency_to_radians(9000.0, 48000), MAX_FILTER_FREQUENCY);
+
+    // nyquist-rate alternation should be crushed by a low pass, and pass through a high pass mostly intact
+    let nyquist = || (0 .. 4800).map(|i| if i % 2 == 0 { 1.0 } else { -1.0 }).collect::<Vec<f32>>();
+    let peak = |s: &[f32]| s[4000..].iter().fold(0.0f32, |m, s| m.max(s.abs()));
+
+    let mut lp = nyquist();
+    Filter::new().process(&FilterParameters { filter_type: FilterType::LowPass, frequency: f, one_over_q: 1.0 }, &mut lp, 1);
+    assert!(peak(&lp) < 0.01, "{}", peak(&lp));
+
+    let mut hp = nyquist();
+    Filter::new().process(&FilterParameters { filter_type: FilterType::HighPass, frequency: f, one_over_q: 1.0 }, &mut hp, 1);
+    assert!(peak(&hp) > 0.9, "{}", peak(&hp));
+}
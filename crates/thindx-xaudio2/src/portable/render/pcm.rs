@@ -0,0 +1,81 @@
+use super::*;
+use crate::portable::*;
+
+use alloc::vec::Vec;
+
+
+
+/// Can [decode] handle `format`?  (Integer PCM of 8, 16, 24, or 32 bits, or 32/64-bit IEEE float PCM, including [WAVE_FORMAT_EXTENSIBLE] equivalents, or valid MS-ADPCM.)
+pub fn can_decode(format: &SourceFormat) -> bool {
+    can_decode_tag_bits(format.format_tag(), format.wave_format().wBitsPerSample) || AdpcmSourceFormat::from_source_format(format).is_ok()
+}
+
+fn can_decode_tag_bits(tag: u16, bits: u16) -> bool {
+    match tag {
+        WAVE_FORMAT_PCM         => matches!(bits, 8 | 16 | 24 | 32),
+        WAVE_FORMAT_IEEE_FLOAT  => matches!(bits, 32 | 64),
+        _                       => false,
+    }
+}
+
+/// Decode whole frames of `bytes` (in `format`) into interleaved [f32] samples, appended to `out`.
+///
+/// Integer PCM is normalized to `-1.0 .. 1.0` (8-bit PCM is unsigned, as per WAV conventions.)
+/// Samples are normalized by their container size, so `wValidBitsPerSample` needs no special handling.
+/// MS-ADPCM is decoded a whole block at a time, via [decode_adpcm].
+/// Formats [can_decode] rejects decode as silence, so the frame count is still honored.
+pub fn decode(format: &SourceFormat, bytes: &[u8], out: &mut Vec<f32>) {
+    if let Ok(adpcm) = AdpcmSourceFormat::from_source_format(format) { return decode_adpcm(&adpcm, bytes, out) }
+    let tag = format.format_tag();
+    let format = format.wave_format();
+    let (channels, block_align, bits) = (usize::from(format.nChannels), usize::from(format.nBlockAlign), format.wBitsPerSample);
+    if channels == 0 || block_align == 0 { return }
+    let frames = bytes.len() / block_align;
+    let bytes = &bytes[.. frames * block_align];
+
+    if !can_decode_tag_bits(tag, bits) {
+        out.resize(out.len() + frames * channels, 0.0);
+        return;
+    }
+
+    let size = usize::from(bits / 8);
+    out.reserve(frames * channels);
+    for frame in bytes.chunks_exact(block_align) {
+        for sample in frame.chunks_exact(size).take(channels) {
+            out.push(match (tag, sample) {
+                (WAVE_FORMAT_IEEE_FLOAT, &[a, b, c, d])             => f32::from_le_bytes([a, b, c, d]),
+                (WAVE_FORMAT_IEEE_FLOAT, &[a, b, c, d, e, f, g, h]) => f64::from_le_bytes([a, b, c, d, e, f, g, h]) as f32,
+                (_, &[a])                                           => (f32::from(a) - 128.0) / 128.0,
+                (_, &[a, b])                                        => f32::from(i16::from_le_bytes([a, b])) / 32768.0,
+                (_, &[a, b, c])                                     => (i32::from_le_bytes([0, a, b, c]) >> 8) as f32 / 8388608.0,
+                (_, &[a, b, c, d])                                  => i32::from_le_bytes([a, b, c, d]) as f32 / 2147483648.0,
+                _                                                   => 0.0,
+            });
+        }
+    }
+}
+
+
+
+#[test] fn decode_pcm() {
+    let mut out = Vec::new();
+    decode(&TypedSourceFormat::<[u8; 1]>::pcm(8000), &[0, 128, 255], &mut out);
+    assert_eq!(out, [-1.0, 0.0, 127.0 / 128.0]);
+
+    let mut out = Vec::new();
+    decode(&TypedSourceFormat::<[i16; 2]>::pcm(8000), bytemuck::cast_slice(&[i16::MIN, 16384, 0, -16384]), &mut out);
+    assert_eq!(out, [-1.0, 0.5, 0.0, -0.5]);
+
+    let mut out = Vec::new();
+    decode(&TypedSourceFormat::<[f32; 1]>::pcm(8000), bytemuck::cast_slice(&[0.25f32, -0.75]), &mut out);
+    assert_eq!(out, [0.25, -0.75]);
+
+    let mut out = Vec::new();
+    decode(&TypedSourceFormat::<[i32; 1]>::pcm_extensible_valid_bits(8000, SPEAKER_MONO, 24), bytemuck::cast_slice(&[i32::MIN, 0x4000_0000]), &mut out);
+    assert_eq!(out, [-1.0, 0.5]);
+
+    let mut out = Vec::new();
+    let adpcm = WaveFormatEx { wFormatTag: WAVE_FORMAT_ADPCM, nChannels: 1, nBlockAlign: 4, .. Default::default() };
+    decode(&unsafe { SourceFormat::from_wave_format(adpcm) }, &[0; 9], &mut out);
+    assert_eq!(out, [0.0; 2], "undecodable formats are silence");
+}
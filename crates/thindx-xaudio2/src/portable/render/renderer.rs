@@ -0,0 +1,789 @@
+use super::*;
+use crate::portable::*;
+
+use winresult::*;
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+
+
+/// `XAUDIO2_END_OF_STREAM`: flag for [Renderer::submit_source_buffer] indicating no more buffers follow.
+pub const END_OF_STREAM : u32 = 0x0040;
+
+const MAX_QUEUED_BUFFERS    : usize = 64;
+const LOOP_INFINITE_RAW     : u32   = LoopCount::INFINITE.0 as u32;
+const PASSES_PER_SECOND     : u64   = 100; // XAUDIO2_QUANTUM_DENOMINATOR / XAUDIO2_QUANTUM_NUMERATOR
+
+#[cfg(windows)] const _ : () = {
+    use thindx_xaudio2_sys::xaudio2_8::*;
+    assert!(END_OF_STREAM                   == XAUDIO2_END_OF_STREAM);
+    assert!(MAX_QUEUED_BUFFERS as u32       == XAUDIO2_MAX_QUEUED_BUFFERS);
+    assert!(PASSES_PER_SECOND as u32        == XAUDIO2_QUANTUM_DENOMINATOR / XAUDIO2_QUANTUM_NUMERATOR);
+};
+
+/// Identifies a voice created by a [Renderer].  Handles are never reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)] pub struct VoiceHandle(u32);
+
+/// A buffer notification, equivalent to the `IXAudio2VoiceCallback` method of the same name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)] pub enum BufferEvent<Tag> {
+    BufferStart(Tag),
+    LoopEnd(Tag),
+    BufferEnd(Tag),
+    StreamEnd,
+}
+
+/// `XAUDIO2_BUFFER`, but owning its audio data, for [Renderer::submit_source_buffer_raw].
+///
+/// Regions follow `XAUDIO2_BUFFER`'s conventions: a `play_length` of `0` plays to the end of the buffer,
+/// a `loop_length` of `0` loops to the end of the play region, and a `loop_count` of `255` loops forever.
+#[derive(Clone, Debug)] pub struct Buffer<Tag> {
+    pub end_of_stream:  bool,
+    pub audio_data:     Arc<[u8]>,
+    pub play_begin:     u32,
+    pub play_length:    u32,
+    pub loop_begin:     u32,
+    pub loop_length:    u32,
+    pub loop_count:     u32,
+    pub context:        Tag,
+}
+
+/// An offline, pure Rust, software mixer emulating XAudio2's voice graph semantics.
+///
+/// Voices are created and configured with methods mirroring [IXAudio2](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2) /
+/// [IXAudio2Voice](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2voice) /
+/// [IXAudio2SourceVoice](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2sourcevoice),
+/// but nothing plays: [render](Self::render) synchronously produces the mastering voice's output as interleaved [f32]s.
+///
+/// ### Processing
+/// Audio is processed in 10ms passes, exactly like XAudio2:
+/// *   Source voices decode PCM or MS-ADPCM (see [can_decode]; xWMA plays as silence), then sample rate convert (with their frequency ratio) to their destinations' rate
+/// *   Submix voices are processed in order of processing stage, then sample rate convert to their destinations' rate
+/// *   Each voice then applies its [filter](Self::set_filter_parameters), [volume](Self::set_volume), and [channel volumes](Self::set_channel_volumes)
+/// *   Each send applies its [output filter](Self::set_output_filter_parameters) and [output matrix](Self::set_output_matrix) before mixing into its destination
+///
+/// Like XAudio2, all of a voice's destinations must share the same input sample rate.
+/// Effects aren't run, but [set_output_channels](Self::set_output_channels) can model an effect chain's channel count changes.
+///
+/// ### Errors
+/// Misuse (unknown voices, bad channel counts, invalid buffer regions, ...) is reported as [E::INVALIDARG].
+///
+/// ### Example
+/// ```
+/// # use thindx_xaudio2::portable::*;
+/// # use thindx_xaudio2::portable::render::*;
+/// let mut r = Renderer::<()>::new();
+/// let _master = r.create_mastering_voice(2, 48000).unwrap();
+/// let format  = TypedSourceFormat::<[f32; 1]>::pcm(48000);
+/// let voice   = r.create_source_voice(&format, 1.0, None).unwrap();
+/// let tone    = (0 .. 4800).map(|i| (i as f32 / 10.0).sin()).collect::<Vec<f32>>();
+/// r.submit_source_buffer(voice, END_OF_STREAM, bytemuck::cast_slice::<f32, u8>(&tone), .., None, None, ()).unwrap();
+/// r.start(voice).unwrap();
+///
+/// let mut out = vec![0.0; 2 * 4800]; // stereo, 100ms @ 48 kHz
+/// r.render(&mut out);
+/// assert_eq!(out[2 * 100 ..][.. 2], [tone[100], tone[100]]); // mono is sent to both front speakers
+/// ```
+pub struct Renderer<Tag = ()> {
+    voices:     BTreeMap<VoiceHandle, Voice<Tag>>,
+    next_voice: u32,
+    passes:     u64,
+    in_pass:    bool,
+    output:     VecDeque<f32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)] enum Kind { Source, Submix, Mastering }
+
+struct Voice<Tag> {
+    kind:                   Kind,
+    input_channels:         u32,
+    output_channels:        u32,
+    input_sample_rate:      u32,
+    processing_stage:       u32,
+    sends:                  Vec<Output>,
+    volume:                 f32,
+    channel_volumes:        Vec<f32>,
+    filter:                 Option<(FilterParameters, Filter)>,
+    resampler:              Resampler,
+    mix:                    Vec<f32>, // submix / mastering input accumulated during the current pass
+
+    // source voices only
+    format:                 Option<SourceFormat>, // for MS-ADPCM / xWMA voices, the decoded (IEEE float) format
+    adpcm:                  Option<AdpcmSourceFormat>,
+    xwma:                   Option<XwmaSourceFormat>,
+    max_frequency_ratio:    f32,
+    frequency_ratio:        f32,
+    started:                bool,
+    queue:                  VecDeque<Queued<Tag>>,
+    flushed:                Vec<Tag>,
+    samples_played:         u64,
+    fraction:               f64,
+    pass_frames:            Option<u32>, // input frames to consume, until processed this pass
+}
+
+struct Output {
+    dest:                   VoiceHandle,
+    matrix:                 Vec<f32>,
+    filter:                 Option<(FilterParameters, Filter)>,
+}
+
+struct Queued<Tag> {
+    buffer:                 Buffer<Tag>,
+    frames:                 u32,
+    position:               u32,
+    loops_remaining:        u32,
+    started:                bool,
+}
+
+impl<Tag: Copy> Default for Renderer<Tag> { fn default() -> Self { Self::new() } }
+
+impl<Tag: Copy> Renderer<Tag> {
+    /// Create a new renderer without any voices.
+    pub fn new() -> Self { Self { voices: BTreeMap::new(), next_voice: 0, passes: 0, in_pass: false, output: VecDeque::new() } }
+
+    fn voice(&self, voice: VoiceHandle) -> Result<&Voice<Tag>, HResultError> { self.voices.get(&voice).ok_or(E::INVALIDARG) }
+    fn voice_mut(&mut self, voice: VoiceHandle) -> Result<&mut Voice<Tag>, HResultError> { self.voices.get_mut(&voice).ok_or(E::INVALIDARG) }
+    fn source_mut(&mut self, voice: VoiceHandle) -> Result<&mut Voice<Tag>, HResultError> { self.voice_mut(voice).and_then(|v| if v.kind == Kind::Source { Ok(v) } else { Err(E::INVALIDARG) }) }
+    fn master(&self) -> Option<VoiceHandle> { self.voices.iter().find(|(_, v)| v.kind == Kind::Mastering).map(|(h, _)| *h) }
+
+    fn add_voice(&mut self, voice: Voice<Tag>, sends: Vec<VoiceHandle>) -> VoiceHandle {
+        self.next_voice += 1;
+        let handle = VoiceHandle(self.next_voice);
+        self.voices.insert(handle, voice);
+        self.connect(handle, sends);
+        handle
+    }
+
+    /// Validate a send list (or default to the mastering voice.)
+    fn sends(&self, kind: Kind, stage: u32, sends: Option<&[VoiceHandle]>) -> Result<Vec<VoiceHandle>, HResultError> {
+        let sends = match sends {
+            None        => Vec::from([self.master().ok_or(E::INVALIDARG)?]),
+            Some(sends) => sends.to_vec(),
+        };
+        let mut rate = None;
+        for (i, dest) in sends.iter().enumerate() {
+            let d = self.voice(*dest)?;
+            match d.kind {
+                Kind::Source                                                        => return Err(E::INVALIDARG),
+                Kind::Submix if kind == Kind::Submix && d.processing_stage <= stage => return Err(E::INVALIDARG),
+                _                                                                   => {},
+            }
+            if sends[..i].contains(dest) { return Err(E::INVALIDARG) }
+            if *rate.get_or_insert(d.input_sample_rate) != d.input_sample_rate { return Err(E::INVALIDARG) }
+        }
+        Ok(sends)
+    }
+
+    /// Replace `voice`'s sends, keeping the matrices and filters of destinations that remain.
+    fn connect(&mut self, voice: VoiceHandle, sends: Vec<VoiceHandle>) {
+        let out_channels = self.voices[&voice].output_channels;
+        let sends = sends.into_iter().map(|dest| {
+            let matrix = default_matrix(out_channels, self.voices[&dest].input_channels);
+            Output { dest, matrix, filter: None }
+        }).collect::<Vec<_>>();
+        let v = self.voices.get_mut(&voice).unwrap();
+        let mut old = core::mem::take(&mut v.sends);
+        v.sends = sends.into_iter().map(|new| match old.iter().position(|o| o.dest == new.dest && o.matrix.len() == new.matrix.len()) {
+            Some(i) => old.swap_remove(i),
+            None    => new,
+        }).collect();
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createmasteringvoice)\]
+    /// Create the (only) mastering voice, whose output [render](Self::render) returns.
+    pub fn create_mastering_voice(&mut self, input_channels: u32, input_sample_rate: u32) -> Result<VoiceHandle, HResultError> {
+        if self.master().is_some() || input_channels == 0 || input_sample_rate == 0 { return Err(E::INVALIDARG) }
+        Ok(self.add_voice(Voice::new(Kind::Mastering, input_channels, input_sample_rate), Vec::new()))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsubmixvoice)\]
+    /// Create a submix voice.  [None] sends to the mastering voice.
+    pub fn create_submix_voice(&mut self, input_channels: u32, input_sample_rate: u32, processing_stage: u32, sends: Option<&[VoiceHandle]>) -> Result<VoiceHandle, HResultError> {
+        if input_channels == 0 || input_sample_rate == 0 { return Err(E::INVALIDARG) }
+        let sends = self.sends(Kind::Submix, processing_stage, sends)?;
+        let mut voice = Voice::new(Kind::Submix, input_channels, input_sample_rate);
+        voice.processing_stage = processing_stage;
+        Ok(self.add_voice(voice, sends))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// Create a source voice.  [None] sends to the mastering voice.
+    pub fn create_source_voice(&mut self, format: &SourceFormat, max_frequency_ratio: f32, sends: Option<&[VoiceHandle]>) -> Result<VoiceHandle, HResultError> {
+        let wave_format = format.wave_format();
+        let (channels, rate, block_align) = (wave_format.nChannels, wave_format.nSamplesPerSec, wave_format.nBlockAlign);
+        if channels == 0 || rate == 0 || block_align == 0 { return Err(E::INVALIDARG) }
+        if !(MIN_FREQ_RATIO ..= MAX_FREQ_RATIO).contains(&max_frequency_ratio) { return Err(E::INVALIDARG) }
+        let sends = self.sends(Kind::Source, 0, sends)?;
+        let mut voice = Voice::new(Kind::Source, channels.into(), rate);
+        voice.format                = Some(format.clone());
+        // MS-ADPCM / xWMA buffers are decoded in their entirety on submit, as blocks / packets don't split into frames
+        let decoded = || unsafe { SourceFormat::from_wave_format(WaveFormatEx {
+            wFormatTag:         WAVE_FORMAT_IEEE_FLOAT,
+            nChannels:          channels,
+            nSamplesPerSec:     rate,
+            nAvgBytesPerSec:    rate * 4 * u32::from(channels),
+            nBlockAlign:        4 * channels,
+            wBitsPerSample:     32,
+            cbSize:             0,
+        })};
+        match format.format_tag() {
+            WAVE_FORMAT_ADPCM                           => { voice.adpcm = Some(AdpcmSourceFormat::from_source_format(format)?); voice.format = Some(decoded()) },
+            WAVE_FORMAT_WMAUDIO2 | WAVE_FORMAT_WMAUDIO3 => { voice.xwma  = Some(XwmaSourceFormat ::from_source_format(format)?); voice.format = Some(decoded()) },
+            _                                           => {},
+        }
+        voice.max_frequency_ratio   = max_frequency_ratio;
+        Ok(self.add_voice(voice, sends))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-destroyvoice)\]
+    /// Destroy a voice, discarding any queued buffers.  Fails if another voice still sends to it.
+    pub fn destroy_voice(&mut self, voice: VoiceHandle) -> Result<(), HResultError> {
+        self.voice(voice)?;
+        if self.voices.values().any(|v| v.sends.iter().any(|s| s.dest == voice)) { return Err(E::INVALIDARG) }
+        self.voices.remove(&voice);
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputvoices)\]
+    /// Change where a voice sends its output.  [None] sends to the mastering voice.
+    pub fn set_output_voices(&mut self, voice: VoiceHandle, sends: Option<&[VoiceHandle]>) -> Result<(), HResultError> {
+        let (kind, stage) = { let v = self.voice(voice)?; (v.kind, v.processing_stage) };
+        if kind == Kind::Mastering { return Err(E::INVALIDARG) }
+        let sends = self.sends(kind, stage, sends)?;
+        if sends.contains(&voice) { return Err(E::INVALIDARG) }
+        self.connect(voice, sends);
+        Ok(())
+    }
+
+    /// Model an effect chain that changes a voice's output channel count.
+    /// Channel volumes and output matrices are reset to their defaults, and extra channels are dropped (or silent channels added.)
+    pub fn set_output_channels(&mut self, voice: VoiceHandle, output_channels: u32) -> Result<(), HResultError> {
+        if output_channels == 0 { return Err(E::INVALIDARG) }
+        let v = self.voice_mut(voice)?;
+        if v.output_channels == output_channels { return Ok(()) }
+        v.output_channels   = output_channels;
+        v.channel_volumes   = alloc::vec![1.0; output_channels as usize];
+        let sends = core::mem::take(&mut v.sends).into_iter().map(|s| s.dest).collect();
+        self.connect(voice, sends);
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setfilterparameters)\]
+    /// Set (and enable) a voice's filter.
+    pub fn set_filter_parameters(&mut self, voice: VoiceHandle, parameters: FilterParameters) -> Result<(), HResultError> {
+        let v = self.voice_mut(voice)?;
+        match v.filter.as_mut() {
+            Some(f) => f.0 = parameters,
+            None    => v.filter = Some((parameters, Filter::new())),
+        }
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputfilterparameters)\]
+    /// Set (and enable) the filter of a voice's send to `destination`.
+    pub fn set_output_filter_parameters(&mut self, voice: VoiceHandle, destination: VoiceHandle, parameters: FilterParameters) -> Result<(), HResultError> {
+        let send = self.voice_mut(voice)?.sends.iter_mut().find(|s| s.dest == destination).ok_or(E::INVALIDARG)?;
+        match send.filter.as_mut() {
+            Some(f) => f.0 = parameters,
+            None    => send.filter = Some((parameters, Filter::new())),
+        }
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setvolume)\]
+    /// Set a voice's overall volume (linear amplitude.)
+    pub fn set_volume(&mut self, voice: VoiceHandle, volume: f32) -> Result<(), HResultError> {
+        if volume.is_nan() { return Err(E::INVALIDARG) }
+        self.voice_mut(voice)?.volume = volume;
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getvolume)\]
+    /// Get a voice's overall volume.
+    pub fn get_volume(&self, voice: VoiceHandle) -> Option<f32> { Some(self.voices.get(&voice)?.volume) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setchannelvolumes)\]
+    /// Set per-output-channel volumes.  `volumes.len()` must match the voice's output channel count.
+    pub fn set_channel_volumes(&mut self, voice: VoiceHandle, volumes: &[f32]) -> Result<(), HResultError> {
+        let v = self.voice_mut(voice)?;
+        if volumes.len() != v.output_channels as usize || volumes.iter().any(|v| v.is_nan()) { return Err(E::INVALIDARG) }
+        v.channel_volumes.copy_from_slice(volumes);
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputmatrix)\]
+    /// Set the volume levels of a voice's send to `destination`.
+    /// `level_matrix[d * source_channels + s]` is the level of source channel `s` in destination channel `d`.
+    pub fn set_output_matrix(&mut self, voice: VoiceHandle, destination: VoiceHandle, source_channels: u32, destination_channels: u32, level_matrix: &[f32]) -> Result<(), HResultError> {
+        let dest_channels = self.voice(destination)?.input_channels;
+        let v = self.voice_mut(voice)?;
+        if source_channels != v.output_channels || destination_channels != dest_channels { return Err(E::INVALIDARG) }
+        if level_matrix.len() != (source_channels * destination_channels) as usize { return Err(E::INVALIDARG) }
+        let send = v.sends.iter_mut().find(|s| s.dest == destination).ok_or(E::INVALIDARG)?;
+        send.matrix.copy_from_slice(level_matrix);
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getoutputmatrix)\]
+    /// Get the volume levels of a voice's send to `destination`.
+    pub fn get_output_matrix(&self, voice: VoiceHandle, destination: VoiceHandle) -> Option<&[f32]> {
+        Some(&self.voices.get(&voice)?.sends.iter().find(|s| s.dest == destination)?.matrix[..])
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-start)\]
+    /// Start consuming and processing a source voice's buffers.
+    pub fn start(&mut self, voice: VoiceHandle) -> Result<(), HResultError> { self.source_mut(voice)?.started = true; Ok(()) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-stop)\]
+    /// Stop consuming and processing a source voice's buffers.
+    pub fn stop(&mut self, voice: VoiceHandle) -> Result<(), HResultError> { self.source_mut(voice)?.started = false; Ok(()) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-exitloop)\]
+    /// Stop looping the current buffer's loop region once it next reaches its end.
+    pub fn exit_loop(&mut self, voice: VoiceHandle) -> Result<(), HResultError> {
+        if let Some(b) = self.source_mut(voice)?.queue.front_mut() { b.loops_remaining = 0 }
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setfrequencyratio)\]
+    /// Set a source voice's frequency ratio, clamped to [MIN_FREQ_RATIO]` ..= max_frequency_ratio`.
+    pub fn set_frequency_ratio(&mut self, voice: VoiceHandle, ratio: f32) -> Result<(), HResultError> {
+        if ratio.is_nan() { return Err(E::INVALIDARG) }
+        let v = self.source_mut(voice)?;
+        v.frequency_ratio = ratio.max(MIN_FREQ_RATIO).min(v.max_frequency_ratio);
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getfrequencyratio)\]
+    /// Get a source voice's frequency ratio.
+    pub fn get_frequency_ratio(&self, voice: VoiceHandle) -> Option<f32> { Some(self.voices.get(&voice)?.frequency_ratio) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setsourcesamplerate)\]
+    /// Change a source voice's input sample rate.  Fails if buffers are still queued.
+    pub fn set_source_sample_rate(&mut self, voice: VoiceHandle, sample_rate: u32) -> Result<(), HResultError> {
+        let v = self.source_mut(voice)?;
+        if !v.queue.is_empty() || sample_rate == 0 { return Err(E::INVALIDARG) }
+        v.input_sample_rate         = sample_rate;
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-submitsourcebuffer)\]
+    /// Add a buffer to a source voice's queue, in the style of [SourceVoice::submit_source_buffer](crate::xaudio2_9::SourceVoice::submit_source_buffer).
+    #[allow(clippy::too_many_arguments)] // mirrors XAUDIO2_BUFFER's fields
+    pub fn submit_source_buffer(
+        &mut self,
+        voice:      VoiceHandle,
+        flags:      u32,
+        audio_data: impl Into<Arc<[u8]>>,
+        play_range: impl Into<SampleRange>,
+        loop_range: impl Into<SampleRange>,
+        loop_count: impl Into<LoopCount>,
+        context:    Tag,
+    ) -> Result<(), HResultError> {
+        if flags & !END_OF_STREAM != 0 { return Err(E::INVALIDARG) }
+        let (play_begin, play_length) = match play_range.into().into_raw_xaudio2_begin_length() { Some(r) => r, None => return Ok(()) };
+        let loop_count = loop_count.into().0;
+        let (loop_begin, loop_length, loop_count) = match loop_range.into().into_raw_xaudio2_begin_length() {
+            Some((begin, length)) if loop_count != 0    => (begin, length, loop_count),
+            _                                           => (0, 0, 0),
+        };
+        self.submit_source_buffer_raw(voice, Buffer {
+            end_of_stream:  flags & END_OF_STREAM != 0,
+            audio_data:     audio_data.into(),
+            play_begin, play_length, loop_begin, loop_length,
+            loop_count:     loop_count.into(),
+            context,
+        })
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-submitsourcebuffer)\]
+    /// Add a buffer to a source voice's queue, with `XAUDIO2_BUFFER`-style regions.
+    ///
+    /// For MS-ADPCM voices, `audio_data` must be whole blocks, and regions (in decoded samples) must be block aligned
+    /// (see [AdpcmSourceFormat].)  xWMA voices require [submit_xwma_buffer_raw](Self::submit_xwma_buffer_raw) instead.
+    pub fn submit_source_buffer_raw(&mut self, voice: VoiceHandle, mut buffer: Buffer<Tag>) -> Result<(), HResultError> {
+        let v = self.source_mut(voice)?;
+        if v.xwma.is_some() { return Err(E::INVALIDARG) }
+        if let Some(adpcm) = v.adpcm.as_ref() {
+            let samples = adpcm.samples(buffer.audio_data.len())?;
+            adpcm.check_region(samples, buffer.play_begin, buffer.play_length)?;
+            if buffer.loop_count != 0 { adpcm.check_region(samples, buffer.loop_begin, buffer.loop_length)? }
+            let mut decoded = Vec::new();
+            decode_adpcm(adpcm, &buffer.audio_data, &mut decoded);
+            buffer.audio_data = bytemuck::cast_slice::<f32, u8>(&decoded).into();
+        }
+        v.enqueue(buffer)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-submitsourcebuffer)\]
+    /// Add an xWMA buffer (with `XAUDIO2_BUFFER_WMA::pDecodedPacketCumulativeBytes`) to an xWMA source voice's queue.
+    ///
+    /// The buffer is validated as per [XwmaSourceFormat::packets], and regions are measured in decoded samples.
+    /// xWMA isn't decoded: the buffer plays as silence of the decoded length.
+    pub fn submit_xwma_buffer_raw(&mut self, voice: VoiceHandle, mut buffer: Buffer<Tag>, decoded_packet_cumulative_bytes: &[u32]) -> Result<(), HResultError> {
+        let v = self.source_mut(voice)?;
+        let xwma = v.xwma.as_ref().ok_or(E::INVALIDARG)?;
+        xwma.packets(buffer.audio_data.len(), decoded_packet_cumulative_bytes)?;
+        let samples = xwma.samples(decoded_packet_cumulative_bytes) as usize * v.input_channels as usize;
+        buffer.audio_data = alloc::vec![0u8; samples * 4].into();
+        v.enqueue(buffer)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-flushsourcebuffers)\]
+    /// Remove pending buffers.  The currently playing buffer is kept unless the voice is stopped.
+    /// Flushed buffers still receive [BufferEvent::BufferEnd] during the next pass.
+    pub fn flush_source_buffers(&mut self, voice: VoiceHandle) -> Result<(), HResultError> {
+        let v = self.source_mut(voice)?;
+        let keep = if v.started && matches!(v.queue.front(), Some(b) if b.started) { 1 } else { 0 };
+        let flushed = v.queue.drain(keep..).map(|b| b.buffer.context).collect::<Vec<_>>();
+        v.flushed.extend(flushed);
+        Ok(())
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-discontinuity)\]
+    /// Mark the last queued buffer as the end of the stream.
+    pub fn discontinuity(&mut self, voice: VoiceHandle) -> Result<(), HResultError> {
+        if let Some(b) = self.source_mut(voice)?.queue.back_mut() { b.buffer.end_of_stream = true }
+        Ok(())
+    }
+
+    /// Is a source voice started?
+    pub fn is_started(&self, voice: VoiceHandle) -> bool { matches!(self.voices.get(&voice), Some(v) if v.started) }
+
+    /// `XAUDIO2_VOICE_STATE::BuffersQueued`: buffers submitted to a source voice but not yet finished.
+    pub fn buffers_queued(&self, voice: VoiceHandle) -> usize { self.voices.get(&voice).map_or(0, |v| v.queue.len()) }
+
+    /// Is the last buffer queued on a source voice flagged [END_OF_STREAM]?
+    pub fn end_of_stream_queued(&self, voice: VoiceHandle) -> bool { matches!(self.voices.get(&voice).and_then(|v| v.queue.back()), Some(b) if b.buffer.end_of_stream) }
+
+    /// `XAUDIO2_VOICE_STATE::SamplesPlayed`: frames a source voice has consumed so far.
+    pub fn samples_played(&self, voice: VoiceHandle) -> u64 { self.voices.get(&voice).map_or(0, |v| v.samples_played) }
+
+    /// `XAUDIO2_VOICE_STATE::pCurrentBufferContext`: the context of the buffer a source voice is currently processing.
+    pub fn current_context(&self, voice: VoiceHandle) -> Option<Tag> { Some(self.voices.get(&voice)?.queue.front()?.buffer.context) }
+
+    /// Source voices, in creation order.
+    pub fn source_voices(&self) -> impl Iterator<Item = VoiceHandle> + '_ { self.voices.iter().filter(|(_, v)| v.kind == Kind::Source).map(|(h, _)| *h) }
+
+    /// The mastering voice's `(channels, sample_rate)`, if it exists.
+    pub fn output_format(&self) -> Option<(u32, u32)> { self.master().map(|m| (self.voices[&m].output_channels, self.voices[&m].input_sample_rate)) }
+
+
+
+    /// Fill `output` with the mastering voice's interleaved output, running as many processing passes as necessary.
+    ///
+    /// Samples from a partially consumed pass are kept for the next call.
+    /// Without a mastering voice, `output` is silenced.
+    pub fn render(&mut self, output: &mut [f32]) { self.render_with(output, |_, _| {}) }
+
+    /// [render](Self::render), reporting buffer events as they happen.
+    pub fn render_with(&mut self, output: &mut [f32], mut on_event: impl FnMut(VoiceHandle, BufferEvent<Tag>)) {
+        let mut written = 0;
+        while written < output.len() {
+            if self.output.is_empty() {
+                self.begin_pass();
+                for voice in self.source_voices().collect::<Vec<_>>() {
+                    for event in self.process_voice(voice) { on_event(voice, event) }
+                }
+                let pass = self.end_pass();
+                if pass.is_empty() { break }
+                self.output.extend(pass);
+            }
+            let n = self.output.len().min(output.len() - written);
+            for (o, s) in output[written ..][.. n].iter_mut().zip(self.output.drain(.. n)) { *o = s }
+            written += n;
+        }
+        output[written ..].fill(0.0);
+    }
+
+    /// Begin a processing pass: clears submix/mastering inputs and determines how many frames each started source voice will consume.
+    ///
+    /// Between [begin_pass](Self::begin_pass) and [process_voice](Self::process_voice), [bytes_required](Self::bytes_required)
+    /// reports how much more audio each source voice needs to avoid starving - buffers submitted in the meantime are consumed this pass.
+    pub fn begin_pass(&mut self) {
+        let pass = self.passes;
+        self.passes += 1;
+        self.in_pass = true;
+        for v in self.voices.values_mut() {
+            v.mix.clear();
+            if v.kind != Kind::Source {
+                v.mix.resize(v.input_channels as usize * pass_frames(v.input_sample_rate, pass), 0.0);
+            }
+            v.pass_frames = Some(0);
+            if v.kind == Kind::Source && v.started {
+                v.fraction += f64::from(v.input_sample_rate) * f64::from(v.frequency_ratio) / PASSES_PER_SECOND as f64;
+                let frames = v.fraction.floor();
+                v.fraction -= frames;
+                v.pass_frames = Some(frames as u32);
+            }
+        }
+    }
+
+    /// `OnVoiceProcessingPassStart`'s `BytesRequired`: bytes of audio `voice` is short of for the current pass.
+    pub fn bytes_required(&self, voice: VoiceHandle) -> u32 {
+        let v = match self.voices.get(&voice) { Some(v) => v, None => return 0 };
+        let missing = u64::from(v.pass_frames.unwrap_or(0)).saturating_sub(v.queued_frames());
+        let bytes = match (v.adpcm.as_ref(), v.xwma.as_ref()) {
+            (Some(adpcm), _) => {
+                let samples_per_block = u64::from(adpcm.samples_per_block());
+                let blocks = missing / samples_per_block + u64::from(missing % samples_per_block != 0); // round up to whole blocks
+                blocks.saturating_mul(adpcm.block_align().into())
+            },
+            (_, Some(xwma))  => missing.saturating_mul(xwma.wave_format().nAvgBytesPerSec.into()) / u64::from(v.input_sample_rate).max(1), // approximate: xWMA is variable bitrate
+            (None, None)     => missing.saturating_mul(u64::from(v.block_align())),
+        };
+        bytes.min(u64::from(u32::MAX)) as u32
+    }
+
+    /// Consume the current pass's audio from a source voice's queue, mixing it into the voice's destinations.
+    /// Returns the buffer events generated, in order.  Voices are only processed once per pass.
+    pub fn process_voice(&mut self, voice: VoiceHandle) -> Vec<BufferEvent<Tag>> {
+        let v = match self.voices.get_mut(&voice) { Some(v) => v, None => return Vec::new() };
+        let mut events = v.flushed.drain(..).map(BufferEvent::BufferEnd).collect::<Vec<_>>();
+        let frames = match (self.in_pass, v.pass_frames.take()) { (true, Some(frames)) if frames > 0 => frames, _ => return events };
+        let input = v.consume(frames, &mut events);
+        let _ = self.mix_voice(voice, input);
+        events
+    }
+
+    /// Finish the current processing pass: processes any source voices [process_voice](Self::process_voice) skipped
+    /// (discarding their events), then submix voices by processing stage, then the mastering voice.
+    ///
+    /// Returns the mastering voice's interleaved output for this pass (empty if there's no mastering voice.)
+    pub fn end_pass(&mut self) -> Vec<f32> {
+        if !self.in_pass { return Vec::new() }
+        for voice in self.source_voices().collect::<Vec<_>>() {
+            if matches!(self.voices[&voice].pass_frames, Some(f) if f > 0) { let _ = self.process_voice(voice); }
+        }
+        let mut submixes = self.voices.iter().filter(|(_, v)| v.kind == Kind::Submix).map(|(h, v)| (v.processing_stage, *h)).collect::<Vec<_>>();
+        submixes.sort();
+        for (_, voice) in submixes {
+            let input = core::mem::take(&mut self.voices.get_mut(&voice).unwrap().mix);
+            let _ = self.mix_voice(voice, input);
+        }
+        self.in_pass = false;
+        match self.master() {
+            None            => Vec::new(),
+            Some(master)    => {
+                let input = core::mem::take(&mut self.voices.get_mut(&master).unwrap().mix);
+                self.mix_voice(master, input)
+            },
+        }
+    }
+
+    /// Run `voice`'s DSP on this pass's interleaved `input`, mix it into its destinations, and return its output.
+    fn mix_voice(&mut self, voice: VoiceHandle, input: Vec<f32>) -> Vec<f32> {
+        let pass = self.passes - 1;
+        let out_rate = {
+            let v = &self.voices[&voice];
+            v.sends.first().map_or(v.input_sample_rate, |s| self.voices[&s.dest].input_sample_rate)
+        };
+        let frames = pass_frames(out_rate, pass);
+        let v = self.voices.get_mut(&voice).unwrap();
+        let (in_ch, out_ch) = (v.input_channels as usize, v.output_channels as usize);
+
+        let mut samples = alloc::vec![0.0; in_ch * frames];
+        v.resampler.process(in_ch, &input, &mut samples);
+        if let Some((parameters, filter)) = v.filter.as_mut() { filter.process(parameters, &mut samples, in_ch) }
+
+        if out_ch != in_ch {
+            let mut remapped = alloc::vec![0.0; out_ch * frames];
+            for (i, o) in samples.chunks_exact(in_ch).zip(remapped.chunks_exact_mut(out_ch)) {
+                let n = in_ch.min(out_ch);
+                o[..n].copy_from_slice(&i[..n]);
+            }
+            samples = remapped;
+        }
+
+        for frame in samples.chunks_exact_mut(out_ch) {
+            for (s, channel_volume) in frame.iter_mut().zip(v.channel_volumes.iter()) { *s *= v.volume * channel_volume }
+        }
+
+        let mut mixes = Vec::with_capacity(v.sends.len());
+        for send in v.sends.iter_mut() {
+            let mut sent = samples.clone();
+            if let Some((parameters, filter)) = send.filter.as_mut() { filter.process(parameters, &mut sent, out_ch) }
+            let dest_ch = send.matrix.len() / out_ch;
+            let mut mixed = alloc::vec![0.0; dest_ch * frames];
+            for (s, d) in sent.chunks_exact(out_ch).zip(mixed.chunks_exact_mut(dest_ch)) {
+                for (dc, d) in d.iter_mut().enumerate() {
+                    *d = s.iter().zip(&send.matrix[dc * out_ch ..][.. out_ch]).map(|(s, l)| s * l).sum();
+                }
+            }
+            mixes.push((send.dest, mixed));
+        }
+
+        for (dest, mixed) in mixes {
+            let dest = self.voices.get_mut(&dest).unwrap();
+            for (d, s) in dest.mix.iter_mut().zip(mixed) { *d += s }
+        }
+        samples
+    }
+}
+
+impl<Tag: Copy> Voice<Tag> {
+    fn new(kind: Kind, input_channels: u32, input_sample_rate: u32) -> Self {
+        Self {
+            kind, input_channels, input_sample_rate,
+            output_channels:        input_channels,
+            processing_stage:       0,
+            sends:                  Vec::new(),
+            volume:                 1.0,
+            channel_volumes:        alloc::vec![1.0; input_channels as usize],
+            filter:                 None,
+            resampler:              Resampler::new(),
+            mix:                    Vec::new(),
+            format:                 None,
+            adpcm:                  None,
+            xwma:                   None,
+            max_frequency_ratio:    1.0,
+            frequency_ratio:        1.0,
+            started:                false,
+            queue:                  VecDeque::new(),
+            flushed:                Vec::new(),
+            samples_played:         0,
+            fraction:               0.0,
+            pass_frames:            None,
+        }
+    }
+
+    /// Validate and queue a buffer of (decoded, if necessary) audio data.
+    fn enqueue(&mut self, buffer: Buffer<Tag>) -> Result<(), HResultError> {
+        let block_align = usize::from(self.block_align());
+        let bytes = buffer.audio_data.len();
+        if self.queue.len() >= MAX_QUEUED_BUFFERS                                   { return Err(E::INVALIDARG) }
+        if bytes == 0 || bytes % block_align != 0 || bytes / block_align > u32::MAX as usize { return Err(E::INVALIDARG) }
+        let frames = (bytes / block_align) as u32;
+        let play_end = if buffer.play_length == 0 { frames } else { buffer.play_begin.saturating_add(buffer.play_length) };
+        if buffer.play_begin >= frames || play_end > frames                         { return Err(E::INVALIDARG) }
+        if buffer.loop_count > LOOP_INFINITE_RAW                                    { return Err(E::INVALIDARG) }
+        if buffer.loop_count != 0 {
+            let loop_end = if buffer.loop_length == 0 { play_end } else { buffer.loop_begin.saturating_add(buffer.loop_length) };
+            if buffer.loop_begin < buffer.play_begin || buffer.loop_begin >= loop_end || loop_end > play_end { return Err(E::INVALIDARG) }
+        }
+        self.queue.push_back(Queued { position: buffer.play_begin, loops_remaining: buffer.loop_count, started: false, frames, buffer });
+        Ok(())
+    }
+
+    /// Bytes per frame of a source voice's format (`0` for other voices.)
+    fn block_align(&self) -> u16 { self.format.as_ref().map_or(0, |f| f.wave_format().nBlockAlign) }
+
+    /// Frames of audio queued but not yet consumed (saturating at [u32::MAX] for infinite loops.)
+    fn queued_frames(&self) -> u64 {
+        self.queue.iter().map(|b| {
+            if b.loops_remaining == LOOP_INFINITE_RAW { return u64::from(u32::MAX) }
+            let loop_len = u64::from(b.loop_end() - b.buffer.loop_begin);
+            u64::from(b.play_end() - b.position) + loop_len * u64::from(b.loops_remaining)
+        }).fold(0, u64::saturating_add)
+    }
+
+    /// Consume `frames` of queued audio, returning it decoded (padded with silence if the queue runs dry.)
+    fn consume(&mut self, mut frames: u32, events: &mut Vec<BufferEvent<Tag>>) -> Vec<f32> {
+        let (channels, block_align) = (self.input_channels as usize, usize::from(self.block_align()));
+        let mut decoded = Vec::with_capacity(frames as usize * channels);
+
+        while let Some(b) = self.queue.front_mut() {
+            if !b.started {
+                b.started = true;
+                events.push(BufferEvent::BufferStart(b.buffer.context));
+            }
+            let end = if b.loops_remaining > 0 { b.loop_end() } else { b.play_end() };
+            let take = frames.min(end.saturating_sub(b.position));
+            let bytes = &b.buffer.audio_data[b.position as usize * block_align ..][.. take as usize * block_align];
+            if let Some(format) = &self.format { decode(format, bytes, &mut decoded) }
+            b.position += take;
+            frames -= take;
+            self.samples_played += u64::from(take);
+
+            if b.position < end { break } // out of frames for this pass
+            if b.loops_remaining > 0 {
+                events.push(BufferEvent::LoopEnd(b.buffer.context));
+                if b.loops_remaining != LOOP_INFINITE_RAW { b.loops_remaining -= 1 }
+                b.position = b.buffer.loop_begin;
+                if frames == 0 { break }
+            } else {
+                let b = self.queue.pop_front().unwrap();
+                events.push(BufferEvent::BufferEnd(b.buffer.context));
+                if b.buffer.end_of_stream { events.push(BufferEvent::StreamEnd) }
+            }
+        }
+
+        decoded.resize(decoded.len() + frames as usize * channels, 0.0);
+        decoded
+    }
+}
+
+impl<Tag> Queued<Tag> {
+    fn play_end(&self) -> u32 { if self.buffer.play_length == 0 { self.frames } else { self.buffer.play_begin + self.buffer.play_length } }
+    fn loop_end(&self) -> u32 { if self.buffer.loop_length == 0 { self.play_end() } else { self.buffer.loop_begin + self.buffer.loop_length } }
+}
+
+/// Frames processed at `sample_rate` during pass `pass`.  Fractional rates alternate, so every voice at a given rate agrees.
+fn pass_frames(sample_rate: u32, pass: u64) -> usize {
+    let rate = u64::from(sample_rate);
+    ((pass + 1) * rate / PASSES_PER_SECOND - pass * rate / PASSES_PER_SECOND) as usize
+}
+
+/// A rough approximation of XAudio2's default channel mapping: identity where channels line up, mono is sent to front left/right, and stereo is averaged down to mono.
+pub fn default_matrix(source_channels: u32, destination_channels: u32) -> Vec<f32> {
+    let (s, d) = (source_channels as usize, destination_channels as usize);
+    let mut m = alloc::vec![0.0; s * d];
+    match (s, d) {
+        (1, d) if d >= 2    => { m[0] = 1.0; m[1] = 1.0; },
+        (2, 1)              => { m[0] = 0.5; m[1] = 0.5; },
+        (s, d)              => for c in 0 .. s.min(d) { m[c + s * c] = 1.0 },
+    }
+    m
+}
+
+
+
+#[test] fn graph() {
+    let mut r = Renderer::<u32>::new();
+    let master  = r.create_mastering_voice(2, 1000).unwrap();
+    let submix  = r.create_submix_voice(1, 500, 0, None).unwrap();
+    let format  = TypedSourceFormat::<[f32; 1]>::pcm(1000);
+    let voice   = r.create_source_voice(&format, 2.0, Some(&[submix][..])).unwrap();
+    assert!(r.create_source_voice(&format, 2.0, Some(&[submix, master][..])).is_err(), "destinations must share a sample rate");
+    assert!(r.destroy_voice(submix).is_err(), "still a destination");
+
+    r.set_volume(voice, 0.5).unwrap();
+    r.set_output_matrix(submix, master, 1, 2, &[1.0, -1.0]).unwrap();
+    let ones = [1.0f32; 40];
+    r.submit_source_buffer(voice, END_OF_STREAM, bytemuck::cast_slice::<f32, u8>(&ones), .., None, None, 7).unwrap();
+    r.start(voice).unwrap();
+
+    let mut events = Vec::new();
+    let mut out = [0.0; 2 * 50]; // 5 passes of 10 frames @ 1 kHz
+    r.render_with(&mut out, |v, e| events.push((v, e)));
+    assert_eq!(events, [(voice, BufferEvent::BufferStart(7)), (voice, BufferEvent::BufferEnd(7)), (voice, BufferEvent::StreamEnd)]);
+    assert_eq!(out[2 * 10 ..][.. 2], [0.5, -0.5], "1 kHz source → 500 Hz submix → 1 kHz master");
+    assert_eq!(out[2 * 49 ..], [0.0, 0.0], "starved");
+    assert_eq!(r.samples_played(voice), 40);
+
+    r.set_frequency_ratio(voice, 9.0).unwrap();
+    assert_eq!(r.get_frequency_ratio(voice), Some(2.0), "clamped to max_frequency_ratio");
+}
+
+#[test] fn loops_and_flush() {
+    let mut r = Renderer::<u32>::new();
+    let _master = r.create_mastering_voice(1, 1000).unwrap();
+    let format  = TypedSourceFormat::<[u8; 1]>::pcm(1000);
+    let voice   = r.create_source_voice(&format, 1.0, None).unwrap();
+    r.submit_source_buffer(voice, 0, alloc::vec![128u8; 25], .., SampleRange::try_from(5_u32 .. 15).unwrap(), LoopCount::try_from(2_u8).unwrap(), 1).unwrap();
+    r.submit_source_buffer(voice, 0, alloc::vec![128u8; 10], .., None, None, 2).unwrap();
+    r.start(voice).unwrap();
+
+    let mut events = Vec::new();
+    r.render_with(&mut [0.0; 50], |_, e| events.push(e)); // 0..5 + 5..15 (loop) + 5..15 (loop) + 5..25 + second buffer
+    assert_eq!(events, [BufferEvent::BufferStart(1), BufferEvent::LoopEnd(1), BufferEvent::LoopEnd(1), BufferEvent::BufferEnd(1), BufferEvent::BufferStart(2)]);
+
+    r.submit_source_buffer(voice, 0, alloc::vec![128u8; 10], .., None, None, 3).unwrap();
+    r.stop(voice).unwrap();
+    r.flush_source_buffers(voice).unwrap();
+    assert_eq!(r.buffers_queued(voice), 0);
+    events.clear();
+    r.render_with(&mut [0.0; 10], |_, e| events.push(e));
+    assert_eq!(events, [BufferEvent::BufferEnd(2), BufferEvent::BufferEnd(3)]);
+}
@@ -0,0 +1,73 @@
+use alloc::vec::Vec;
+
+
+
+/// Linear interpolating sample rate converter.
+///
+/// Each call stretches however many input frames a processing pass consumed over however many output frames it produces.
+/// The last input frame of the previous call is retained, so consecutive passes interpolate seamlessly.
+/// (XAudio2 itself uses a higher quality polyphase filter - this trades fidelity for simple, deterministic output.)
+#[derive(Clone, Debug, Default)] pub struct Resampler {
+    last: Vec<f32>,
+}
+
+impl Resampler {
+    /// Create a new resampler, with silence as the "previous" frame.
+    pub fn new() -> Self { Self::default() }
+
+    /// Forget the previous frame (e.g. after a discontinuity.)
+    pub fn reset(&mut self) { self.last.clear() }
+
+    /// Resample interleaved `input` into interleaved `output`, both of `channels` channels.
+    ///
+    /// When both contain the same number of frames, `input` is copied as-is.
+    /// When `input` is empty, `output` is silenced.
+    pub fn process(&mut self, channels: usize, input: &[f32], output: &mut [f32]) {
+        if channels == 0 { return }
+        if self.last.len() != channels { self.last = alloc::vec![0.0; channels] }
+        let n = input .len() / channels;
+        let m = output.len() / channels;
+
+        if n == 0 {
+            output.fill(0.0);
+            self.last.fill(0.0);
+            return;
+        } else if n == m {
+            output.copy_from_slice(&input[.. n * channels]);
+        } else {
+            // output frame `j` samples input position `(j+1)*n/m - 1`, where position `-1` is the previous call's last frame
+            for (j, out) in output.chunks_exact_mut(channels).enumerate() {
+                let p = (j + 1) as f64 * n as f64 / m as f64 - 1.0;
+                let i = p.floor();
+                let t = (p - i) as f32;
+                let i = i as isize;
+                let b = &input[(i + 1).min(n as isize - 1) as usize * channels ..][.. channels];
+                for c in 0 .. channels {
+                    let a = if i < 0 { self.last[c] } else { input[i as usize * channels + c] };
+                    out[c] = a + (b[c] - a) * t;
+                }
+            }
+        }
+        self.last.copy_from_slice(&input[(n - 1) * channels .. n * channels]);
+    }
+}
+
+
+
+#[test] fn resample() {
+    let mut r = Resampler::new();
+    let mut out = [0.0; 4];
+    r.process(1, &[1.0, 2.0, 3.0, 4.0], &mut out);
+    assert_eq!(out, [1.0, 2.0, 3.0, 4.0]);
+
+    let mut out = [0.0; 4];
+    r.process(1, &[6.0, 8.0], &mut out); // upsample 2x, continuing from 4.0
+    assert_eq!(out, [5.0, 6.0, 7.0, 8.0]);
+
+    let mut out = [0.0; 4];
+    r.process(2, &[1.0, -1.0, 3.0, -3.0, 5.0, -5.0, 7.0, -7.0], &mut out); // downsample 2x, stereo
+    assert_eq!(out, [3.0, -3.0, 7.0, -7.0]);
+
+    r.process(2, &[], &mut out);
+    assert_eq!(out, [0.0; 4]);
+}
@@ -0,0 +1,618 @@
+use super::*;
+use crate::portable::*;
+
+use winresult::*;
+
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::Deref;
+use std::sync::{Mutex, MutexGuard};
+
+
+
+/// `XAUDIO2_COMMIT_NOW`: apply an operation immediately, instead of deferring it until [Engine::commit_changes].
+pub const COMMIT_NOW            : u32 = 0;
+
+/// `XAUDIO2_COMMIT_ALL`: have [Engine::commit_changes] apply every pending operation set.
+pub const COMMIT_ALL            : u32 = 0;
+
+/// `XAUDIO2_INVALID_OPSET`: never a valid operation set.
+pub const INVALID_OPSET         : u32 = !0;
+
+/// `XAUDIO2_VOICE_USEFILTER`: enables [Voice::set_filter_parameters].
+pub const VOICE_USEFILTER       : u32 = 0x0008;
+
+/// `XAUDIO2_PLAY_TAILS`: accepted by [SourceVoiceUntyped::stop] (effects aren't run, so there are no tails to play.)
+pub const PLAY_TAILS            : u32 = 0x0020;
+
+/// `XAUDIO2_SEND_USEFILTER`: enables [Voice::set_output_filter_parameters] for a send.
+pub const SEND_USEFILTER        : u32 = 0x0080;
+
+/// `XAUDIO2_VOICE_NOSAMPLESPLAYED`: skips [VoiceState::SamplesPlayed] in [SourceVoiceUntyped::get_state].
+pub const VOICE_NOSAMPLESPLAYED : u32 = 0x0100;
+
+/// `XAUDIO2_MIN_FREQ_RATIO`: the smallest frequency ratio a source voice plays at.
+pub const MIN_FREQ_RATIO        : f32 = 1.0 / 1024.0;
+
+/// `XAUDIO2_MAX_FREQ_RATIO`: the largest `max_frequency_ratio` a source voice can be created with.
+pub const MAX_FREQ_RATIO        : f32 = 1024.0;
+
+/// `XAUDIO2_MAX_VOLUME_LEVEL`: the largest volume (or negated, the smallest) accepted by [Voice::set_volume] and friends.
+pub const MAX_VOLUME_LEVEL      : f32 = 16777216.0;
+
+#[cfg(windows)] const _ : () = {
+    use thindx_xaudio2_sys::xaudio2_8::*;
+    assert!(COMMIT_NOW              == XAUDIO2_COMMIT_NOW);
+    assert!(COMMIT_ALL              == XAUDIO2_COMMIT_ALL);
+    assert!(INVALID_OPSET           == XAUDIO2_INVALID_OPSET);
+    assert!(VOICE_USEFILTER         == XAUDIO2_VOICE_USEFILTER);
+    assert!(PLAY_TAILS              == XAUDIO2_PLAY_TAILS);
+    assert!(SEND_USEFILTER          == XAUDIO2_SEND_USEFILTER);
+    assert!(VOICE_NOSAMPLESPLAYED   == XAUDIO2_VOICE_NOSAMPLESPLAYED);
+    assert!(MIN_FREQ_RATIO          == XAUDIO2_MIN_FREQ_RATIO);
+    assert!(MAX_FREQ_RATIO          == XAUDIO2_MAX_FREQ_RATIO);
+    assert!(MAX_VOLUME_LEVEL        == XAUDIO2_MAX_VOLUME_LEVEL);
+};
+
+/// A [Renderer] behind the same method surface as `IXAudio2` and its voices (see `xaudio2_9::xaudio2::{Voice, SourceVoiceUntyped}`), on every platform.
+///
+/// Mix graph code (sends, volumes, output matrices, filters, frequency ratios, operation sets) can be written once against these methods,
+/// then golden-tested without an audio device (e.g. on Linux CI) by [render](Self::render)ing offline.
+///
+/// ### Differences from XAudio2
+/// *   Nothing plays: [render](Self::render) synchronously produces the mastering voice's output.
+/// *   Effect chains, voice callbacks, and devices aren't supported - creation methods omit those parameters.
+///     Use [render_with](Self::render_with) for buffer events.
+/// *   Errors (including XAudio2's `XAUDIO2_E_INVALID_CALL` cases) are reported as [E::INVALIDARG].
+/// *   Like XAudio2, voices are destroyed when dropped.  Drop voices before the voices they send to.
+///
+/// ### Example
+/// ```
+/// # use thindx_xaudio2::portable::*;
+/// # use thindx_xaudio2::portable::render::*;
+/// let engine  = Engine::<()>::new();
+/// let _master = engine.create_mastering_voice(2, 48000, 0).unwrap();
+/// let format  = TypedSourceFormat::<[f32; 1]>::pcm(48000);
+/// let voice   = engine.create_source_voice(&format, 0, 1.0, None).unwrap();
+/// let tone    = (0 .. 4800).map(|i| (i as f32 / 10.0).sin()).collect::<Vec<f32>>();
+/// voice.submit_source_buffer(END_OF_STREAM, bytemuck::cast_slice::<f32, u8>(&tone), .., None, None, ()).unwrap();
+/// voice.set_volume(0.5, 1).unwrap();
+/// voice.start(0, 1).unwrap();
+/// engine.commit_changes(1).unwrap();
+///
+/// let mut out = vec![0.0; 2 * 4800]; // stereo, 100ms @ 48 kHz
+/// engine.render(&mut out);
+/// assert_eq!(out[2 * 100 ..][.. 2], [0.5 * tone[100], 0.5 * tone[100]]);
+/// ```
+pub struct Engine<Tag = ()>(Arc<Mutex<Graph<Tag>>>);
+
+/// A voice created by an [Engine], mirroring `xaudio2_9::xaudio2::Voice`.  Destroyed when dropped.
+pub struct Voice<Tag: Copy = ()> {
+    engine: Engine<Tag>,
+    handle: VoiceHandle,
+}
+
+/// A mastering voice created by [Engine::create_mastering_voice].
+pub struct MasteringVoice<Tag: Copy = ()>(Voice<Tag>);
+
+/// A submix voice created by [Engine::create_submix_voice].
+pub struct SubmixVoice<Tag: Copy = ()>(Voice<Tag>);
+
+/// A source voice created by [Engine::create_source_voice], mirroring `xaudio2_9::xaudio2::SourceVoiceUntyped`.
+pub struct SourceVoiceUntyped<Tag: Copy = ()>(Voice<Tag>);
+
+/// `XAUDIO2_SEND_DESCRIPTOR`: a destination for [Engine::create_submix_voice], [Engine::create_source_voice], or [Voice::set_output_voices].
+pub struct SendDescriptor<'a, Tag: Copy = ()> {
+    pub flags:          u32,
+    pub output_voice:   &'a Voice<Tag>,
+}
+
+impl<'a, Tag: Copy> SendDescriptor<'a, Tag> {
+    pub fn new(flags: u32, output_voice: &'a Voice<Tag>) -> Self { Self { flags, output_voice } }
+}
+
+/// `XAUDIO2_VOICE_DETAILS`, as returned by [Voice::get_voice_details].
+#[allow(non_snake_case)] #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)] pub struct VoiceDetails {
+    pub CreationFlags:          u32,
+    pub ActiveFlags:            u32,
+    pub InputChannels:          u32,
+    pub InputSampleRate:        u32,
+}
+
+/// `XAUDIO2_VOICE_STATE`, as returned by [SourceVoiceUntyped::get_state].
+#[allow(non_snake_case)] #[derive(Clone, Copy, Debug, PartialEq, Eq)] pub struct VoiceState<Tag> {
+    pub pCurrentBufferContext:  Option<Tag>,
+    pub BuffersQueued:          u32,
+    pub SamplesPlayed:          u64,
+}
+
+struct Graph<Tag> {
+    renderer:   Renderer<Tag>,
+    voices:     BTreeMap<VoiceHandle, Info>,
+    pending:    Vec<Pending>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)] enum Kind { Source, Submix, Mastering }
+
+struct Info {
+    kind:                   Kind,
+    flags:                  u32,
+    input_channels:         u32,
+    input_sample_rate:      u32,
+    sends:                  Vec<(u32, VoiceHandle)>,
+    max_frequency_ratio:    f32,
+
+    // most recently requested parameters (what `get_*` methods report, uncommitted operation sets included)
+    volume:                 f32,
+    channel_volumes:        Vec<f32>,
+    output_matrices:        BTreeMap<VoiceHandle, Vec<f32>>,
+    filter:                 FilterParameters,
+    output_filters:         BTreeMap<VoiceHandle, FilterParameters>,
+    frequency_ratio:        f32,
+}
+
+struct Pending {
+    operation_set:  u32,
+    voice:          VoiceHandle,
+    op:             Op,
+}
+
+enum Op {
+    Start,
+    Stop,
+    ExitLoop,
+    Volume(f32),
+    ChannelVolumes(Vec<f32>),
+    OutputMatrix(VoiceHandle, Vec<f32>),
+    Filter(FilterParameters),
+    OutputFilter(VoiceHandle, FilterParameters),
+    FrequencyRatio(f32),
+}
+
+fn check_volume(volume: f32) -> Result<(), HResultError> {
+    if (-MAX_VOLUME_LEVEL ..= MAX_VOLUME_LEVEL).contains(&volume) { Ok(()) } else { Err(E::INVALIDARG) }
+}
+
+impl<Tag: Copy> Clone for Engine<Tag> { fn clone(&self) -> Self { Self(Arc::clone(&self.0)) } }
+impl<Tag: Copy> Default for Engine<Tag> { fn default() -> Self { Self::new() } }
+
+impl<Tag: Copy> Engine<Tag> {
+    /// Create a new engine without any voices.
+    pub fn new() -> Self { Self(Arc::new(Mutex::new(Graph { renderer: Renderer::new(), voices: BTreeMap::new(), pending: Vec::new() }))) }
+
+    fn lock(&self) -> MutexGuard<Graph<Tag>> { self.0.lock().unwrap_or_else(|poison| poison.into_inner()) }
+
+    /// Resolve a send list to `(flags, destination)` pairs.  [None] (the mastering voice) resolves to [None].
+    fn send_list(&self, send_list: Option<&[SendDescriptor<Tag>]>) -> Result<Option<Vec<(u32, VoiceHandle)>>, HResultError> {
+        let send_list = match send_list { Some(sl) => sl, None => return Ok(None) };
+        send_list.iter().map(|send| {
+            if send.flags & !SEND_USEFILTER != 0 || !Arc::ptr_eq(&send.output_voice.engine.0, &self.0) { return Err(E::INVALIDARG) }
+            Ok((send.flags, send.output_voice.handle))
+        }).collect::<Result<Vec<_>, _>>().map(Some)
+    }
+
+    fn voice(&self, handle: VoiceHandle) -> Voice<Tag> { Voice { engine: self.clone(), handle } }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createmasteringvoice)\]
+    /// Creates the (only) mastering voice, whose output [render](Self::render) returns.  `flags` must be `0`.
+    pub fn create_mastering_voice(&self, input_channels: u32, input_sample_rate: u32, flags: u32) -> Result<MasteringVoice<Tag>, HResultError> {
+        if flags != 0 { return Err(E::INVALIDARG) }
+        let mut graph = self.lock();
+        let handle = graph.renderer.create_mastering_voice(input_channels, input_sample_rate)?;
+        graph.add_voice(handle, Kind::Mastering, flags, input_channels, input_sample_rate, None);
+        Ok(MasteringVoice(self.voice(handle)))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsubmixvoice)\]
+    /// Creates and configures a submix voice.  [None] sends to the mastering voice.
+    pub fn create_submix_voice(
+        &self,
+        input_channels:     u32,
+        input_sample_rate:  u32,
+        flags:              u32,
+        processing_stage:   u32,
+        send_list:          Option<&[SendDescriptor<Tag>]>,
+    ) -> Result<SubmixVoice<Tag>, HResultError> {
+        if flags & !VOICE_USEFILTER != 0 { return Err(E::INVALIDARG) }
+        let sends = self.send_list(send_list)?;
+        let mut graph = self.lock();
+        let handles = sends.as_ref().map(|s| s.iter().map(|s| s.1).collect::<Vec<_>>());
+        let handle = graph.renderer.create_submix_voice(input_channels, input_sample_rate, processing_stage, handles.as_deref())?;
+        graph.add_voice(handle, Kind::Submix, flags, input_channels, input_sample_rate, sends);
+        Ok(SubmixVoice(self.voice(handle)))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// Creates and configures a source voice.  [None] sends to the mastering voice.
+    pub fn create_source_voice(
+        &self,
+        format:                 &SourceFormat,
+        flags:                  u32,
+        max_frequency_ratio:    f32,
+        send_list:              Option<&[SendDescriptor<Tag>]>,
+    ) -> Result<SourceVoiceUntyped<Tag>, HResultError> {
+        if flags & !VOICE_USEFILTER != 0 { return Err(E::INVALIDARG) }
+        let sends = self.send_list(send_list)?;
+        let mut graph = self.lock();
+        let handles = sends.as_ref().map(|s| s.iter().map(|s| s.1).collect::<Vec<_>>());
+        let handle = graph.renderer.create_source_voice(format, max_frequency_ratio, handles.as_deref())?;
+        let wave_format = format.wave_format();
+        graph.add_voice(handle, Kind::Source, flags, wave_format.nChannels.into(), wave_format.nSamplesPerSec, sends);
+        graph.voices.get_mut(&handle).unwrap().max_frequency_ratio = max_frequency_ratio;
+        Ok(SourceVoiceUntyped(self.voice(handle)))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-commitchanges)\]
+    /// Atomically applies a set of operations that were tagged with `operation_set`, or every pending set for [COMMIT_ALL].
+    pub fn commit_changes(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        if operation_set == INVALID_OPSET { return Err(E::INVALIDARG) }
+        let mut graph = self.lock();
+        let pending = core::mem::take(&mut graph.pending);
+        for p in pending {
+            if operation_set == COMMIT_ALL || p.operation_set == operation_set {
+                graph.apply(p.voice, &p.op);
+            } else {
+                graph.pending.push(p);
+            }
+        }
+        Ok(S::OK)
+    }
+
+    /// Fill `output` with the mastering voice's interleaved output, as per [Renderer::render].
+    pub fn render(&self, output: &mut [f32]) { self.lock().renderer.render(output) }
+
+    /// Fill `output` with the mastering voice's interleaved output, as per [Renderer::render_with].
+    ///
+    /// Events are reported once `output` has been filled (so `on_event` may call back into this engine's voices):
+    /// buffers submitted from `on_event` only play from the next call.
+    pub fn render_with(&self, output: &mut [f32], mut on_event: impl FnMut(VoiceHandle, BufferEvent<Tag>)) {
+        let mut events = Vec::new();
+        self.lock().renderer.render_with(output, |voice, event| events.push((voice, event)));
+        for (voice, event) in events { on_event(voice, event) }
+    }
+}
+
+impl<Tag: Copy> Graph<Tag> {
+    fn info(&self, voice: VoiceHandle) -> Result<&Info, HResultError> { self.voices.get(&voice).ok_or(E::INVALIDARG) }
+
+    fn add_voice(&mut self, handle: VoiceHandle, kind: Kind, flags: u32, input_channels: u32, input_sample_rate: u32, sends: Option<Vec<(u32, VoiceHandle)>>) {
+        let sends = match (kind, sends) {
+            (Kind::Mastering, _)    => Vec::new(),
+            (_, Some(sends))        => sends,
+            (_, None)               => self.voices.iter().filter(|(_, v)| v.kind == Kind::Mastering).map(|(h, _)| (0, *h)).collect(),
+        };
+        if flags & VOICE_USEFILTER != 0 { let _ = self.renderer.set_filter_parameters(handle, FilterParameters::default()); }
+        self.voices.insert(handle, Info {
+            kind, flags, input_channels, input_sample_rate,
+            sends:                  Vec::new(),
+            max_frequency_ratio:    1.0,
+            volume:                 1.0,
+            channel_volumes:        alloc::vec![1.0; input_channels as usize],
+            output_matrices:        BTreeMap::new(),
+            filter:                 FilterParameters::default(),
+            output_filters:         BTreeMap::new(),
+            frequency_ratio:        1.0,
+        });
+        self.set_sends(handle, sends);
+    }
+
+    /// Record `voice`'s sends, which the renderer voice must already be connected to, giving new destinations default matrices and filters.
+    fn set_sends(&mut self, voice: VoiceHandle, sends: Vec<(u32, VoiceHandle)>) {
+        let dest_channels = sends.iter().map(|s| self.voices[&s.1].input_channels).collect::<Vec<_>>();
+        let v = self.voices.get_mut(&voice).unwrap();
+        v.output_matrices.retain(|dest, _| sends.iter().any(|s| s.1 == *dest));
+        v.output_filters .retain(|dest, _| sends.iter().any(|s| s.1 == *dest));
+        for (&(flags, dest), &channels) in sends.iter().zip(dest_channels.iter()) {
+            if v.sends.iter().any(|s| s.1 == dest) { continue }
+            v.output_matrices.insert(dest, default_matrix(v.input_channels, channels));
+            v.output_filters .insert(dest, FilterParameters::default());
+            if flags & SEND_USEFILTER != 0 { let _ = self.renderer.set_output_filter_parameters(voice, dest, FilterParameters::default()); }
+        }
+        v.sends = sends;
+    }
+
+    /// Apply `op` to `voice` immediately, or defer it until [Engine::commit_changes] is called for `operation_set`.
+    fn deferrable(&mut self, voice: VoiceHandle, operation_set: u32, op: Op) -> Result<HResultSuccess, HResultError> {
+        if operation_set == INVALID_OPSET { return Err(E::INVALIDARG) }
+        let v = self.voices.get_mut(&voice).ok_or(E::INVALIDARG)?;
+        match &op {
+            Op::Start | Op::Stop | Op::ExitLoop => {},
+            Op::Volume(vol)                     => v.volume = *vol,
+            Op::ChannelVolumes(vols)            => v.channel_volumes = vols.clone(),
+            Op::OutputMatrix(dest, m)           => { v.output_matrices.insert(*dest, m.clone()); },
+            Op::Filter(f)                       => v.filter = *f,
+            Op::OutputFilter(dest, f)           => { v.output_filters.insert(*dest, *f); },
+            Op::FrequencyRatio(r)               => v.frequency_ratio = *r,
+        }
+        if operation_set == COMMIT_NOW {
+            self.apply(voice, &op);
+        } else {
+            self.pending.push(Pending { operation_set, voice, op });
+        }
+        Ok(S::OK)
+    }
+
+    /// Put `op` into effect for rendering.  Arguments were validated when `op` was requested,
+    /// but deferred operations targeting voices or sends that no longer exist are ignored.
+    fn apply(&mut self, voice: VoiceHandle, op: &Op) {
+        let dest_channels = |dest: &VoiceHandle| self.voices.get(dest).map_or(0, |d| d.input_channels);
+        let r = &mut self.renderer;
+        let _ = match op {
+            Op::Start                   => r.start(voice),
+            Op::Stop                    => r.stop(voice),
+            Op::ExitLoop                => r.exit_loop(voice),
+            Op::Volume(v)               => r.set_volume(voice, *v),
+            Op::ChannelVolumes(v)       => r.set_channel_volumes(voice, v),
+            Op::OutputMatrix(d, m)      => { let channels = dest_channels(d); r.set_output_matrix(voice, *d, m.len() as u32 / channels.max(1), channels, m) },
+            Op::Filter(f)               => r.set_filter_parameters(voice, *f),
+            Op::OutputFilter(d, f)      => r.set_output_filter_parameters(voice, *d, *f),
+            Op::FrequencyRatio(ratio)   => r.set_frequency_ratio(voice, *ratio),
+        };
+    }
+}
+
+impl<Tag: Copy> Voice<Tag> {
+    /// The [Renderer] voice, as reported by [Engine::render_with].
+    pub fn handle(&self) -> VoiceHandle { self.handle }
+
+    /// Resolve `destination_voice` to one of this voice's current sends.
+    fn send_to(&self, graph: &Graph<Tag>, destination_voice: &Voice<Tag>) -> Result<(u32, VoiceHandle), HResultError> {
+        if !Arc::ptr_eq(&self.engine.0, &destination_voice.engine.0) { return Err(E::INVALIDARG) }
+        graph.info(self.handle)?.sends.iter().copied().find(|s| s.1 == destination_voice.handle).ok_or(E::INVALIDARG)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getvoicedetails)\]
+    /// Returns information about the creation flags, input channels, and sample rate of this voice.
+    pub fn get_voice_details(&self) -> VoiceDetails {
+        let graph = self.engine.lock();
+        graph.voices.get(&self.handle).map_or(VoiceDetails::default(), |v| VoiceDetails {
+            CreationFlags:      v.flags,
+            ActiveFlags:        v.flags,
+            InputChannels:      v.input_channels,
+            InputSampleRate:    v.input_sample_rate,
+        })
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputvoices)\]
+    /// Designates a new set of submix or mastering voices to receive the output of this voice.  [None] sends to the mastering voice.
+    pub fn set_output_voices(&self, send_list: Option<&[SendDescriptor<Tag>]>) -> Result<HResultSuccess, HResultError> {
+        let sends = self.engine.send_list(send_list)?;
+        let mut graph = self.engine.lock();
+        let handles = sends.as_ref().map(|s| s.iter().map(|s| s.1).collect::<Vec<_>>());
+        graph.renderer.set_output_voices(self.handle, handles.as_deref())?;
+        let sends = match sends {
+            Some(sends) => sends,
+            None        => graph.voices.iter().filter(|(_, v)| v.kind == Kind::Mastering).map(|(h, _)| (0, *h)).collect(),
+        };
+        graph.set_sends(self.handle, sends);
+        Ok(S::OK)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setfilterparameters)\]
+    /// Sets this voice's filter parameters.  Requires [VOICE_USEFILTER].
+    pub fn set_filter_parameters(&self, parameters: &FilterParameters, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        let mut graph = self.engine.lock();
+        if graph.info(self.handle)?.flags & VOICE_USEFILTER == 0 { return Err(E::INVALIDARG) }
+        graph.deferrable(self.handle, operation_set, Op::Filter(*parameters))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getfilterparameters)\]
+    /// Gets this voice's filter parameters.
+    pub fn get_filter_parameters(&self) -> FilterParameters {
+        self.engine.lock().voices.get(&self.handle).map_or(FilterParameters::default(), |v| v.filter)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputfilterparameters)\]
+    /// Sets the filter parameters on one of this voice's sends.  Requires [SEND_USEFILTER].
+    pub fn set_output_filter_parameters(&self, destination_voice: &Voice<Tag>, parameters: &FilterParameters, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        let mut graph = self.engine.lock();
+        let (flags, dest) = self.send_to(&graph, destination_voice)?;
+        if flags & SEND_USEFILTER == 0 { return Err(E::INVALIDARG) }
+        graph.deferrable(self.handle, operation_set, Op::OutputFilter(dest, *parameters))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getoutputfilterparameters)\]
+    /// Returns the filter parameters from one of this voice's sends.
+    pub fn get_output_filter_parameters(&self, destination_voice: &Voice<Tag>) -> FilterParameters {
+        let graph = self.engine.lock();
+        let filter = graph.voices.get(&self.handle).and_then(|v| v.output_filters.get(&destination_voice.handle));
+        match filter { Some(f) if Arc::ptr_eq(&self.engine.0, &destination_voice.engine.0) => *f, _ => FilterParameters::default() }
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setvolume)\]
+    /// Sets the overall volume level for this voice.
+    pub fn set_volume(&self, volume: f32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        check_volume(volume)?;
+        self.engine.lock().deferrable(self.handle, operation_set, Op::Volume(volume))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getvolume)\]
+    /// Gets this voice's current overall volume level.
+    pub fn get_volume(&self) -> f32 { self.engine.lock().voices.get(&self.handle).map_or(1.0, |v| v.volume) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setchannelvolumes)\]
+    /// Sets the volume levels for this voice's output channels.
+    pub fn set_channel_volumes(&self, volumes: &[f32], operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        let mut graph = self.engine.lock();
+        if volumes.len() != graph.info(self.handle)?.channel_volumes.len() { return Err(E::INVALIDARG) }
+        for v in volumes.iter().copied() { check_volume(v)? }
+        graph.deferrable(self.handle, operation_set, Op::ChannelVolumes(volumes.to_vec()))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getchannelvolumes)\]
+    /// Returns the volume levels for this voice's output channels.  `volumes.len()` must match this voice's output channel count.
+    pub fn get_channel_volumes(&self, volumes: &mut [f32]) {
+        let graph = self.engine.lock();
+        if let Some(v) = graph.voices.get(&self.handle) { if v.channel_volumes.len() == volumes.len() { volumes.copy_from_slice(&v.channel_volumes) } }
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputmatrix)\]
+    /// Sets the volume level of each channel of this voice's send to `destination_voice`.
+    /// `level_matrix[d * source_channels + s]` is the level of source channel `s` in destination channel `d`.
+    pub fn set_output_matrix(&self, destination_voice: &Voice<Tag>, source_channels: u32, destination_channels: u32, level_matrix: &[f32], operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        let mut graph = self.engine.lock();
+        let (_, dest) = self.send_to(&graph, destination_voice)?;
+        if source_channels      != graph.info(self.handle)?.channel_volumes.len() as u32    { return Err(E::INVALIDARG) }
+        if destination_channels != graph.info(dest)?.input_channels                         { return Err(E::INVALIDARG) }
+        if level_matrix.len()   != (source_channels * destination_channels) as usize        { return Err(E::INVALIDARG) }
+        for v in level_matrix.iter().copied() { check_volume(v)? }
+        graph.deferrable(self.handle, operation_set, Op::OutputMatrix(dest, level_matrix.to_vec()))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2voice-getoutputmatrix)\]
+    /// Gets the volume level of each channel of this voice's send to `destination_voice`.
+    pub fn get_output_matrix(&self, destination_voice: &Voice<Tag>, source_channels: u32, destination_channels: u32, level_matrix: &mut [f32]) {
+        if !Arc::ptr_eq(&self.engine.0, &destination_voice.engine.0) { return }
+        let graph = self.engine.lock();
+        let matrix = match graph.voices.get(&self.handle).and_then(|v| v.output_matrices.get(&destination_voice.handle)) { Some(m) => m, None => return };
+        if matrix.len() == (source_channels * destination_channels) as usize && matrix.len() == level_matrix.len() { level_matrix.copy_from_slice(matrix) }
+    }
+}
+
+impl<Tag: Copy> SourceVoiceUntyped<Tag> {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-start)\]
+    /// Makes this voice start consuming and processing audio.  `flags` must be `0`.
+    pub fn start(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        if flags != 0 { return Err(E::INVALIDARG) }
+        self.engine.lock().deferrable(self.handle, operation_set, Op::Start)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-stop)\]
+    /// Makes this voice stop consuming audio.  `flags` may include [PLAY_TAILS].
+    pub fn stop(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        if flags & !PLAY_TAILS != 0 { return Err(E::INVALIDARG) }
+        self.engine.lock().deferrable(self.handle, operation_set, Op::Stop)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-submitsourcebuffer)\]
+    /// Adds a new audio buffer to this voice's input queue, as per [Renderer::submit_source_buffer].
+    pub fn submit_source_buffer(
+        &self,
+        flags:          u32,
+        audio_data:     impl Into<Arc<[u8]>>,
+        play_range:     impl Into<SampleRange>,
+        loop_range:     impl Into<SampleRange>,
+        loop_count:     impl Into<LoopCount>,
+        context:        Tag,
+    ) -> Result<HResultSuccess, HResultError> {
+        self.engine.lock().renderer.submit_source_buffer(self.handle, flags, audio_data, play_range, loop_range, loop_count, context)?;
+        Ok(S::OK)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-flushsourcebuffers)\]
+    /// Removes all pending audio buffers from this voice's queue.
+    pub fn flush_source_buffers(&self) -> Result<HResultSuccess, HResultError> {
+        self.engine.lock().renderer.flush_source_buffers(self.handle)?;
+        Ok(S::OK)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-discontinuity)\]
+    /// Notifies the voice of an intentional break in the stream of audio buffers.
+    pub fn discontinuity(&self) -> Result<HResultSuccess, HResultError> {
+        self.engine.lock().renderer.discontinuity(self.handle)?;
+        Ok(S::OK)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-exitloop)\]
+    /// Breaks out of the current loop when its end is reached.
+    pub fn exit_loop(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        self.engine.lock().deferrable(self.handle, operation_set, Op::ExitLoop)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getstate)\]
+    /// Returns the number of buffers currently queued on this voice, the context of the currently processing buffer (if any), and the samples played so far.
+    pub fn get_state(&self, flags: u32) -> VoiceState<Tag> {
+        let graph = self.engine.lock();
+        let r = &graph.renderer;
+        VoiceState {
+            pCurrentBufferContext:  r.current_context(self.handle),
+            BuffersQueued:          r.buffers_queued(self.handle) as u32,
+            SamplesPlayed:          if flags & VOICE_NOSAMPLESPLAYED != 0 { 0 } else { r.samples_played(self.handle) },
+        }
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setfrequencyratio)\]
+    /// Sets this voice's frequency adjustment, i.e. its pitch, clamped to [MIN_FREQ_RATIO]` ..= max_frequency_ratio` like XAudio2 does.
+    pub fn set_frequency_ratio(&self, ratio: f32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        if ratio.is_nan() { return Err(E::INVALIDARG) }
+        let mut graph = self.engine.lock();
+        let ratio = ratio.max(MIN_FREQ_RATIO).min(graph.info(self.handle)?.max_frequency_ratio);
+        graph.deferrable(self.handle, operation_set, Op::FrequencyRatio(ratio))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getfrequencyratio)\]
+    /// Returns the frequency adjustment ratio of this voice.
+    pub fn get_frequency_ratio(&self) -> f32 { self.engine.lock().voices.get(&self.handle).map_or(1.0, |v| v.frequency_ratio) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setsourcesamplerate)\]
+    /// Reconfigures the voice to consume source data at a different sample rate than the rate specified when the voice was created.
+    /// Fails if buffers are still queued.
+    pub fn set_source_sample_rate(&self, new_source_sample_rate: u32) -> Result<HResultSuccess, HResultError> {
+        let mut graph = self.engine.lock();
+        graph.renderer.set_source_sample_rate(self.handle, new_source_sample_rate)?;
+        if let Some(v) = graph.voices.get_mut(&self.handle) { v.input_sample_rate = new_source_sample_rate }
+        Ok(S::OK)
+    }
+}
+
+impl<Tag: Copy> Drop for Voice<Tag> {
+    fn drop(&mut self) {
+        let mut graph = self.engine.lock();
+        if graph.renderer.destroy_voice(self.handle).is_err() { return } // still sent to by another voice
+        graph.voices.remove(&self.handle);
+        graph.pending.retain(|p| p.voice != self.handle);
+    }
+}
+
+impl<Tag: Copy> Deref for MasteringVoice    <Tag> { type Target = Voice<Tag>; fn deref(&self) -> &Voice<Tag> { &self.0 } }
+impl<Tag: Copy> Deref for SubmixVoice       <Tag> { type Target = Voice<Tag>; fn deref(&self) -> &Voice<Tag> { &self.0 } }
+impl<Tag: Copy> Deref for SourceVoiceUntyped<Tag> { type Target = Voice<Tag>; fn deref(&self) -> &Voice<Tag> { &self.0 } }
+
+
+
+#[test] fn voices() {
+    let engine  = Engine::<u32>::new();
+    let master  = engine.create_mastering_voice(1, 48000, 0).unwrap();
+    let submix  = engine.create_submix_voice(1, 48000, 0, 0, None).unwrap();
+    let sends   = [SendDescriptor::new(0, &submix)];
+    let format  = TypedSourceFormat::<[f32; 1]>::pcm(48000);
+    let voice   = engine.create_source_voice(&format, 0, 2.0, Some(&sends)).unwrap();
+    voice.submit_source_buffer(END_OF_STREAM, bytemuck::cast_slice::<f32, u8>(&[1.0; 960]), .., None, None, 42).unwrap();
+
+    // deferred operations report their latest values, but only apply when committed
+    voice.start(0, COMMIT_NOW).unwrap();
+    voice.set_volume(0.5, 7).unwrap();
+    voice.set_output_matrix(&submix, 1, 1, &[0.5], 7).unwrap();
+    voice.set_frequency_ratio(9.0, 8).unwrap();
+    assert_eq!(voice.get_volume(), 0.5);
+    assert_eq!(voice.get_frequency_ratio(), 2.0, "clamped to max_frequency_ratio");
+    let mut matrix = [0.0];
+    voice.get_output_matrix(&submix, 1, 1, &mut matrix);
+    assert_eq!(matrix, [0.5]);
+
+    let mut out = [0.0; 480]; // 1 pass
+    engine.render(&mut out);
+    assert_eq!(out[240], 1.0);
+    assert_eq!(voice.get_state(0), VoiceState { pCurrentBufferContext: Some(42), BuffersQueued: 1, SamplesPlayed: 480 });
+
+    engine.commit_changes(7).unwrap();
+    engine.render(&mut out);
+    assert_eq!(out[240], 0.25);
+
+    // misuse
+    let other = Engine::<u32>::new();
+    let other_master = other.create_mastering_voice(1, 48000, 0).unwrap();
+    assert_eq!(voice.set_output_matrix(&master,       1, 1, &[1.0], COMMIT_NOW).err(), Some(E::INVALIDARG), "not a send");
+    assert_eq!(voice.set_output_matrix(&other_master, 1, 1, &[1.0], COMMIT_NOW).err(), Some(E::INVALIDARG), "another engine");
+    assert_eq!(voice.set_output_matrix(&submix,       1, 1, &[],    COMMIT_NOW).err(), Some(E::INVALIDARG), "too small");
+    assert_eq!(voice.set_filter_parameters(&FilterParameters::default(), COMMIT_NOW).err(), Some(E::INVALIDARG), "no VOICE_USEFILTER");
+    assert_eq!(voice.set_output_filter_parameters(&submix, &FilterParameters::default(), COMMIT_NOW).err(), Some(E::INVALIDARG), "no SEND_USEFILTER");
+    assert_eq!(voice.set_volume(0.5, INVALID_OPSET).err(), Some(E::INVALIDARG));
+    assert_eq!(engine.create_mastering_voice(1, 48000, 0).err(), Some(E::INVALIDARG), "only one mastering voice");
+
+    // rerouting to the mastering voice uses a default matrix
+    voice.set_output_voices(None).unwrap();
+    voice.get_output_matrix(&master, 1, 1, &mut matrix);
+    assert_eq!(matrix, [1.0]);
+}
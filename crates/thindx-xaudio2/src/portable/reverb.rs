@@ -0,0 +1,399 @@
+use bytemuck::{Pod, Zeroable};
+use winresult::*;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2fx/ns-xaudio2fx-xaudio2fx_reverb_parameters)\]
+/// XAUDIO2FX_REVERB_PARAMETERS: platform independent equivalent with an identical (packed, 57 byte) layout.
+///
+/// This is the XAudio 2.9 layout (including `SideDelay` and `DisableLateField`), as accepted by `xaudio2_9::xaudio2::create_reverb`.
+/// See [reverb_convert_i3dl2_to_native] to convert from an [I3dl2ReverbParameters] preset.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Zeroable, Pod, PartialEq)]
+#[repr(C, packed(1))] pub struct ReverbParameters {
+    /// Percentage of the output that will be reverb (`0.0 ..= 100.0`.)
+    pub WetDryMix:              f32,
+
+    /// Delay of the first reflection relative to the direct path, in milliseconds (`0 ..= 300`.)
+    pub ReflectionsDelay:       u32,
+
+    /// Delay of reverb relative to the first reflection, in milliseconds (`0 ..= 85`.)
+    pub ReverbDelay:            u8,
+
+    /// Delay for the left rear and right rear output, in milliseconds (`0 ..= 5`, or `0 ..= 20` for 7.1 reverb.)
+    pub RearDelay:              u8,
+
+    /// Delay for the left side and right side output, in milliseconds (`0 ..= 5`.)
+    pub SideDelay:              u8,
+
+    /// Position of the left input within the simulated space relative to the listener (`0 ..= 30`.)
+    pub PositionLeft:           u8,
+
+    /// Position of the right input within the simulated space relative to the listener (`0 ..= 30`.)
+    pub PositionRight:          u8,
+
+    /// Gives a greater or lesser impression of distance from the source to the listener (left, `0 ..= 30`.)
+    pub PositionMatrixLeft:     u8,
+
+    /// Gives a greater or lesser impression of distance from the source to the listener (right, `0 ..= 30`.)
+    pub PositionMatrixRight:    u8,
+
+    /// Echo density in the early reflections (`0 ..= 15`.)
+    pub EarlyDiffusion:         u8,
+
+    /// Echo density in the late field reverb (`0 ..= 15`.)
+    pub LateDiffusion:          u8,
+
+    /// Low frequency decay time adjustment (`0 ..= 12`, `8` is no adjustment.)
+    pub LowEQGain:              u8,
+
+    /// Corner frequency of the low pass filter controlled by `LowEQGain` (`0 ..= 9` for `50 ..= 500` Hz.)
+    pub LowEQCutoff:            u8,
+
+    /// High frequency decay time adjustment (`0 ..= 8`, `8` is no adjustment.)
+    pub HighEQGain:             u8,
+
+    /// Corner frequency of the high pass filter controlled by `HighEQGain` (`0 ..= 14` for `1000 ..= 8000` Hz.)
+    pub HighEQCutoff:           u8,
+
+    /// Corner frequency of the room effect low pass filter, in Hz (`20.0 ..= 20000.0`.)
+    pub RoomFilterFreq:         f32,
+
+    /// Pass band intensity level of the room effect low pass filter, in dB (`-100.0 ..= 0.0`.)
+    pub RoomFilterMain:         f32,
+
+    /// Intensity level of the room effect low pass filter at `RoomFilterFreq`, in dB (`-100.0 ..= 0.0`.)
+    pub RoomFilterHF:           f32,
+
+    /// Intensity of the early reflections, in dB (`-100.0 ..= 20.0`.)
+    pub ReflectionsGain:        f32,
+
+    /// Intensity of the late field reverberation, in dB (`-100.0 ..= 20.0`.)
+    pub ReverbGain:             f32,
+
+    /// Reverberation decay time at 1 kHz, in seconds (`0.1 ..`)
+    pub DecayTime:              f32,
+
+    /// Modal density in the late field reverb, as a percentage (`0.0 ..= 100.0`.)
+    pub Density:                f32,
+
+    /// Apparent size of the acoustic space, in feet (`1.0 ..= 100.0`.)
+    pub RoomSize:               f32,
+
+    /// `BOOL`: nonzero disables late field reflection calculations.
+    pub DisableLateField:       i32,
+}
+
+const _ : () = assert!(core::mem::size_of ::<ReverbParameters>() == 57);
+const _ : () = assert!(core::mem::align_of::<ReverbParameters>() == 1);
+
+impl Default for ReverbParameters {
+    fn default() -> Self {
+        Self {
+            WetDryMix:              REVERB_DEFAULT_WET_DRY_MIX,
+            ReflectionsDelay:       REVERB_DEFAULT_REFLECTIONS_DELAY,
+            ReverbDelay:            REVERB_DEFAULT_REVERB_DELAY,
+            RearDelay:              REVERB_DEFAULT_REAR_DELAY,
+            SideDelay:              REVERB_DEFAULT_7POINT1_SIDE_DELAY,
+            PositionLeft:           REVERB_DEFAULT_POSITION,
+            PositionRight:          REVERB_DEFAULT_POSITION,
+            PositionMatrixLeft:     REVERB_DEFAULT_POSITION_MATRIX,
+            PositionMatrixRight:    REVERB_DEFAULT_POSITION_MATRIX,
+            EarlyDiffusion:         REVERB_DEFAULT_EARLY_DIFFUSION,
+            LateDiffusion:          REVERB_DEFAULT_LATE_DIFFUSION,
+            LowEQGain:              REVERB_DEFAULT_LOW_EQ_GAIN,
+            LowEQCutoff:            REVERB_DEFAULT_LOW_EQ_CUTOFF,
+            HighEQGain:             REVERB_DEFAULT_HIGH_EQ_GAIN,
+            HighEQCutoff:           REVERB_DEFAULT_HIGH_EQ_CUTOFF,
+            RoomFilterFreq:         REVERB_DEFAULT_ROOM_FILTER_FREQ,
+            RoomFilterMain:         REVERB_DEFAULT_ROOM_FILTER_MAIN,
+            RoomFilterHF:           REVERB_DEFAULT_ROOM_FILTER_HF,
+            ReflectionsGain:        REVERB_DEFAULT_REFLECTIONS_GAIN,
+            ReverbGain:             REVERB_DEFAULT_REVERB_GAIN,
+            DecayTime:              REVERB_DEFAULT_DECAY_TIME,
+            Density:                REVERB_DEFAULT_DENSITY,
+            RoomSize:               REVERB_DEFAULT_ROOM_SIZE,
+            DisableLateField:       0,
+        }
+    }
+}
+
+impl ReverbParameters {
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if any field is out of range (or NaN.)
+    ///
+    /// `RearDelay` is checked against [REVERB_MAX_7POINT1_REAR_DELAY], since whether the reverb outputs 7.1 isn't known here.
+    pub fn validate(&self) -> Result<(), HResultError> {
+        let valid
+            =  (REVERB_MIN_WET_DRY_MIX          ..= REVERB_MAX_WET_DRY_MIX          ).contains(&{self.WetDryMix})
+            && (REVERB_MIN_REFLECTIONS_DELAY    ..= REVERB_MAX_REFLECTIONS_DELAY    ).contains(&{self.ReflectionsDelay})
+            && (REVERB_MIN_REVERB_DELAY         ..= REVERB_MAX_REVERB_DELAY         ).contains(&self.ReverbDelay)
+            && (REVERB_MIN_7POINT1_REAR_DELAY   ..= REVERB_MAX_7POINT1_REAR_DELAY   ).contains(&self.RearDelay)
+            && (REVERB_MIN_7POINT1_SIDE_DELAY   ..= REVERB_MAX_7POINT1_SIDE_DELAY   ).contains(&self.SideDelay)
+            && [self.PositionLeft, self.PositionRight, self.PositionMatrixLeft, self.PositionMatrixRight].iter().all(|p| (REVERB_MIN_POSITION ..= REVERB_MAX_POSITION).contains(p))
+            && [self.EarlyDiffusion, self.LateDiffusion].iter().all(|d| (REVERB_MIN_DIFFUSION ..= REVERB_MAX_DIFFUSION).contains(d))
+            && (REVERB_MIN_LOW_EQ_GAIN          ..= REVERB_MAX_LOW_EQ_GAIN          ).contains(&self.LowEQGain)
+            && (REVERB_MIN_LOW_EQ_CUTOFF        ..= REVERB_MAX_LOW_EQ_CUTOFF        ).contains(&self.LowEQCutoff)
+            && (REVERB_MIN_HIGH_EQ_GAIN         ..= REVERB_MAX_HIGH_EQ_GAIN         ).contains(&self.HighEQGain)
+            && (REVERB_MIN_HIGH_EQ_CUTOFF       ..= REVERB_MAX_HIGH_EQ_CUTOFF       ).contains(&self.HighEQCutoff)
+            && (REVERB_MIN_ROOM_FILTER_FREQ     ..= REVERB_MAX_ROOM_FILTER_FREQ     ).contains(&{self.RoomFilterFreq})
+            && (REVERB_MIN_ROOM_FILTER_MAIN     ..= REVERB_MAX_ROOM_FILTER_MAIN     ).contains(&{self.RoomFilterMain})
+            && (REVERB_MIN_ROOM_FILTER_HF       ..= REVERB_MAX_ROOM_FILTER_HF       ).contains(&{self.RoomFilterHF})
+            && (REVERB_MIN_REFLECTIONS_GAIN     ..= REVERB_MAX_REFLECTIONS_GAIN     ).contains(&{self.ReflectionsGain})
+            && (REVERB_MIN_REVERB_GAIN          ..= REVERB_MAX_REVERB_GAIN          ).contains(&{self.ReverbGain})
+            && {self.DecayTime} >= REVERB_MIN_DECAY_TIME
+            && (REVERB_MIN_DENSITY              ..= REVERB_MAX_DENSITY              ).contains(&{self.Density})
+            && (REVERB_MIN_ROOM_SIZE            ..= REVERB_MAX_ROOM_SIZE            ).contains(&{self.RoomSize});
+        if valid { Ok(()) } else { Err(E::INVALIDARG) }
+    }
+}
+
+#[doc = "Minimum [ReverbParameters::WetDryMix]"]                       pub const REVERB_MIN_WET_DRY_MIX            : f32   = 0.0;
+#[doc = "Minimum [ReverbParameters::ReflectionsDelay]"]                pub const REVERB_MIN_REFLECTIONS_DELAY      : u32   = 0;
+#[doc = "Minimum [ReverbParameters::ReverbDelay]"]                     pub const REVERB_MIN_REVERB_DELAY           : u8    = 0;
+#[doc = "Minimum [ReverbParameters::RearDelay]"]                       pub const REVERB_MIN_REAR_DELAY             : u8    = 0;
+#[doc = "Minimum [ReverbParameters::RearDelay] (7.1)"]                 pub const REVERB_MIN_7POINT1_REAR_DELAY     : u8    = 0;
+#[doc = "Minimum [ReverbParameters::SideDelay]"]                       pub const REVERB_MIN_7POINT1_SIDE_DELAY     : u8    = 0;
+#[doc = "Minimum [ReverbParameters::PositionLeft] etc."]               pub const REVERB_MIN_POSITION               : u8    = 0;
+#[doc = "Minimum [ReverbParameters::EarlyDiffusion] etc."]             pub const REVERB_MIN_DIFFUSION              : u8    = 0;
+#[doc = "Minimum [ReverbParameters::LowEQGain]"]                       pub const REVERB_MIN_LOW_EQ_GAIN            : u8    = 0;
+#[doc = "Minimum [ReverbParameters::LowEQCutoff]"]                     pub const REVERB_MIN_LOW_EQ_CUTOFF          : u8    = 0;
+#[doc = "Minimum [ReverbParameters::HighEQGain]"]                      pub const REVERB_MIN_HIGH_EQ_GAIN           : u8    = 0;
+#[doc = "Minimum [ReverbParameters::HighEQCutoff]"]                    pub const REVERB_MIN_HIGH_EQ_CUTOFF         : u8    = 0;
+#[doc = "Minimum [ReverbParameters::RoomFilterFreq]"]                  pub const REVERB_MIN_ROOM_FILTER_FREQ       : f32   = 20.0;
+#[doc = "Minimum [ReverbParameters::RoomFilterMain]"]                  pub const REVERB_MIN_ROOM_FILTER_MAIN       : f32   = -100.0;
+#[doc = "Minimum [ReverbParameters::RoomFilterHF]"]                    pub const REVERB_MIN_ROOM_FILTER_HF         : f32   = -100.0;
+#[doc = "Minimum [ReverbParameters::ReflectionsGain]"]                 pub const REVERB_MIN_REFLECTIONS_GAIN       : f32   = -100.0;
+#[doc = "Minimum [ReverbParameters::ReverbGain]"]                      pub const REVERB_MIN_REVERB_GAIN            : f32   = -100.0;
+#[doc = "Minimum [ReverbParameters::DecayTime]"]                       pub const REVERB_MIN_DECAY_TIME             : f32   = 0.1;
+#[doc = "Minimum [ReverbParameters::Density]"]                         pub const REVERB_MIN_DENSITY                : f32   = 0.0;
+#[doc = "Minimum [ReverbParameters::RoomSize]"]                        pub const REVERB_MIN_ROOM_SIZE              : f32   = 0.0;
+
+#[doc = "Maximum [ReverbParameters::WetDryMix]"]                       pub const REVERB_MAX_WET_DRY_MIX            : f32   = 100.0;
+#[doc = "Maximum [ReverbParameters::ReflectionsDelay]"]                pub const REVERB_MAX_REFLECTIONS_DELAY      : u32   = 300;
+#[doc = "Maximum [ReverbParameters::ReverbDelay]"]                     pub const REVERB_MAX_REVERB_DELAY           : u8    = 85;
+#[doc = "Maximum [ReverbParameters::RearDelay]"]                       pub const REVERB_MAX_REAR_DELAY             : u8    = 5;
+#[doc = "Maximum [ReverbParameters::RearDelay] (7.1)"]                 pub const REVERB_MAX_7POINT1_REAR_DELAY     : u8    = 20;
+#[doc = "Maximum [ReverbParameters::SideDelay]"]                       pub const REVERB_MAX_7POINT1_SIDE_DELAY     : u8    = 5;
+#[doc = "Maximum [ReverbParameters::PositionLeft] etc."]               pub const REVERB_MAX_POSITION               : u8    = 30;
+#[doc = "Maximum [ReverbParameters::EarlyDiffusion] etc."]             pub const REVERB_MAX_DIFFUSION              : u8    = 15;
+#[doc = "Maximum [ReverbParameters::LowEQGain]"]                       pub const REVERB_MAX_LOW_EQ_GAIN            : u8    = 12;
+#[doc = "Maximum [ReverbParameters::LowEQCutoff]"]                     pub const REVERB_MAX_LOW_EQ_CUTOFF          : u8    = 9;
+#[doc = "Maximum [ReverbParameters::HighEQGain]"]                      pub const REVERB_MAX_HIGH_EQ_GAIN           : u8    = 8;
+#[doc = "Maximum [ReverbParameters::HighEQCutoff]"]                    pub const REVERB_MAX_HIGH_EQ_CUTOFF         : u8    = 14;
+#[doc = "Maximum [ReverbParameters::RoomFilterFreq]"]                  pub const REVERB_MAX_ROOM_FILTER_FREQ       : f32   = 20000.0;
+#[doc = "Maximum [ReverbParameters::RoomFilterMain]"]                  pub const REVERB_MAX_ROOM_FILTER_MAIN       : f32   = 0.0;
+#[doc = "Maximum [ReverbParameters::RoomFilterHF]"]                    pub const REVERB_MAX_ROOM_FILTER_HF         : f32   = 0.0;
+#[doc = "Maximum [ReverbParameters::ReflectionsGain]"]                 pub const REVERB_MAX_REFLECTIONS_GAIN       : f32   = 20.0;
+#[doc = "Maximum [ReverbParameters::ReverbGain]"]                      pub const REVERB_MAX_REVERB_GAIN            : f32   = 20.0;
+#[doc = "Maximum [ReverbParameters::Density]"]                         pub const REVERB_MAX_DENSITY                : f32   = 100.0;
+#[doc = "Maximum [ReverbParameters::RoomSize]"]                        pub const REVERB_MAX_ROOM_SIZE              : f32   = 100.0;
+
+#[doc = "Default [ReverbParameters::WetDryMix]"]                       pub const REVERB_DEFAULT_WET_DRY_MIX        : f32   = 100.0;
+#[doc = "Default [ReverbParameters::ReflectionsDelay]"]                pub const REVERB_DEFAULT_REFLECTIONS_DELAY  : u32   = 5;
+#[doc = "Default [ReverbParameters::ReverbDelay]"]                     pub const REVERB_DEFAULT_REVERB_DELAY       : u8    = 5;
+#[doc = "Default [ReverbParameters::RearDelay]"]                       pub const REVERB_DEFAULT_REAR_DELAY         : u8    = 5;
+#[doc = "Default [ReverbParameters::RearDelay] (7.1)"]                 pub const REVERB_DEFAULT_7POINT1_REAR_DELAY : u8    = 20;
+#[doc = "Default [ReverbParameters::SideDelay]"]                       pub const REVERB_DEFAULT_7POINT1_SIDE_DELAY : u8    = 5;
+#[doc = "Default [ReverbParameters::PositionLeft] etc."]               pub const REVERB_DEFAULT_POSITION           : u8    = 6;
+#[doc = "Default [ReverbParameters::PositionMatrixLeft] etc."]         pub const REVERB_DEFAULT_POSITION_MATRIX    : u8    = 27;
+#[doc = "Default [ReverbParameters::EarlyDiffusion]"]                  pub const REVERB_DEFAULT_EARLY_DIFFUSION    : u8    = 8;
+#[doc = "Default [ReverbParameters::LateDiffusion]"]                   pub const REVERB_DEFAULT_LATE_DIFFUSION     : u8    = 8;
+#[doc = "Default [ReverbParameters::LowEQGain]"]                       pub const REVERB_DEFAULT_LOW_EQ_GAIN        : u8    = 8;
+#[doc = "Default [ReverbParameters::LowEQCutoff]"]                     pub const REVERB_DEFAULT_LOW_EQ_CUTOFF      : u8    = 4;
+#[doc = "Default [ReverbParameters::HighEQGain]"]                      pub const REVERB_DEFAULT_HIGH_EQ_GAIN       : u8    = 8;
+#[doc = "Default [ReverbParameters::HighEQCutoff]"]                    pub const REVERB_DEFAULT_HIGH_EQ_CUTOFF     : u8    = 4;
+#[doc = "Default [ReverbParameters::RoomFilterFreq]"]                  pub const REVERB_DEFAULT_ROOM_FILTER_FREQ   : f32   = 5000.0;
+#[doc = "Default [ReverbParameters::RoomFilterMain]"]                  pub const REVERB_DEFAULT_ROOM_FILTER_MAIN   : f32   = 0.0;
+#[doc = "Default [ReverbParameters::RoomFilterHF]"]                    pub const REVERB_DEFAULT_ROOM_FILTER_HF     : f32   = 0.0;
+#[doc = "Default [ReverbParameters::ReflectionsGain]"]                 pub const REVERB_DEFAULT_REFLECTIONS_GAIN   : f32   = 0.0;
+#[doc = "Default [ReverbParameters::ReverbGain]"]                      pub const REVERB_DEFAULT_REVERB_GAIN        : f32   = 0.0;
+#[doc = "Default [ReverbParameters::DecayTime]"]                       pub const REVERB_DEFAULT_DECAY_TIME         : f32   = 1.0;
+#[doc = "Default [ReverbParameters::Density]"]                         pub const REVERB_DEFAULT_DENSITY            : f32   = 100.0;
+#[doc = "Default [ReverbParameters::RoomSize]"]                        pub const REVERB_DEFAULT_ROOM_SIZE          : f32   = 100.0;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2fx/ns-xaudio2fx-xaudio2fx_reverb_i3dl2_parameters)\]
+/// XAUDIO2FX_REVERB_I3DL2_PARAMETERS: [I3DL2](https://www.iasig.org/pubs/3dl2v1.pdf) (Interactive 3D Audio Rendering Guidelines Level 2.0) reverb parameters.
+///
+/// See the `I3DL2_PRESET_*` constants for common environments, and [reverb_convert_i3dl2_to_native] to use them.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)]
+#[repr(C, packed(1))] pub struct I3dl2ReverbParameters {
+    /// Percentage of the output that will be reverb (`0.0 ..= 100.0`.)
+    pub WetDryMix:              f32,
+
+    /// Attenuation of the room effect, in millibels (`-10000 ..= 0`.)
+    pub Room:                   i32,
+
+    /// Attenuation of the room high frequency effect, in millibels (`-10000 ..= 0`.)
+    pub RoomHF:                 i32,
+
+    /// Ignored by [reverb_convert_i3dl2_to_native].
+    pub RoomRolloffFactor:      f32,
+
+    /// Reverberation decay time at low frequencies, in seconds (`0.1 ..= 20.0`.)
+    pub DecayTime:              f32,
+
+    /// Ratio of high frequency to low frequency decay time (`0.1 ..= 2.0`.)
+    pub DecayHFRatio:           f32,
+
+    /// Attenuation of early reflections relative to `Room`, in millibels (`-10000 ..= 1000`.)
+    pub Reflections:            i32,
+
+    /// Delay of the first reflection relative to the direct path, in seconds (`0.0 ..= 0.3`.)
+    pub ReflectionsDelay:       f32,
+
+    /// Attenuation of late reverberation relative to `Room`, in millibels (`-10000 ..= 2000`.)
+    pub Reverb:                 i32,
+
+    /// Delay of late reverberation relative to the first reflection, in seconds (`0.0 ..= 0.1`.)
+    pub ReverbDelay:            f32,
+
+    /// Echo density in the late reverberation decay, as a percentage (`0.0 ..= 100.0`.)
+    pub Diffusion:              f32,
+
+    /// Modal density in the late reverberation decay, as a percentage (`0.0 ..= 100.0`.)
+    pub Density:                f32,
+
+    /// Reference high frequency, in Hz (`20.0 ..= 20000.0`.)
+    pub HFReference:            f32,
+}
+
+const _ : () = assert!(core::mem::size_of ::<I3dl2ReverbParameters>() == 52);
+
+const fn i3dl2(WetDryMix: f32, Room: i32, RoomHF: i32, RoomRolloffFactor: f32, DecayTime: f32, DecayHFRatio: f32, Reflections: i32, ReflectionsDelay: f32, Reverb: i32, ReverbDelay: f32, Diffusion: f32, Density: f32, HFReference: f32) -> I3dl2ReverbParameters {
+    #![allow(non_snake_case)]
+    I3dl2ReverbParameters { WetDryMix, Room, RoomHF, RoomRolloffFactor, DecayTime, DecayHFRatio, Reflections, ReflectionsDelay, Reverb, ReverbDelay, Diffusion, Density, HFReference }
+}
+
+#[doc = "I3DL2 preset: no reverb"]             pub const I3DL2_PRESET_DEFAULT          : I3dl2ReverbParameters = i3dl2(100.0, -10000,      0, 0.0,  1.00, 0.50, -10000, 0.020, -10000, 0.040, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: generic"]               pub const I3DL2_PRESET_GENERIC          : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -100, 0.0,  1.49, 0.83,  -2602, 0.007,    200, 0.011, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: padded cell"]           pub const I3DL2_PRESET_PADDEDCELL       : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -6000, 0.0,  0.17, 0.10,  -1204, 0.001,    207, 0.002, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: room"]                  pub const I3DL2_PRESET_ROOM             : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -454, 0.0,  0.40, 0.83,  -1646, 0.002,     53, 0.003, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: bathroom"]              pub const I3DL2_PRESET_BATHROOM         : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -1200, 0.0,  1.49, 0.54,   -370, 0.007,   1030, 0.011, 100.0,  60.0, 5000.0);
+#[doc = "I3DL2 preset: living room"]           pub const I3DL2_PRESET_LIVINGROOM       : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -6000, 0.0,  0.50, 0.10,  -1376, 0.003,  -1104, 0.004, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: stone room"]            pub const I3DL2_PRESET_STONEROOM        : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -300, 0.0,  2.31, 0.64,   -711, 0.012,     83, 0.017, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: auditorium"]            pub const I3DL2_PRESET_AUDITORIUM       : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -476, 0.0,  4.32, 0.59,   -789, 0.020,   -289, 0.030, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: concert hall"]          pub const I3DL2_PRESET_CONCERTHALL      : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -500, 0.0,  3.92, 0.70,  -1230, 0.020,     -2, 0.029, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: cave"]                  pub const I3DL2_PRESET_CAVE             : I3dl2ReverbParameters = i3dl2(100.0,  -1000,      0, 0.0,  2.91, 1.30,   -602, 0.015,   -302, 0.022, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: arena"]                 pub const I3DL2_PRESET_ARENA            : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -698, 0.0,  7.24, 0.33,  -1166, 0.020,     16, 0.030, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: hangar"]                pub const I3DL2_PRESET_HANGAR           : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -1000, 0.0, 10.05, 0.23,   -602, 0.020,    198, 0.030, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: carpeted hallway"]      pub const I3DL2_PRESET_CARPETEDHALLWAY  : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -4000, 0.0,  0.30, 0.10,  -1831, 0.002,  -1630, 0.030, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: hallway"]               pub const I3DL2_PRESET_HALLWAY          : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -300, 0.0,  1.49, 0.59,  -1219, 0.007,    441, 0.011, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: stone corridor"]        pub const I3DL2_PRESET_STONECORRIDOR    : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -237, 0.0,  2.70, 0.79,  -1214, 0.013,    395, 0.020, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: alley"]                 pub const I3DL2_PRESET_ALLEY            : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -270, 0.0,  1.49, 0.86,  -1204, 0.007,     -4, 0.011, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: forest"]                pub const I3DL2_PRESET_FOREST           : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -3300, 0.0,  1.49, 0.54,  -2560, 0.162,   -613, 0.088,  79.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: city"]                  pub const I3DL2_PRESET_CITY             : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -800, 0.0,  1.49, 0.67,  -2273, 0.007,  -2217, 0.011,  50.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: mountains"]             pub const I3DL2_PRESET_MOUNTAINS        : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -2500, 0.0,  1.49, 0.21,  -2780, 0.300,  -2014, 0.100,  27.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: quarry"]                pub const I3DL2_PRESET_QUARRY           : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -1000, 0.0,  1.49, 0.83, -10000, 0.061,    500, 0.025, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: plain"]                 pub const I3DL2_PRESET_PLAIN            : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -2000, 0.0,  1.49, 0.50,  -2466, 0.179,  -2514, 0.100,  21.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: parking lot"]           pub const I3DL2_PRESET_PARKINGLOT       : I3dl2ReverbParameters = i3dl2(100.0,  -1000,      0, 0.0,  1.65, 1.50,  -1363, 0.008,  -1153, 0.012, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: sewer pipe"]            pub const I3DL2_PRESET_SEWERPIPE        : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -1000, 0.0,  2.81, 0.14,    429, 0.014,    648, 0.021,  80.0,  60.0, 5000.0);
+#[doc = "I3DL2 preset: underwater"]            pub const I3DL2_PRESET_UNDERWATER       : I3dl2ReverbParameters = i3dl2(100.0,  -1000,  -4000, 0.0,  1.49, 0.10,   -449, 0.007,   1700, 0.011, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: small room"]            pub const I3DL2_PRESET_SMALLROOM        : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -600, 0.0,  1.10, 0.83,   -400, 0.005,    500, 0.010, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: medium room"]           pub const I3DL2_PRESET_MEDIUMROOM       : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -600, 0.0,  1.30, 0.83,  -1000, 0.010,   -200, 0.020, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: large room"]            pub const I3DL2_PRESET_LARGEROOM        : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -600, 0.0,  1.50, 0.83,  -1600, 0.020,  -1000, 0.040, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: medium hall"]           pub const I3DL2_PRESET_MEDIUMHALL       : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -600, 0.0,  1.80, 0.70,  -1300, 0.015,   -800, 0.030, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: large hall"]            pub const I3DL2_PRESET_LARGEHALL        : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -600, 0.0,  1.80, 0.70,  -2000, 0.030,  -1400, 0.060, 100.0, 100.0, 5000.0);
+#[doc = "I3DL2 preset: plate"]                 pub const I3DL2_PRESET_PLATE            : I3dl2ReverbParameters = i3dl2(100.0,  -1000,   -200, 0.0,  1.30, 0.90,      0, 0.002,      0, 0.010, 100.0,  75.0, 5000.0);
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2fx/nf-xaudio2fx-reverbconverti3dl2tonative)\]
+/// ReverbConvertI3DL2ToNative: converts I3DL2 parameters to native [ReverbParameters].
+///
+/// `seven_dot_one_reverb` (XAudio 2.9+, `TRUE` by default in C++) selects the 7.1 default `RearDelay` ([REVERB_DEFAULT_7POINT1_REAR_DELAY])
+/// instead of the 5.1 default ([REVERB_DEFAULT_REAR_DELAY].)  Pre-2.9 headers behave as if it were `false`.
+/// `RoomRolloffFactor` is ignored.
+pub fn reverb_convert_i3dl2_to_native(i3dl2: &I3dl2ReverbParameters, seven_dot_one_reverb: bool) -> ReverbParameters {
+    let i3dl2 = *i3dl2;
+    let (decay_time, decay_hf_ratio) = (i3dl2.DecayTime, i3dl2.DecayHFRatio);
+
+    let (low_eq_gain, high_eq_gain, decay_time) = if decay_hf_ratio >= 1.0 {
+        let index = ((-4.0 * f64::from(decay_hf_ratio).log10()) as i32).max(-8);
+        ((if index < 0 { index + 8 } else { 8 }) as u8, 8, decay_time * decay_hf_ratio)
+    } else {
+        let index = ((4.0 * f64::from(decay_hf_ratio).log10()) as i32).max(-8);
+        (8, (if index < 0 { index + 8 } else { 8 }) as u8, decay_time)
+    };
+
+    let reflections_delay = i3dl2.ReflectionsDelay * 1000.0;
+    let reflections_delay = if reflections_delay >= REVERB_MAX_REFLECTIONS_DELAY as f32 { (REVERB_MAX_REFLECTIONS_DELAY - 1) as f32 } else if reflections_delay <= 1.0 { 1.0 } else { reflections_delay };
+
+    let reverb_delay = i3dl2.ReverbDelay * 1000.0;
+    let reverb_delay = if reverb_delay >= f32::from(REVERB_MAX_REVERB_DELAY) { f32::from(REVERB_MAX_REVERB_DELAY - 1) } else { reverb_delay };
+
+    let diffusion = (15.0 * i3dl2.Diffusion / 100.0) as u8;
+
+    ReverbParameters {
+        WetDryMix:              i3dl2.WetDryMix,
+        ReflectionsDelay:       reflections_delay as u32,
+        ReverbDelay:            reverb_delay as u8,
+        RearDelay:              if seven_dot_one_reverb { REVERB_DEFAULT_7POINT1_REAR_DELAY } else { REVERB_DEFAULT_REAR_DELAY },
+        SideDelay:              REVERB_DEFAULT_7POINT1_SIDE_DELAY,
+        PositionLeft:           REVERB_DEFAULT_POSITION,
+        PositionRight:          REVERB_DEFAULT_POSITION,
+        PositionMatrixLeft:     REVERB_DEFAULT_POSITION_MATRIX,
+        PositionMatrixRight:    REVERB_DEFAULT_POSITION_MATRIX,
+        EarlyDiffusion:         diffusion,
+        LateDiffusion:          diffusion,
+        LowEQGain:              low_eq_gain,
+        LowEQCutoff:            4,
+        HighEQGain:             high_eq_gain,
+        HighEQCutoff:           6,
+        RoomFilterFreq:         i3dl2.HFReference,
+        RoomFilterMain:         i3dl2.Room as f32 / 100.0,
+        RoomFilterHF:           i3dl2.RoomHF as f32 / 100.0,
+        ReflectionsGain:        i3dl2.Reflections as f32 / 100.0,
+        ReverbGain:             i3dl2.Reverb as f32 / 100.0,
+        DecayTime:              decay_time,
+        Density:                i3dl2.Density,
+        RoomSize:               REVERB_DEFAULT_ROOM_SIZE,
+        DisableLateField:       0,
+    }
+}
+
+
+
+#[test] fn i3dl2_to_native() {
+    let native = reverb_convert_i3dl2_to_native(&I3DL2_PRESET_GENERIC, true);
+    assert_eq!({native.WetDryMix},          100.0);
+    assert_eq!({native.ReflectionsDelay},   7);
+    assert_eq!({native.ReverbDelay},        11);
+    assert_eq!({native.RearDelay},          REVERB_DEFAULT_7POINT1_REAR_DELAY);
+    assert_eq!({native.EarlyDiffusion},     15);
+    assert_eq!({native.LowEQGain},          8);
+    assert_eq!({native.HighEQGain},         8); // (4 * log10(0.83)) as i32 == 0
+    assert_eq!({native.RoomFilterMain},     -10.0);
+    assert_eq!({native.RoomFilterHF},       -1.0);
+    assert_eq!({native.ReflectionsGain},    -26.02);
+    assert_eq!({native.ReverbGain},         2.0);
+    assert_eq!({native.DecayTime},          1.49);
+
+    let cave = reverb_convert_i3dl2_to_native(&I3DL2_PRESET_CAVE, false);
+    assert_eq!({cave.RearDelay},            REVERB_DEFAULT_REAR_DELAY);
+    assert_eq!({cave.LowEQGain},            8); // (-4 * log10(1.3)) as i32 == 0
+    assert_eq!({cave.DecayTime},            2.91 * 1.30);
+
+    let living = reverb_convert_i3dl2_to_native(&I3DL2_PRESET_LIVINGROOM, true);
+    assert_eq!({living.HighEQGain},         5); // (4 * log10(0.1f32 as f64)) as i32 == -3 (not -4!), as in C
+    assert_eq!({living.ReflectionsDelay},   3);
+
+    let default = reverb_convert_i3dl2_to_native(&I3DL2_PRESET_DEFAULT, true);
+    assert_eq!({default.ReverbDelay},       40);
+    assert_eq!({default.ReflectionsDelay},  20);
+}
+
+#[test] fn validate() {
+    assert_eq!(ReverbParameters::default().validate(), Ok(()));
+    for preset in [I3DL2_PRESET_DEFAULT, I3DL2_PRESET_GENERIC, I3DL2_PRESET_CAVE, I3DL2_PRESET_LIVINGROOM, I3DL2_PRESET_CONCERTHALL] {
+        assert_eq!(reverb_convert_i3dl2_to_native(&preset, true ).validate(), Ok(()));
+        assert_eq!(reverb_convert_i3dl2_to_native(&preset, false).validate(), Ok(()));
+    }
+    assert_eq!(ReverbParameters { WetDryMix: 101.0,         .. Default::default() }.validate(), Err(E::INVALIDARG));
+    assert_eq!(ReverbParameters { RearDelay: 21,            .. Default::default() }.validate(), Err(E::INVALIDARG));
+    assert_eq!(ReverbParameters { PositionMatrixRight: 31,  .. Default::default() }.validate(), Err(E::INVALIDARG));
+    assert_eq!(ReverbParameters { DecayTime: 0.0,           .. Default::default() }.validate(), Err(E::INVALIDARG));
+    assert_eq!(ReverbParameters { RoomFilterHF: f32::NAN,   .. Default::default() }.validate(), Err(E::INVALIDARG));
+}
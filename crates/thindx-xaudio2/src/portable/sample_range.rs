@@ -1,4 +1,3 @@
-#[allow(unused_imports)] use super::xaudio2::sys::*;
 use bytemuck::Zeroable;
 use winresult::*;
 use core::convert::Infallible;
@@ -6,13 +5,13 @@ use core::ops::*;
 
 
 
-/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_buffer)\] [XAUDIO2_BUFFER]::{\*Begin, \*Length} tuple - as measured in "samples".
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_buffer)\] `XAUDIO2_BUFFER::{*Begin, *Length}` tuple - as measured in "samples".
 ///
 /// Somewhat similar to a storable [RangeBounds]<[u32]>.  Generally corresponds to one of:
-/// *   [XAUDIO2_BUFFER]::{[PlayBegin](XAUDIO2_BUFFER::PlayBegin), [PlayLength](XAUDIO2_BUFFER::PlayLength)}
-/// *   [XAUDIO2_BUFFER]::{[LoopBegin](XAUDIO2_BUFFER::LoopBegin), [LoopLength](XAUDIO2_BUFFER::LoopLength)}
+/// *   `XAUDIO2_BUFFER::{PlayBegin, PlayLength}`
+/// *   `XAUDIO2_BUFFER::{LoopBegin, LoopLength}`
 ///
-/// The internal representation of this type doesn't 100% match [XAUDIO2_BUFFER]'s fields.  Notably:
+/// The internal representation of this type doesn't 100% match `XAUDIO2_BUFFER`'s fields.  Notably:
 /// *   `(PlayBegin, PlayLength) == (0, 0)` means "play the full buffer"
 /// *   `(PlayBegin, PlayLength) == (x, 0)` is generally invalid (but this type uses it to mean "empty")
 /// *   `(LoopBegin, LoopLength, LoopCount) == (0, 0, 0)` means no loop region
@@ -70,3 +69,18 @@ impl TryFrom<Range<u32>> for SampleRange {
         Ok(Self { begin, length })
     }
 }
+
+#[test] fn conversions() {
+    assert_eq!(SampleRange::from(..),                           SampleRange::FULL);
+    assert_eq!(SampleRange::from(None),                         SampleRange::EMPTY);
+    assert_eq!(SampleRange::try_from(0_u32 .. 0),               Ok(SampleRange::EMPTY));
+    assert_eq!(SampleRange::try_from(5_u32 .. 5),               Ok(SampleRange::EMPTY));
+    assert!(SampleRange::try_from(Range { start: 5_u32, end: 4 }).is_err());
+    assert!(SampleRange::try_from(..=u32::MAX).is_err());
+
+    assert_eq!(SampleRange::FULL .into_raw_xaudio2_begin_length(),                              Some((0, 0)));
+    assert_eq!(SampleRange::EMPTY.into_raw_xaudio2_begin_length(),                              None);
+    assert_eq!(SampleRange::try_from(10_u32 ..  30).unwrap().into_raw_xaudio2_begin_length(),   Some((10, 20)));
+    assert_eq!(SampleRange::try_from(10_u32 ..= 30).unwrap().into_raw_xaudio2_begin_length(),   Some((10, 21)));
+    assert_eq!(SampleRange::try_from(      ..  30_u32).unwrap().into_raw_xaudio2_begin_length(), Some(( 0, 30)));
+}
@@ -0,0 +1,233 @@
+use super::*;
+
+use winresult::*;
+
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::ops::*;
+
+
+
+/// A [WAVEFORMATEX](WaveFormatEx) or [WAVEFORMATEXTENSIBLE] suitable for passing to [IXAudio2::CreateSourceVoice].
+///
+/// XAudio2 supports the following PCM formats:
+/// *   8-bit (unsigned) integer PCM
+/// *   16-bit integer PCM (optimal format for XAudio2)
+/// *   20-bit integer PCM (either in 24 or 32 bit containers)
+/// *   24-bit integer PCM (either in 24 or 32 bit containers)
+/// *   32-bit integer PCM
+/// *   32-bit float PCM (preferred format after 16-bit integer)
+///
+/// Owns the complete header, including any `cbSize` bytes of trailing format specific data.
+/// Since that's heap allocated, it's [Clone] but not [Copy]: pass `&SourceFormat` where possible (as the `create_source_voice*` methods take.)
+///
+/// [WAVEFORMATEXTENSIBLE]:         https://learn.microsoft.com/en-us/windows-hardware/drivers/ddi/ksmedia/ns-ksmedia-waveformatextensible
+/// [IXAudio2::CreateSourceVoice]:  https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice
+#[derive(Clone)] pub struct SourceFormat(Box<[u8]>); // invariant: `0.len() == size_of::<WaveFormatEx>() + cbSize`
+
+impl AsRef<SourceFormat> for SourceFormat { fn as_ref(&self) -> &SourceFormat { self } }
+
+impl SourceFormat {
+    /// Construct [SourceFormat] from a [WAVEFORMATEX](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatex)-compatible [WaveFormatEx].
+    ///
+    /// ### Safety
+    /// The exact safety requirements are a bit murky.  However:
+    /// *   `WAVEFORMATEX::cbSize`      in particular must be valid to avoid buffer overruns.
+    /// *   `WAVEFORMATEX::wFormatTag`  should be valid for `self` to avoid buffer overruns / unhandled switch cases.
+    /// *   Particularly large integers could easily lead to integer overflow related undefined behavior.
+    /// *   Unexpected enum values could easily lead to exercising undefined behavior via unhandled switch cases.
+    pub unsafe fn from_wave_format(format: WaveFormatEx) -> Self {
+        assert!(format.wFormatTag != WAVE_FORMAT_EXTENSIBLE, "use WAVEFORMATEXTENSIBLE instead for WAVE_FORMAT_EXTENSIBLE");
+        assert!(format.cbSize == 0, "WAVEFORMATEX cannot store any trailing data");
+        Self(bytemuck::bytes_of(&format).into())
+    }
+
+    /// Construct [SourceFormat] from a [WAVEFORMATEXTENSIBLE](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatextensible)-compatible [WaveFormatExtensible].
+    ///
+    /// ### Safety
+    /// As per [SourceFormat::from_wave_format].  Additionally, `SubFormat` should be a GUID XAudio2 understands.
+    pub unsafe fn from_wave_format_extensible(format: WaveFormatExtensible) -> Self {
+        assert!(format.Format.wFormatTag == WAVE_FORMAT_EXTENSIBLE, "WAVEFORMATEXTENSIBLE requires WAVE_FORMAT_EXTENSIBLE");
+        assert!(format.Format.cbSize == WAVE_FORMAT_EXTENSIBLE_CB_SIZE, "WAVEFORMATEXTENSIBLE must have a cbSize of 22");
+        Self(bytemuck::bytes_of(&format).into())
+    }
+
+    /// Construct [SourceFormat] from a complete header: a [WaveFormatEx] followed by exactly `cbSize` bytes of trailing data (e.g. the contents of a `.wav` file's `fmt ` chunk.)
+    ///
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if `bytes` is too short for a [WaveFormatEx], or if its length disagrees with `cbSize`.
+    ///
+    /// ### Safety
+    /// As per [SourceFormat::from_wave_format], except `cbSize` is validated.
+    pub unsafe fn from_wave_format_bytes(bytes: &[u8]) -> Result<Self, HResultError> {
+        let header = bytes.get(..size_of::<WaveFormatEx>()).ok_or(E::INVALIDARG)?;
+        let header = bytemuck::pod_read_unaligned::<WaveFormatEx>(header);
+        if bytes.len() != size_of::<WaveFormatEx>() + usize::from(header.cbSize) { return Err(E::INVALIDARG) }
+        Ok(Self(bytes.into()))
+    }
+
+    /// Construct [SourceFormat] from a [WAVEFORMATEX](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatex).
+    ///
+    /// ### Safety
+    /// As per [SourceFormat::from_wave_format].
+    #[cfg(windows)] pub unsafe fn from_wave_format_ex(format: winapi::shared::mmreg::WAVEFORMATEX) -> Self {
+        unsafe { Self::from_wave_format(format.into()) }
+    }
+
+    /// The underlying [WaveFormatEx] header.
+    pub fn wave_format(&self) -> &WaveFormatEx { bytemuck::from_bytes(&self.0[..size_of::<WaveFormatEx>()]) }
+
+    /// The underlying [WaveFormatExtensible] header, if this is a [WAVE_FORMAT_EXTENSIBLE] format.
+    pub fn wave_format_extensible(&self) -> Option<&WaveFormatExtensible> {
+        if self.wave_format().wFormatTag != WAVE_FORMAT_EXTENSIBLE { return None }
+        self.0.get(..size_of::<WaveFormatExtensible>()).map(bytemuck::from_bytes)
+    }
+
+    /// The effective `WAVE_FORMAT_*` tag: `SubFormat`'s tag for [WAVE_FORMAT_EXTENSIBLE] formats (if it has one), otherwise `wFormatTag`.
+    pub fn format_tag(&self) -> u16 {
+        self.wave_format_extensible().and_then(|f| {f.SubFormat}.wave_format_tag()).unwrap_or(self.wave_format().wFormatTag)
+    }
+
+    /// The complete header: [wave_format](Self::wave_format) followed by `cbSize` bytes of format specific data.
+    pub fn as_bytes(&self) -> &[u8] { &self.0 }
+
+    /// [IXAudio2::CreateSourceVoice]-friendly parameter.
+    ///
+    /// [IXAudio2::CreateSourceVoice]:  https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice
+    #[cfg(windows)] pub fn as_source_format(&self) -> *const winapi::shared::mmreg::WAVEFORMATEX { self.0.as_ptr().cast() }
+
+    fn basic<S: Sized, const C: usize>(fmt: u16, hz: u32) -> TypedSourceFormat<[S; C]> {
+        let sc_size = if let Ok(n) = u16::try_from(size_of::<[S; C]>()) { n } else { panic!("size_of::<[S; C]>() > u16::MAX") };
+        let s_size  = if let Ok(n) = u16::try_from(size_of::< S    >()) { n } else { panic!("size_of::<S>() > u16::MAX") };
+        unsafe{TypedSourceFormat::new(SourceFormat::from_wave_format(WaveFormatEx{
+            wFormatTag:         fmt,
+            nChannels:          C.try_into().expect("too many channels"),
+            nSamplesPerSec:     hz,
+            nAvgBytesPerSec:    hz * (sc_size as u32),
+            nBlockAlign:        sc_size,
+            wBitsPerSample:     s_size * 8,
+            cbSize:             0,
+        }))}
+    }
+
+    fn extensible<S: Sized, const C: usize>(fmt: u16, hz: u32, channel_mask: u32, valid_bits: u16) -> TypedSourceFormat<[S; C]> {
+        let basic = *Self::basic::<S, C>(fmt, hz).wave_format();
+        assert!(channel_mask.count_ones() <= C as u32, "channel_mask specifies more speakers than there are channels");
+        assert!(0 < valid_bits && valid_bits <= basic.wBitsPerSample, "valid_bits must be in the range 1 ..= bits per sample");
+        unsafe{TypedSourceFormat::new(SourceFormat::from_wave_format_extensible(WaveFormatExtensible {
+            Format:                 WaveFormatEx { wFormatTag: WAVE_FORMAT_EXTENSIBLE, cbSize: WAVE_FORMAT_EXTENSIBLE_CB_SIZE, .. basic },
+            wValidBitsPerSample:    valid_bits,
+            dwChannelMask:          channel_mask,
+            SubFormat:              Guid::from_wave_format_tag(fmt),
+        }))}
+    }
+}
+
+// TODO: impl Debug for SourceFormat
+
+
+
+/// [SourceFormat], but with additional type information.
+#[derive(Clone)] pub struct TypedSourceFormat<Sample>(SourceFormat, PhantomData<Sample>);
+
+impl<S> AsRef<SourceFormat> for TypedSourceFormat<S> { fn as_ref(&self) -> &SourceFormat { &self.0 } }
+impl<S> Deref for TypedSourceFormat<S> { fn deref(&self) -> &Self::Target { &self.0 } type Target = SourceFormat; }
+
+impl<S> TypedSourceFormat<S> {
+    /// Construct a [TypedSourceFormat] from a [SourceFormat]
+    ///
+    /// ### Safety
+    /// *   `format` should match the type `S`.
+    pub unsafe fn new(format: SourceFormat) -> Self {
+        Self(format, PhantomData)
+    }
+}
+
+impl<S: HasPcmWaveFormat, const C: usize> TypedSourceFormat<[S; C]> {
+    /// [Pulse-code modulation](https://en.wikipedia.org/wiki/Pulse-code_modulation) format, implemented for [TypedSourceFormat]<\[[u8] | [i16] | [i32] | [f32]; 1 \| 2\]>.
+    ///
+    /// N.B. 8-bit is unsigned, but 16 and 32 bit are *signed*
+    pub fn pcm(hz: u32) -> Self { SourceFormat::basic(S::pcm_wave_format(), hz) }
+
+    /// [WAVEFORMATEXTENSIBLE](WaveFormatExtensible) [PCM](Self::pcm), assigning channels to the speakers of `channel_mask` (e.g. [SPEAKER_5POINT1] for 6 channels.)
+    ///
+    /// ### Panics
+    /// *   If `channel_mask` specifies more than `C` speakers.
+    pub fn pcm_extensible(hz: u32, channel_mask: u32) -> Self { SourceFormat::extensible(S::pcm_wave_format(), hz, channel_mask, (size_of::<S>() * 8) as u16) }
+
+    /// [pcm_extensible](Self::pcm_extensible), but only the most significant `valid_bits` of each `S` are meaningful (e.g. 24-bit audio in [i32] containers.)
+    ///
+    /// ### Panics
+    /// *   If `channel_mask` specifies more than `C` speakers.
+    /// *   If `valid_bits` is `0` or larger than `S`.
+    pub fn pcm_extensible_valid_bits(hz: u32, channel_mask: u32, valid_bits: u16) -> Self { SourceFormat::extensible(S::pcm_wave_format(), hz, channel_mask, valid_bits) }
+}
+
+/// [u8] | [i16] | [i32] | [f32]
+///
+/// N.B. 8-bit is unsigned, but 16 and 32 bit are *signed*
+pub unsafe trait HasPcmWaveFormat {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatex#remarks)\]
+    /// WAVE_FORMAT_\* tag values
+    fn pcm_wave_format() -> u16;
+}
+
+unsafe impl HasPcmWaveFormat for u8     { fn pcm_wave_format() -> u16 { WAVE_FORMAT_PCM } }
+unsafe impl HasPcmWaveFormat for i16    { fn pcm_wave_format() -> u16 { WAVE_FORMAT_PCM } }
+unsafe impl HasPcmWaveFormat for i32    { fn pcm_wave_format() -> u16 { WAVE_FORMAT_PCM } }
+unsafe impl HasPcmWaveFormat for f32    { fn pcm_wave_format() -> u16 { WAVE_FORMAT_IEEE_FLOAT } }
+
+#[test] fn pcm() {
+    let mono  = TypedSourceFormat::<[i16; 1]>::pcm(44100);
+    let f = *mono.wave_format();
+    assert_eq!({f.wFormatTag},      WAVE_FORMAT_PCM);
+    assert_eq!({f.nChannels},       1);
+    assert_eq!({f.nSamplesPerSec},  44100);
+    assert_eq!({f.nAvgBytesPerSec}, 44100 * 2);
+    assert_eq!({f.nBlockAlign},     2);
+    assert_eq!({f.wBitsPerSample},  16);
+    assert_eq!({f.cbSize},          0);
+
+    assert_eq!(mono.as_bytes(), bytemuck::bytes_of(&f));
+
+    let stereo = TypedSourceFormat::<[f32; 2]>::pcm(48000);
+    let f = *stereo.wave_format();
+    assert_eq!({f.wFormatTag},      WAVE_FORMAT_IEEE_FLOAT);
+    assert_eq!({f.nChannels},       2);
+    assert_eq!({f.nAvgBytesPerSec}, 48000 * 8);
+    assert_eq!({f.nBlockAlign},     8);
+    assert_eq!({f.wBitsPerSample},  32);
+}
+
+#[test] fn wave_format_bytes() {
+    let mut bytes = bytemuck::bytes_of(&WaveFormatEx { wFormatTag: WAVE_FORMAT_ADPCM, cbSize: 2, .. Default::default() }).to_vec();
+    assert!(unsafe { SourceFormat::from_wave_format_bytes(&bytes) }.is_err(), "missing trailing bytes");
+    bytes.extend_from_slice(&[1, 2]);
+    let format = unsafe { SourceFormat::from_wave_format_bytes(&bytes) }.unwrap();
+    assert_eq!(format.as_bytes(), &bytes[..]);
+    assert_eq!({format.wave_format().wFormatTag}, WAVE_FORMAT_ADPCM);
+    bytes.push(3);
+    assert!(unsafe { SourceFormat::from_wave_format_bytes(&bytes) }.is_err(), "excess trailing bytes");
+    assert!(unsafe { SourceFormat::from_wave_format_bytes(&bytes[..17]) }.is_err(), "truncated header");
+}
+
+#[test] fn pcm_extensible() {
+    let surround = TypedSourceFormat::<[i32; 6]>::pcm_extensible_valid_bits(48000, SPEAKER_5POINT1, 24);
+    let f = *surround.wave_format_extensible().unwrap();
+    assert_eq!({f.Format.wFormatTag},       WAVE_FORMAT_EXTENSIBLE);
+    assert_eq!({f.Format.nChannels},        6);
+    assert_eq!({f.Format.nAvgBytesPerSec},  48000 * 24);
+    assert_eq!({f.Format.nBlockAlign},      24);
+    assert_eq!({f.Format.wBitsPerSample},   32);
+    assert_eq!({f.Format.cbSize},           22);
+    assert_eq!({f.wValidBitsPerSample},     24);
+    assert_eq!({f.dwChannelMask},           SPEAKER_5POINT1);
+    assert_eq!({f.SubFormat},               KSDATAFORMAT_SUBTYPE_PCM);
+    assert_eq!(surround.format_tag(),       WAVE_FORMAT_PCM);
+    assert_eq!(surround.as_bytes().len(),   40);
+
+    let stereo = TypedSourceFormat::<[f32; 2]>::pcm_extensible(44100, SPEAKER_STEREO);
+    assert_eq!({stereo.wave_format_extensible().unwrap().wValidBitsPerSample}, 32);
+    assert_eq!(stereo.format_tag(), WAVE_FORMAT_IEEE_FLOAT);
+    assert!(TypedSourceFormat::<[f32; 2]>::pcm(44100).wave_format_extensible().is_none());
+}
@@ -0,0 +1,457 @@
+//! \[[wikipedia.org](https://en.wikipedia.org/wiki/WAV)\] RIFF/WAVE (`.wav`) and RIFF/XWMA (`.xwm`) file parsing
+//!
+//! ### References
+//! *   [WAVE PCM soundfile format](http://soundfile.sapp.org/doc/WaveFormat/)
+//! *   [RIFF File Structure](https://johnloomis.org/cpe102/asgn/asgn1/riff.html)
+//! *   [Multimedia Programming Interface and Data Specifications 1.0](https://www.aelius.com/njh/wavemetatools/doc/riffmci.pdf) (`smpl`, `cue `)
+//! *   [XAudio2 Audio Formats](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-audio-formats)
+
+use crate::portable::*;
+
+use bytemuck::{Pod, Zeroable};
+use winresult::*;
+
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use core::mem::size_of;
+
+
+
+/// `FOURCC` chunk / form type identifier, e.g. `*b"fmt "`.
+pub type FourCC = [u8; 4];
+
+/// A parsed RIFF/WAVE file, borrowing its audio data from the original bytes.
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::portable::wav::Wav;
+/// let bytes = std::fs::read(r"C:\Windows\Media\notify.wav").unwrap();
+/// let wav = Wav::parse(&bytes).unwrap();
+/// println!("{} Hz, {} byte(s) of audio data", {wav.format.wave_format().nSamplesPerSec}, wav.data.len());
+/// ```
+#[derive(Clone)] pub struct Wav<'a> {
+    /// The validated `fmt ` chunk, ready for `create_source_voice_dynamic`.
+    pub format:     SourceFormat,
+
+    /// The `data` chunk, truncated to whole blocks of `nBlockAlign` bytes (encoders commonly write a partial final ADPCM block.)
+    pub data:       &'a [u8],
+
+    /// The `smpl` chunk, if any.
+    pub sampler:    Option<Sampler>,
+
+    /// The `cue ` chunk's points, if any.
+    pub cues:       Vec<CuePoint>,
+}
+
+impl<'a> Wav<'a> {
+    /// Parse and validate a RIFF/WAVE file.
+    ///
+    /// Accepts the formats XAudio2 can play from a `.wav` file:
+    /// *   [WAVE_FORMAT_PCM] (8, 16, 24, or 32-bit)
+    /// *   [WAVE_FORMAT_IEEE_FLOAT] (32-bit)
+    /// *   [WAVE_FORMAT_EXTENSIBLE] (with a PCM or IEEE float `SubFormat`)
+    /// *   [WAVE_FORMAT_ADPCM]
+    /// *   [WAVE_FORMAT_WMAUDIO2] / [WAVE_FORMAT_WMAUDIO3]
+    ///
+    /// Unrecognized chunks are skipped.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        let (id, riff, _after) = chunk(bytes).ok_or(Error::NotRiff)?;
+        if id != *b"RIFF" { return Err(Error::NotRiff) }
+        let (form, mut chunks) = split_fourcc(riff).ok_or(Error::NotRiff)?;
+        if form != *b"WAVE" { return Err(Error::NotWave(form)) }
+
+        let mut format  = None;
+        let mut data    = None;
+        let mut sampler = None;
+        let mut cues    = None;
+
+        while !chunks.is_empty() {
+            let (id, chunk, after) = chunk(chunks).ok_or(Error::Truncated)?;
+            match &id {
+                b"fmt " => { if format .is_some() { return Err(Error::DuplicateChunk(id)) }; format  = Some(parse_format(chunk)?) },
+                b"data" => { if data   .is_some() { return Err(Error::DuplicateChunk(id)) }; data    = Some(chunk) },
+                b"smpl" => { if sampler.is_some() { return Err(Error::DuplicateChunk(id)) }; sampler = Some(Sampler::parse(chunk)?) },
+                b"cue " => { if cues   .is_some() { return Err(Error::DuplicateChunk(id)) }; cues    = Some(parse_cues(chunk)?) },
+                _       => {},
+            }
+            chunks = after;
+        }
+
+        let format  = format.ok_or(Error::MissingChunk(*b"fmt "))?;
+        let data    = data  .ok_or(Error::MissingChunk(*b"data"))?;
+        let block_align = usize::from(format.wave_format().nBlockAlign);
+        let data        = &data[.. data.len() - data.len() % block_align];
+
+        Ok(Self { format, data, sampler, cues: cues.unwrap_or_default() })
+    }
+}
+
+/// A parsed RIFF/XWMA file (as produced by `xWMAEncode`), borrowing its audio data from the original bytes.
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::portable::wav::Xwm;
+/// let bytes = std::fs::read("music.xwm").unwrap();
+/// let xwm = Xwm::parse(&bytes).unwrap();
+/// println!("{} packet(s) of audio data", xwm.decoded_packet_cumulative_bytes.len());
+/// ```
+#[derive(Clone)] pub struct Xwm<'a> {
+    /// The validated `fmt ` chunk, ready for `create_source_voice_dynamic`.
+    pub format:                             XwmaSourceFormat,
+
+    /// The `data` chunk: whole packets of `nBlockAlign` bytes.
+    pub data:                               &'a [u8],
+
+    /// The `dpds` chunk: decoded packet cumulative bytes, one entry per packet of `data`.
+    pub decoded_packet_cumulative_bytes:    Vec<u32>,
+}
+
+impl<'a> Xwm<'a> {
+    /// Parse and validate a RIFF/XWMA file.
+    ///
+    /// The `fmt ` chunk must be [WAVE_FORMAT_WMAUDIO2] or [WAVE_FORMAT_WMAUDIO3], and `data` must match `dpds` as per [XwmaSourceFormat::packets].
+    /// Unrecognized chunks are skipped.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        let (id, riff, _after) = chunk(bytes).ok_or(Error::NotRiff)?;
+        if id != *b"RIFF" { return Err(Error::NotRiff) }
+        let (form, mut chunks) = split_fourcc(riff).ok_or(Error::NotRiff)?;
+        if form != *b"XWMA" { return Err(Error::NotXwma(form)) }
+
+        let mut format  = None;
+        let mut data    = None;
+        let mut dpds    = None;
+
+        while !chunks.is_empty() {
+            let (id, chunk, after) = chunk(chunks).ok_or(Error::Truncated)?;
+            match &id {
+                b"fmt " => { if format.is_some() { return Err(Error::DuplicateChunk(id)) }; format = Some(parse_format(chunk)?) },
+                b"data" => { if data  .is_some() { return Err(Error::DuplicateChunk(id)) }; data   = Some(chunk) },
+                b"dpds" => { if dpds  .is_some() { return Err(Error::DuplicateChunk(id)) }; dpds   = Some(parse_dpds(chunk)?) },
+                _       => {},
+            }
+            chunks = after;
+        }
+
+        let format  = format.ok_or(Error::MissingChunk(*b"fmt "))?;
+        let data    = data  .ok_or(Error::MissingChunk(*b"data"))?;
+        let dpds    = dpds  .ok_or(Error::MissingChunk(*b"dpds"))?;
+        let format  = XwmaSourceFormat::from_source_format(&format).map_err(|_| Error::UnsupportedFormat(format.format_tag()))?;
+        format.packets(data.len(), &dpds).map_err(|_| Error::InvalidData("`data` chunk doesn't match the `dpds` chunk's packets"))?;
+
+        Ok(Self { format, data, decoded_packet_cumulative_bytes: dpds })
+    }
+}
+
+
+
+/// The `smpl` chunk: sampler / looping metadata.
+#[derive(Clone, Debug, Default, PartialEq, Eq)] pub struct Sampler {
+    pub manufacturer:           u32,
+    pub product:                u32,
+    /// Duration of a sample, in nanoseconds.
+    pub sample_period:          u32,
+    pub midi_unity_note:        u32,
+    pub midi_pitch_fraction:    u32,
+    pub smpte_format:           u32,
+    pub smpte_offset:           u32,
+    pub loops:                  Vec<SampleLoop>,
+}
+
+impl Sampler {
+    fn parse(chunk: &[u8]) -> Result<Self, Error> {
+        const INVALID : Error = Error::InvalidData("`smpl` chunk too small for its loop count");
+        let header = chunk.get(..36).ok_or(INVALID)?;
+        let [manufacturer, product, sample_period, midi_unity_note, midi_pitch_fraction, smpte_format, smpte_offset, num_loops, _sampler_data] = bytemuck::pod_read_unaligned::<[u32; 9]>(header);
+        let loops = usize::try_from(num_loops).map_err(|_| INVALID)?;
+        let loops = loops.checked_mul(size_of::<SampleLoop>()).and_then(|n| chunk.get(36 .. 36 + n)).ok_or(INVALID)?;
+        let loops = loops.chunks_exact(size_of::<SampleLoop>()).map(bytemuck::pod_read_unaligned).collect();
+        Ok(Self { manufacturer, product, sample_period, midi_unity_note, midi_pitch_fraction, smpte_format, smpte_offset, loops })
+    }
+}
+
+/// A `smpl` chunk loop.  Positions are measured in sample frames.
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, Hash)]
+#[repr(C)] pub struct SampleLoop {
+    /// Identifies the matching [CuePoint::id], if any.
+    pub cue_point_id:   u32,
+    /// `0` = forward, `1` = ping-pong, `2` = backward.  XAudio2 only loops forward.
+    pub loop_type:      u32,
+    pub start:          u32,
+    /// Inclusive.
+    pub end:            u32,
+    pub fraction:       u32,
+    /// Number of times to play the loop, or `0` to loop forever.
+    pub play_count:     u32,
+}
+
+impl SampleLoop {
+    /// `start ..= end`, as a loop region for submitting a buffer.
+    pub fn range(&self) -> Result<SampleRange, HResultError> { SampleRange::try_from(self.start ..= self.end) }
+
+    /// `play_count`, converted to the number of times XAudio2 should loop back (saturating at [LoopCount::MAX].)
+    pub fn loop_count(&self) -> LoopCount {
+        match self.play_count {
+            0 => LoopCount::INFINITE,
+            n => LoopCount(u8::try_from(n - 1).unwrap_or(u8::MAX).min(LoopCount::MAX.0)),
+        }
+    }
+}
+
+/// A `cue ` chunk point.
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, Hash)]
+#[repr(C)] pub struct CuePoint {
+    pub id:             u32,
+    /// Play order position.
+    pub position:       u32,
+    /// Chunk containing the cue (generally `*b"data"`.)
+    pub data_chunk_id:  FourCC,
+    pub chunk_start:    u32,
+    pub block_start:    u32,
+    /// Sample frame offset of the cue, relative to `block_start`.
+    pub sample_offset:  u32,
+}
+
+fn parse_dpds(chunk: &[u8]) -> Result<Vec<u32>, Error> {
+    if chunk.len() % 4 != 0 { return Err(Error::InvalidData("`dpds` chunk isn't a whole number of u32s")) }
+    Ok(chunk.chunks_exact(4).map(bytemuck::pod_read_unaligned).collect())
+}
+
+fn parse_cues(chunk: &[u8]) -> Result<Vec<CuePoint>, Error> {
+    const INVALID : Error = Error::InvalidData("`cue ` chunk too small for its cue point count");
+    let (count, points) = split_u32(chunk).ok_or(INVALID)?;
+    let count = usize::try_from(count).map_err(|_| INVALID)?;
+    let points = count.checked_mul(size_of::<CuePoint>()).and_then(|n| points.get(..n)).ok_or(INVALID)?;
+    Ok(points.chunks_exact(size_of::<CuePoint>()).map(bytemuck::pod_read_unaligned).collect())
+}
+
+
+
+/// An error parsing a `.wav` file with [Wav::parse], or a `.xwm` file with [Xwm::parse].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive] pub enum Error {
+    /// The file doesn't start with a `RIFF` chunk.
+    NotRiff,
+    /// The `RIFF` form type wasn't `WAVE`.
+    NotWave(FourCC),
+    /// The `RIFF` form type wasn't `XWMA`.
+    NotXwma(FourCC),
+    /// A chunk extends past the end of the file.
+    Truncated,
+    /// A required chunk (`fmt `, `data`, or for `.xwm` files, `dpds`) was missing.
+    MissingChunk(FourCC),
+    /// A chunk that should only appear once appeared multiple times.
+    DuplicateChunk(FourCC),
+    /// The `fmt ` chunk's `wFormatTag` isn't playable by XAudio2.
+    UnsupportedFormat(u16),
+    /// A chunk's contents were malformed or inconsistent.
+    InvalidData(&'static str),
+}
+
+impl Display for Error {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let fourcc = |f: &FourCC| f.iter().map(|&b| char::from(b)).collect::<alloc::string::String>();
+        match self {
+            Error::NotRiff                  => write!(fmt, "invalid .wav file: not a RIFF container"),
+            Error::NotWave(form)            => write!(fmt, "invalid .wav file: RIFF form type {:?} isn't \"WAVE\"", fourcc(form)),
+            Error::NotXwma(form)            => write!(fmt, "invalid .xwm file: RIFF form type {:?} isn't \"XWMA\"", fourcc(form)),
+            Error::Truncated                => write!(fmt, "invalid .wav file: truncated chunk"),
+            Error::MissingChunk(id)         => write!(fmt, "invalid .wav file: missing {:?} chunk", fourcc(id)),
+            Error::DuplicateChunk(id)       => write!(fmt, "invalid .wav file: {:?} chunk encountered multiple times", fourcc(id)),
+            Error::UnsupportedFormat(tag)   => write!(fmt, "unsupported .wav file: wFormatTag 0x{tag:04X}"),
+            Error::InvalidData(reason)      => write!(fmt, "invalid .wav file: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for HResultError { fn from(_: Error) -> Self { E::INVALIDARG } }
+
+
+
+const MIN_CHANNELS      : u16 = 1;
+const MAX_CHANNELS      : u16 = 64;
+const MIN_SAMPLE_RATE   : u32 = 1000;
+const MAX_SAMPLE_RATE   : u32 = 200000;
+
+#[cfg(windows)] const _ : () = {
+    use thindx_xaudio2_sys::xaudio2_8::*;
+    assert!(MAX_CHANNELS    as u32 == XAUDIO2_MAX_AUDIO_CHANNELS);
+    assert!(MIN_SAMPLE_RATE        == XAUDIO2_MIN_SAMPLE_RATE);
+    assert!(MAX_SAMPLE_RATE        == XAUDIO2_MAX_SAMPLE_RATE);
+};
+
+/// Validate a `fmt ` chunk, returning a [SourceFormat] with any irrelevant trailing data stripped.
+fn parse_format(chunk: &[u8]) -> Result<SourceFormat, Error> {
+    // PCMWAVEFORMAT (16 bytes) lacks `cbSize`
+    let header = chunk.get(..16).ok_or(Error::InvalidData("`fmt ` chunk too small"))?;
+    let mut format = WaveFormatEx::default();
+    bytemuck::bytes_of_mut(&mut format)[..16].copy_from_slice(header);
+    let cb_size = chunk.get(16..18).map_or(0, |b| u16::from_le_bytes([b[0], b[1]]));
+    let extra = chunk.get(18 ..).unwrap_or(&[]).get(.. usize::from(cb_size)).ok_or(Error::InvalidData("`fmt ` chunk too small for its cbSize"))?;
+
+    let WaveFormatEx { wFormatTag: tag, nChannels: channels, nSamplesPerSec: rate, nAvgBytesPerSec: avg, nBlockAlign: block_align, wBitsPerSample: bits, .. } = format;
+    if !(MIN_CHANNELS    ..= MAX_CHANNELS   ).contains(&channels)   { return Err(Error::InvalidData("nChannels out of range")) }
+    if !(MIN_SAMPLE_RATE ..= MAX_SAMPLE_RATE).contains(&rate)       { return Err(Error::InvalidData("nSamplesPerSec out of range")) }
+    if block_align == 0                                             { return Err(Error::InvalidData("nBlockAlign is 0")) }
+
+    let pcm_block = |container_bits: u16| -> Result<(), Error> {
+        if u32::from(block_align) != u32::from(channels) * u32::from(container_bits / 8) { return Err(Error::InvalidData("nBlockAlign doesn't match nChannels * wBitsPerSample")) }
+        if u64::from(avg) != u64::from(rate) * u64::from(block_align) { return Err(Error::InvalidData("nAvgBytesPerSec doesn't match nSamplesPerSec * nBlockAlign")) }
+        Ok(())
+    };
+
+    let extra : &[u8] = match tag {
+        WAVE_FORMAT_PCM => {
+            if !matches!(bits, 8 | 16 | 24 | 32) { return Err(Error::InvalidData("PCM wBitsPerSample must be 8, 16, 24, or 32")) }
+            pcm_block(bits)?;
+            &[]
+        },
+        WAVE_FORMAT_IEEE_FLOAT => {
+            if bits != 32 { return Err(Error::InvalidData("IEEE float wBitsPerSample must be 32")) }
+            pcm_block(bits)?;
+            &[]
+        },
+        WAVE_FORMAT_EXTENSIBLE => {
+            let extra = extra.get(..usize::from(WAVE_FORMAT_EXTENSIBLE_CB_SIZE)).ok_or(Error::InvalidData("WAVEFORMATEXTENSIBLE cbSize must be at least 22"))?;
+            let mut extensible = WaveFormatExtensible::default();
+            bytemuck::bytes_of_mut(&mut extensible)[size_of::<WaveFormatEx>()..].copy_from_slice(extra);
+            let WaveFormatExtensible { wValidBitsPerSample: valid_bits, dwChannelMask: mask, SubFormat: sub_format, .. } = extensible;
+            match sub_format.wave_format_tag() {
+                Some(WAVE_FORMAT_PCM)           => if !matches!(bits, 8 | 16 | 24 | 32) { return Err(Error::InvalidData("PCM wBitsPerSample must be 8, 16, 24, or 32")) },
+                Some(WAVE_FORMAT_IEEE_FLOAT)    => if bits != 32 { return Err(Error::InvalidData("IEEE float wBitsPerSample must be 32")) },
+                _                               => return Err(Error::InvalidData("unrecognized WAVEFORMATEXTENSIBLE SubFormat")),
+            }
+            if valid_bits > bits                            { return Err(Error::InvalidData("wValidBitsPerSample exceeds wBitsPerSample")) }
+            if mask.count_ones() > u32::from(channels)      { return Err(Error::InvalidData("dwChannelMask specifies more speakers than nChannels")) }
+            pcm_block(bits)?;
+            extra
+        },
+        WAVE_FORMAT_ADPCM => {
+            let extra = extra.get(..32).ok_or(Error::InvalidData("ADPCMWAVEFORMAT cbSize must be at least 32"))?;
+            let mut adpcm = AdpcmWaveFormat { wfx: format, .. Default::default() };
+            adpcm.wfx.cbSize = 32;
+            bytemuck::bytes_of_mut(&mut adpcm)[size_of::<WaveFormatEx>()..].copy_from_slice(extra);
+            let adpcm = AdpcmSourceFormat::from_adpcm_wave_format(adpcm).map_err(|_| Error::InvalidData("invalid ADPCMWAVEFORMAT (nChannels, wBitsPerSample, nBlockAlign, wSamplesPerBlock, or coefficients)"))?;
+            return Ok(adpcm.into());
+        },
+        WAVE_FORMAT_WMAUDIO2 | WAVE_FORMAT_WMAUDIO3 => {
+            if bits != 16   { return Err(Error::InvalidData("xWMA wBitsPerSample must be 16")) }
+            if avg == 0     { return Err(Error::InvalidData("xWMA nAvgBytesPerSec is 0")) }
+            &[]
+        },
+        other => return Err(Error::UnsupportedFormat(other)),
+    };
+
+    format.cbSize = extra.len() as u16; // ≤ cb_size
+    let mut bytes = bytemuck::bytes_of(&format).to_vec();
+    bytes.extend_from_slice(extra);
+    Ok(unsafe { SourceFormat::from_wave_format_bytes(&bytes) }.expect("bug: inconsistent cbSize"))
+}
+
+/// Returns: id, chunk data, after (including any pad byte)
+fn chunk(bytes: &[u8]) -> Option<(FourCC, &[u8], &[u8])> {
+    let (id,   bytes) = split_fourcc(bytes)?;
+    let (size, bytes) = split_u32(bytes)?;
+    let size = usize::try_from(size).ok()?;
+    let chunk = bytes.get(..size)?;
+    let after = bytes.get(size + size % 2 ..).unwrap_or(&[]); // tolerate a missing final pad byte
+    Some((id, chunk, after))
+}
+
+fn split_fourcc(bytes: &[u8]) -> Option<(FourCC, &[u8])> {
+    let fourcc = bytes.get(..4)?;
+    Some((bytemuck::pod_read_unaligned(fourcc), &bytes[4..]))
+}
+
+fn split_u32(bytes: &[u8]) -> Option<(u32, &[u8])> {
+    let (fourcc, rest) = split_fourcc(bytes)?;
+    Some((u32::from_le_bytes(fourcc), rest))
+}
+
+
+
+#[cfg(test)] fn riff(form: &FourCC, chunks: &[(&FourCC, &[u8])]) -> Vec<u8> {
+    let mut body = form.to_vec();
+    for (id, data) in chunks.iter().copied() {
+        body.extend_from_slice(id);
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(data);
+        if data.len() % 2 != 0 { body.push(0) }
+    }
+    let mut file = b"RIFF".to_vec();
+    file.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    file.extend_from_slice(&body);
+    file
+}
+
+#[test] fn parse_pcm() {
+    let fmt = TypedSourceFormat::<[i16; 2]>::pcm(44100);
+    let smpl = [0u32, 0, 22675, 60, 0, 0, 0, 1, 0, 7, 0, 100, 199, 0, 3];
+    let cue  = [1u32, 7, 0, u32::from_le_bytes(*b"data"), 0, 0, 100];
+    let data = [0u8; 8];
+    let file = riff(b"WAVE", &[
+        (b"LIST", &[1, 2, 3]), // odd sized, padded
+        (b"fmt ", &fmt.as_bytes()[..16]), // PCMWAVEFORMAT
+        (b"data", &data),
+        (b"smpl", bytemuck::cast_slice(&smpl)),
+        (b"cue ", bytemuck::cast_slice(&cue)),
+    ]);
+
+    let wav = Wav::parse(&file).unwrap();
+    assert_eq!(wav.format.as_bytes(), fmt.as_bytes());
+    assert_eq!(wav.data, &data);
+
+    let sampler = wav.sampler.unwrap();
+    assert_eq!(sampler.sample_period, 22675);
+    assert_eq!(sampler.loops, [SampleLoop { cue_point_id: 7, loop_type: 0, start: 100, end: 199, fraction: 0, play_count: 3 }]);
+    assert_eq!(sampler.loops[0].range(), SampleRange::try_from(100 .. 200));
+    assert_eq!(sampler.loops[0].loop_count(), LoopCount(2));
+    assert_eq!(wav.cues, [CuePoint { id: 7, position: 0, data_chunk_id: *b"data", chunk_start: 0, block_start: 0, sample_offset: 100 }]);
+
+    let partial = riff(b"WAVE", &[(b"fmt ", fmt.as_bytes()), (b"data", &[0u8; 10])]);
+    assert_eq!(Wav::parse(&partial).unwrap().data.len(), 8, "partial final block is truncated");
+}
+
+#[test] fn parse_formats() {
+    let parse = |fmt: &[u8]| Wav::parse(&riff(b"WAVE", &[(b"fmt ", fmt), (b"data", &[0; 72])])).map(|wav| wav.format.as_bytes().to_vec());
+    let header = |tag, channels, rate, block_align, bits, cb_size| bytemuck::bytes_of(&WaveFormatEx { wFormatTag: tag, nChannels: channels, nSamplesPerSec: rate, nAvgBytesPerSec: rate * u32::from(block_align), nBlockAlign: block_align, wBitsPerSample: bits, cbSize: cb_size }).to_vec();
+
+    let mut extensible = TypedSourceFormat::<[i32; 2]>::pcm_extensible_valid_bits(48000, SPEAKER_STEREO, 24).as_bytes().to_vec();
+    assert_eq!(parse(&extensible), Ok(extensible.clone()));
+    extensible[20..24].copy_from_slice(&0x7u32.to_le_bytes());
+    assert!(parse(&extensible).is_err(), "3 speakers for 2 channels");
+
+    let mut adpcm = header(WAVE_FORMAT_ADPCM, 1, 22050, 36, 4, 32);
+    adpcm.extend_from_slice(&60u16.to_le_bytes());
+    adpcm.extend_from_slice(&7u16.to_le_bytes());
+    adpcm.extend_from_slice(bytemuck::cast_slice(&ADPCM_COEFFICIENTS));
+    assert_eq!(parse(&adpcm), Ok(adpcm.clone()));
+    adpcm[18..20].copy_from_slice(&64u16.to_le_bytes());
+    assert!(parse(&adpcm).is_err(), "wrong wSamplesPerBlock");
+
+    let mut padded_pcm = header(WAVE_FORMAT_PCM, 1, 8000, 1, 8, 2);
+    padded_pcm.extend_from_slice(&[0, 0]);
+    assert_eq!(parse(&padded_pcm), Ok(header(WAVE_FORMAT_PCM, 1, 8000, 1, 8, 0)), "irrelevant trailing data is stripped");
+
+    assert!(parse(&header(WAVE_FORMAT_IEEE_FLOAT, 1, 8000, 8, 64, 0)).is_err());
+    assert_eq!(parse(&header(0x0050, 1, 8000, 1, 8, 0)), Err(Error::UnsupportedFormat(0x0050)));
+    assert_eq!(parse(&header(WAVE_FORMAT_PCM, 1, 8000, 1, 8, 0)[..15]), Err(Error::InvalidData("`fmt ` chunk too small")));
+    assert_eq!(Wav::parse(&riff(b"WAVE", &[(b"data", &[])])).err(), Some(Error::MissingChunk(*b"fmt ")));
+    assert_eq!(Wav::parse(&riff(b"AVI ", &[])).err(), Some(Error::NotWave(*b"AVI ")));
+}
+
+#[test] fn parse_xwm() {
+    let fmt = XwmaSourceFormat::new(WAVE_FORMAT_WMAUDIO2, 1, 22050, 2000, 4).unwrap();
+    let dpds = [4096u32, 8192];
+    let file = riff(b"XWMA", &[(b"fmt ", fmt.as_bytes()), (b"dpds", bytemuck::cast_slice(&dpds)), (b"data", &[0; 8])]);
+    let xwm = Xwm::parse(&file).unwrap();
+    assert_eq!(xwm.format.as_bytes(), fmt.as_bytes());
+    assert_eq!(xwm.decoded_packet_cumulative_bytes, dpds);
+    assert_eq!(xwm.data.len(), 8);
+
+    assert_eq!(Xwm::parse(&riff(b"XWMA", &[(b"fmt ", fmt.as_bytes()), (b"dpds", bytemuck::cast_slice(&dpds)), (b"data", &[0; 4])])).err(), Some(Error::InvalidData("`data` chunk doesn't match the `dpds` chunk's packets")));
+    assert_eq!(Xwm::parse(&riff(b"XWMA", &[(b"fmt ", fmt.as_bytes()), (b"data", &[0; 8])])).err(), Some(Error::MissingChunk(*b"dpds")));
+    assert_eq!(Xwm::parse(&riff(b"XWMA", &[(b"fmt ", TypedSourceFormat::<[i16; 1]>::pcm(22050).as_bytes()), (b"dpds", &[]), (b"data", &[])])).err(), Some(Error::UnsupportedFormat(WAVE_FORMAT_PCM)));
+    assert_eq!(Xwm::parse(&riff(b"WAVE", &[])).err(), Some(Error::NotXwma(*b"WAVE")));
+}
@@ -0,0 +1,105 @@
+use bytemuck::{Pod, Zeroable};
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatex)\]
+/// WAVEFORMATEX: platform independent equivalent with an identical (packed, 18 byte) layout.
+///
+/// On Windows, this converts to/from `winapi::shared::mmreg::WAVEFORMATEX`.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, Hash)]
+#[repr(C, packed(1))] pub struct WaveFormatEx {
+    /// Waveform-audio format type (e.g. [WAVE_FORMAT_PCM], [WAVE_FORMAT_IEEE_FLOAT], [WAVE_FORMAT_EXTENSIBLE], ...)
+    pub wFormatTag:         u16,
+
+    /// Number of channels in the waveform-audio data.
+    pub nChannels:          u16,
+
+    /// Sample rate, in samples per second (hertz.)
+    pub nSamplesPerSec:     u32,
+
+    /// Required average data-transfer rate, in bytes per second.
+    pub nAvgBytesPerSec:    u32,
+
+    /// Block alignment, in bytes.  The minimum atomic unit of data for the `wFormatTag` format type.
+    pub nBlockAlign:        u16,
+
+    /// Bits per sample for the `wFormatTag` format type.
+    pub wBitsPerSample:     u16,
+
+    /// Size, in bytes, of extra format information appended to the end of the structure.
+    pub cbSize:             u16,
+}
+
+const _ : () = assert!(core::mem::size_of ::<WaveFormatEx>() == 18);
+const _ : () = assert!(core::mem::align_of::<WaveFormatEx>() == 1);
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatex#members)\] Integer [Pulse-code modulation](https://en.wikipedia.org/wiki/Pulse-code_modulation)
+pub const WAVE_FORMAT_PCM           : u16 = 0x0001;
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-audio-formats)\] Microsoft [ADPCM](https://en.wikipedia.org/wiki/Adaptive_differential_pulse-code_modulation) (`ADPCMWAVEFORMAT`)
+pub const WAVE_FORMAT_ADPCM         : u16 = 0x0002;
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatex#members)\] IEEE floating point [Pulse-code modulation](https://en.wikipedia.org/wiki/Pulse-code_modulation)
+pub const WAVE_FORMAT_IEEE_FLOAT    : u16 = 0x0003;
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-audio-formats)\] xWMA (Windows Media Audio 9)
+pub const WAVE_FORMAT_WMAUDIO2      : u16 = 0x0161;
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-audio-formats)\] xWMA (Windows Media Audio 9 Professional)
+pub const WAVE_FORMAT_WMAUDIO3      : u16 = 0x0162;
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatextensible)\] WAVEFORMATEXTENSIBLE header (format is determined by `SubFormat`)
+pub const WAVE_FORMAT_EXTENSIBLE    : u16 = 0xFFFE;
+
+#[cfg(windows)] const _ : () = {
+    use winapi::shared::mmreg as mmreg;
+    assert!(core::mem::size_of::<mmreg::WAVEFORMATEX>() == core::mem::size_of::<WaveFormatEx>());
+    assert!(WAVE_FORMAT_PCM         == mmreg::WAVE_FORMAT_PCM);
+    assert!(WAVE_FORMAT_ADPCM       == mmreg::WAVE_FORMAT_ADPCM);
+    assert!(WAVE_FORMAT_IEEE_FLOAT  == mmreg::WAVE_FORMAT_IEEE_FLOAT);
+    assert!(WAVE_FORMAT_EXTENSIBLE  == mmreg::WAVE_FORMAT_EXTENSIBLE);
+};
+
+#[cfg(windows)] impl From<winapi::shared::mmreg::WAVEFORMATEX> for WaveFormatEx {
+    fn from(f: winapi::shared::mmreg::WAVEFORMATEX) -> Self {
+        Self {
+            wFormatTag:         f.wFormatTag,
+            nChannels:          f.nChannels,
+            nSamplesPerSec:     f.nSamplesPerSec,
+            nAvgBytesPerSec:    f.nAvgBytesPerSec,
+            nBlockAlign:        f.nBlockAlign,
+            wBitsPerSample:     f.wBitsPerSample,
+            cbSize:             f.cbSize,
+        }
+    }
+}
+
+#[cfg(windows)] impl From<WaveFormatEx> for winapi::shared::mmreg::WAVEFORMATEX {
+    fn from(f: WaveFormatEx) -> Self {
+        Self {
+            wFormatTag:         f.wFormatTag,
+            nChannels:          f.nChannels,
+            nSamplesPerSec:     f.nSamplesPerSec,
+            nAvgBytesPerSec:    f.nAvgBytesPerSec,
+            nBlockAlign:        f.nBlockAlign,
+            wBitsPerSample:     f.wBitsPerSample,
+            cbSize:             f.cbSize,
+        }
+    }
+}
+
+#[test] fn layout() {
+    let f = WaveFormatEx { wFormatTag: WAVE_FORMAT_PCM, nChannels: 2, nSamplesPerSec: 44100, nAvgBytesPerSec: 44100 * 4, nBlockAlign: 4, wBitsPerSample: 16, cbSize: 0 };
+    let bytes = bytemuck::bytes_of(&f);
+    assert_eq!(bytes, [
+        0x01, 0x00,                 // wFormatTag
+        0x02, 0x00,                 // nChannels
+        0x44, 0xAC, 0x00, 0x00,     // nSamplesPerSec
+        0x10, 0xB1, 0x02, 0x00,     // nAvgBytesPerSec
+        0x04, 0x00,                 // nBlockAlign
+        0x10, 0x00,                 // wBitsPerSample
+        0x00, 0x00,                 // cbSize
+    ]);
+    assert_eq!(bytemuck::pod_read_unaligned::<WaveFormatEx>(bytes), f);
+}
@@ -0,0 +1,123 @@
+use super::*;
+
+use bytemuck::{Pod, Zeroable};
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/guiddef/ns-guiddef-guid)\]
+/// GUID: platform independent equivalent with an identical (16 byte) layout.
+///
+/// On Windows, this converts to/from `winapi::shared::guiddef::GUID`.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)] pub struct Guid {
+    pub Data1: u32,
+    pub Data2: u16,
+    pub Data3: u16,
+    pub Data4: [u8; 8],
+}
+
+const _ : () = assert!(core::mem::size_of::<Guid>() == 16);
+
+impl Guid {
+    /// `DEFINE_WAVEFORMATEX_GUID(tag)`: `{tag-0000-0010-8000-00AA00389B71}`, the `KSDATAFORMAT_SUBTYPE_*` equivalent of a `WAVE_FORMAT_*` tag.
+    pub const fn from_wave_format_tag(tag: u16) -> Self { Self { Data1: tag as u32, Data2: 0x0000, Data3: 0x0010, Data4: [0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71] } }
+
+    /// The `WAVE_FORMAT_*` tag this `KSDATAFORMAT_SUBTYPE_*` was defined from, if any (the inverse of [from_wave_format_tag](Self::from_wave_format_tag).)
+    pub fn wave_format_tag(&self) -> Option<u16> {
+        let tag = u16::try_from(self.Data1).ok()?;
+        if *self == Self::from_wave_format_tag(tag) { Some(tag) } else { None }
+    }
+}
+
+#[cfg(windows)] impl From<winapi::shared::guiddef::GUID> for Guid {
+    fn from(g: winapi::shared::guiddef::GUID) -> Self { Self { Data1: g.Data1, Data2: g.Data2, Data3: g.Data3, Data4: g.Data4 } }
+}
+
+#[cfg(windows)] impl From<Guid> for winapi::shared::guiddef::GUID {
+    fn from(g: Guid) -> Self { Self { Data1: g.Data1, Data2: g.Data2, Data3: g.Data3, Data4: g.Data4 } }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows-hardware/drivers/audio/subformat-guids-for-compressed-audio-formats)\] Integer PCM `SubFormat` for [WaveFormatExtensible]
+pub const KSDATAFORMAT_SUBTYPE_PCM          : Guid = Guid::from_wave_format_tag(WAVE_FORMAT_PCM);
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows-hardware/drivers/audio/subformat-guids-for-compressed-audio-formats)\] IEEE floating point PCM `SubFormat` for [WaveFormatExtensible]
+pub const KSDATAFORMAT_SUBTYPE_IEEE_FLOAT   : Guid = Guid::from_wave_format_tag(WAVE_FORMAT_IEEE_FLOAT);
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatextensible)\]
+/// WAVEFORMATEXTENSIBLE: platform independent equivalent with an identical (packed, 40 byte) layout.
+///
+/// Describes formats [WaveFormatEx] can't: more than 2 channels with specific speaker assignments,
+/// samples with fewer valid bits than their container (e.g. 24-in-32 or 20-in-24), etc.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq, Hash)]
+#[repr(C, packed(1))] pub struct WaveFormatExtensible {
+    /// `wFormatTag` must be [WAVE_FORMAT_EXTENSIBLE], `cbSize` must be at least 22, and `wBitsPerSample` is the container size.
+    pub Format:                 WaveFormatEx,
+
+    /// Valid bits per sample, `<= Format.wBitsPerSample`.
+    /// (The C definition is a union with `wSamplesPerBlock` / `wReserved`, but XAudio2 only uses PCM and float sub formats.)
+    pub wValidBitsPerSample:    u16,
+
+    /// Bitmask of `SPEAKER_*` positions, assigned to channels in order of increasing bit.  `0` leaves channels unassigned.
+    pub dwChannelMask:          u32,
+
+    /// The actual format (e.g. [KSDATAFORMAT_SUBTYPE_PCM], [KSDATAFORMAT_SUBTYPE_IEEE_FLOAT].)
+    pub SubFormat:              Guid,
+}
+
+const _ : () = assert!(core::mem::size_of ::<WaveFormatExtensible>() == 40);
+const _ : () = assert!(core::mem::align_of::<WaveFormatExtensible>() == 1);
+
+/// The `cbSize` of a [WaveFormatExtensible]: the bytes following [WaveFormatExtensible::Format].
+pub(crate) const WAVE_FORMAT_EXTENSIBLE_CB_SIZE : u16 = (core::mem::size_of::<WaveFormatExtensible>() - core::mem::size_of::<WaveFormatEx>()) as u16;
+
+#[cfg(windows)] const _ : () = {
+    use winapi::shared::mmreg as mmreg;
+    assert!(core::mem::size_of::<mmreg::WAVEFORMATEXTENSIBLE>() == core::mem::size_of::<WaveFormatExtensible>());
+};
+
+
+
+// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/mmreg/ns-mmreg-waveformatextensible#remarks)\] [WaveFormatExtensible::dwChannelMask] speaker positions
+#[doc = "Front left speaker"]               pub const SPEAKER_FRONT_LEFT            : u32 = 0x00000001;
+#[doc = "Front right speaker"]              pub const SPEAKER_FRONT_RIGHT           : u32 = 0x00000002;
+#[doc = "Front center speaker"]             pub const SPEAKER_FRONT_CENTER          : u32 = 0x00000004;
+#[doc = "Low frequency speaker"]            pub const SPEAKER_LOW_FREQUENCY         : u32 = 0x00000008;
+#[doc = "Back left speaker"]                pub const SPEAKER_BACK_LEFT             : u32 = 0x00000010;
+#[doc = "Back right speaker"]               pub const SPEAKER_BACK_RIGHT            : u32 = 0x00000020;
+#[doc = "Front left of center speaker"]     pub const SPEAKER_FRONT_LEFT_OF_CENTER  : u32 = 0x00000040;
+#[doc = "Front right of center speaker"]    pub const SPEAKER_FRONT_RIGHT_OF_CENTER : u32 = 0x00000080;
+#[doc = "Back center speaker"]              pub const SPEAKER_BACK_CENTER           : u32 = 0x00000100;
+#[doc = "Side left speaker"]                pub const SPEAKER_SIDE_LEFT             : u32 = 0x00000200;
+#[doc = "Side right speaker"]               pub const SPEAKER_SIDE_RIGHT            : u32 = 0x00000400;
+#[doc = "Top center speaker"]               pub const SPEAKER_TOP_CENTER            : u32 = 0x00000800;
+#[doc = "Top front left speaker"]           pub const SPEAKER_TOP_FRONT_LEFT        : u32 = 0x00001000;
+#[doc = "Top front center speaker"]         pub const SPEAKER_TOP_FRONT_CENTER      : u32 = 0x00002000;
+#[doc = "Top front right speaker"]          pub const SPEAKER_TOP_FRONT_RIGHT       : u32 = 0x00004000;
+#[doc = "Top back left speaker"]            pub const SPEAKER_TOP_BACK_LEFT         : u32 = 0x00008000;
+#[doc = "Top back center speaker"]          pub const SPEAKER_TOP_BACK_CENTER       : u32 = 0x00010000;
+#[doc = "Top back right speaker"]           pub const SPEAKER_TOP_BACK_RIGHT        : u32 = 0x00020000;
+
+// Common speaker layouts (as defined by `audiodefs.h` / `x3daudio.h`)
+#[doc = "1 channel"]                        pub const SPEAKER_MONO                  : u32 = SPEAKER_FRONT_CENTER;
+#[doc = "2 channel"]                        pub const SPEAKER_STEREO                : u32 = SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT;
+#[doc = "3 channel"]                        pub const SPEAKER_2POINT1               : u32 = SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT | SPEAKER_LOW_FREQUENCY;
+#[doc = "4 channel"]                        pub const SPEAKER_SURROUND              : u32 = SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT | SPEAKER_FRONT_CENTER | SPEAKER_BACK_CENTER;
+#[doc = "4 channel"]                        pub const SPEAKER_QUAD                  : u32 = SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT | SPEAKER_BACK_LEFT | SPEAKER_BACK_RIGHT;
+#[doc = "5 channel"]                        pub const SPEAKER_4POINT1               : u32 = SPEAKER_QUAD | SPEAKER_LOW_FREQUENCY;
+#[doc = "6 channel"]                        pub const SPEAKER_5POINT1               : u32 = SPEAKER_QUAD | SPEAKER_FRONT_CENTER | SPEAKER_LOW_FREQUENCY;
+#[doc = "8 channel"]                        pub const SPEAKER_7POINT1               : u32 = SPEAKER_5POINT1 | SPEAKER_FRONT_LEFT_OF_CENTER | SPEAKER_FRONT_RIGHT_OF_CENTER;
+#[doc = "6 channel"]                        pub const SPEAKER_5POINT1_SURROUND      : u32 = SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT | SPEAKER_FRONT_CENTER | SPEAKER_LOW_FREQUENCY | SPEAKER_SIDE_LEFT | SPEAKER_SIDE_RIGHT;
+#[doc = "8 channel"]                        pub const SPEAKER_7POINT1_SURROUND      : u32 = SPEAKER_5POINT1 | SPEAKER_SIDE_LEFT | SPEAKER_SIDE_RIGHT;
+
+
+
+#[test] fn sub_format() {
+    assert_eq!(KSDATAFORMAT_SUBTYPE_PCM.wave_format_tag(),          Some(WAVE_FORMAT_PCM));
+    assert_eq!(KSDATAFORMAT_SUBTYPE_IEEE_FLOAT.wave_format_tag(),   Some(WAVE_FORMAT_IEEE_FLOAT));
+    assert_eq!(Guid { Data1: 1, .. Guid::default() }.wave_format_tag(), None);
+    assert_eq!(bytemuck::bytes_of(&KSDATAFORMAT_SUBTYPE_PCM), [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71]);
+}
@@ -0,0 +1,45 @@
+//! ✔️ Platform independent X3DAudio types - listeners, emitters, cones, distance curves, and DSP settings
+//!
+//! These are owned equivalents of `x3daudio.h`'s structs: [Emitter] owns its channel azimuths and distance curves,
+//! and [DspSettings] owns its matrix coefficients and delay times.
+//! [Spatializer] fills [DspSettings] in pure Rust, and on Windows, `xaudio2_9::x3daudio::X3DAudio` fills them via `X3DAudioCalculate`.
+//! Both implement [Calculator].
+//!
+//! ### References
+//! *   [X3DAudio](https://learn.microsoft.com/en-us/windows/win32/xaudio2/x3daudio)
+//! *   [x3daudio.h header](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/)
+
+use winresult::*;
+
+mod calculator;                     pub use calculator::*;
+mod cone;                           pub use cone::*;
+mod distance_curve;                 pub use distance_curve::*;
+mod dsp_settings;                   pub use dsp_settings::*;
+mod emitter;                        pub use emitter::*;
+mod listener;                       pub use listener::*;
+mod spatializer;                    pub use spatializer::*;
+mod vector;                         pub use vector::*;
+
+
+
+#[allow(clippy::approx_constant)] // mirror x3daudio.h's literals exactly, rather than core::f32::consts
+#[doc = "`π`, as defined by `x3daudio.h`"]                                pub const PI                : f32 = 3.141592654;
+#[allow(clippy::approx_constant)]
+#[doc = "`2π`, as defined by `x3daudio.h`"]                               pub const TWO_PI            : f32 = 6.283185307;
+#[doc = "Speed of sound in meters per second (dry air at ~20°C)"]         pub const SPEED_OF_SOUND    : f32 = 343.5;
+
+// Calculation control flags
+#[doc = "Calculate [DspSettings::matrix_coefficients]"]                   pub const CALCULATE_MATRIX          : u32 = 0x00000001;
+#[doc = "Calculate [DspSettings::delay_times] (stereo final mix only)"]   pub const CALCULATE_DELAY           : u32 = 0x00000002;
+#[doc = "Calculate [DspSettings::lpf_direct_coefficient]"]                pub const CALCULATE_LPF_DIRECT      : u32 = 0x00000004;
+#[doc = "Calculate [DspSettings::lpf_reverb_coefficient]"]                pub const CALCULATE_LPF_REVERB      : u32 = 0x00000008;
+#[doc = "Calculate [DspSettings::reverb_level]"]                          pub const CALCULATE_REVERB          : u32 = 0x00000010;
+#[doc = "Calculate [DspSettings::doppler_factor]"]                        pub const CALCULATE_DOPPLER         : u32 = 0x00000020;
+#[doc = "Calculate [DspSettings::emitter_to_listener_angle]"]             pub const CALCULATE_EMITTER_ANGLE   : u32 = 0x00000040;
+#[doc = "Don't pan to the front center speaker (matrix only)"]            pub const CALCULATE_ZEROCENTER      : u32 = 0x00010000;
+#[doc = "Mix all source channels equally into the LFE (matrix only)"]     pub const CALCULATE_REDIRECT_TO_LFE : u32 = 0x00020000;
+
+/// Emitter azimuth marking a channel as LFE (see [Emitter::channel_azimuths].)
+pub const LFE_AZIMUTH : f32 = TWO_PI;
+
+fn check(ok: bool) -> Result<(), HResultError> { if ok { Ok(()) } else { Err(E::INVALIDARG) } }
@@ -0,0 +1,27 @@
+use super::*;
+
+
+
+/// Something that calculates [DspSettings] for an [Emitter] heard by a [Listener]:
+/// [Spatializer] (pure Rust), or `xaudio2_9::x3daudio::X3DAudio` (`X3DAudioCalculate`) on Windows.
+pub trait Calculator {
+    /// The number of channels in the final mix ([DspSettings::dst_channel_count].)
+    fn dst_channel_count(&self) -> u32;
+
+    /// Calculate the `flags` (`CALCULATE_*`) parts of `settings` for `emitter` as heard by `listener`,
+    /// resizing `settings`' buffers as needed.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `listener` or `emitter` fail validation ([Listener::validate], [Emitter::validate])
+    fn calculate_into(&self, listener: &Listener, emitter: &Emitter, flags: u32, settings: &mut DspSettings) -> Result<(), HResultError>;
+
+    /// Calculate the `flags` (`CALCULATE_*`) parts of a new [DspSettings] for `emitter` as heard by `listener`.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `listener` or `emitter` fail validation ([Listener::validate], [Emitter::validate])
+    fn calculate(&self, listener: &Listener, emitter: &Emitter, flags: u32) -> Result<DspSettings, HResultError> {
+        let mut settings = DspSettings::new(emitter.channel_count(), self.dst_channel_count());
+        self.calculate_into(listener, emitter, flags, &mut settings)?;
+        Ok(settings)
+    }
+}
@@ -0,0 +1,62 @@
+use super::*;
+
+use bytemuck::{Pod, Zeroable};
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_cone)\]
+/// X3DAUDIO_CONE: scales DSP settings by the angle between a [Listener] / single-channel [Emitter]'s front orientation and the other party.
+///
+/// Angles are in radians within 0 ..= [TWO_PI] (with `InnerAngle <= OuterAngle`.)
+/// Settings are linearly interpolated between the inner and outer cones.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Zeroable, Pod, PartialEq)]
+#[repr(C)] pub struct Cone {
+    /// Inner cone angle, in radians.
+    pub InnerAngle:     f32,
+    /// Outer cone angle, in radians.
+    pub OuterAngle:     f32,
+    /// Volume scaler on/within the inner cone (0 ..= 2.)
+    pub InnerVolume:    f32,
+    /// Volume scaler on/beyond the outer cone (0 ..= 2.)
+    pub OuterVolume:    f32,
+    /// LPF coefficient subtrahend on/within the inner cone (0 ..= 1.)
+    pub InnerLPF:       f32,
+    /// LPF coefficient subtrahend on/beyond the outer cone (0 ..= 1.)
+    pub OuterLPF:       f32,
+    /// Reverb send level scaler on/within the inner cone (0 ..= 2.)
+    pub InnerReverb:    f32,
+    /// Reverb send level scaler on/beyond the outer cone (0 ..= 2.)
+    pub OuterReverb:    f32,
+}
+
+impl Cone {
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if any field is out of range (or NaN.)
+    pub fn validate(&self) -> Result<(), HResultError> {
+        check((0.0 ..= TWO_PI).contains(&self.InnerAngle))?;
+        check((self.InnerAngle ..= TWO_PI).contains(&self.OuterAngle))?;
+        check((0.0 ..= 2.0).contains(&self.InnerVolume))?;
+        check((0.0 ..= 2.0).contains(&self.OuterVolume))?;
+        check((0.0 ..= 1.0).contains(&self.InnerLPF))?;
+        check((0.0 ..= 1.0).contains(&self.OuterLPF))?;
+        check((0.0 ..= 2.0).contains(&self.InnerReverb))?;
+        check((0.0 ..= 2.0).contains(&self.OuterReverb))?;
+        Ok(())
+    }
+}
+
+/// X3DAudioDefault_DirectionalCone: full volume within 90°, -3 dB beyond 180°, with more filtering and reverb behind.
+pub const DIRECTIONAL_CONE : Cone = Cone {
+    InnerAngle:     PI / 2.0,
+    OuterAngle:     PI,
+    InnerVolume:    1.0,
+    OuterVolume:    0.708,
+    InnerLPF:       0.0,
+    OuterLPF:       0.25,
+    InnerReverb:    0.708,
+    OuterReverb:    1.0,
+};
+
+#[cfg(windows)] impl From<Cone> for crate::sys::x3daudio::X3DAUDIO_CONE {
+    fn from(c: Cone) -> Self { bytemuck::cast(c) }
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+use bytemuck::{Pod, Zeroable};
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_distance_curve_point)\]
+/// X3DAUDIO_DISTANCE_CURVE_POINT: a point of a piecewise linear distance curve.
+///
+/// A curve is a `[DistanceCurvePoint]` of at least two points, sorted by strictly ascending `Distance`,
+/// starting at `0.0` and ending at `1.0` (see [validate_distance_curve].)
+/// Distances are normalized: they're multiplied by [Emitter::curve_distance_scaler] to get world units.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)]
+#[repr(C)] pub struct DistanceCurvePoint {
+    /// Normalized distance (0 ..= 1.)
+    pub Distance:   f32,
+    /// DSP setting (volume, LPF coefficient, reverb level, etc.) at `Distance`.
+    pub DSPSetting: f32,
+}
+
+impl DistanceCurvePoint {
+    /// `DistanceCurvePoint { Distance, DSPSetting }`
+    pub const fn new(distance: f32, dsp_setting: f32) -> Self { Self { Distance: distance, DSPSetting: dsp_setting } }
+}
+
+/// X3DAudioDefault_LinearCurvePoints: full volume at distance 0, silence at distance 1.
+pub const LINEAR_CURVE : [DistanceCurvePoint; 2] = [DistanceCurvePoint::new(0.0, 1.0), DistanceCurvePoint::new(1.0, 0.0)];
+
+/// Returns <code>[Err]\([E::INVALIDARG]\)</code> unless `curve` has at least two points, with strictly ascending distances from `0.0` to `1.0`.
+pub fn validate_distance_curve(curve: &[DistanceCurvePoint]) -> Result<(), HResultError> {
+    check(curve.len() >= 2 && u32::try_from(curve.len()).is_ok())?;
+    check(curve[0].Distance == 0.0 && curve[curve.len()-1].Distance == 1.0)?;
+    check(curve.windows(2).all(|w| w[0].Distance < w[1].Distance))?;
+    check(curve.iter().all(|p| p.DSPSetting.is_finite()))?;
+    Ok(())
+}
+
+#[cfg(windows)] impl From<DistanceCurvePoint> for crate::sys::x3daudio::X3DAUDIO_DISTANCE_CURVE_POINT {
+    fn from(p: DistanceCurvePoint) -> Self { bytemuck::cast(p) }
+}
@@ -0,0 +1,81 @@
+use alloc::vec::Vec;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_dsp_settings)\]
+/// X3DAUDIO_DSP_SETTINGS: the results of an X3DAudio calculation, with owned buffers.
+///
+/// Values not requested via `CALCULATE_*` flags are left as they were.
+#[derive(Clone, Debug, Default, PartialEq)] pub struct DspSettings {
+    /// `src_channel_count * dst_channel_count` volume levels, with the level from source channel `s` to destination channel `d` at `[d * src_channel_count + s]`.
+    ///
+    /// This is the layout expected by `Voice::set_output_matrix`.
+    pub matrix_coefficients:            Vec<f32>,
+
+    /// `dst_channel_count` delays in milliseconds (stereo final mixes only.)
+    pub delay_times:                    Vec<f32>,
+
+    /// Channels of the emitter.
+    pub src_channel_count:              u32,
+
+    /// Channels of the final mix (e.g. the mastering voice.)
+    pub dst_channel_count:              u32,
+
+    /// Direct-path LPF coefficient (for [LowPassOnePoleFilter](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ne-xaudio2-xaudio2_filter_type) `Frequency`.)
+    pub lpf_direct_coefficient:         f32,
+
+    /// Reverb-path LPF coefficient (for [LowPassOnePoleFilter](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ne-xaudio2-xaudio2_filter_type) `Frequency`.)
+    pub lpf_reverb_coefficient:         f32,
+
+    /// Reverb send level (for the output matrix to a reverb submix.)
+    pub reverb_level:                   f32,
+
+    /// Doppler shift factor: scale the source voice's frequency ratio by this.
+    pub doppler_factor:                 f32,
+
+    /// Angle between the emitter's front orientation and the listener, in radians.
+    pub emitter_to_listener_angle:      f32,
+
+    /// Distance from the emitter to the listener, in world units (always calculated.)
+    pub emitter_to_listener_distance:   f32,
+
+    /// Emitter velocity towards the listener, in world units per second (doppler only.)
+    pub emitter_velocity_component:     f32,
+
+    /// Listener velocity towards the emitter, in world units per second (doppler only.)
+    pub listener_velocity_component:    f32,
+}
+
+impl DspSettings {
+    /// Allocate zeroed buffers for an `src_channel_count` channel emitter mixed into `dst_channel_count` channels, with a [doppler_factor](Self::doppler_factor) of `1.0`.
+    pub fn new(src_channel_count: u32, dst_channel_count: u32) -> Self {
+        let mut s = Self { doppler_factor: 1.0, .. Default::default() };
+        s.resize(src_channel_count, dst_channel_count);
+        s
+    }
+
+    /// Set the channel counts, resizing (and if necessary, reallocating) the owned buffers to match.
+    pub fn resize(&mut self, src_channel_count: u32, dst_channel_count: u32) {
+        self.src_channel_count = src_channel_count;
+        self.dst_channel_count = dst_channel_count;
+        self.matrix_coefficients.resize(src_channel_count as usize * dst_channel_count as usize, 0.0);
+        self.delay_times.resize(dst_channel_count as usize, 0.0);
+    }
+
+    /// The level from source channel `src` to destination channel `dst`, if in bounds.
+    pub fn matrix_coefficient(&self, src: u32, dst: u32) -> Option<f32> {
+        if src >= self.src_channel_count || dst >= self.dst_channel_count { return None }
+        self.matrix_coefficients.get(dst as usize * self.src_channel_count as usize + src as usize).copied()
+    }
+}
+
+#[test] fn dsp_settings_layout() {
+    let mut s = DspSettings::new(2, 6);
+    assert_eq!(s.matrix_coefficients, alloc::vec![0.0; 12]);
+    assert_eq!(s.delay_times.len(), 6);
+    s.matrix_coefficients[5 * 2 + 1] = 0.5;
+    assert_eq!(s.matrix_coefficient(1, 5), Some(0.5));
+    assert_eq!(s.matrix_coefficient(2, 5), None);
+    s.resize(1, 2);
+    assert_eq!(s.matrix_coefficients.len(), 2);
+}
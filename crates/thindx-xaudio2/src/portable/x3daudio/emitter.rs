@@ -0,0 +1,109 @@
+use super::*;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_emitter)\]
+/// X3DAUDIO_EMITTER: a single-point or multi-point 3D audio source.
+///
+/// Curves left as [None] use X3DAudio's defaults (see each field.)
+#[derive(Clone, Debug, PartialEq)] pub struct Emitter {
+    /// Sound cone (single-channel emitters only), or [None] for an omnidirectional emitter.
+    pub cone:                   Option<Cone>,
+
+    /// Front orientation: must be normalized when used (emitter angle, multi-channel matrix, or `cone` calculations.)
+    pub orient_front:           Vector,
+
+    /// Top orientation: must be normalized and orthogonal to `orient_front` for multi-channel matrix calculations.
+    pub orient_top:             Vector,
+
+    /// Position, in world units.
+    pub position:               Vector,
+
+    /// Velocity, in world units per second (only used for doppler calculations - `position` isn't updated by it.)
+    pub velocity:               Vector,
+
+    /// Radius of the inner sphere, within which the sound is spread across all speakers (0 ..= [f32::MAX].)
+    pub inner_radius:           f32,
+
+    /// Angle of the inner cone, within which the sound is spread across all speakers (0 ..= [PI]/4.)
+    pub inner_radius_angle:     f32,
+
+    /// Distance of multi-channel emitters' channels from `position` (`>= 0`.)
+    pub channel_radius:         f32,
+
+    /// One azimuth per channel (at least one), in radians clockwise from `orient_front` as seen from above (so [PI]/2 is to the right, 0 ..= [TWO_PI]), or [LFE_AZIMUTH] for an LFE channel.
+    /// Only used for multi-channel emitters.
+    pub channel_azimuths:       Vec<f32>,
+
+    /// Volume curve, or [None] for an inverse square law falloff beyond [curve_distance_scaler](Self::curve_distance_scaler).
+    pub volume_curve:           Option<Vec<DistanceCurvePoint>>,
+
+    /// LFE curve, or [None] for an inverse square law falloff beyond [curve_distance_scaler](Self::curve_distance_scaler).
+    pub lfe_curve:              Option<Vec<DistanceCurvePoint>>,
+
+    /// Direct-path LPF coefficient curve, or [None] for `[(0.0, 1.0), (1.0, 0.75)]`.
+    pub lpf_direct_curve:       Option<Vec<DistanceCurvePoint>>,
+
+    /// Reverb-path LPF coefficient curve, or [None] for `[(0.0, 0.75), (1.0, 0.75)]`.
+    pub lpf_reverb_curve:       Option<Vec<DistanceCurvePoint>>,
+
+    /// Reverb send level curve, or [None] for `[(0.0, 1.0), (1.0, 0.0)]`.
+    pub reverb_curve:           Option<Vec<DistanceCurvePoint>>,
+
+    /// Scales normalized curve distances into world units ([f32::MIN_POSITIVE] ..= [f32::MAX].)
+    pub curve_distance_scaler:  f32,
+
+    /// Exaggerates (or diminishes) the doppler effect (0 ..= [f32::MAX].)
+    pub doppler_scaler:         f32,
+}
+
+impl Default for Emitter {
+    /// An omnidirectional, stationary, mono emitter at the origin, with default curves and scalers of `1.0`.
+    fn default() -> Self {
+        Self {
+            cone:                   None,
+            orient_front:           Vector::FRONT,
+            orient_top:             Vector::UP,
+            position:               Vector::ZERO,
+            velocity:               Vector::ZERO,
+            inner_radius:           0.0,
+            inner_radius_angle:     0.0,
+            channel_radius:         0.0,
+            channel_azimuths:       vec![0.0],
+            volume_curve:           None,
+            lfe_curve:              None,
+            lpf_direct_curve:       None,
+            lpf_reverb_curve:       None,
+            reverb_curve:           None,
+            curve_distance_scaler:  1.0,
+            doppler_scaler:         1.0,
+        }
+    }
+}
+
+impl Emitter {
+    /// The number of channels this emitter emits (`channel_azimuths.len()`.)
+    pub fn channel_count(&self) -> u32 { self.channel_azimuths.len() as u32 }
+
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if any field is out of range (or NaN), or any curve is invalid (see [validate_distance_curve].)
+    ///
+    /// Orientations aren't checked for orthonormality: callers typically derive them from transforms with some floating point error.
+    pub fn validate(&self) -> Result<(), HResultError> {
+        if let Some(cone) = self.cone.as_ref() { cone.validate()?; }
+        check(self.orient_front.is_finite() && self.orient_top.is_finite() && self.position.is_finite() && self.velocity.is_finite())?;
+        check((0.0 ..= f32::MAX).contains(&self.inner_radius))?;
+        check((0.0 ..= PI/4.0).contains(&self.inner_radius_angle))?;
+        check((0.0 ..= f32::MAX).contains(&self.channel_radius))?;
+        check((1 ..= 64).contains(&self.channel_azimuths.len()))?; // XAUDIO2_MAX_AUDIO_CHANNELS
+        check(self.channel_azimuths.iter().all(|a| (0.0 ..= TWO_PI).contains(a)))?;
+        for curve in [&self.volume_curve, &self.lfe_curve, &self.lpf_direct_curve, &self.lpf_reverb_curve, &self.reverb_curve] {
+            if let Some(curve) = curve.as_ref() { validate_distance_curve(curve)?; }
+        }
+        check((f32::MIN_POSITIVE ..= f32::MAX).contains(&self.curve_distance_scaler))?;
+        check((0.0 ..= f32::MAX).contains(&self.doppler_scaler))?;
+        Ok(())
+    }
+}
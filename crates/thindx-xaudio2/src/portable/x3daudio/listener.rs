@@ -0,0 +1,46 @@
+use super::*;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/ns-x3daudio-x3daudio_listener)\]
+/// X3DAUDIO_LISTENER: the point of reception (typically the camera or player.)
+#[derive(Clone, Copy, Debug, PartialEq)] pub struct Listener {
+    /// Front orientation: must be normalized (and orthogonal to `orient_top`) for matrix and delay calculations, or if `cone` is set.
+    pub orient_front:   Vector,
+
+    /// Top orientation: must be normalized and orthogonal to `orient_front` for matrix and delay calculations.
+    pub orient_top:     Vector,
+
+    /// Position, in world units.
+    pub position:       Vector,
+
+    /// Velocity, in world units per second (only used for doppler calculations - `position` isn't updated by it.)
+    pub velocity:       Vector,
+
+    /// Sound cone, or [None] for an omnidirectional listener.
+    pub cone:           Option<Cone>,
+}
+
+impl Default for Listener {
+    /// An omnidirectional, stationary listener at the origin, facing [Vector::FRONT] with [Vector::UP] up.
+    fn default() -> Self {
+        Self {
+            orient_front:   Vector::FRONT,
+            orient_top:     Vector::UP,
+            position:       Vector::ZERO,
+            velocity:       Vector::ZERO,
+            cone:           None,
+        }
+    }
+}
+
+impl Listener {
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if any vector is non-finite, or `cone` is invalid.
+    ///
+    /// Orientations aren't checked for orthonormality: callers typically derive them from transforms with some floating point error.
+    pub fn validate(&self) -> Result<(), HResultError> {
+        check(self.orient_front.is_finite() && self.orient_top.is_finite() && self.position.is_finite() && self.velocity.is_finite())?;
+        if let Some(cone) = self.cone.as_ref() { cone.validate()?; }
+        Ok(())
+    }
+}
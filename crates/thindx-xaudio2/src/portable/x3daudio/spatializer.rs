@@ -0,0 +1,370 @@
+use super::*;
+use crate::portable::*;
+
+use alloc::vec::Vec;
+
+
+
+const MIN_FREQ_RATIO        : f32 = 1.0 / 1024.0;   // XAUDIO2_MIN_FREQ_RATIO
+const MAX_FREQ_RATIO        : f32 = 1024.0;         // XAUDIO2_MAX_FREQ_RATIO
+const MAX_INTERAURAL_DELAY  : f32 = 0.7;            // milliseconds, for a source directly to one side
+
+const DEFAULT_LPF_DIRECT    : [DistanceCurvePoint; 2] = [DistanceCurvePoint::new(0.0, 1.0),  DistanceCurvePoint::new(1.0, 0.75)];
+const DEFAULT_LPF_REVERB    : [DistanceCurvePoint; 2] = [DistanceCurvePoint::new(0.0, 0.75), DistanceCurvePoint::new(1.0, 0.75)];
+const DEFAULT_REVERB        : [DistanceCurvePoint; 2] = [DistanceCurvePoint::new(0.0, 1.0),  DistanceCurvePoint::new(1.0, 0.0)];
+
+/// The azimuth of a single `SPEAKER_*` position, in radians clockwise from the front as seen from above
+/// (the same convention as [Emitter::channel_azimuths].)
+///
+/// Returns [LFE_AZIMUTH] for [SPEAKER_LOW_FREQUENCY], and [None] for height (`SPEAKER_TOP_*`), unknown, or multiple speakers.
+pub fn speaker_azimuth(speaker: u32) -> Option<f32> {
+    match speaker {
+        SPEAKER_FRONT_LEFT              => Some(7.0 * PI / 4.0),
+        SPEAKER_FRONT_RIGHT             => Some(PI / 4.0),
+        SPEAKER_FRONT_CENTER            => Some(0.0),
+        SPEAKER_LOW_FREQUENCY           => Some(LFE_AZIMUTH),
+        SPEAKER_BACK_LEFT               => Some(5.0 * PI / 4.0),
+        SPEAKER_BACK_RIGHT              => Some(3.0 * PI / 4.0),
+        SPEAKER_FRONT_LEFT_OF_CENTER    => Some(15.0 * PI / 8.0),
+        SPEAKER_FRONT_RIGHT_OF_CENTER   => Some(PI / 8.0),
+        SPEAKER_BACK_CENTER             => Some(PI),
+        SPEAKER_SIDE_LEFT               => Some(3.0 * PI / 2.0),
+        SPEAKER_SIDE_RIGHT              => Some(PI / 2.0),
+        _                               => None,
+    }
+}
+
+
+
+/// A pure Rust, X3DAudio-compatible [Calculator]: calculates [DspSettings] without loading any DLLs.
+///
+/// Deterministic for a given build, and usable on any platform (tests, servers validating replays, tools, etc.)
+/// Results follow X3DAudio's documented behavior, but aren't bit-identical to `X3DAudioCalculate`:
+///
+/// *   Emitter channels are panned between the two nearest speakers (by azimuth) with constant power,
+///     in the listener's horizontal plane.  Height (`SPEAKER_TOP_*`) speakers are left silent.
+///     Layouts without rear speakers (e.g. [SPEAKER_STEREO]) mirror sources behind the listener to the front.
+/// *   Within [Emitter::inner_radius], channels are blended towards an even spread across all speakers.
+///     [Emitter::inner_radius_angle] is ignored.
+/// *   [CALCULATE_DELAY] approximates an interaural time difference of up to 0.7 ms (stereo final mixes only.)
+/// *   [DspSettings::doppler_factor] is clamped to `XAUDIO2_MIN_FREQ_RATIO ..= XAUDIO2_MAX_FREQ_RATIO`,
+///     so it can be passed straight to `set_frequency_ratio`.
+///
+/// ### Example
+/// ```
+/// # use thindx_xaudio2::portable::x3daudio::*;
+/// # use thindx_xaudio2::portable::SPEAKER_STEREO;
+/// let spatializer = Spatializer::new(SPEAKER_STEREO, SPEED_OF_SOUND).unwrap();
+/// let listener    = Listener::default();
+/// let emitter     = Emitter { position: Vector::new(2.0, 0.0, 0.0), .. Default::default() };
+/// let dsp = spatializer.calculate(&listener, &emitter, CALCULATE_MATRIX | CALCULATE_DOPPLER).unwrap();
+/// assert_eq!(dsp.matrix_coefficient(0, 0), Some(0.0)); // left
+/// assert_eq!(dsp.matrix_coefficient(0, 1), Some(0.5)); // right (inverse distance beyond `curve_distance_scaler`)
+/// assert_eq!(dsp.doppler_factor, 1.0);
+/// ```
+#[derive(Clone, Debug)] pub struct Spatializer {
+    speaker_channel_mask:   u32,
+    speed_of_sound:         f32,
+    speakers:               Vec<Speaker>,   // one per destination channel
+    pan_all:                Vec<Speaker>,   // panning targets, sorted by azimuth
+    pan_zero_center:        Vec<Speaker>,   // panning targets for CALCULATE_ZEROCENTER
+}
+
+#[derive(Clone, Copy, Debug)] struct Speaker {
+    channel:    usize,
+    mask:       u32,
+    azimuth:    Option<f32>,
+}
+
+impl Spatializer {
+    /// Prepare to calculate [DspSettings] for `speaker_channel_mask` (`SPEAKER_*` bits, one destination channel per bit),
+    /// with doppler using `speed_of_sound` (in world units per second, e.g. [SPEED_OF_SOUND] for meters.)
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `speaker_channel_mask` is `0`, or `speed_of_sound < f32::MIN_POSITIVE` (or NaN)
+    pub fn new(speaker_channel_mask: u32, speed_of_sound: f32) -> Result<Self, HResultError> {
+        if speaker_channel_mask == 0 || !(f32::MIN_POSITIVE ..= f32::MAX).contains(&speed_of_sound) { return Err(E::INVALIDARG) }
+
+        let speakers = (0 .. 32).map(|bit| 1u32 << bit).filter(|mask| speaker_channel_mask & mask != 0).enumerate()
+            .map(|(channel, mask)| Speaker { channel, mask, azimuth: speaker_azimuth(mask) })
+            .collect::<Vec<_>>();
+
+        let mut pan_all = speakers.iter().copied().filter(|s| s.azimuth.is_some() && s.mask != SPEAKER_LOW_FREQUENCY).collect::<Vec<_>>();
+        pan_all.sort_by(|a, b| a.azimuth.partial_cmp(&b.azimuth).unwrap());
+        let mut pan_zero_center = pan_all.clone();
+        if pan_zero_center.len() > 1 { pan_zero_center.retain(|s| s.mask != SPEAKER_FRONT_CENTER); }
+
+        Ok(Self { speaker_channel_mask, speed_of_sound, speakers, pan_all, pan_zero_center })
+    }
+
+    /// The speaker channel mask this was created with.
+    pub fn speaker_channel_mask(&self) -> u32 { self.speaker_channel_mask }
+
+    /// The speed of sound this was created with, in world units per second.
+    pub fn speed_of_sound(&self) -> f32 { self.speed_of_sound }
+
+    /// The number of channels in the final mix ([DspSettings::dst_channel_count].)
+    pub fn dst_channel_count(&self) -> u32 { self.speakers.len() as u32 }
+
+    /// Calculate the `flags` (`CALCULATE_*`) parts of [DspSettings] for `emitter` as heard by `listener`.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `listener` or `emitter` fail validation ([Listener::validate], [Emitter::validate])
+    pub fn calculate(&self, listener: &Listener, emitter: &Emitter, flags: u32) -> Result<DspSettings, HResultError> {
+        let mut settings = DspSettings::new(emitter.channel_count(), self.dst_channel_count());
+        self.calculate_into(listener, emitter, flags, &mut settings)?;
+        Ok(settings)
+    }
+
+    /// Like [calculate](Self::calculate), but reuses `settings`' buffers (resizing them as needed) to avoid per-frame allocations.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `listener` or `emitter` fail validation ([Listener::validate], [Emitter::validate])
+    pub fn calculate_into(&self, listener: &Listener, emitter: &Emitter, flags: u32, settings: &mut DspSettings) -> Result<(), HResultError> {
+        listener.validate()?;
+        emitter.validate()?;
+        settings.resize(emitter.channel_count(), self.dst_channel_count());
+
+        let to_listener = listener.position - emitter.position;
+        let distance    = to_listener.length();
+        let direction   = if distance > 0.0 { to_listener * (1.0 / distance) } else { Vector::ZERO };
+        let normalized  = distance / emitter.curve_distance_scaler;
+        settings.emitter_to_listener_distance = distance;
+
+        let emitter_angle   = angle_between(emitter.orient_front, direction);
+        let listener_angle  = angle_between(listener.orient_front, -direction);
+        let emitter_cone    = if emitter.channel_count() == 1 { emitter.cone.map_or(ConeScale::NONE, |c| ConeScale::new(&c, emitter_angle)) } else { ConeScale::NONE };
+        let listener_cone   = listener.cone.map_or(ConeScale::NONE, |c| ConeScale::new(&c, listener_angle));
+
+        if flags & CALCULATE_MATRIX != 0 {
+            self.matrix(listener, emitter, flags, emitter_cone.volume * listener_cone.volume, &mut settings.matrix_coefficients);
+        }
+
+        if flags & CALCULATE_DELAY != 0 {
+            settings.delay_times.iter_mut().for_each(|d| *d = 0.0);
+            if self.speaker_channel_mask == SPEAKER_STEREO {
+                let (x, _, z) = listener_local(listener, emitter.position);
+                let h = (x*x + z*z).sqrt();
+                let lateral = if h > 0.0 { x / h } else { 0.0 }; // +1 = right, -1 = left
+                settings.delay_times[0] = lateral.max(0.0) * MAX_INTERAURAL_DELAY;
+                settings.delay_times[1] = (-lateral).max(0.0) * MAX_INTERAURAL_DELAY;
+            }
+        }
+
+        if flags & CALCULATE_LPF_DIRECT != 0 {
+            let lpf = evaluate(emitter.lpf_direct_curve.as_deref().unwrap_or(&DEFAULT_LPF_DIRECT), normalized);
+            settings.lpf_direct_coefficient = (lpf - emitter_cone.lpf - listener_cone.lpf).clamp(0.0, 1.0);
+        }
+
+        if flags & CALCULATE_LPF_REVERB != 0 {
+            let lpf = evaluate(emitter.lpf_reverb_curve.as_deref().unwrap_or(&DEFAULT_LPF_REVERB), normalized);
+            settings.lpf_reverb_coefficient = (lpf - emitter_cone.lpf - listener_cone.lpf).clamp(0.0, 1.0);
+        }
+
+        if flags & CALCULATE_REVERB != 0 {
+            let level = evaluate(emitter.reverb_curve.as_deref().unwrap_or(&DEFAULT_REVERB), normalized);
+            settings.reverb_level = level * emitter_cone.reverb * listener_cone.reverb;
+        }
+
+        if flags & CALCULATE_DOPPLER != 0 {
+            let emitter_velocity    = emitter.velocity.dot(direction);
+            let listener_velocity   = listener.velocity.dot(direction);
+            settings.emitter_velocity_component     = emitter_velocity;
+            settings.listener_velocity_component    = listener_velocity;
+            settings.doppler_factor = if emitter.doppler_scaler == 0.0 { 1.0 } else {
+                let speed = self.speed_of_sound / emitter.doppler_scaler;
+                let num = speed - listener_velocity.min(speed);
+                let den = speed - emitter_velocity.min(speed);
+                if den > 0.0 { (num / den).clamp(MIN_FREQ_RATIO, MAX_FREQ_RATIO) } else { MAX_FREQ_RATIO }
+            };
+        }
+
+        if flags & CALCULATE_EMITTER_ANGLE != 0 {
+            settings.emitter_to_listener_angle = emitter_angle;
+        }
+
+        Ok(())
+    }
+
+    fn matrix(&self, listener: &Listener, emitter: &Emitter, flags: u32, cone_volume: f32, matrix: &mut [f32]) {
+        matrix.iter_mut().for_each(|m| *m = 0.0);
+        let src_count       = emitter.channel_azimuths.len();
+        let distance        = (listener.position - emitter.position).length();
+        let volume          = attenuation(emitter.volume_curve.as_deref(), distance, emitter.curve_distance_scaler) * cone_volume;
+        let lfe_volume      = attenuation(emitter.lfe_curve.as_deref(),    distance, emitter.curve_distance_scaler);
+        let pan             = if flags & CALCULATE_ZEROCENTER != 0 { &self.pan_zero_center[..] } else { &self.pan_all[..] };
+        let emitter_right   = emitter.orient_top.cross(emitter.orient_front);
+        let mut has_lfe     = false;
+
+        for (src, &azimuth) in emitter.channel_azimuths.iter().enumerate() {
+            if src_count > 1 && azimuth == LFE_AZIMUTH {
+                has_lfe = true;
+                for speaker in self.speakers.iter().filter(|s| s.mask == SPEAKER_LOW_FREQUENCY) {
+                    matrix[speaker.channel * src_count + src] = lfe_volume;
+                }
+                continue;
+            }
+
+            let position = if src_count > 1 {
+                let (sin, cos) = azimuth.sin_cos();
+                emitter.position + (emitter.orient_front * cos + emitter_right * sin) * emitter.channel_radius
+            } else {
+                emitter.position
+            };
+
+            let (x, y, z) = listener_local(listener, position);
+            let horizontal  = (x*x + z*z).sqrt();
+            let spread      = if horizontal <= 1e-6 * (horizontal + y.abs()) { 1.0 }
+                else if emitter.inner_radius > 0.0 { (1.0 - (x*x + y*y + z*z).sqrt() / emitter.inner_radius).max(0.0) }
+                else { 0.0 };
+            let azimuth = x.atan2(z).rem_euclid(TWO_PI);
+            pan_into(pan, azimuth, spread, volume, |channel, gain| matrix[channel * src_count + src] += gain);
+        }
+
+        if flags & CALCULATE_REDIRECT_TO_LFE != 0 && !has_lfe {
+            for speaker in self.speakers.iter().filter(|s| s.mask == SPEAKER_LOW_FREQUENCY) {
+                for src in 0 .. src_count { matrix[speaker.channel * src_count + src] = lfe_volume / src_count as f32; }
+            }
+        }
+    }
+}
+
+impl Calculator for Spatializer {
+    fn dst_channel_count(&self) -> u32 { Spatializer::dst_channel_count(self) }
+    fn calculate_into(&self, listener: &Listener, emitter: &Emitter, flags: u32, settings: &mut DspSettings) -> Result<(), HResultError> {
+        Spatializer::calculate_into(self, listener, emitter, flags, settings)
+    }
+}
+
+
+
+#[derive(Clone, Copy)] struct ConeScale { volume: f32, lpf: f32, reverb: f32 }
+
+impl ConeScale {
+    const NONE : Self = Self { volume: 1.0, lpf: 0.0, reverb: 1.0 };
+
+    /// Interpolate `cone`'s settings for `angle` off axis (cone angles are full angles, so compare against half.)
+    fn new(cone: &Cone, angle: f32) -> Self {
+        let (inner, outer) = (cone.InnerAngle / 2.0, cone.OuterAngle / 2.0);
+        let t = if angle <= inner { 0.0 } else if angle >= outer { 1.0 } else { (angle - inner) / (outer - inner) };
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        Self {
+            volume: lerp(cone.InnerVolume, cone.OuterVolume),
+            lpf:    lerp(cone.InnerLPF,    cone.OuterLPF),
+            reverb: lerp(cone.InnerReverb, cone.OuterReverb),
+        }
+    }
+}
+
+/// Angle between `a` and `b` in radians (0 ..= [PI]), or `0` if either is zero length.
+fn angle_between(a: Vector, b: Vector) -> f32 {
+    let len = a.length() * b.length();
+    if len <= 0.0 { return 0.0 }
+    (a.dot(b) / len).clamp(-1.0, 1.0).acos()
+}
+
+/// `position` relative to `listener`, as (right, up, front) components.
+fn listener_local(listener: &Listener, position: Vector) -> (f32, f32, f32) {
+    let p = position - listener.position;
+    let right = listener.orient_top.cross(listener.orient_front);
+    (p.dot(right), p.dot(listener.orient_top), p.dot(listener.orient_front))
+}
+
+/// Piecewise linear evaluation of a (validated) distance curve at `normalized` distance, clamped to the curve's ends.
+fn evaluate(curve: &[DistanceCurvePoint], normalized: f32) -> f32 {
+    let d = normalized.clamp(0.0, 1.0);
+    for w in curve.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        if d <= b.Distance { return a.DSPSetting + (b.DSPSetting - a.DSPSetting) * (d - a.Distance) / (b.Distance - a.Distance) }
+    }
+    curve[curve.len()-1].DSPSetting
+}
+
+/// Volume at `distance` world units: `curve`, or an inverse square law (amplitude ∝ 1/distance) beyond `scaler`.
+fn attenuation(curve: Option<&[DistanceCurvePoint]>, distance: f32, scaler: f32) -> f32 {
+    match curve {
+        Some(curve)                 => evaluate(curve, distance / scaler),
+        None if distance <= scaler  => 1.0,
+        None                        => scaler / distance,
+    }
+}
+
+/// Pan a source at `azimuth` between the nearest pair of `speakers` (sorted by azimuth), blended towards an even spread by `spread` (0 ..= 1.)
+fn pan_into(speakers: &[Speaker], azimuth: f32, spread: f32, volume: f32, mut add: impl FnMut(usize, f32)) {
+    let n = speakers.len();
+    if n == 0 { return }
+    if n == 1 { return add(speakers[0].channel, volume) }
+
+    let mut directional = [(0usize, 0.0f32); 2];
+    let rear = speakers.iter().any(|s| s.azimuth.map_or(false, |a| a > PI/2.0 && a < 3.0*PI/2.0));
+    let azimuth = if !rear && azimuth > PI/2.0 && azimuth < 3.0*PI/2.0 { (PI - azimuth).rem_euclid(TWO_PI) } else { azimuth };
+    let az = |i: usize| speakers[i].azimuth.unwrap_or(0.0);
+    let prev = (0 .. n).rev().find(|&i| az(i) <= azimuth).unwrap_or(n-1);
+    let next = (prev + 1) % n;
+    let offset  = (azimuth - az(prev)).rem_euclid(TWO_PI);
+    let span    = (az(next) - az(prev)).rem_euclid(TWO_PI);
+    if span > PI {
+        // No speakers in this direction: snap to the nearer edge of the gap
+        let nearest = if offset <= span - offset { prev } else { next };
+        directional[0] = (speakers[nearest].channel, 1.0);
+    } else {
+        let (sin, cos) = (offset / span * PI / 2.0).sin_cos();
+        directional = [(speakers[prev].channel, cos), (speakers[next].channel, sin)];
+    }
+
+    let even = 1.0 / (n as f32).sqrt();
+    for s in speakers {
+        let d = directional.iter().filter(|(c, _)| *c == s.channel).map(|(_, g)| *g).sum::<f32>();
+        add(s.channel, volume * (d + (even - d) * spread));
+    }
+}
+
+
+
+#[test] fn spatializer() {
+    let stereo      = Spatializer::new(SPEAKER_STEREO,  SPEED_OF_SOUND).unwrap();
+    let surround    = Spatializer::new(SPEAKER_5POINT1, SPEED_OF_SOUND).unwrap();
+    let listener    = Listener::default();
+    let at = |x, z| Emitter { position: Vector::new(x, 0.0, z), .. Default::default() };
+    let near = |a: f32, b: f32| (a - b).abs() < 1e-5;
+
+    // panning
+    let front = stereo.calculate(&listener, &at(0.0, 1.0), CALCULATE_MATRIX).unwrap();
+    assert!(near(front.matrix_coefficients[0], 0.5f32.sqrt()) && near(front.matrix_coefficients[1], 0.5f32.sqrt()), "{:?}", front.matrix_coefficients);
+    assert_eq!(stereo.calculate(&listener, &at(-1.0, 0.0), CALCULATE_MATRIX).unwrap().matrix_coefficients, [1.0, 0.0]);
+    assert_eq!(stereo.calculate(&listener, &at( 1.0, 0.0), CALCULATE_MATRIX).unwrap().matrix_coefficients, [0.0, 1.0]);
+    let behind = stereo.calculate(&listener, &at(0.0, -1.0), CALCULATE_MATRIX).unwrap();
+    assert!(near(behind.matrix_coefficients[0], behind.matrix_coefficients[1]));
+
+    // 5.1: FL FR FC LFE BL BR
+    let center = surround.calculate(&listener, &at(0.0, 1.0), CALCULATE_MATRIX).unwrap();
+    assert_eq!(center.matrix_coefficients, [0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+    let zero_center = surround.calculate(&listener, &at(0.0, 1.0), CALCULATE_MATRIX | CALCULATE_ZEROCENTER | CALCULATE_REDIRECT_TO_LFE).unwrap();
+    assert!(near(zero_center.matrix_coefficients[0], zero_center.matrix_coefficients[1]));
+    assert_eq!(zero_center.matrix_coefficients[2..], [0.0, 1.0, 0.0, 0.0]);
+    let back_right = surround.calculate(&listener, &at(1.0, -1.0), CALCULATE_MATRIX).unwrap();
+    assert!(near(back_right.matrix_coefficients[5], 0.5f32.sqrt()), "{:?}", back_right.matrix_coefficients);
+
+    // distance, cones, filters, reverb
+    let far = stereo.calculate(&listener, &Emitter { cone: Some(DIRECTIONAL_CONE), orient_front: -Vector::FRONT, .. at(0.0, 4.0) }, !0).unwrap();
+    assert_eq!(far.emitter_to_listener_distance, 4.0);
+    assert!(near(far.matrix_coefficients[0], 0.25 * 0.5f32.sqrt()));
+    assert_eq!((far.lpf_direct_coefficient, far.lpf_reverb_coefficient, far.reverb_level, far.emitter_to_listener_angle), (0.75, 0.75, 0.0, 0.0));
+    let away = stereo.calculate(&listener, &Emitter { cone: Some(DIRECTIONAL_CONE), .. at(0.0, 0.5) }, !0).unwrap();
+    assert!(near(away.matrix_coefficients[0], 0.708 * 0.5f32.sqrt()));
+    assert!(near(away.lpf_direct_coefficient, 0.875 - 0.25) && near(away.reverb_level, 0.5) && near(away.emitter_to_listener_angle, PI));
+    assert!(near(away.delay_times[0], 0.0) && near(away.delay_times[1], 0.0));
+
+    // doppler
+    let approaching = stereo.calculate(&listener, &Emitter { velocity: Vector::new(0.0, 0.0, -SPEED_OF_SOUND / 2.0), .. at(0.0, 10.0) }, CALCULATE_DOPPLER).unwrap();
+    assert!(near(approaching.doppler_factor, 2.0) && near(approaching.emitter_velocity_component, SPEED_OF_SOUND / 2.0));
+    let supersonic = stereo.calculate(&listener, &Emitter { velocity: Vector::new(0.0, 0.0, -2.0 * SPEED_OF_SOUND), .. at(0.0, 10.0) }, CALCULATE_DOPPLER).unwrap();
+    assert_eq!(supersonic.doppler_factor, MAX_FREQ_RATIO);
+
+    // multi-channel emitters
+    let wide = Emitter { channel_azimuths: alloc::vec![3.0 * PI / 2.0, PI / 2.0, LFE_AZIMUTH], channel_radius: 1.0, orient_front: -Vector::FRONT, .. at(0.0, 1.0) };
+    let wide = surround.calculate(&listener, &wide, CALCULATE_MATRIX).unwrap();
+    assert_eq!(wide.matrix_coefficient(2, 3), Some(1.0));
+    assert!(wide.matrix_coefficient(0, 1).unwrap() > wide.matrix_coefficient(0, 0).unwrap(), "emitter faces the listener, so its left is the listener's right: {:?}", wide.matrix_coefficients);
+    assert_eq!(stereo.calculate(&Listener { orient_front: Vector::new(f32::NAN, 0.0, 0.0), .. listener }, &at(0.0, 1.0), 0).err(), Some(E::INVALIDARG));
+}
@@ -0,0 +1,56 @@
+use bytemuck::{Pod, Zeroable};
+use core::ops::{Add, Mul, Neg, Sub};
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/d3d9types/ns-d3d9types-d3dvector)\]
+/// X3DAUDIO_VECTOR / D3DVECTOR: a 3D vector in a left-handed cartesian coordinate system (+x right, +y up, +z forward.)
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq)]
+#[repr(C)] pub struct Vector {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vector {
+    /// `(0, 0, 0)`
+    pub const ZERO  : Vector = Vector::new(0.0, 0.0, 0.0);
+    /// `(0, 1, 0)`: the default [Listener::orient_top](super::Listener::orient_top)
+    pub const UP    : Vector = Vector::new(0.0, 1.0, 0.0);
+    /// `(0, 0, 1)`: the default [Listener::orient_front](super::Listener::orient_front)
+    pub const FRONT : Vector = Vector::new(0.0, 0.0, 1.0);
+
+    /// `Vector { x, y, z }`
+    pub const fn new(x: f32, y: f32, z: f32) -> Self { Self { x, y, z } }
+
+    /// Dot product.
+    pub fn dot(self, other: Self) -> f32 { self.x * other.x + self.y * other.y + self.z * other.z }
+
+    /// Cross product (left-handed: `FRONT.cross(UP)` points left.)
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Euclidean length.
+    pub fn length(self) -> f32 { self.dot(self).sqrt() }
+
+    /// `true` if all components are finite.
+    pub fn is_finite(self) -> bool { self.x.is_finite() && self.y.is_finite() && self.z.is_finite() }
+}
+
+impl Add for Vector { type Output = Self; fn add(self, o: Self) -> Self { Self::new(self.x + o.x, self.y + o.y, self.z + o.z) } }
+impl Sub for Vector { type Output = Self; fn sub(self, o: Self) -> Self { Self::new(self.x - o.x, self.y - o.y, self.z - o.z) } }
+impl Neg for Vector { type Output = Self; fn neg(self) -> Self { Self::new(-self.x, -self.y, -self.z) } }
+impl Mul<f32> for Vector { type Output = Self; fn mul(self, s: f32) -> Self { Self::new(self.x * s, self.y * s, self.z * s) } }
+
+#[cfg(windows)] impl From<Vector> for crate::sys::x3daudio::X3DAUDIO_VECTOR {
+    fn from(v: Vector) -> Self { Self { x: v.x, y: v.y, z: v.z } }
+}
+
+#[cfg(windows)] impl From<crate::sys::x3daudio::X3DAUDIO_VECTOR> for Vector {
+    fn from(v: crate::sys::x3daudio::X3DAUDIO_VECTOR) -> Self { Self { x: v.x, y: v.y, z: v.z } }
+}
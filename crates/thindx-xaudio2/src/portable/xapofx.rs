@@ -0,0 +1,240 @@
+use super::*;
+
+use bytemuck::{Pod, Zeroable};
+use winresult::*;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xapofx-overview)\] `__uuidof(FXEQ)`: four band equalizer ([FxEqParameters])
+pub const CLSID_FXEQ                : Guid = Guid { Data1: 0xF5E01117, Data2: 0xD6C4, Data3: 0x485A, Data4: [0xA3, 0xF5, 0x69, 0x51, 0x96, 0xF3, 0xDB, 0xFA] };
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xapofx-overview)\] `__uuidof(FXMasteringLimiter)`: mastering limiter ([FxMasteringLimiterParameters])
+pub const CLSID_FXMASTERINGLIMITER  : Guid = Guid { Data1: 0xC4137916, Data2: 0x2BE1, Data3: 0x46FD, Data4: [0x85, 0x99, 0x44, 0x15, 0x36, 0xF4, 0x98, 0x56] };
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xapofx-overview)\] `__uuidof(FXReverb)`: simple reverb ([FxReverbParameters])
+pub const CLSID_FXREVERB            : Guid = Guid { Data1: 0x7D9ACA56, Data2: 0xCB68, Data3: 0x4807, Data4: [0xB6, 0x32, 0xB1, 0x37, 0x35, 0x2E, 0x85, 0x96] };
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xapofx-overview)\] `__uuidof(FXEcho)`: echo ([FxEchoParameters], initialized with [FxEchoInitData])
+pub const CLSID_FXECHO              : Guid = Guid { Data1: 0x5039D740, Data2: 0xF736, Data3: 0x449A, Data4: [0x84, 0xD3, 0xA5, 0x62, 0x02, 0x55, 0x7B, 0x87] };
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/ns-xapofx-fxeq_parameters)\]
+/// FXEQ_PARAMETERS: parameters for [CLSID_FXEQ], a four band equalizer.
+///
+/// FXEQ only supports frame rates between [FXEQ_MIN_FRAMERATE] and [FXEQ_MAX_FRAMERATE].
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Zeroable, Pod, PartialEq)]
+#[repr(C)] pub struct FxEqParameters {
+    /// Center frequency of band 0, in Hz ([FXEQ_MIN_FREQUENCY_CENTER] ..= [FXEQ_MAX_FREQUENCY_CENTER].)
+    pub FrequencyCenter0:   f32,
+    /// Gain of band 0, as an amplitude ratio ([FXEQ_MIN_GAIN] ..= [FXEQ_MAX_GAIN].)
+    pub Gain0:              f32,
+    /// Width of band 0, in octaves ([FXEQ_MIN_BANDWIDTH] ..= [FXEQ_MAX_BANDWIDTH].)
+    pub Bandwidth0:         f32,
+    /// Center frequency of band 1, in Hz.
+    pub FrequencyCenter1:   f32,
+    /// Gain of band 1, as an amplitude ratio.
+    pub Gain1:              f32,
+    /// Width of band 1, in octaves.
+    pub Bandwidth1:         f32,
+    /// Center frequency of band 2, in Hz.
+    pub FrequencyCenter2:   f32,
+    /// Gain of band 2, as an amplitude ratio.
+    pub Gain2:              f32,
+    /// Width of band 2, in octaves.
+    pub Bandwidth2:         f32,
+    /// Center frequency of band 3, in Hz.
+    pub FrequencyCenter3:   f32,
+    /// Gain of band 3, as an amplitude ratio.
+    pub Gain3:              f32,
+    /// Width of band 3, in octaves.
+    pub Bandwidth3:         f32,
+}
+
+impl Default for FxEqParameters {
+    fn default() -> Self {
+        Self {
+            FrequencyCenter0:   FXEQ_DEFAULT_FREQUENCY_CENTER_0,    Gain0: FXEQ_DEFAULT_GAIN,   Bandwidth0: FXEQ_DEFAULT_BANDWIDTH,
+            FrequencyCenter1:   FXEQ_DEFAULT_FREQUENCY_CENTER_1,    Gain1: FXEQ_DEFAULT_GAIN,   Bandwidth1: FXEQ_DEFAULT_BANDWIDTH,
+            FrequencyCenter2:   FXEQ_DEFAULT_FREQUENCY_CENTER_2,    Gain2: FXEQ_DEFAULT_GAIN,   Bandwidth2: FXEQ_DEFAULT_BANDWIDTH,
+            FrequencyCenter3:   FXEQ_DEFAULT_FREQUENCY_CENTER_3,    Gain3: FXEQ_DEFAULT_GAIN,   Bandwidth3: FXEQ_DEFAULT_BANDWIDTH,
+        }
+    }
+}
+
+impl FxEqParameters {
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if any field is out of range (or NaN.)
+    pub fn validate(&self) -> Result<(), HResultError> {
+        let bands = [
+            (self.FrequencyCenter0, self.Gain0, self.Bandwidth0),
+            (self.FrequencyCenter1, self.Gain1, self.Bandwidth1),
+            (self.FrequencyCenter2, self.Gain2, self.Bandwidth2),
+            (self.FrequencyCenter3, self.Gain3, self.Bandwidth3),
+        ];
+        for (frequency_center, gain, bandwidth) in bands {
+            if !(FXEQ_MIN_FREQUENCY_CENTER  ..= FXEQ_MAX_FREQUENCY_CENTER   ).contains(&frequency_center   ) { return Err(E::INVALIDARG) }
+            if !(FXEQ_MIN_GAIN              ..= FXEQ_MAX_GAIN               ).contains(&gain               ) { return Err(E::INVALIDARG) }
+            if !(FXEQ_MIN_BANDWIDTH         ..= FXEQ_MAX_BANDWIDTH          ).contains(&bandwidth          ) { return Err(E::INVALIDARG) }
+        }
+        Ok(())
+    }
+}
+
+#[doc = "Minimum frame rate supported by [CLSID_FXEQ]"]               pub const FXEQ_MIN_FRAMERATE                : u32 = 22000;
+#[doc = "Maximum frame rate supported by [CLSID_FXEQ]"]               pub const FXEQ_MAX_FRAMERATE                : u32 = 48000;
+#[doc = "Minimum [FxEqParameters::FrequencyCenter0] etc."]            pub const FXEQ_MIN_FREQUENCY_CENTER         : f32 = 20.0;
+#[doc = "Maximum [FxEqParameters::FrequencyCenter0] etc."]            pub const FXEQ_MAX_FREQUENCY_CENTER         : f32 = 20000.0;
+#[doc = "Default [FxEqParameters::FrequencyCenter0]"]                 pub const FXEQ_DEFAULT_FREQUENCY_CENTER_0   : f32 = 100.0;
+#[doc = "Default [FxEqParameters::FrequencyCenter1]"]                 pub const FXEQ_DEFAULT_FREQUENCY_CENTER_1   : f32 = 800.0;
+#[doc = "Default [FxEqParameters::FrequencyCenter2]"]                 pub const FXEQ_DEFAULT_FREQUENCY_CENTER_2   : f32 = 2000.0;
+#[doc = "Default [FxEqParameters::FrequencyCenter3]"]                 pub const FXEQ_DEFAULT_FREQUENCY_CENTER_3   : f32 = 10000.0;
+#[doc = "Minimum [FxEqParameters::Gain0] etc. (-18 dB)"]              pub const FXEQ_MIN_GAIN                     : f32 = 0.126;
+#[doc = "Maximum [FxEqParameters::Gain0] etc. (+18 dB)"]              pub const FXEQ_MAX_GAIN                     : f32 = 7.94;
+#[doc = "Default [FxEqParameters::Gain0] etc. (0 dB)"]                pub const FXEQ_DEFAULT_GAIN                 : f32 = 1.0;
+#[doc = "Minimum [FxEqParameters::Bandwidth0] etc."]                  pub const FXEQ_MIN_BANDWIDTH                : f32 = 0.1;
+#[doc = "Maximum [FxEqParameters::Bandwidth0] etc."]                  pub const FXEQ_MAX_BANDWIDTH                : f32 = 2.0;
+#[doc = "Default [FxEqParameters::Bandwidth0] etc."]                  pub const FXEQ_DEFAULT_BANDWIDTH            : f32 = 1.0;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/ns-xapofx-fxmasteringlimiter_parameters)\]
+/// FXMASTERINGLIMITER_PARAMETERS: parameters for [CLSID_FXMASTERINGLIMITER], which prevents clipping.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Zeroable, Pod, PartialEq, Eq)]
+#[repr(C)] pub struct FxMasteringLimiterParameters {
+    /// Speed at which the limiter stops affecting audio once it drops below the limiter's threshold ([FXMASTERINGLIMITER_MIN_RELEASE] ..= [FXMASTERINGLIMITER_MAX_RELEASE].)
+    pub Release:    u32,
+    /// Threshold of the limiter ([FXMASTERINGLIMITER_MIN_LOUDNESS] ..= [FXMASTERINGLIMITER_MAX_LOUDNESS].)
+    pub Loudness:   u32,
+}
+
+impl Default for FxMasteringLimiterParameters {
+    fn default() -> Self { Self { Release: FXMASTERINGLIMITER_DEFAULT_RELEASE, Loudness: FXMASTERINGLIMITER_DEFAULT_LOUDNESS } }
+}
+
+impl FxMasteringLimiterParameters {
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if any field is out of range.
+    pub fn validate(&self) -> Result<(), HResultError> {
+        if !(FXMASTERINGLIMITER_MIN_RELEASE  ..= FXMASTERINGLIMITER_MAX_RELEASE ).contains(&self.Release ) { return Err(E::INVALIDARG) }
+        if !(FXMASTERINGLIMITER_MIN_LOUDNESS ..= FXMASTERINGLIMITER_MAX_LOUDNESS).contains(&self.Loudness) { return Err(E::INVALIDARG) }
+        Ok(())
+    }
+}
+
+#[doc = "Minimum [FxMasteringLimiterParameters::Release]"]            pub const FXMASTERINGLIMITER_MIN_RELEASE        : u32 = 1;
+#[doc = "Maximum [FxMasteringLimiterParameters::Release]"]            pub const FXMASTERINGLIMITER_MAX_RELEASE        : u32 = 20;
+#[doc = "Default [FxMasteringLimiterParameters::Release]"]            pub const FXMASTERINGLIMITER_DEFAULT_RELEASE    : u32 = 6;
+#[doc = "Minimum [FxMasteringLimiterParameters::Loudness]"]           pub const FXMASTERINGLIMITER_MIN_LOUDNESS       : u32 = 1;
+#[doc = "Maximum [FxMasteringLimiterParameters::Loudness]"]           pub const FXMASTERINGLIMITER_MAX_LOUDNESS       : u32 = 1800;
+#[doc = "Default [FxMasteringLimiterParameters::Loudness]"]           pub const FXMASTERINGLIMITER_DEFAULT_LOUDNESS   : u32 = 1000;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/ns-xapofx-fxreverb_parameters)\]
+/// FXREVERB_PARAMETERS: parameters for [CLSID_FXREVERB], a simple (and cheap) reverb.
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Zeroable, Pod, PartialEq)]
+#[repr(C)] pub struct FxReverbParameters {
+    /// Diffusion ([FXREVERB_MIN_DIFFUSION] ..= [FXREVERB_MAX_DIFFUSION].)
+    pub Diffusion:  f32,
+    /// Room size ([FXREVERB_MIN_ROOMSIZE] ..= [FXREVERB_MAX_ROOMSIZE].)
+    pub RoomSize:   f32,
+}
+
+impl Default for FxReverbParameters {
+    fn default() -> Self { Self { Diffusion: FXREVERB_DEFAULT_DIFFUSION, RoomSize: FXREVERB_DEFAULT_ROOMSIZE } }
+}
+
+impl FxReverbParameters {
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if any field is out of range (or NaN.)
+    pub fn validate(&self) -> Result<(), HResultError> {
+        if !(FXREVERB_MIN_DIFFUSION ..= FXREVERB_MAX_DIFFUSION).contains(&self.Diffusion) { return Err(E::INVALIDARG) }
+        if !(FXREVERB_MIN_ROOMSIZE  ..= FXREVERB_MAX_ROOMSIZE ).contains(&self.RoomSize ) { return Err(E::INVALIDARG) }
+        Ok(())
+    }
+}
+
+#[doc = "Minimum [FxReverbParameters::Diffusion]"]                    pub const FXREVERB_MIN_DIFFUSION        : f32 = 0.0;
+#[doc = "Maximum [FxReverbParameters::Diffusion]"]                    pub const FXREVERB_MAX_DIFFUSION        : f32 = 1.0;
+#[doc = "Default [FxReverbParameters::Diffusion]"]                    pub const FXREVERB_DEFAULT_DIFFUSION    : f32 = 0.9;
+#[doc = "Minimum [FxReverbParameters::RoomSize]"]                     pub const FXREVERB_MIN_ROOMSIZE         : f32 = 0.0001;
+#[doc = "Maximum [FxReverbParameters::RoomSize]"]                     pub const FXREVERB_MAX_ROOMSIZE         : f32 = 1.0;
+#[doc = "Default [FxReverbParameters::RoomSize]"]                     pub const FXREVERB_DEFAULT_ROOMSIZE     : f32 = 0.6;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/ns-xapofx-fxecho_parameters)\]
+/// FXECHO_PARAMETERS: parameters for [CLSID_FXECHO].
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Zeroable, Pod, PartialEq)]
+#[repr(C)] pub struct FxEchoParameters {
+    /// Ratio of wet (processed) to dry (original) signal ([FXECHO_MIN_WETDRYMIX] ..= [FXECHO_MAX_WETDRYMIX].)
+    pub WetDryMix:  f32,
+    /// Amount of output fed back into the input ([FXECHO_MIN_FEEDBACK] ..= [FXECHO_MAX_FEEDBACK].)
+    pub Feedback:   f32,
+    /// Delay to all channels, in milliseconds ([FXECHO_MIN_DELAY] ..= [FxEchoInitData::MaxDelay].)
+    pub Delay:      f32,
+}
+
+impl Default for FxEchoParameters {
+    fn default() -> Self { Self { WetDryMix: FXECHO_DEFAULT_WETDRYMIX, Feedback: FXECHO_DEFAULT_FEEDBACK, Delay: FXECHO_DEFAULT_DELAY } }
+}
+
+impl FxEchoParameters {
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if any field is out of range (or NaN.)
+    ///
+    /// N.B. `Delay` is only checked against [FXECHO_MAX_DELAY]: the effect itself also rejects delays beyond its [FxEchoInitData::MaxDelay].
+    pub fn validate(&self) -> Result<(), HResultError> {
+        if !(FXECHO_MIN_WETDRYMIX   ..= FXECHO_MAX_WETDRYMIX).contains(&self.WetDryMix  ) { return Err(E::INVALIDARG) }
+        if !(FXECHO_MIN_FEEDBACK    ..= FXECHO_MAX_FEEDBACK ).contains(&self.Feedback   ) { return Err(E::INVALIDARG) }
+        if !(FXECHO_MIN_DELAY       ..= FXECHO_MAX_DELAY    ).contains(&self.Delay      ) { return Err(E::INVALIDARG) }
+        Ok(())
+    }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/ns-xapofx-fxecho_initdata)\]
+/// FXECHO_INITDATA: creation parameters for [CLSID_FXECHO].
+#[allow(non_snake_case)] // match original naming instead
+#[derive(Clone, Copy, Debug, Zeroable, Pod, PartialEq)]
+#[repr(C)] pub struct FxEchoInitData {
+    /// Maximum [FxEchoParameters::Delay], in milliseconds ([FXECHO_MIN_DELAY] ..= [FXECHO_MAX_DELAY].)  Determines the size of the delay buffer.
+    pub MaxDelay:   f32,
+}
+
+impl Default for FxEchoInitData {
+    fn default() -> Self { Self { MaxDelay: FXECHO_DEFAULT_DELAY } }
+}
+
+impl FxEchoInitData {
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if `MaxDelay` is out of range (or NaN.)
+    pub fn validate(&self) -> Result<(), HResultError> {
+        if !(FXECHO_MIN_DELAY ..= FXECHO_MAX_DELAY).contains(&self.MaxDelay) { return Err(E::INVALIDARG) }
+        Ok(())
+    }
+}
+
+#[doc = "Minimum [FxEchoParameters::WetDryMix]"]                      pub const FXECHO_MIN_WETDRYMIX          : f32 = 0.0;
+#[doc = "Maximum [FxEchoParameters::WetDryMix]"]                      pub const FXECHO_MAX_WETDRYMIX          : f32 = 1.0;
+#[doc = "Default [FxEchoParameters::WetDryMix]"]                      pub const FXECHO_DEFAULT_WETDRYMIX      : f32 = 0.5;
+#[doc = "Minimum [FxEchoParameters::Feedback]"]                       pub const FXECHO_MIN_FEEDBACK           : f32 = 0.0;
+#[doc = "Maximum [FxEchoParameters::Feedback]"]                       pub const FXECHO_MAX_FEEDBACK           : f32 = 1.0;
+#[doc = "Default [FxEchoParameters::Feedback]"]                       pub const FXECHO_DEFAULT_FEEDBACK       : f32 = 0.5;
+#[doc = "Minimum [FxEchoParameters::Delay] / [FxEchoInitData::MaxDelay]"] pub const FXECHO_MIN_DELAY          : f32 = 1.0;
+#[doc = "Maximum [FxEchoParameters::Delay] / [FxEchoInitData::MaxDelay]"] pub const FXECHO_MAX_DELAY          : f32 = 2000.0;
+#[doc = "Default [FxEchoParameters::Delay] / [FxEchoInitData::MaxDelay]"] pub const FXECHO_DEFAULT_DELAY      : f32 = 500.0;
+
+
+
+#[test] fn validate() {
+    assert_eq!(FxEqParameters::default().validate(), Ok(()));
+    assert_eq!(FxEqParameters { Gain2: 8.0, .. Default::default() }.validate(), Err(E::INVALIDARG));
+    assert_eq!(FxEqParameters { Bandwidth3: f32::NAN, .. Default::default() }.validate(), Err(E::INVALIDARG));
+    assert_eq!(FxMasteringLimiterParameters::default().validate(), Ok(()));
+    assert_eq!(FxMasteringLimiterParameters { Release: 0, .. Default::default() }.validate(), Err(E::INVALIDARG));
+    assert_eq!(FxReverbParameters::default().validate(), Ok(()));
+    assert_eq!(FxReverbParameters { RoomSize: 0.0, .. Default::default() }.validate(), Err(E::INVALIDARG));
+    assert_eq!(FxEchoParameters::default().validate(), Ok(()));
+    assert_eq!(FxEchoParameters { Delay: 2001.0, .. Default::default() }.validate(), Err(E::INVALIDARG));
+    assert_eq!(FxEchoInitData { MaxDelay: 0.5 }.validate(), Err(E::INVALIDARG));
+}
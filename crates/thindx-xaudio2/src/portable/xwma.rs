@@ -0,0 +1,89 @@
+use super::*;
+
+use winresult::*;
+
+use core::ops::Deref;
+
+
+
+/// A validated [WAVE_FORMAT_WMAUDIO2] / [WAVE_FORMAT_WMAUDIO3] (xWMA) [SourceFormat].
+///
+/// xWMA data is submitted in whole packets of `nBlockAlign` bytes, alongside a "decoded packet cumulative bytes" (`dpds`) table:
+/// entry `i` is the number of PCM bytes produced by decoding packets `0 ..= i` (see [XAUDIO2_BUFFER_WMA](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_buffer_wma).)
+///
+/// [SourceVoiceDynamic::submit_xwma_buffer_unchecked](crate::xaudio2_9::SourceVoiceDynamic::submit_xwma_buffer_unchecked) uses this to validate buffers.
+#[derive(Clone)] pub struct XwmaSourceFormat(SourceFormat);
+
+impl AsRef<SourceFormat> for XwmaSourceFormat { fn as_ref(&self) -> &SourceFormat { &self.0 } }
+impl Deref for XwmaSourceFormat { fn deref(&self) -> &Self::Target { &self.0 } type Target = SourceFormat; }
+impl From<XwmaSourceFormat> for SourceFormat { fn from(f: XwmaSourceFormat) -> Self { f.0 } }
+
+impl XwmaSourceFormat {
+    /// xWMA with `tag` ([WAVE_FORMAT_WMAUDIO2] or [WAVE_FORMAT_WMAUDIO3]), `avg_bytes_per_sec` (the encoded bitrate / 8), and `block_align` (the packet size.)
+    ///
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> if `tag` isn't an xWMA tag, or `channels`, `hz`, `avg_bytes_per_sec`, or `block_align` is `0`.
+    /// XAudio2 itself only supports specific combinations of sample rates and bitrates: use the values from an encoded `.xwm` file (see [wav::Xwm](super::wav::Xwm).)
+    pub fn new(tag: u16, channels: u16, hz: u32, avg_bytes_per_sec: u32, block_align: u16) -> Result<Self, HResultError> {
+        Self::from_wave_format(WaveFormatEx {
+            wFormatTag:         tag,
+            nChannels:          channels,
+            nSamplesPerSec:     hz,
+            nAvgBytesPerSec:    avg_bytes_per_sec,
+            nBlockAlign:        block_align,
+            wBitsPerSample:     16,
+            cbSize:             0,
+        })
+    }
+
+    /// Validate a [WaveFormatEx] as per [new](Self::new).  `wBitsPerSample` must be `16` and `cbSize` must be `0`.
+    pub fn from_wave_format(format: WaveFormatEx) -> Result<Self, HResultError> {
+        let WaveFormatEx { wFormatTag: tag, nChannels: channels, nSamplesPerSec: hz, nAvgBytesPerSec: avg, nBlockAlign: block_align, wBitsPerSample: bits, cbSize: cb_size } = format;
+        if !matches!(tag, WAVE_FORMAT_WMAUDIO2 | WAVE_FORMAT_WMAUDIO3) || bits != 16 || cb_size != 0 { return Err(E::INVALIDARG) }
+        if channels == 0 || hz == 0 || avg == 0 || block_align == 0 { return Err(E::INVALIDARG) }
+        Ok(Self(unsafe { SourceFormat::from_wave_format(format) }))
+    }
+
+    /// Validate a [SourceFormat] as per [from_wave_format](Self::from_wave_format).
+    pub fn from_source_format(format: &SourceFormat) -> Result<Self, HResultError> {
+        if format.as_bytes().len() != core::mem::size_of::<WaveFormatEx>() { return Err(E::INVALIDARG) }
+        Self::from_wave_format(*format.wave_format())
+    }
+
+    /// Bytes per packet (`nBlockAlign`.)
+    pub fn block_align(&self) -> u16 { self.wave_format().nBlockAlign }
+
+    /// Validate `bytes` of audio data against a decoded packet cumulative bytes table, returning the packet count.
+    ///
+    /// Returns <code>[Err]\([E::INVALIDARG]\)</code> unless:
+    /// *   `bytes` is a nonzero, whole number of packets
+    /// *   `decoded_packet_cumulative_bytes` has an entry per packet
+    /// *   `decoded_packet_cumulative_bytes` never decreases, and every entry is a whole number of (16-bit PCM) frames
+    pub fn packets(&self, bytes: usize, decoded_packet_cumulative_bytes: &[u32]) -> Result<u32, HResultError> {
+        let block_align = usize::from(self.block_align());
+        if bytes == 0 || bytes % block_align != 0 || bytes / block_align != decoded_packet_cumulative_bytes.len() { return Err(E::INVALIDARG) }
+        let frame = 2 * u32::from(self.wave_format().nChannels);
+        if decoded_packet_cumulative_bytes.iter().any(|b| b % frame != 0) { return Err(E::INVALIDARG) }
+        if decoded_packet_cumulative_bytes.windows(2).any(|w| w[0] > w[1]) { return Err(E::INVALIDARG) }
+        u32::try_from(decoded_packet_cumulative_bytes.len()).map_err(|_| E::INVALIDARG)
+    }
+
+    /// The number of decoded samples (frames) described by a decoded packet cumulative bytes table.
+    pub fn samples(&self, decoded_packet_cumulative_bytes: &[u32]) -> u32 {
+        decoded_packet_cumulative_bytes.last().map_or(0, |b| b / (2 * u32::from(self.wave_format().nChannels)))
+    }
+}
+
+
+
+#[test] fn xwma() {
+    let f = XwmaSourceFormat::new(WAVE_FORMAT_WMAUDIO2, 2, 44100, 6000, 2230).unwrap();
+    assert_eq!(f.as_bytes().len(), 18);
+    assert_eq!(f.packets(2230 * 2, &[8192, 16384]), Ok(2));
+    assert_eq!(f.samples(&[8192, 16384]), 4096);
+    assert!(f.packets(2230 * 2, &[8192]).is_err(), "dpds length mismatch");
+    assert!(f.packets(2230 * 2 - 1, &[8192, 16384]).is_err(), "partial packet");
+    assert!(f.packets(2230 * 2, &[16384, 8192]).is_err(), "decreasing");
+    assert!(f.packets(2230 * 2, &[8190, 16384]).is_err(), "partial frame");
+    assert!(XwmaSourceFormat::new(WAVE_FORMAT_PCM, 2, 44100, 6000, 2230).is_err());
+    assert!(XwmaSourceFormat::new(WAVE_FORMAT_WMAUDIO3, 2, 44100, 6000, 0).is_err());
+}
@@ -0,0 +1,260 @@
+//! ✔️ XAudio 2.7 - Windows XP? via redist
+//!
+//! * SDK:      [DirectX SDK (June 2010)]((https://www.microsoft.com/en-us/download/details.aspx?id=6812))
+//!
+//! XAudio 2.7 is a COM class registered by the DirectX redist rather than a DLL export:
+//! [xaudio2::create] uses `CoCreateInstance` + [IXAudio2::Initialize](xaudio2::sys::IXAudio2::Initialize).
+//! Its interfaces are *not* ABI compatible with XAudio 2.8+ (despite sharing names), so this module has its own [XAudio2](xaudio2::XAudio2) and voice types.
+//!
+//! ### References
+//! *   [XAudio2 Versions: XAudio 2.7 and earlier (Windows 7)](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-versions#xaudio-27-and-earlier-windows-7)
+//! *   [XAudio does not ship with Windows 7](https://stackoverflow.com/a/49524446/953531)
+//! *   [Known Issues - XAudio 2.7](https://walbourn.github.io/known-issues-xaudio-2-7/)
+//! *   [The Zombie DirectX SDK](https://walbourn.github.io/the-zombie-directx-sdk/)
+//! *   [Not So Direct Setup](https://walbourn.github.io/not-so-direct-setup/)
+
+use super::xaudio2_8 as next;
+
+mod ixaudio2_ext;
+mod voices;
+
+// Might not remain pub
+#[doc(hidden)] pub use xaudio2::sys::{
+    IXAudio2,
+    IXAudio2Vtbl,
+    IXAudio2Voice,
+    IXAudio2VoiceVtbl,
+    IXAudio2SourceVoice,
+    IXAudio2SourceVoiceVtbl,
+    IXAudio2SubmixVoice,
+    IXAudio2SubmixVoiceVtbl,
+    IXAudio2MasteringVoice,
+    IXAudio2MasteringVoiceVtbl,
+    IXAudio2EngineCallback,
+    IXAudio2EngineCallbackVtbl,
+    IXAudio2VoiceCallback,
+    IXAudio2VoiceCallbackVtbl,
+};
+
+/// `XAudio2*` & `XAUDIO2_*`
+pub mod xaudio2 {
+    use super::*;
+    use winapi::shared::winerror::FAILED;
+    use winapi::shared::wtypesbase::CLSCTX_INPROC_SERVER;
+    use winapi::um::combaseapi::CoCreateInstance;
+    use winresult::*;
+    use core::ptr::null_mut;
+
+    // Re-exports
+    #[doc(no_inline)] pub use winresult::{HResult, HResultError};
+
+    #[doc(inline)] pub use crate::portable::{
+        // Structs
+        SourceFormat,
+        TypedSourceFormat,
+
+        // Constants
+        NO_LOOP_REGION,
+        MAX_LOOP_COUNT,
+        LOOP_INFINITE,
+    };
+
+    #[doc(inline)] pub use next::xaudio2::{
+        // Structs
+        EffectDescriptor,   // XAUDIO2_EFFECT_DESCRIPTOR is unchanged
+
+        // Traits
+        EngineCallback,     // IXAudio2EngineCallback is unchanged
+    };
+
+    pub use super::ixaudio2_ext::*;
+    pub use super::voices::*;
+
+    /// Raw low level FFI bindings
+    ///
+    pub use thindx_xaudio2_sys::xaudio2_7 as sys;
+
+    pub use sys::XAUDIO2_DLL    as DLL;
+    pub use sys::XAUDIO2_DLL_A  as DLL_A;
+    pub use sys::XAUDIO2_DLL_W  as DLL_W;
+
+    pub use sys::XAUDIO2D_DLL    as D_DLL;
+    pub use sys::XAUDIO2D_DLL_A  as D_DLL_A;
+    pub use sys::XAUDIO2D_DLL_W  as D_DLL_W;
+
+    pub use sys::{
+        XAUDIO2_MAX_BUFFER_BYTES                        as MAX_BUFFER_BYTES,
+        XAUDIO2_MAX_QUEUED_BUFFERS                      as MAX_QUEUED_BUFFERS,
+        XAUDIO2_MAX_BUFFERS_SYSTEM                      as MAX_BUFFERS_SYSTEM,
+        XAUDIO2_MAX_AUDIO_CHANNELS                      as MAX_AUDIO_CHANNELS,
+        XAUDIO2_MIN_SAMPLE_RATE                         as MIN_SAMPLE_RATE,
+        XAUDIO2_MAX_SAMPLE_RATE                         as MAX_SAMPLE_RATE,
+        XAUDIO2_MAX_VOLUME_LEVEL                        as MAX_VOLUME_LEVEL,
+        XAUDIO2_MIN_FREQ_RATIO                          as MIN_FREQ_RATIO,
+        XAUDIO2_MAX_FREQ_RATIO                          as MAX_FREQ_RATIO,
+        XAUDIO2_DEFAULT_FREQ_RATIO                      as DEFAULT_FREQ_RATIO,
+        XAUDIO2_MAX_FILTER_ONEOVERQ                     as MAX_FILTER_ONEOVERQ,
+        XAUDIO2_MAX_FILTER_FREQUENCY                    as MAX_FILTER_FREQUENCY,
+        XAUDIO2_MAX_INSTANCES                           as MAX_INSTANCES,
+        XAUDIO2_MAX_RATIO_TIMES_RATE_XMA_MONO           as MAX_RATIO_TIMES_RATE_XMA_MONO,
+        XAUDIO2_MAX_RATIO_TIMES_RATE_XMA_MULTICHANNEL   as MAX_RATIO_TIMES_RATE_XMA_MULTICHANNEL,
+        XAUDIO2_COMMIT_NOW                              as COMMIT_NOW,
+        XAUDIO2_COMMIT_ALL                              as COMMIT_ALL,
+        XAUDIO2_INVALID_OPSET                           as INVALID_OPSET,
+        XAUDIO2_DEFAULT_CHANNELS                        as DEFAULT_CHANNELS,
+        XAUDIO2_DEFAULT_SAMPLERATE                      as DEFAULT_SAMPLERATE,
+        XAUDIO2_DEBUG_ENGINE                            as DEBUG_ENGINE,
+        XAUDIO2_VOICE_NOPITCH                           as VOICE_NOPITCH,
+        XAUDIO2_VOICE_NOSRC                             as VOICE_NOSRC,
+        XAUDIO2_VOICE_USEFILTER                         as VOICE_USEFILTER,
+        XAUDIO2_VOICE_MUSIC                             as VOICE_MUSIC,
+        XAUDIO2_PLAY_TAILS                              as PLAY_TAILS,
+        XAUDIO2_END_OF_STREAM                           as END_OF_STREAM,
+        XAUDIO2_SEND_USEFILTER                          as SEND_USEFILTER,
+        XAUDIO2_DEFAULT_FILTER_TYPE                     as DEFAULT_FILTER_TYPE,
+        XAUDIO2_DEFAULT_FILTER_FREQUENCY                as DEFAULT_FILTER_FREQUENCY,
+        XAUDIO2_DEFAULT_FILTER_ONEOVERQ                 as DEFAULT_FILTER_ONEOVERQ,
+        XAUDIO2_QUANTUM_NUMERATOR                       as QUANTUM_NUMERATOR,
+        XAUDIO2_QUANTUM_DENOMINATOR                     as QUANTUM_DENOMINATOR,
+        XAUDIO2_QUANTUM_MS                              as QUANTUM_MS,
+        FACILITY_XAUDIO2                                as FACILITY,
+        XAUDIO2_E_INVALID_CALL                          as E_INVALID_CALL,
+        XAUDIO2_E_XMA_DECODER_ERROR                     as E_XMA_DECODER_ERROR,
+        XAUDIO2_E_XAPO_CREATION_FAILED                  as E_XAPO_CREATION_FAILED,
+        XAUDIO2_E_DEVICE_INVALIDATED                    as E_DEVICE_INVALIDATED,
+    };
+
+    pub use sys::{
+        XAUDIO2_PROCESSOR  as Processor,
+        Processor1,
+        Processor2,
+        Processor3,
+        Processor4,
+        Processor5,
+        Processor6,
+        Processor7,
+        Processor8,
+        Processor9,
+        Processor10,
+        Processor11,
+        Processor12,
+        Processor13,
+        Processor14,
+        Processor15,
+        Processor16,
+        Processor17,
+        Processor18,
+        Processor19,
+        Processor20,
+        Processor21,
+        Processor22,
+        Processor23,
+        Processor24,
+        Processor25,
+        Processor26,
+        Processor27,
+        Processor28,
+        Processor29,
+        Processor30,
+        Processor31,
+        Processor32,
+        XAUDIO2_ANY_PROCESSOR       as ANY_PROCESSOR,
+        XAUDIO2_DEFAULT_PROCESSOR   as DEFAULT_PROCESSOR,
+    };
+
+    pub use sys::{
+        XAUDIO2_DEVICE_ROLE         as DeviceRole,
+        NotDefaultDevice,
+        DefaultConsoleDevice,
+        DefaultMultimediaDevice,
+        DefaultCommunicationsDevice,
+        DefaultGameDevice,
+        GlobalDefaultDevice,
+        InvalidDeviceRole,
+    };
+
+    #[cfg(feature = "helper-functions")] pub use next::xaudio2::{
+        decibels_to_amplitude_ratio,
+        amplitude_ratio_to_decibels,
+        semitones_to_frequency_ratio,
+        frequency_ratio_to_semitones,
+        cutoff_frequency_to_radians,
+        radians_to_cutoff_frequency,
+    };
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_send_descriptor)\]
+    /// [XAUDIO2_SEND_DESCRIPTOR](sys::XAUDIO2_SEND_DESCRIPTOR): safe equivalent
+    #[repr(C, packed(1))] pub struct SendDescriptor<'a> { // XXX: note that packed here means output_voice isn't 8-byte aligned on x64!
+        pub flags:          u32,
+        pub output_voice:   &'a sys::IXAudio2Voice,
+    }
+    impl<'a> SendDescriptor<'a> {
+        pub fn new(flags: u32, output_voice: &'a sys::IXAudio2Voice) -> Self { Self { flags, output_voice } }
+    }
+    const _ : () = assert!(core::mem::align_of::<sys::XAUDIO2_SEND_DESCRIPTOR>() <= core::mem::align_of::<SendDescriptor>());
+    const _ : () = assert!(core::mem::size_of ::<sys::XAUDIO2_SEND_DESCRIPTOR>() == core::mem::size_of ::<SendDescriptor>());
+
+    pub use sys::{
+        XAUDIO2_DEBUG_CONFIGURATION     as DebugConfiguration,
+        XAUDIO2_DEVICE_DETAILS          as DeviceDetails,
+        XAUDIO2_FILTER_PARAMETERS       as FilterParameters,
+        XAUDIO2_PERFORMANCE_DATA        as PerformanceData,
+        XAUDIO2_VOICE_DETAILS           as VoiceDetails,
+        XAUDIO2_VOICE_STATE             as VoiceState,
+    };
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-xaudio2create)\] XAudio2Create (XAudio 2.7 / DirectX SDK edition):
+    /// `CoCreateInstance`s [CLSID_XAudio2](sys::CLSID_XAudio2) (or [CLSID_XAudio2_Debug](sys::CLSID_XAudio2_Debug) if `flags` contains [DEBUG_ENGINE]),
+    /// then [Initialize](sys::IXAudio2::Initialize)s it.
+    ///
+    /// Initialize COM (see e.g. [mcom::init::mta]) before calling this function, or it will fail with [CO::E_NOTINITIALIZED].
+    /// The June 2010 DirectX redist (or, for [DEBUG_ENGINE], the SDK itself) must be installed to register the classes.
+    ///
+    /// As a workaround for [known issues](https://walbourn.github.io/known-issues-xaudio-2-7/), this also pins
+    /// [DLL] (or [D_DLL]) in memory, so the engine's DLL isn't unloaded out from under any lingering worker threads.
+    ///
+    /// ### Safety
+    /// As per [xaudio2_9::xaudio2::create](crate::xaudio2_9::xaudio2::create), plus:
+    /// *   XAudio 2.7 is legacy, unmaintained, and has known crash bugs (see above.)
+    ///
+    /// ### Arguments
+    /// *   `flags`     - `0` or [DEBUG_ENGINE]
+    /// *   `processor` - The processor(s) to run XAudio2's worker thread(s) on ([None] = [DEFAULT_PROCESSOR], which is [ANY_PROCESSOR] in XAudio 2.7.)
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use thindx_xaudio2::xaudio2_7::*;
+    /// mcom::init::mta().unwrap();
+    ///
+    /// let xaudio2 = unsafe { xaudio2::create(0, xaudio2::Processor1) }.unwrap();
+    /// for device in 0 .. xaudio2.get_device_count().unwrap() {
+    ///     let details = xaudio2.get_device_details(device).unwrap();
+    ///     let name = { details.DisplayName };
+    ///     let name = String::from_utf16_lossy(&name[..name.iter().position(|&ch| ch == 0).unwrap_or(name.len())]);
+    ///     println!("device {device}: {name}");
+    /// }
+    /// let master = xaudio2.create_mastering_voice(xaudio2::DEFAULT_CHANNELS, xaudio2::DEFAULT_SAMPLERATE, 0, 0, None).unwrap();
+    /// ```
+    ///
+    /// ### Errors
+    /// *   [CO::E_NOTINITIALIZED]      - if COM wasn't initialized on this thread
+    /// *   `REGDB_E_CLASSNOTREG`       - if the DirectX redist (or SDK, for [DEBUG_ENGINE]) isn't installed
+    /// *   [HResultError::from_win32]\([ERROR::NOINTERFACE])   - if [IXAudio2] was null despite the function "succeeding" (thindx specific)
+    /// *   [E_INVALID_CALL]            - if `flags` or `processor` is invalid
+    ///
+    /// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
+    pub unsafe fn create(flags: u32, processor: impl Into<Option<Processor>>) -> Result<XAudio2, HResultError> {
+        let debug = flags & DEBUG_ENGINE != 0;
+        let _ = minidl::Library::load(if debug { D_DLL } else { DLL }); // intentionally never unloaded
+
+        let clsid = if debug { &sys::CLSID_XAudio2_Debug } else { &sys::CLSID_XAudio2 };
+        let mut xaudio2 : *mut sys::IXAudio2 = null_mut();
+        let hr = unsafe { CoCreateInstance(clsid, null_mut(), CLSCTX_INPROC_SERVER, &sys::IID_IXAudio2, (&mut xaudio2 as *mut *mut sys::IXAudio2).cast()) };
+        if FAILED(hr) { return Err(HResultError::from(hr as u32)) }
+        if xaudio2.is_null() { return Err(HResultError::from_win32(ERROR::NOINTERFACE)) } // CoCreateInstance "succeeded" but gave us a null ptr?
+        let xaudio2 = unsafe { XAudio2::from_raw(xaudio2) };
+
+        unsafe { xaudio2.Initialize(flags, processor.into().unwrap_or(DEFAULT_PROCESSOR)) }.succeeded()?;
+        Ok(xaudio2)
+    }
+}
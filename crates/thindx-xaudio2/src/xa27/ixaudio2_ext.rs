@@ -0,0 +1,248 @@
+use super::xaudio2;
+use super::xaudio2::sys::*;
+
+use winresult::*;
+
+use core::ptr::{null_mut, null};
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2)\]
+/// IXAudio2
+/// &mdash; Top-level XAudio 2.7 COM interface/factory pointer.
+///
+#[derive(Clone)] #[repr(transparent)] pub struct XAudio2(mcom::Rc<IXAudio2>);
+impl core::ops::Deref for XAudio2 { type Target = mcom::Rc<IXAudio2>; fn deref(&self) -> &Self::Target { &self.0 } }
+
+impl XAudio2 {
+    /// Take ownership of a raw [IXAudio2] interface pointer.
+    ///
+    /// ### Safety
+    /// *   `raw` must be a valid, non-null, [Initialize](IXAudio2::Initialize)d XAudio 2.7 [IXAudio2] interface pointer.
+    /// *   `Self` takes ownership of one reference to `raw` (released on drop.)
+    pub unsafe fn from_raw(raw: *mut IXAudio2) -> Self { Self(unsafe { mcom::Rc::from_raw(raw) }) }
+
+    /// \[<strike>microsoft.com</strike>\]
+    /// Returns the number of audio output devices available.
+    pub fn get_device_count(&self) -> Result<u32, HResultError> {
+        let mut count = 0;
+        unsafe { self.GetDeviceCount(&mut count) }.succeeded()?;
+        Ok(count)
+    }
+
+    /// \[<strike>microsoft.com</strike>\]
+    /// Returns information about the device with the given index (`0 .. get_device_count()`.)
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `index` is out of bounds
+    pub fn get_device_details(&self, index: u32) -> Result<xaudio2::DeviceDetails, HResultError> {
+        let mut details = Default::default();
+        unsafe { self.GetDeviceDetails(index, &mut details) }.succeeded()?;
+        Ok(details)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-registerforcallbacks)\]
+    /// Adds a new client to receive XAudio2's engine callbacks.
+    ///
+    /// [IXAudio2EngineCallback] is unchanged from XAudio 2.8, so [xaudio2_8::xaudio2::EngineCallback::wrap](crate::xaudio2_8::xaudio2::EngineCallback::wrap) works here too.
+    pub fn register_for_callbacks(&self, callback: &'static IXAudio2EngineCallback) -> Result<HResultSuccess, HResultError> {
+        // SAFETY: IXAudio2EngineCallback must outlive self - this is enforced by &'static lifetime.
+        unsafe { self.RegisterForCallbacks(callback) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-unregisterforcallbacks)\]
+    /// Removes an existing receiver of XAudio2 engine callbacks.
+    pub fn unregister_for_callbacks(&self, callback: &IXAudio2EngineCallback) {
+        // SAFETY: Since we're *un*registering `callback`, it need not be 'static.
+        unsafe { self.UnregisterForCallbacks(callback) }
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// Creates and configures a source voice.
+    ///
+    /// ### Safety
+    /// *   `callback` may make demands of submitted [XAUDIO2_BUFFER::pContext]s for soundness purpouses.
+    pub unsafe fn create_source_voice_unchecked<'xa2cb>(
+        &'xa2cb self,
+        format:                 &xaudio2::SourceFormat,
+        flags:                  u32,
+        max_frequency_ratio:    f32,
+        callback:               Option<&'xa2cb IXAudio2VoiceCallback>,
+        send_list:              Option<&[xaudio2::SendDescriptor]>,
+        effect_chain:           Option<&[xaudio2::EffectDescriptor]>,
+    ) -> Result<xaudio2::SourceVoice<'xa2cb>, HResultError> {
+        let mut voice = null_mut();
+        let send_list = voice_sends(send_list)?;
+        let effect_chain = effect_chain_of(effect_chain)?;
+
+        let hr = unsafe { self.CreateSourceVoice(
+            &mut voice,
+            format.as_source_format(),
+            flags,
+            max_frequency_ratio,
+            callback                    .map_or(null(), |c| c),
+            send_list       .as_ref()   .map_or(null(), |c| c),
+            effect_chain    .as_ref()   .map_or(null(), |c| c),
+        )};
+        let voice = unsafe { xaudio2::SourceVoice::from_raw_opt(self, voice) };
+        hr.succeeded()?;
+        let voice = voice.ok_or(E::NOINTERFACE)?;
+        Ok(voice)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsubmixvoice)\]
+    /// Creates and configures a submix voice.
+    pub fn create_submix_voice(
+        &self,
+        input_channels:     u32,
+        input_sample_rate:  u32,
+        flags:              u32,
+        processing_stage:   u32,
+        send_list:          Option<&[xaudio2::SendDescriptor]>,
+        effect_chain:       Option<&[xaudio2::EffectDescriptor]>,
+    ) -> Result<xaudio2::SubmixVoice, HResultError> {
+        let mut voice = null_mut();
+        let send_list = voice_sends(send_list)?;
+        let effect_chain = effect_chain_of(effect_chain)?;
+
+        let hr = unsafe { self.CreateSubmixVoice(
+            &mut voice,
+            input_channels,
+            input_sample_rate,
+            flags,
+            processing_stage,
+            send_list       .as_ref().map_or(null(), |c| c),
+            effect_chain    .as_ref().map_or(null(), |c| c),
+        )};
+        let voice = unsafe { xaudio2::SubmixVoice::from_raw_opt(self, voice) };
+        hr.succeeded()?;
+        let voice = voice.ok_or(E::NOINTERFACE)?;
+        Ok(voice)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createmasteringvoice)\]
+    /// Creates and configures a mastering voice.
+    ///
+    /// | Argument          | Default   |
+    /// | ----------------- | --------- |
+    /// | input_channels    | [xaudio2::DEFAULT_CHANNELS]
+    /// | input_sample_rate | [xaudio2::DEFAULT_SAMPLERATE]
+    /// | flags             | 0
+    /// | device_index      | 0 (the global default device)
+    /// | effect_chain      | None
+    pub fn create_mastering_voice(
+        &self,
+        input_channels:     u32,
+        input_sample_rate:  u32,
+        flags:              u32,
+        device_index:       u32,
+        effect_chain:       Option<&[xaudio2::EffectDescriptor]>,
+    ) -> Result<xaudio2::MasteringVoice, HResultError> {
+        let mut voice = null_mut();
+        let effect_chain = effect_chain_of(effect_chain)?;
+
+        let hr = unsafe { self.CreateMasteringVoice(
+            &mut voice,
+            input_channels,
+            input_sample_rate,
+            flags,
+            device_index,
+            effect_chain    .as_ref().map_or(null(), |c| c),
+        )};
+        let voice = unsafe { xaudio2::MasteringVoice::from_raw_opt(self, voice) };
+        hr.succeeded()?;
+        let voice = voice.ok_or(E::NOINTERFACE)?;
+        Ok(voice)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-startengine)\]
+    /// Creates and starts the audio processing thread.
+    pub fn start_engine(&self) -> Result<HResultSuccess, HResultError> { unsafe { self.StartEngine() }.succeeded() }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-stopengine)\]
+    /// Stops and destroys the audio processing thread.
+    pub fn stop_engine(&self) { unsafe { self.StopEngine() } }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-commitchanges)\]
+    /// Atomically applies a set of operations previously tagged with a given identifier.
+    pub fn commit_changes(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> { unsafe { self.CommitChanges(operation_set) }.succeeded() }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-getperformancedata)\]
+    /// Returns current resource usage details: memory, CPU, etc.
+    pub fn get_performance_data(&self) -> xaudio2::PerformanceData {
+        let mut data = Default::default();
+        unsafe { self.GetPerformanceData(&mut data) };
+        data
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-setdebugconfiguration)\]
+    /// Configures XAudio2's debug output (when created with [xaudio2::DEBUG_ENGINE].)
+    pub fn set_debug_configuration(&self, debug_configuration: &xaudio2::DebugConfiguration, _reserved: Option<core::convert::Infallible>) {
+        unsafe { self.SetDebugConfiguration(debug_configuration, null()) };
+    }
+}
+
+fn voice_sends(send_list: Option<&[xaudio2::SendDescriptor]>) -> Result<Option<XAUDIO2_VOICE_SENDS>, HResultError> {
+    send_list.map(|sl| -> Result<XAUDIO2_VOICE_SENDS, HResultError> { Ok(XAUDIO2_VOICE_SENDS {
+        SendCount:  u32::try_from(sl.len()).map_err(|_| E::INVALIDARG)?,
+        pSends:     sl.as_ptr() as *mut _,
+    })}).transpose()
+}
+
+fn effect_chain_of(effect_chain: Option<&[xaudio2::EffectDescriptor]>) -> Result<Option<XAUDIO2_EFFECT_CHAIN>, HResultError> {
+    effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
+        EffectCount:        u32::try_from(ec.len()).map_err(|_| E::INVALIDARG)?,
+        pEffectDescriptors: ec.as_ptr() as *mut _,
+    })}).transpose()
+}
+
+
+
+#[test] #[ignore = "requires XAudio 2.7 (the DirectX End-User Runtime), which CI doesn't install"] fn enumerate_devices() {
+    mcom::init::mta().expect("mcom::init::mta");
+    let xaudio2 = unsafe { xaudio2::create(0, None) }.expect("xaudio2::create");
+
+    let count = xaudio2.get_device_count().expect("get_device_count");
+    for device in 0 .. count {
+        let details = xaudio2.get_device_details(device).expect("get_device_details");
+        let name = { details.DisplayName };
+        assert!(name.contains(&0), "device {device}: DisplayName isn't nul terminated");
+    }
+    assert!(xaudio2.get_device_details(count).is_err(), "get_device_details({count}) should be out of bounds");
+}
+
+#[test] fn vtable_layout() {
+    use core::mem::{MaybeUninit, size_of};
+    use core::ptr::addr_of;
+
+    // Slot indices per the June 2010 DirectX SDK's XAudio2.h (`IXAudio2` slots include `IUnknown`'s 3 methods, voices aren't COM objects.)
+    macro_rules! slot { ($vtable:ty, $method:ident) => {{
+        let vtable = MaybeUninit::<$vtable>::uninit();
+        let base = vtable.as_ptr();
+        (unsafe { addr_of!((*base).$method) } as usize - base as usize) / size_of::<usize>()
+    }}}
+
+    assert_eq!(slot!(IXAudio2Vtbl, GetDeviceCount),             3);
+    assert_eq!(slot!(IXAudio2Vtbl, GetDeviceDetails),           4);
+    assert_eq!(slot!(IXAudio2Vtbl, Initialize),                 5);
+    assert_eq!(slot!(IXAudio2Vtbl, RegisterForCallbacks),       6);
+    assert_eq!(slot!(IXAudio2Vtbl, CreateSourceVoice),          8);
+    assert_eq!(slot!(IXAudio2Vtbl, CreateMasteringVoice),       10);
+    assert_eq!(slot!(IXAudio2Vtbl, CommitChanges),              13);
+    assert_eq!(slot!(IXAudio2Vtbl, SetDebugConfiguration),      15);
+    assert_eq!(size_of::<IXAudio2Vtbl>(),                       16 * size_of::<usize>());
+
+    assert_eq!(slot!(IXAudio2VoiceVtbl, GetVoiceDetails),       0);
+    assert_eq!(slot!(IXAudio2VoiceVtbl, SetEffectParameters),   6);
+    assert_eq!(slot!(IXAudio2VoiceVtbl, SetOutputMatrix),       16);
+    assert_eq!(slot!(IXAudio2VoiceVtbl, DestroyVoice),          18);
+    assert_eq!(size_of::<IXAudio2VoiceVtbl>(),                  19 * size_of::<usize>());
+    assert_eq!(size_of::<IXAudio2MasteringVoiceVtbl>(),         19 * size_of::<usize>(), "2.7 has no GetChannelMask");
+    assert_eq!(size_of::<IXAudio2SubmixVoiceVtbl>(),            19 * size_of::<usize>());
+
+    assert_eq!(slot!(IXAudio2SourceVoiceVtbl, Start),               19);
+    assert_eq!(slot!(IXAudio2SourceVoiceVtbl, SubmitSourceBuffer),  21);
+    assert_eq!(slot!(IXAudio2SourceVoiceVtbl, GetState),            25);
+    assert_eq!(slot!(IXAudio2SourceVoiceVtbl, SetSourceSampleRate), 28);
+    assert_eq!(size_of::<IXAudio2SourceVoiceVtbl>(),                29 * size_of::<usize>());
+}
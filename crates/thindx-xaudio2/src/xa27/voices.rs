@@ -0,0 +1,249 @@
+use super::xaudio2;
+use super::xaudio2::sys::*;
+#[cfg(doc)] use xaudio2::*;
+
+use winresult::*;
+
+use core::marker::PhantomData;
+use core::mem::transmute;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{NonNull, null};
+
+
+
+macro_rules! voices {
+    ($(
+        $(#[doc = $voice_doc:literal])*
+        pub struct $voice:ident <'xa2> ( NonNull< $ivoice:ty > );
+    )*) => {$(
+        $(#[doc = $voice_doc])*
+        #[repr(transparent)] pub struct $voice <'xa2> {
+            factory:    PhantomData<&'xa2 IXAudio2>,
+            voice:      NonNull< $ivoice >,
+        }
+
+        impl<'xa2> $voice <'xa2> {
+            /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-destroyvoice)\]
+            /// Destroys this voice, stopping it if necessary and removing it from the XAudio2 graph.
+            ///
+            /// (Dropping the voice also implicitly stops/removes it.)
+            pub fn destroy_voice(self) {}
+
+            /// Create a voice wrapper from a raw pointer.
+            ///
+            /// If `raw` is null, will return [None].
+            ///
+            /// ### Safety
+            /// *   `raw` must be a valid XAudio 2.7 interface pointer if not null.
+            /// *   `Self` takes ownership of `raw`.
+            pub unsafe fn from_raw_opt(_xa2: &'xa2 IXAudio2, raw: *const $ivoice) -> Option<Self> { Some(Self {
+                factory:    PhantomData,
+                voice:      NonNull::new(raw as *mut _)?,
+            })}
+
+            /// Convert `self` back into a raw pointer, relinquishing ownership.
+            pub fn into_raw(self) -> *const $ivoice {
+                let ptr = self.voice.as_ptr();
+                core::mem::forget(self);
+                ptr
+            }
+
+            pub fn as_raw(&self) -> *const $ivoice { self.voice.as_ptr() }
+            pub(crate) fn as_ref(&self) -> &$ivoice { unsafe { self.voice.as_ref() } }
+        }
+
+        impl<'xa2> Drop for $voice <'xa2> { fn drop(&mut self) { unsafe { (*self.voice.as_ptr()).DestroyVoice() } } }
+    )*};
+}
+
+voices! {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2voice)\] XAudio 2.7 [IXAudio2Voice]
+    pub struct Voice<'xa2>(NonNull<IXAudio2Voice>);
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2masteringvoice)\] XAudio 2.7 [IXAudio2MasteringVoice]
+    ///
+    /// XAudio 2.7 has no `GetChannelMask`: use [XAudio2::get_device_details]`(device_index)?.OutputFormat.dwChannelMask` instead.
+    pub struct MasteringVoice<'xa2>(NonNull<IXAudio2MasteringVoice>);
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2submixvoice)\] XAudio 2.7 [IXAudio2SubmixVoice]
+    pub struct SubmixVoice<'xa2>(NonNull<IXAudio2SubmixVoice>);
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nn-xaudio2-ixaudio2sourcevoice)\] XAudio 2.7 [IXAudio2SourceVoice]
+    pub struct SourceVoice<'xa2>(NonNull<IXAudio2SourceVoice>);
+}
+
+impl<'xa2> Deref    for MasteringVoice<'xa2>    { fn deref    (&    self) -> &    Self::Target { unsafe { transmute(self) } } type Target = Voice<'xa2>; }
+impl<'xa2> Deref    for SubmixVoice<'xa2>       { fn deref    (&    self) -> &    Self::Target { unsafe { transmute(self) } } type Target = Voice<'xa2>; }
+impl<'xa2> Deref    for SourceVoice<'xa2>       { fn deref    (&    self) -> &    Self::Target { unsafe { transmute(self) } } type Target = Voice<'xa2>; }
+
+impl<'xa2> DerefMut for MasteringVoice<'xa2>    { fn deref_mut(&mut self) -> &mut Self::Target { unsafe { transmute(self) } } }
+impl<'xa2> DerefMut for SubmixVoice<'xa2>       { fn deref_mut(&mut self) -> &mut Self::Target { unsafe { transmute(self) } } }
+impl<'xa2> DerefMut for SourceVoice<'xa2>       { fn deref_mut(&mut self) -> &mut Self::Target { unsafe { transmute(self) } } }
+
+
+
+impl Voice<'_> {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-getvoicedetails)\]
+    /// Returns the basic characteristics of this voice.
+    pub fn get_voice_details(&self) -> xaudio2::VoiceDetails {
+        let mut details = Default::default();
+        unsafe { self.as_ref().GetVoiceDetails(&mut details) };
+        details
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputvoices)\]
+    /// Replaces the set of submix/mastering voices that receive this voice's output.
+    pub fn set_output_voices(&self, send_list: Option<&[xaudio2::SendDescriptor]>) -> Result<HResultSuccess, HResultError> {
+        let send_list = send_list.map(|sl| -> Result<XAUDIO2_VOICE_SENDS, HResultError> { Ok(XAUDIO2_VOICE_SENDS {
+            SendCount:  u32::try_from(sl.len()).map_err(|_| E::INVALIDARG)?,
+            pSends:     sl.as_ptr() as *mut _,
+        })}).transpose()?;
+        unsafe { self.as_ref().SetOutputVoices(send_list.as_ref().map_or(null(), |r| r)) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-seteffectchain)\]
+    /// Replaces this voice's current effect chain with a new one.
+    pub fn set_effect_chain(&self, effect_chain: Option<&[xaudio2::EffectDescriptor]>) -> Result<HResultSuccess, HResultError> {
+        let effect_chain = effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
+            EffectCount:        u32::try_from(ec.len()).map_err(|_| E::INVALIDARG)?,
+            pEffectDescriptors: ec.as_ptr() as *mut _,
+        })}).transpose()?;
+        unsafe { self.as_ref().SetEffectChain(effect_chain.as_ref().map_or(null(), |r| r)) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-enableeffect)\]
+    /// Enables an effect in this voice's effect chain.
+    pub fn enable_effect(&self, effect_index: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().EnableEffect(effect_index, operation_set) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-disableeffect)\]
+    /// Disables an effect in this voice's effect chain.
+    pub fn disable_effect(&self, effect_index: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().DisableEffect(effect_index, operation_set) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-geteffectstate)\]
+    /// Returns the running state of an effect.
+    pub fn get_effect_state(&self, effect_index: u32) -> bool {
+        let mut state = Default::default();
+        unsafe { self.as_ref().GetEffectState(effect_index, &mut state) };
+        state.into()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setfilterparameters)\]
+    /// Sets this voice's filter parameters.
+    pub fn set_filter_parameters(&self, parameters: &xaudio2::FilterParameters, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().SetFilterParameters(parameters, operation_set) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-getfilterparameters)\]
+    /// Returns this voice's current filter parameters.
+    pub fn get_filter_parameters(&self) -> xaudio2::FilterParameters {
+        let mut parameters = Default::default();
+        unsafe { self.as_ref().GetFilterParameters(&mut parameters) };
+        parameters
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setvolume)\]
+    /// Sets this voice's overall volume level.
+    pub fn set_volume(&self, volume: f32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().SetVolume(volume, operation_set) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-getvolume)\]
+    /// Obtains this voice's current overall volume level.
+    pub fn get_volume(&self) -> f32 {
+        let mut v = 0.0;
+        unsafe { self.as_ref().GetVolume(&mut v) };
+        v
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setchannelvolumes)\]
+    /// Sets this voice's per-channel volume levels.
+    pub fn set_channel_volumes(&self, volumes: &[f32], operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        let channels = u32::try_from(volumes.len()).map_err(|_| E::INVALIDARG)?;
+        unsafe { self.as_ref().SetChannelVolumes(channels, volumes.as_ptr(), operation_set) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setoutputmatrix)\]
+    /// Sets the volume levels used to mix from each channel of this voice's output audio to each channel of a given destination voice's input audio.
+    pub fn set_output_matrix(&self, destination_voice: &IXAudio2Voice, source_channels: u32, destination_channels: u32, level_matrix: &[f32], operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        let n = (source_channels as usize).checked_mul(destination_channels as usize).ok_or(E::INVALIDARG)?;
+        if level_matrix.len() != n { return Err(E::INVALIDARG) }
+        unsafe { self.as_ref().SetOutputMatrix(destination_voice, source_channels, destination_channels, level_matrix.as_ptr(), operation_set) }.succeeded()
+    }
+}
+
+impl SourceVoice<'_> {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-start)\]
+    /// Makes this voice start consuming and processing audio.
+    pub fn start(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().Start(flags, operation_set) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-stop)\]
+    /// Makes this voice stop consuming audio.
+    pub fn stop(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().Stop(flags, operation_set) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-submitsourcebuffer)\]
+    /// Adds a new audio buffer to this voice's input queue.
+    ///
+    /// ### Safety
+    /// *   `buffer.pAudioData` (and `buffer_wma.pDecodedPacketCumulativeBytes`) must remain valid until XAudio2 is done with them
+    ///     (e.g. until [OnBufferEnd](IXAudio2VoiceCallback::OnBufferEnd), [flush_source_buffers](Self::flush_source_buffers) + [stop](Self::stop), or the voice is destroyed.)
+    /// *   `buffer.pContext` must be valid for whatever the voice's callback expects of it.
+    pub unsafe fn submit_source_buffer_unchecked(&self, buffer: &XAUDIO2_BUFFER, buffer_wma: Option<&XAUDIO2_BUFFER_WMA>) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().SubmitSourceBuffer(buffer, buffer_wma.map_or(null(), |b| b)) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-flushsourcebuffers)\]
+    /// Removes all pending audio buffers from this voice's queue.
+    pub fn flush_source_buffers(&self) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().FlushSourceBuffers() }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-discontinuity)\]
+    /// Notifies the voice of an intentional break in the stream of audio buffers (e.g. the end of a sound), to prevent XAudio2 from interpreting an empty buffer queue as a glitch.
+    pub fn discontinuity(&self) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().Discontinuity() }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-exitloop)\]
+    /// Breaks out of the current loop when its end is reached.
+    pub fn exit_loop(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().ExitLoop(operation_set) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getstate)\]
+    /// Returns the number of buffers currently queued on this voice, the pContext value associated with the currently processing buffer (if any), and the samples played.
+    ///
+    /// Unlike XAudio 2.8+, there are no flags: [SamplesPlayed](VoiceState::SamplesPlayed) is always calculated.
+    pub fn get_state(&self) -> xaudio2::VoiceState {
+        let mut state = Default::default();
+        unsafe { self.as_ref().GetState(&mut state) };
+        state
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setfrequencyratio)\]
+    /// Sets this voice's frequency adjustment, i.e. its pitch.
+    pub fn set_frequency_ratio(&self, ratio: f32, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().SetFrequencyRatio(ratio, operation_set) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getfrequencyratio)\]
+    /// Returns this voice's current frequency adjustment ratio.
+    pub fn get_frequency_ratio(&self) -> f32 {
+        let mut ratio = 0.0;
+        unsafe { self.as_ref().GetFrequencyRatio(&mut ratio) };
+        ratio
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setsourcesamplerate)\]
+    /// Reconfigures this voice to treat its source data as being at a different sample rate than the original one specified at creation.
+    pub fn set_source_sample_rate(&self, new_source_sample_rate: u32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.as_ref().SetSourceSampleRate(new_source_sample_rate) }.succeeded()
+    }
+}
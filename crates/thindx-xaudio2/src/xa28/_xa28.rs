@@ -6,20 +6,23 @@
 //! *   [XAudio2 Versions: XAudio 2.8 (Windows 8.x)](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xaudio2-versions#xaudio-28-windows-8x)
 //! *   [XAudio2 and Windows 8](https://walbourn.github.io/xaudio2-and-windows-8/)
 
-mod context;
+mod effect_chain;                   pub(crate) use effect_chain::{forget_voice_effects, set_voice_effects};
 mod engine_callback;
+mod exports;                       pub(crate) use exports::io_error_to_hresult;
 mod ixaudio2_ext;                   pub use ixaudio2_ext::*;
 mod ixaudio2masteringvoice_ext;     pub use ixaudio2masteringvoice_ext::*;
 mod ixaudio2voice_ext;              pub use ixaudio2voice_ext::*;
 mod ixaudio2sourcevoice_ext;        pub use ixaudio2sourcevoice_ext::*;
+mod owned_voice;
 mod source_buffer;                  pub(crate) use source_buffer::*;
-mod loop_count;
-mod sample_range;
-mod source_format;
+mod render_source_voice;
 mod source_voice_dynamic;
 mod source_voice;
+mod spatial_emitter;
+mod streaming_source_voice;
 mod voices;
 mod voice_callback;
+mod xapo;
 
 
 // Might not remain pub
@@ -46,16 +49,42 @@ mod voice_callback;
 pub mod xaudio2 {
     // Re-exports
     #[doc(no_inline)] pub use winresult::{HResult, HResultError};
+    use winresult::ERROR;
+    #[cfg(doc)] use winresult::CO;
 
-    pub use super::context::*;
+    #[doc(inline)] pub use crate::portable::{
+        // Structs
+        AdpcmSourceFormat,
+        LoopCount,
+        ParameterChannel,
+        Processing,
+        SampleRange,
+        SourceFormat,
+        TypedSourceFormat,
+        XwmaSourceFormat,
+
+        // Traits
+        Context,
+        HasPcmWaveFormat,
+
+        // Constants
+        NO_LOOP_REGION,
+        MAX_LOOP_COUNT,
+        LOOP_INFINITE,
+    };
+
+    pub use super::effect_chain::*;
     pub use super::engine_callback::*;
-    pub use super::loop_count::*;
-    pub use super::sample_range::*;
-    pub use super::source_format::*;
+    pub use super::exports::Exports;
+    pub use super::owned_voice::*;
+    pub use super::render_source_voice::*;
     pub use super::source_voice_dynamic::*;
     pub use super::source_voice::*;
+    pub use super::spatial_emitter::*;
+    pub use super::streaming_source_voice::*;
     pub use super::voices::*;
     pub use super::voice_callback::*;
+    pub use super::xapo::*;
 
     /// Raw low level FFI bindings
     ///
@@ -190,6 +219,64 @@ pub mod xaudio2 {
     const _ : () = assert!(core::mem::align_of::<sys::XAUDIO2_SEND_DESCRIPTOR>() <= core::mem::align_of::<SendDescriptor>());
     const _ : () = assert!(core::mem::size_of ::<sys::XAUDIO2_SEND_DESCRIPTOR>() == core::mem::size_of ::<SendDescriptor>());
 
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-xaudio2create)\] XAudio2Create:
+    /// Creates a new [IXAudio2](sys::IXAudio2) instance using the `XAudio2_8.dll` that ships with Windows 8+.
+    ///
+    /// Initialize COM (see e.g. [mcom::init::mta]) before calling this function.
+    /// This call may succeed without COM, but basic operations like creating voices will then fail with e.g. [CO::E_NOTINITIALIZED].
+    ///
+    /// ### Safety
+    /// See [xaudio2_9::xaudio2::create](crate::xaudio2_9::xaudio2::create) - the same caveats apply.
+    ///
+    /// ### Arguments
+    /// *   `flags`     - Must be [None]
+    /// *   `processor` - The processor(s) to run XAudio2's worker thread(s) on.
+    ///
+    ///     | Value                     | Description   |
+    ///     | ------------------------- | ------------- |
+    ///     | [None]                    | Same as [DEFAULT_PROCESSOR] (XAudio 2.8 has no `USE_DEFAULT_PROCESSOR`)
+    ///     | [DEFAULT_PROCESSOR]       | Hardcoded SDK default processor (e.g. [Processor1])   |
+    ///     | [ANY_PROCESSOR]           | ⚠️ Spawn threads for every processor/core!  (Excessive!)  |
+    ///     | [Processor1]              | Run specifically on processor/core 1                  |
+    ///     |   ..                      |   ..                                                  |
+    ///     | [Processor32]             | Run specifically on processor/core 32                 |
+    ///
+    /// ### Example
+    /// ```
+    /// use thindx_xaudio2::xaudio2_8::*;
+    /// let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+    /// ```
+    ///
+    /// ### Errors
+    /// *   [HResultError::from_win32]\([ERROR::BAD_EXE_FORMAT])    - if `XAudio2_8.dll` had the wrong architecture (perhaps a 32-bit DLL in a 64-bit process or vicea versa?)
+    /// *   [HResultError::from_win32]\([ERROR::MOD_NOT_FOUND])     - if `XAudio2_8.dll` could not be found (e.g. on Windows 7)
+    /// *   [HResultError::from_win32]\([ERROR::INVALID_LIBRARY])   - if `XAudio2_8.dll` loading failed to load in a strange way
+    /// *   [HResultError::from_win32]\([ERROR::PROC_NOT_FOUND])    - if `XAudio2_8.dll` failed to export `XAudio2Create`
+    /// *   [HResultError::from_win32]\([ERROR::NOINTERFACE])       - if [IXAudio2](sys::IXAudio2) was null despite the function "succeeding" (thindx specific)
+    /// *   [E_INVALID_CALL]                                        - if `processor` is invalid
+    ///
+    /// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
+    pub unsafe fn create(flags: Option<core::convert::Infallible>, processor: impl Into<Option<Processor>>) -> Result<super::XAudio2, HResultError> {
+        #![allow(non_snake_case)]
+
+        let exports = Exports::from_default_path_cached_hresult()?;
+
+        let _ = flags;
+        let processor = processor.into().unwrap_or(DEFAULT_PROCESSOR);
+        let mut xaudio2 = None;
+
+        let hr = if let Some(XAudio2Create) = exports.XAudio2Create {
+            unsafe { XAudio2Create(&mut xaudio2, 0, processor) }
+        } else {
+            // See xaudio2_9::xaudio2::create: `exports` might be a cached copy of XAudio2's exports, loaded long ago, so GetLastError() is meaningless.
+            HResultError::from_win32(ERROR::PROC_NOT_FOUND).into()
+        };
+
+        hr.succeeded()?;
+        let xaudio2 = xaudio2.ok_or(HResultError::from_win32(ERROR::NOINTERFACE))?; // XAudio2Create "succeeded" but gave us a null ptr?
+        Ok(xaudio2)
+    }
+
     pub use sys::{
         XAUDIO2_DEBUG_CONFIGURATION     as DebugConfiguration,
         XAUDIO2_FILTER_PARAMETERS       as FilterParameters,
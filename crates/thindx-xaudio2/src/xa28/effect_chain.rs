@@ -0,0 +1,214 @@
+use super::xaudio2::*;
+
+use winapi::um::unknwnbase::IUnknown;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use core::fmt::{self, Debug, Formatter};
+use core::marker::PhantomData;
+use core::ops::Deref;
+use std::sync::{Mutex, MutexGuard};
+
+
+
+/// Parameters accepted by an effect via [Voice::set_effect_parameters] / [Voice::get_effect_parameters].
+///
+/// Implementing this is safe: that an effect expects exactly `Self`'s layout is asserted by the `unsafe` [TypedEffect::from_unknown].
+/// Handing an effect the wrong parameter type is no more unsafe than [Voice::set_effect_parameters_raw]: XAudio2 passes the size
+/// along, and effects are expected to reject blocks they don't understand with [E::INVALIDARG](winresult::E::INVALIDARG).
+pub trait EffectParameters : bytemuck::Pod + Send + Sync + 'static {
+    /// Checked by [Voice::set_effect_parameters] before parameters are handed to the effect, and by [Voice::get_effect_parameters] after they're read back.
+    ///
+    /// Defaults to accepting everything.  Effects that don't validate their own parameters should return [E::INVALIDARG](winresult::E::INVALIDARG) for out of range values.
+    fn validate(&self) -> Result<(), HResultError> { Ok(()) }
+}
+
+/// An audio effect (APO) whose parameters are described by `P`, as returned by e.g. `xaudio2_9::xaudio2::create_reverb`.
+///
+/// `P` is usually an [EffectParameters] type, but needn't be (e.g. `xaudio2_9::xaudio2::VolumeMeterLevels` contains pointers, and has dedicated accessors.)
+///
+/// Convert it into a [TypedEffectDescriptor] via [descriptor](Self::descriptor) to add it to an [EffectChain].
+pub struct TypedEffect<P> {
+    effect:     mcom::Rc<IUnknown>,
+    parameters: PhantomData<fn(P)>,
+}
+
+impl<P> Clone for TypedEffect<P> { fn clone(&self) -> Self { Self { effect: self.effect.clone(), parameters: PhantomData } } }
+
+impl<P> TypedEffect<P> {
+    /// Assert that `effect`'s parameters are described by `P`.
+    ///
+    /// ### Safety
+    /// *   If `P` is an [EffectParameters] type, `effect` must accept `P` via `IXAPOParameters::SetParameters` / `GetParameters`.
+    /// *   Otherwise, `effect` must meet the requirements of `P`'s accessors.
+    pub unsafe fn from_unknown(effect: mcom::Rc<IUnknown>) -> Self { Self { effect, parameters: PhantomData } }
+
+    /// The underlying effect.
+    pub fn as_unknown(&self) -> &mcom::Rc<IUnknown> { &self.effect }
+
+    /// Discard the parameter type.
+    pub fn into_unknown(self) -> mcom::Rc<IUnknown> { self.effect }
+
+    /// Describe this effect for an [EffectChain]: enabled if `initial_state`, producing `output_channels` channels.
+    pub fn descriptor(&self, initial_state: bool, output_channels: u32) -> TypedEffectDescriptor<P> {
+        TypedEffectDescriptor { descriptor: EffectDescriptor::new(self.effect.clone(), initial_state, output_channels), parameters: PhantomData }
+    }
+}
+
+/// An [EffectDescriptor] whose effect's parameters are described by `P` (see [TypedEffect].)
+///
+/// Add it to an [EffectChain] to get an [EffectHandle] for type checked parameter access.
+pub struct TypedEffectDescriptor<P> {
+    descriptor: EffectDescriptor,
+    parameters: PhantomData<fn(P)>,
+}
+
+impl<P> Clone for TypedEffectDescriptor<P> { fn clone(&self) -> Self { Self { descriptor: self.descriptor.clone(), parameters: PhantomData } } }
+
+impl<P> TypedEffectDescriptor<P> {
+    /// Assert that `descriptor.effect`'s parameters are described by `P`.
+    ///
+    /// ### Safety
+    /// *   As per [TypedEffect::from_unknown].
+    pub unsafe fn new(descriptor: EffectDescriptor) -> Self { Self { descriptor, parameters: PhantomData } }
+
+    /// The underlying (untyped) [EffectDescriptor].
+    pub fn descriptor(&self) -> &EffectDescriptor { &self.descriptor }
+
+    /// Discard the parameter type.
+    pub fn into_descriptor(self) -> EffectDescriptor { self.descriptor }
+}
+
+/// A typed reference to an effect within the [EffectChain] it was returned from.
+///
+/// The handle keeps its effect alive, and is checked on use: a voice only accepts it if that very effect is at [index](Self::index) of the
+/// chain it was created with (or last given via [set_effect_chain](Voice::set_effect_chain)), and rejects it with [E::INVALIDARG](winresult::E::INVALIDARG) otherwise.
+/// Chains set by other means (e.g. calling `SetEffectChain` directly, or wrapping a voice via `from_raw`) aren't known, so handles are rejected for those voices.
+pub struct EffectHandle<P> {
+    effect:             mcom::Rc<IUnknown>,
+    index:              u32,
+    output_channels:    u32,
+    parameters:         PhantomData<fn(P)>,
+}
+
+impl<P> Clone for EffectHandle<P> { fn clone(&self) -> Self { Self { effect: self.effect.clone(), index: self.index, output_channels: self.output_channels, parameters: PhantomData } } }
+impl<P> Debug for EffectHandle<P> { fn fmt(&self, fmt: &mut Formatter) -> fmt::Result { write!(fmt, "EffectHandle({})", self.index) } }
+
+impl<P> EffectHandle<P> {
+    /// The effect's index within its chain (for [enable_effect](Voice::enable_effect), [get_effect_state](Voice::get_effect_state), etc.)
+    pub fn index(&self) -> u32 { self.index }
+
+    /// The `output_channels` the effect was [described](TypedEffect::descriptor) with.
+    pub fn output_channels(&self) -> u32 { self.output_channels }
+
+    /// [index](Self::index), if `voice`'s chain has this handle's effect there, or [E::INVALIDARG](winresult::E::INVALIDARG) otherwise.
+    pub(crate) fn index_in(&self, voice: &Voice) -> Result<u32, HResultError> {
+        let effect = &*self.effect as *const IUnknown as usize;
+        match voice_effects().get(&(voice.as_ref() as *const _ as usize)).and_then(|effects| effects.get(self.index as usize)) {
+            Some(e) if *e == effect => Ok(self.index),
+            _                       => Err(winresult::E::INVALIDARG),
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    /// The effects of each voice's chain (by address), as last passed to voice creation or [Voice::set_effect_chain].
+    /// XAudio2 has no way to query a voice's chain, so [EffectHandle]s are checked against this instead.
+    static ref VOICE_EFFECTS : Mutex<BTreeMap<usize, Vec<usize>>> = Default::default();
+}
+
+fn voice_effects() -> MutexGuard<'static, BTreeMap<usize, Vec<usize>>> { VOICE_EFFECTS.lock().unwrap_or_else(|poison| poison.into_inner()) }
+
+/// Record that the voice at `voice` now has `effect_chain` (after it was successfully created with, or given, it.)
+pub(crate) fn set_voice_effects(voice: usize, effect_chain: Option<&[EffectDescriptor]>) {
+    let effects = effect_chain.unwrap_or(&[]).iter().map(|d| &*d.effect as *const IUnknown as usize).collect::<Vec<_>>();
+    let mut voices = voice_effects();
+    if effects.is_empty() { voices.remove(&voice); } else { voices.insert(voice, effects); }
+}
+
+/// Forget the effects of the voice at `voice` (as it's being destroyed.)
+pub(crate) fn forget_voice_effects(voice: usize) { voice_effects().remove(&voice); }
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/ns-xaudio2-xaudio2_effect_chain)\]
+/// A list of [EffectDescriptor]s, handing out [EffectHandle]s as effects are added.
+///
+/// Derefs to `[EffectDescriptor]`, for passing to voice creation or [set_effect_chain](Voice::set_effect_chain).
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// let mut chain = xaudio2::EffectChain::new();
+/// let echo = chain.push(xaudio2::create_fx_echo(&Default::default()).unwrap().descriptor(true, 2));
+/// let submix = xaudio2.create_submix_voice(2, 44100, 0, 0, None, Some(chain.as_slice())).unwrap();
+/// submix.set_effect_parameters(&echo, &xaudio2::FxEchoParameters { WetDryMix: 0.5, Feedback: 0.5, Delay: 250.0 }, xaudio2::COMMIT_NOW).unwrap();
+/// ```
+#[derive(Clone, Default)] pub struct EffectChain {
+    descriptors: Vec<EffectDescriptor>,
+}
+
+impl EffectChain {
+    /// An empty effect chain.
+    pub fn new() -> Self { Self::default() }
+
+    /// Append an effect, returning a handle for type checked parameter access.
+    ///
+    /// ### Panics
+    /// *   If the chain already has [u32::MAX] effects.
+    pub fn push<P>(&mut self, effect: TypedEffectDescriptor<P>) -> EffectHandle<P> {
+        let (unknown, output_channels) = (effect.descriptor.effect.clone(), effect.descriptor.output_channels);
+        EffectHandle { effect: unknown, index: self.push_descriptor(effect.descriptor), output_channels, parameters: PhantomData }
+    }
+
+    /// Append an effect whose parameters are unknown, returning its index.
+    ///
+    /// ### Panics
+    /// *   If the chain already has [u32::MAX] effects.
+    pub fn push_descriptor(&mut self, descriptor: EffectDescriptor) -> u32 {
+        let index = u32::try_from(self.descriptors.len()).expect("EffectChain::push: too many effects");
+        self.descriptors.push(descriptor);
+        index
+    }
+
+    /// The [EffectDescriptor]s of this chain.
+    pub fn as_slice(&self) -> &[EffectDescriptor] { &self.descriptors[..] }
+}
+
+impl Deref for EffectChain { fn deref(&self) -> &Self::Target { &self.descriptors[..] } type Target = [EffectDescriptor]; }
+
+
+
+#[test] fn effect_handles() {
+    use crate::mock;
+    use winresult::E;
+
+    #[derive(Clone, Copy, Debug, PartialEq, bytemuck::Zeroable, bytemuck::Pod)] #[repr(C)] struct Gain(f32);
+    impl EffectParameters for Gain { fn validate(&self) -> Result<(), HResultError> { if self.0 >= 0.0 { Ok(()) } else { Err(E::INVALIDARG) } } }
+
+    struct Passthrough;
+    impl Xapo for Passthrough { fn process(&mut self, input: &mut [f32], output: &mut [f32]) { output.copy_from_slice(input) } }
+    let gain = || unsafe { TypedEffect::<Gain>::from_unknown(Passthrough.wrap().into_unknown()) }; // the mock only stores parameters
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let _master = xaudio2.create_mastering_voice(2, 48000, 0, (), None, DEFAULT_AUDIO_CATEGORY).unwrap();
+    let (mut chain_a, mut chain_b) = (EffectChain::new(), EffectChain::new());
+    let gain_a  = chain_a.push(gain().descriptor(true, 2));
+    let gain_b  = chain_b.push(gain().descriptor(true, 2));
+    let a       = xaudio2.create_submix_voice(2, 48000, 0, 0, None, Some(chain_a.as_slice())).unwrap();
+    let b       = xaudio2.create_submix_voice(2, 48000, 0, 0, None, Some(chain_b.as_slice())).unwrap();
+    assert_eq!(gain_a.index(), gain_b.index());
+
+    assert!(a.set_effect_parameters(&gain_a, &Gain(0.5), COMMIT_NOW).is_ok());
+    assert_eq!(a.get_effect_parameters(&gain_a), Ok(Gain(0.5)));
+    assert_eq!(b.set_effect_parameters(&gain_a, &Gain(0.5), COMMIT_NOW).err(), Some(E::INVALIDARG.into()), "gain_a isn't in b's chain, despite sharing an index");
+    assert_eq!(b.get_effect_parameters(&gain_a).err(), Some(E::INVALIDARG.into()));
+    assert_eq!(a.set_effect_parameters(&gain_a, &Gain(-1.0), COMMIT_NOW).err(), Some(E::INVALIDARG.into()), "fails validation");
+
+    b.set_effect_parameters_raw(0, &Gain(-1.0), COMMIT_NOW).unwrap();
+    assert_eq!(b.get_effect_parameters(&gain_b).err(), Some(E::INVALIDARG.into()), "reads are validated too");
+
+    a.set_effect_chain(None).unwrap();
+    assert_eq!(a.set_effect_parameters(&gain_a, &Gain(0.5), COMMIT_NOW).err(), Some(E::INVALIDARG.into()), "gain_a was removed from a's chain");
+}
@@ -78,7 +78,7 @@ impl<EC: EngineCallback> EngineCallbackWrapper<EC> {
 }
 
 #[test] fn test() {
-    use crate::xaudio2_9::*; // XXX: no xaudio2::create for 2.8 yet
+    use crate::xaudio2_8::*;
     use alloc::boxed::Box;
 
     struct EC;
@@ -0,0 +1,160 @@
+use super::{XAudio2, xaudio2::sys::*};
+use winapi::shared::guiddef::REFCLSID;
+use winapi::um::unknwnbase::IUnknown;
+use thindx_xaudio2_sys::x3daudio::{X3DAudioCalculateFn, X3DAudioInitializeFn};
+use winresult::{ERROR, ErrorCode, HResult, HResultError};
+use core::ffi::c_void;
+
+
+
+/// `XAudio2_8.dll` exports.
+///
+/// Unlike `XAudio2_9.dll`, there's no `XAudio2CreateWithVersionInfo`, `XAudio2CreateWithSharedContexts`, or `*V2_*` aliases:
+/// ```text
+/// CreateAudioReverb
+/// CreateAudioVolumeMeter
+/// CreateFX
+/// X3DAudioCalculate
+/// X3DAudioInitialize
+/// XAudio2Create
+/// ```
+#[allow(non_snake_case)] // match original naming instead
+#[non_exhaustive] pub struct Exports {
+    /// \[<strike>microsoft.com</strike>\]
+    /// Implementation detail of [XAudio2CreateReverb].<br>
+    /// Unlike [XAudio2CreateReverb], this has no `Flags` parameter (it is unused and discarded.)
+    ///
+    /// | Argument          | Description   |
+    /// | ----------------- | ------------- |
+    /// | ppApo             | Output audio effect.  Can be [QueryInterface]d or [mcom::Rc::try_cast]ed to [IXAPO] or [IXAPOParameters].
+    ///
+    /// The returned effect takes XAudio 2.8's `XAUDIO2FX_REVERB_PARAMETERS` (no `DisableLateField`.)
+    ///
+    /// [QueryInterface]:       https://learn.microsoft.com/en-us/cpp/atl/queryinterface?view=msvc-170
+    /// [IXAPO]:                https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapo
+    /// [IXAPOParameters]:      https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapoparameters
+    /// [XAudio2CreateReverb]:  https://learn.microsoft.com/en-us/windows/win32/api/xaudio2fx/nf-xaudio2fx-xaudio2createreverb
+    pub CreateAudioReverb:      Option<unsafe extern "stdcall" fn(ppApo: *mut *mut IUnknown) -> HResult>,
+    // XAUDIO2FX_STDAPI -> STDAPI -> EXTERN_C HRESULT STDAPICALLTYPE -> __stdcall ?
+
+
+    /// \[<strike>microsoft.com</strike>\]
+    /// Implementation detail of [XAudio2CreateVolumeMeter].<br>
+    /// Unlike [XAudio2CreateVolumeMeter], this has no `Flags` parameter (it is unused and discarded.)
+    ///
+    /// | Argument          | Description   |
+    /// | ----------------- | ------------- |
+    /// | ppApo             | Output audio effect.  Can be [QueryInterface]d or [mcom::Rc::try_cast]ed to [IXAPO] or [IXAPOParameters].
+    ///
+    /// [QueryInterface]:           https://learn.microsoft.com/en-us/cpp/atl/queryinterface?view=msvc-170
+    /// [IXAPO]:                    https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapo
+    /// [IXAPOParameters]:          https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapoparameters
+    /// [XAudio2CreateVolumeMeter]: https://learn.microsoft.com/en-us/windows/win32/api/xaudio2fx/nf-xaudio2fx-xaudio2createvolumemeter
+    pub CreateAudioVolumeMeter: Option<unsafe extern "stdcall" fn(ppApo: *mut *mut IUnknown) -> HResult>,
+    // XAUDIO2FX_STDAPI -> STDAPI -> EXTERN_C HRESULT STDAPICALLTYPE -> __stdcall ?
+
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/nf-xapofx-createfx)\]
+    /// Creates an instance of the requested [XAPOFX](https://learn.microsoft.com/en-us/windows/win32/xaudio2/xapofx-overview) effect.
+    ///
+    /// | Argument          | Description   |
+    /// | ----------------- | ------------- |
+    /// | clsid             | Effect class ID (e.g. `CLSID_{FXEQ,FXMasteringLimiter,FXReverb,FXEcho,...}`)
+    /// | pEffect           | Output audio effect.  Can be [QueryInterface]d or [mcom::Rc::try_cast]ed to [IXAPO] or [IXAPOParameters].
+    /// | pInitDat          | Pointer to effect class specific parameter structure (e.g. `FXECHO_PARAMETERS` for `CLSID_FXEcho`.)
+    /// | InitDataByteSize  | Size of effect class specific parameter structure (e.g. `size_of::<FXECHO_PARAMETERS>()`.)
+    ///
+    /// [QueryInterface]:           https://learn.microsoft.com/en-us/cpp/atl/queryinterface?view=msvc-170
+    /// [IXAPO]:                    https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapo
+    /// [IXAPOParameters]:          https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapoparameters
+    pub CreateFX: Option<unsafe extern "cdecl" fn(clsid: REFCLSID, pEffect: *mut *mut IUnknown, pInitDat: *const c_void, InitDataByteSize: u32) -> HResult>,
+    // FX_API_ -> STDAPIV_ -> EXTERN_C STDAPIVCALLTYPE -> __cdecl
+
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudiocalculate)\]
+    /// Calculates DSP settings with respect to 3D parameters.
+    ///
+    /// | Argument          | Description   |
+    /// | ----------------- | ------------- |
+    /// | Instance          | 3D audio instance handle (acquired with [X3DAudioInitialize])
+    /// | pListener         | Represents the point of reception
+    /// | pEmitter          | Represents the sound source
+    /// | Flags             | `X3DAUDIO_CALCULATE_*`
+    /// | pDSPSettings      | Calculation results
+    ///
+    /// [X3DAudioInitialize]:   https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudioinitialize
+    pub X3DAudioCalculate:      Option<X3DAudioCalculateFn>,
+
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudioinitialize)\]
+    /// Sets all global 3D audio constants.
+    ///
+    /// | Argument              | Description   |
+    /// | --------------------- | ------------- |
+    /// | SpeakerChannelMask    | Assignment of channels to speaker positions. This value must not be zero. The only permissible value on Xbox 360 is `SPEAKER_XBOX`.
+    /// | SpeedOfSound          | Speed of sound, in user-defined world units per second. Use this value only for doppler calculations. It must be greater than or equal to [f32::MIN_POSITIVE].
+    pub X3DAudioInitialize:     Option<X3DAudioInitializeFn>,
+
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-xaudio2create)\]
+    /// Creates a new XAudio2 object and returns a pointer to its [IXAudio2] interface.
+    ///
+    /// | Argument          | Default                       | Description   |
+    /// | ----------------- | ----------------------------- | ------------- |
+    /// | ppXAudio2         |                               | Returned [IXAudio2]
+    /// | Flags             | 0                             | Reserved flags (must be 0)
+    /// | XAudio2Processor  | [XAUDIO2_DEFAULT_PROCESSOR]   | What processor(s) to run XAudio2 on.
+    pub XAudio2Create: Option<unsafe extern "system" fn(ppXAudio2: &mut Option<XAudio2>, Flags: u32, XAudio2Processor: XAUDIO2_PROCESSOR) -> HResult>,
+}
+
+impl Exports {
+    pub fn from_default_path_cached() -> &'static std::io::Result<Self> {
+        lazy_static::lazy_static! { static ref EXPORTS : std::io::Result<Exports> = Exports::from_default_path(); }
+        &*EXPORTS
+    }
+
+    /// [from_default_path_cached](Self::from_default_path_cached), with I/O errors converted to [HResultError]s (e.g. [ERROR::MOD_NOT_FOUND].)
+    pub(crate) fn from_default_path_cached_hresult() -> Result<&'static Self, HResultError> {
+        Self::from_default_path_cached().as_ref().map_err(io_error_to_hresult)
+    }
+
+    pub fn from_default_path() -> std::io::Result<Self> {
+        Self::from_path(super::xaudio2::DLL)
+    }
+
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let lib = minidl::Library::load(path)?; // TODO: LOAD_LIBRARY_SEARCH_SYSTEM32: https://github.com/MaulingMonkey/minidl/issues/2
+        Self::from_minidl_0_1(lib)
+    }
+
+    pub fn from_minidl_0_1(lib: minidl::Library) -> std::io::Result<Self> {
+        Ok(Self {
+            CreateAudioReverb:                  unsafe { lib.sym_opt("CreateAudioReverb\0") },
+            CreateAudioVolumeMeter:             unsafe { lib.sym_opt("CreateAudioVolumeMeter\0") },
+            CreateFX:                           unsafe { lib.sym_opt("CreateFX\0") },
+            X3DAudioCalculate:                  unsafe { lib.sym_opt("X3DAudioCalculate\0") },
+            X3DAudioInitialize:                 unsafe { lib.sym_opt("X3DAudioInitialize\0") },
+            XAudio2Create:                      unsafe { lib.sym_opt("XAudio2Create\0") },
+        })
+    }
+}
+
+/// Convert a DLL loading error into an [HResultError] (e.g. [ERROR::MOD_NOT_FOUND], or [ERROR::INVALID_LIBRARY] if there's no OS error code.)
+pub(crate) fn io_error_to_hresult(err: &std::io::Error) -> HResultError {
+    let code = if let Some(code) = err.raw_os_error() {
+        let code = code as u32;
+        if let Ok(code) = u16::try_from(code) {
+            ErrorCode::from(code)
+        } else {
+            return HResultError::from(code);
+        }
+    } else {
+        ERROR::INVALID_LIBRARY
+    };
+    HResultError::from_win32(code)
+}
+
+#[test] fn exports() {
+    let exports = Exports::from_default_path().unwrap();
+    assert!(exports.XAudio2Create.is_some());
+}
@@ -18,6 +18,13 @@ use core::ptr::{null_mut, null};
 impl core::ops::Deref for XAudio2 { type Target = mcom::Rc<IXAudio2>; fn deref(&self) -> &Self::Target { &self.0 } }
 
 impl XAudio2 {
+    /// Take ownership of a raw [IXAudio2] interface pointer.
+    ///
+    /// ### Safety
+    /// *   `raw` must be a valid, non-null [IXAudio2] interface pointer.
+    /// *   `Self` takes ownership of one reference to `raw` (released on drop.)
+    pub unsafe fn from_raw(raw: *mut IXAudio2) -> Self { Self(unsafe { mcom::Rc::from_raw(raw) }) }
+
     /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-registerforcallbacks)\]
     /// Adds a new client to receive XAudio2's engine callbacks.
     pub fn register_for_callbacks(&self, callback: &'static IXAudio2EngineCallback) -> Result<HResultSuccess, HResultError> {
@@ -79,6 +86,41 @@ impl XAudio2 {
         Ok(unsafe { xaudio2::SourceVoiceDynamic::from_raw(self, voice.into_raw().cast()) })
     }
 
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// Creates a source voice that streams audio from `decoder`, keeping up to `max_queued_buffers` chunks queued.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG] if `max_queued_buffers` is `0` or exceeds [MAX_QUEUED_BUFFERS](xaudio2::MAX_QUEUED_BUFFERS).
+    pub fn create_streaming_source_voice<D: xaudio2::StreamDecoder>(
+        &self,
+        format:                 &xaudio2::SourceFormat,
+        flags:                  u32,
+        max_frequency_ratio:    f32,
+        max_queued_buffers:     usize,
+        decoder:                D,
+        send_list:              Option<&[xaudio2::SendDescriptor]>,
+        effect_chain:           Option<&[xaudio2::EffectDescriptor]>,
+    ) -> Result<xaudio2::StreamingSourceVoice<D>, HResultError> {
+        xaudio2::StreamingSourceVoice::new(self, format, flags, max_frequency_ratio, max_queued_buffers, decoder, send_list, effect_chain)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// Creates a source voice that calls `renderer` to generate audio on demand.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG] if `S` isn't exactly one frame of `format` (`nBlockAlign` bytes.)
+    pub fn create_render_source_voice<S: bytemuck::Pod + Send + Sync, R: FnMut(&mut [S]) + Send + 'static>(
+        &self,
+        format:                 &xaudio2::TypedSourceFormat<S>,
+        flags:                  u32,
+        max_frequency_ratio:    f32,
+        renderer:               R,
+        send_list:              Option<&[xaudio2::SendDescriptor]>,
+        effect_chain:           Option<&[xaudio2::EffectDescriptor]>,
+    ) -> Result<xaudio2::RenderSourceVoice<S, R>, HResultError> {
+        xaudio2::RenderSourceVoice::new(self, format, flags, max_frequency_ratio, renderer, send_list, effect_chain)
+    }
+
     /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
     /// Creates and configures a source voice.
     ///
@@ -100,7 +142,7 @@ impl XAudio2 {
             pSends:     sl.as_ptr() as *mut _,
         })}).transpose()?;
 
-        let effect_chain = effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
+        let raw_effect_chain = effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
             EffectCount:        u32::try_from(ec.len()).map_err(|_| E::INVALIDARG)?,
             pEffectDescriptors: ec.as_ptr() as *mut _,
         })}).transpose()?;
@@ -112,11 +154,12 @@ impl XAudio2 {
             max_frequency_ratio,
             callback                    .map_or(null(), |c| c),
             send_list       .as_ref()   .map_or(null(), |c| c),
-            effect_chain    .as_ref()   .map_or(null(), |c| c),
+            raw_effect_chain.as_ref()   .map_or(null(), |c| c),
         )};
         let voice = unsafe { xaudio2::SourceVoiceUntyped::from_raw_opt(self, voice) };
         hr.succeeded()?;
         let voice = voice.ok_or(E::NOINTERFACE)?;
+        set_voice_effects(voice.as_raw() as usize, effect_chain);
         Ok(voice)
     }
 
@@ -138,7 +181,7 @@ impl XAudio2 {
             pSends:     sl.as_ptr() as *mut _,
         })}).transpose()?;
 
-        let effect_chain = effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
+        let raw_effect_chain = effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
             EffectCount:        u32::try_from(ec.len()).map_err(|_| E::INVALIDARG)?,
             pEffectDescriptors: ec.as_ptr() as *mut _,
         })}).transpose()?;
@@ -150,11 +193,12 @@ impl XAudio2 {
             flags,
             processing_stage,
             send_list       .as_ref().map_or(null(), |c| c),
-            effect_chain    .as_ref().map_or(null(), |c| c),
+            raw_effect_chain.as_ref().map_or(null(), |c| c),
         )};
         let voice = unsafe { xaudio2::SubmixVoice::from_raw_opt(self, voice) };
         hr.succeeded()?;
         let voice = voice.ok_or(E::NOINTERFACE)?;
+        set_voice_effects(voice.as_raw() as usize, effect_chain);
         Ok(voice)
     }
 
@@ -180,7 +224,7 @@ impl XAudio2 {
     ) -> Result<xaudio2::MasteringVoice, HResultError> {
         let mut voice = null_mut();
 
-        let effect_chain = effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
+        let raw_effect_chain = effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
             EffectCount:        u32::try_from(ec.len()).map_err(|_| E::INVALIDARG)?,
             pEffectDescriptors: ec.as_ptr() as *mut _,
         })}).transpose()?;
@@ -191,12 +235,13 @@ impl XAudio2 {
             input_sample_rate,
             flags,
             device_id       .try_into().map_err(|_| E::INVALIDARG)?.as_opt_cstr(),
-            effect_chain    .as_ref().map_or(null(), |c| c),
+            raw_effect_chain.as_ref().map_or(null(), |c| c),
             stream_category,
         )};
         let voice = unsafe { xaudio2::MasteringVoice::from_raw_opt(self, voice) };
         hr.succeeded()?;
         let voice = voice.ok_or(E::NOINTERFACE)?;
+        set_voice_effects(voice.as_raw() as usize, effect_chain);
         Ok(voice)
     }
 
@@ -28,12 +28,16 @@ impl xaudio2::Voice<'_> {
 
     /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-seteffectchain)\]
     /// Replaces this voice's current effect chain with a new one.
+    ///
+    /// [EffectHandle](xaudio2::EffectHandle)s from the old chain will no longer be accepted by this voice.
     pub fn set_effect_chain(&self, effect_chain: Option<&[xaudio2::EffectDescriptor]>) -> Result<HResultSuccess, HResultError> {
-        let effect_chain = effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
+        let raw_effect_chain = effect_chain.map(|ec| -> Result<XAUDIO2_EFFECT_CHAIN, HResultError> { Ok(XAUDIO2_EFFECT_CHAIN {
             EffectCount:        u32::try_from(ec.len()).map_err(|_| E::INVALIDARG)?,
             pEffectDescriptors: ec.as_ptr() as *mut _,
         })}).transpose()?;
-        unsafe { self.as_ref().SetEffectChain(effect_chain.as_ref().map_or(null(), |r| r)) }.succeeded()
+        let hr = unsafe { self.as_ref().SetEffectChain(raw_effect_chain.as_ref().map_or(null(), |r| r)) }.succeeded()?;
+        super::set_voice_effects(self.as_ref() as *const IXAudio2Voice as usize, effect_chain);
+        Ok(hr)
     }
 
     /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-enableeffect)\]
@@ -61,7 +65,9 @@ impl xaudio2::Voice<'_> {
     ///
     /// Unlike IXAPOParameters::SetParameters, this method may be called from any thread.
     /// XAudio2 implements appropriate synchronization to copy the parameters to the realtime audio processing thread.
-    pub fn set_effect_parameters_raw<P: bytemuck::Pod>(&self, effect_index: u32, parameters: &P, operation_set: u32) -> Result<HResultSuccess, HResultError> { // TODO: constraint P to "EffectParameters" trait?
+    ///
+    /// Not type checked: prefer [set_effect_parameters](Self::set_effect_parameters).
+    pub fn set_effect_parameters_raw<P: bytemuck::Pod>(&self, effect_index: u32, parameters: &P, operation_set: u32) -> Result<HResultSuccess, HResultError> {
         let parameters = bytemuck::bytes_of(parameters);
         let parameter_bytes = u32::try_from(core::mem::size_of_val(parameters)).map_err(|_| E::INVALIDARG)?;
         unsafe { self.as_ref().SetEffectParameters(effect_index, parameters.as_ptr(), parameter_bytes, operation_set) }.succeeded()
@@ -69,7 +75,9 @@ impl xaudio2::Voice<'_> {
 
     /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-geteffectparameters)\]
     /// Obtains the current effect-specific parameters.
-    pub fn get_effect_parameters_raw<P: bytemuck::Pod + bytemuck::Zeroable>(&self, effect_index: u32) -> Result<P, HResultError> { // TODO: constraint P to "EffectParameters" trait?
+    ///
+    /// Not type checked: prefer [get_effect_parameters](Self::get_effect_parameters).
+    pub fn get_effect_parameters_raw<P: bytemuck::Pod + bytemuck::Zeroable>(&self, effect_index: u32) -> Result<P, HResultError> {
         let mut parameters = P::zeroed();
         let bytes = bytemuck::bytes_of_mut(&mut parameters);
         let n_bytes = u32::try_from(core::mem::size_of_val(bytes)).map_err(|_| E::INVALIDARG)?;
@@ -77,6 +85,32 @@ impl xaudio2::Voice<'_> {
         Ok(parameters)
     }
 
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-seteffectparameters)\]
+    /// Sets the parameters of the effect identified by `effect` (from this voice's [EffectChain](xaudio2::EffectChain).)
+    ///
+    /// May be called from any thread.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `effect` isn't in this voice's current effect chain
+    /// *   [E::INVALIDARG]     - if `parameters` fails [EffectParameters::validate](xaudio2::EffectParameters::validate)
+    pub fn set_effect_parameters<P: xaudio2::EffectParameters>(&self, effect: &xaudio2::EffectHandle<P>, parameters: &P, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        let index = effect.index_in(self)?;
+        parameters.validate()?;
+        self.set_effect_parameters_raw(index, parameters, operation_set)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-geteffectparameters)\]
+    /// Obtains the current parameters of the effect identified by `effect` (from this voice's [EffectChain](xaudio2::EffectChain).)
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `effect` isn't in this voice's current effect chain
+    /// *   [E::INVALIDARG]     - if the effect reported parameters that fail [EffectParameters::validate](xaudio2::EffectParameters::validate)
+    pub fn get_effect_parameters<P: xaudio2::EffectParameters>(&self, effect: &xaudio2::EffectHandle<P>) -> Result<P, HResultError> {
+        let parameters : P = self.get_effect_parameters_raw(effect.index_in(self)?)?;
+        parameters.validate()?;
+        Ok(parameters)
+    }
+
     /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-setfilterparameters)\]
     /// Sets this voice's filter parameters.
     pub fn set_filter_parameters(&self, parameters: &xaudio2::FilterParameters, operation_set: u32) -> Result<HResultSuccess, HResultError> {
@@ -0,0 +1,233 @@
+use super::*;
+use super::xaudio2::*;
+
+use winapi::um::audiosessiontypes::AUDIO_STREAM_CATEGORY;
+use winresult::*;
+
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::ptr;
+
+
+
+/// A `'static` voice that keeps its [XAudio2] factory alive by holding a clone of it, instead of borrowing it.
+///
+/// Regular voices (e.g. <code>[MasteringVoice]&lt;'xa2&gt;</code>) borrow the [XAudio2] that created them, which makes them awkward
+/// to store in ECS components, asset caches, or anything else that demands `'static`.
+/// `Owned` voices are created by the `*_owned` methods of [XAudio2] (e.g. [XAudio2::create_mastering_voice_owned]),
+/// and otherwise behave like the voice they [Deref] to.
+///
+/// N.B. there's no `DerefMut`: `core::mem::swap`ping the voices of two `Owned`s would leave each holding a voice of the other's engine,
+/// which could then outlive it.  Voice methods only need `&self`, so [Deref] is all that's needed.
+///
+/// ### Example
+/// ```
+/// use thindx_xaudio2::xaudio2_9::*;
+///
+/// mcom::init::mta().unwrap();
+/// let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// let master : xaudio2::OwnedMasteringVoice = xaudio2.create_mastering_voice_owned(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+/// drop(xaudio2); // `master` keeps the engine alive
+/// master.set_volume(0.5, xaudio2::COMMIT_NOW).unwrap();
+/// ```
+pub struct Owned<V> {
+    voice:      V,          // N.B. declared first: must be destroyed before `xaudio2` is released
+    xaudio2:    XAudio2,
+}
+
+/// A `'static` [MasteringVoice] holding a clone of its [XAudio2].
+pub type OwnedMasteringVoice                = Owned<MasteringVoice<'static>>;
+
+/// A `'static` [SubmixVoice] holding a clone of its [XAudio2].
+pub type OwnedSubmixVoice                   = Owned<SubmixVoice<'static>>;
+
+/// A `'static` [SourceVoiceUntyped] holding a clone of its [XAudio2].
+pub type OwnedSourceVoiceUntyped            = Owned<SourceVoiceUntyped<'static>>;
+
+/// A `'static` [SourceVoiceDynamic] holding a clone of its [XAudio2].
+pub type OwnedSourceVoiceDynamic<C>         = Owned<SourceVoiceDynamic<'static, C>>;
+
+/// A `'static` [SourceVoice] holding a clone of its [XAudio2].
+pub type OwnedSourceVoice<S, C>             = Owned<SourceVoice<'static, S, C>>;
+
+/// A `'static` [StreamingSourceVoice] holding a clone of its [XAudio2].
+pub type OwnedStreamingSourceVoice<D>       = Owned<StreamingSourceVoice<'static, D>>;
+
+/// A `'static` [RenderSourceVoice] holding a clone of its [XAudio2].
+pub type OwnedRenderSourceVoice<S, R>       = Owned<RenderSourceVoice<'static, S, R>>;
+
+impl<V> Owned<V> {
+    /// Pair a `'static` voice with the [XAudio2] that created it.
+    ///
+    /// ### Safety
+    /// *   `voice` must have been created by `xaudio2` (or another clone of the same [IXAudio2].)
+    pub unsafe fn from_raw_parts(xaudio2: XAudio2, voice: V) -> Self { Self { voice, xaudio2 } }
+
+    /// The [XAudio2] this voice was created by (and keeps alive.)
+    pub fn xaudio2(&self) -> &XAudio2 { &self.xaudio2 }
+}
+
+impl<V> Deref for Owned<V> { fn deref(&self) -> &Self::Target { &self.voice } type Target = V; }
+
+
+
+impl XAudio2 {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// [Self::create_source_voice_typed_callback], returning an [Owned] voice.
+    pub fn create_source_voice_typed_callback_owned<S: Send + Sync + Sized + 'static, VC: VoiceCallback>(
+        &self,
+        format:                 &TypedSourceFormat<S>,
+        flags:                  u32,
+        max_frequency_ratio:    f32,
+        callback:               &'static VoiceCallbackWrapper<VC>,
+        send_list:              Option<&[SendDescriptor]>,
+        effect_chain:           Option<&[EffectDescriptor]>,
+    ) -> Result<OwnedSourceVoice<S, VC::BufferContext>, HResultError> {
+        let voice = self.create_source_voice_typed_callback(format, flags, max_frequency_ratio, callback, send_list, effect_chain)?;
+        Ok(self.own(voice))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// [Self::create_source_voice_dynamic], returning an [Owned] voice.
+    pub fn create_source_voice_dynamic_owned<VC: VoiceCallback>(
+        &self,
+        format:                 &SourceFormat,
+        flags:                  u32,
+        max_frequency_ratio:    f32,
+        callback:               &'static VoiceCallbackWrapper<VC>,
+        send_list:              Option<&[SendDescriptor]>,
+        effect_chain:           Option<&[EffectDescriptor]>,
+    ) -> Result<OwnedSourceVoiceDynamic<VC::BufferContext>, HResultError> {
+        let voice = self.create_source_voice_dynamic(format, flags, max_frequency_ratio, callback, send_list, effect_chain)?;
+        Ok(self.own(voice))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// [Self::create_streaming_source_voice], returning an [Owned] voice.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG] if `max_queued_buffers` is `0` or exceeds [MAX_QUEUED_BUFFERS].
+    pub fn create_streaming_source_voice_owned<D: StreamDecoder>(
+        &self,
+        format:                 &SourceFormat,
+        flags:                  u32,
+        max_frequency_ratio:    f32,
+        max_queued_buffers:     usize,
+        decoder:                D,
+        send_list:              Option<&[SendDescriptor]>,
+        effect_chain:           Option<&[EffectDescriptor]>,
+    ) -> Result<OwnedStreamingSourceVoice<D>, HResultError> {
+        let voice = self.create_streaming_source_voice(format, flags, max_frequency_ratio, max_queued_buffers, decoder, send_list, effect_chain)?;
+        Ok(self.own(voice))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsourcevoice)\]
+    /// [Self::create_render_source_voice], returning an [Owned] voice.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG] if `S` isn't exactly one frame of `format` (`nBlockAlign` bytes.)
+    pub fn create_render_source_voice_owned<S: bytemuck::Pod + Send + Sync, R: FnMut(&mut [S]) + Send + 'static>(
+        &self,
+        format:                 &TypedSourceFormat<S>,
+        flags:                  u32,
+        max_frequency_ratio:    f32,
+        renderer:               R,
+        send_list:              Option<&[SendDescriptor]>,
+        effect_chain:           Option<&[EffectDescriptor]>,
+    ) -> Result<OwnedRenderSourceVoice<S, R>, HResultError> {
+        let voice = self.create_render_source_voice(format, flags, max_frequency_ratio, renderer, send_list, effect_chain)?;
+        Ok(self.own(voice))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createsubmixvoice)\]
+    /// [Self::create_submix_voice], returning an [Owned] voice.
+    pub fn create_submix_voice_owned(
+        &self,
+        input_channels:     u32,
+        input_sample_rate:  u32,
+        flags:              u32,
+        processing_stage:   u32,
+        send_list:          Option<&[SendDescriptor]>,
+        effect_chain:       Option<&[EffectDescriptor]>,
+    ) -> Result<OwnedSubmixVoice, HResultError> {
+        let voice = self.create_submix_voice(input_channels, input_sample_rate, flags, processing_stage, send_list, effect_chain)?;
+        Ok(self.own(voice))
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2-createmasteringvoice)\]
+    /// [Self::create_mastering_voice], returning an [Owned] voice.
+    pub fn create_mastering_voice_owned(
+        &self,
+        input_channels:     u32,
+        input_sample_rate:  u32,
+        flags:              u32,
+        device_id:          impl abistr::TryIntoAsOptCStr<u16>,
+        effect_chain:       Option<&[EffectDescriptor]>,
+        stream_category:    AUDIO_STREAM_CATEGORY,
+    ) -> Result<OwnedMasteringVoice, HResultError> {
+        let voice = self.create_mastering_voice(input_channels, input_sample_rate, flags, device_id, effect_chain, stream_category)?;
+        Ok(self.own(voice))
+    }
+
+    fn own<V: IntoStatic>(&self, voice: V) -> Owned<V::Static> {
+        // SAFETY: ✔️ `voice` was just created by `self`, and the clone of `self` outlives it (see field order of `Owned`)
+        Owned { voice: unsafe { voice.into_static() }, xaudio2: self.clone() }
+    }
+}
+
+
+
+/// A voice type that can have its `'xa2` lifetime extended to `'static`.
+///
+/// ### Safety
+/// *   `Self::Static` must be `Self` with only `'xa2` replaced by `'static` (and thus have an identical layout.)
+pub(crate) unsafe trait IntoStatic: Sized {
+    type Static;
+
+    /// ### Safety
+    /// *   The [IXAudio2] that created `self` must outlive the returned voice.
+    unsafe fn into_static(self) -> Self::Static {
+        let this = ManuallyDrop::new(self);
+        unsafe { ptr::read((&*this as *const Self).cast::<Self::Static>()) }
+    }
+}
+
+unsafe impl<'xa2> IntoStatic for MasteringVoice<'xa2>       { type Static = MasteringVoice<'static>; }
+unsafe impl<'xa2> IntoStatic for SubmixVoice<'xa2>          { type Static = SubmixVoice<'static>; }
+unsafe impl<'xa2> IntoStatic for SourceVoiceUntyped<'xa2>   { type Static = SourceVoiceUntyped<'static>; }
+unsafe impl<'xa2, C: Send + Sync + Sized + 'static> IntoStatic for SourceVoiceDynamic<'xa2, C> { type Static = SourceVoiceDynamic<'static, C>; }
+unsafe impl<'xa2, S: Send + Sync + Sized + 'static, C: Send + Sync + Sized + 'static> IntoStatic for SourceVoice<'xa2, S, C> { type Static = SourceVoice<'static, S, C>; }
+unsafe impl<'xa2, D: StreamDecoder> IntoStatic for StreamingSourceVoice<'xa2, D> { type Static = StreamingSourceVoice<'static, D>; }
+unsafe impl<'xa2, S: bytemuck::Pod + Send + Sync, R: FnMut(&mut [S]) + Send + 'static> IntoStatic for RenderSourceVoice<'xa2, S, R> { type Static = RenderSourceVoice<'static, S, R>; }
+
+
+
+#[test] fn owned_voices() {
+    use crate::mock;
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let master  = xaudio2.create_mastering_voice_owned(2, 1000, 0, (), None, DEFAULT_AUDIO_CATEGORY).expect("create_mastering_voice_owned");
+    let submix  = xaudio2.create_submix_voice_owned(2, 1000, 0, 0, None, None).expect("create_submix_voice_owned");
+    let sends   = [SendDescriptor::new(0, submix.as_ref())];
+    let tone    = xaudio2.create_render_source_voice_owned(&TypedSourceFormat::<[f32; 2]>::pcm(1000), 0, 1.0, |frames: &mut [[f32; 2]]| frames.fill([0.5; 2]), Some(&sends[..]), None).expect("create_render_source_voice_owned");
+    drop(xaudio2);
+
+    fn assert_static<T: 'static>(_: &T) {}
+    assert_static(&master);
+    assert_static(&submix);
+    assert_static(&tone);
+
+    assert_eq!(submix.get_voice_details().InputChannels, 2);
+    tone.start(COMMIT_NOW).expect("start");
+    master.xaudio2().commit_changes(COMMIT_ALL).expect("commit_changes");
+    engine.step();
+    let id = engine.voice_id(tone.as_raw()).unwrap();
+    assert_eq!(engine.voice(id).unwrap().samples_played, 10, "the engine keeps running after the original XAudio2 is dropped");
+    assert!(engine.calls().iter().any(|call| call.method == "CommitChanges"));
+
+    drop(tone); // N.B. destroy voices before those they send to
+    drop(submix);
+    drop(master);
+    assert!(engine.warnings().is_empty(), "{:?}", engine.warnings());
+}
@@ -0,0 +1,203 @@
+use super::*;
+use super::xaudio2::*;
+use super::xaudio2::sys::*;
+
+use winresult::*;
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use core::mem::{size_of, transmute};
+use core::ops::Deref;
+use core::ptr::{NonNull, null_mut};
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use std::sync::{Mutex, MutexGuard};
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voicecallback-onvoiceprocessingpassstart)\]
+/// A source voice whose audio is generated on demand ("pull model".)
+///
+/// Whenever XAudio2 reports it needs `bytes_required` more bytes to avoid starving, the renderer is called (on an XAudio2 thread)
+/// to fill exactly that many frames, which are then submitted.  Sample buffers are recycled once XAudio2 is done with them,
+/// so steady state rendering doesn't reallocate audio data - although each submit still makes two small bookkeeping allocations
+/// (boxing the pooled buffer, and its `pContext`) on the XAudio2 thread.
+///
+/// Since the renderer blocks XAudio2's processing, it should be quick.  To parameterize it, either share state
+/// with it (e.g. atomics), or reconfigure it via [with_renderer](Self::with_renderer).
+/// Other [Voice] methods (volume, filters, output matrices, ...) are available via [Deref].
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// # let _master = xaudio2.create_mastering_voice(xaudio2::DEFAULT_CHANNELS, xaudio2::DEFAULT_SAMPLERATE, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+/// let format = xaudio2::TypedSourceFormat::<[f32; 2]>::pcm(44100);
+/// let mut phase = 0.0_f32;
+/// let tone = xaudio2.create_render_source_voice(&format, 0, xaudio2::DEFAULT_FREQ_RATIO, move |frames: &mut [[f32; 2]]| {
+///     for frame in frames {
+///         phase = (phase + 440.0 / 44100.0).fract();
+///         *frame = [0.2 * f32::sin(phase * core::f32::consts::TAU); 2];
+///     }
+/// }, None, None).unwrap();
+/// tone.start(xaudio2::COMMIT_NOW).unwrap();
+/// ```
+pub struct RenderSourceVoice<'xa2, S: bytemuck::Pod + Send + Sync, R: FnMut(&mut [S]) + Send + 'static> {
+    voice:      SourceVoiceDynamic<'xa2, ()>,                   // N.B. declared first: must be destroyed before `_callback` is freed
+    _callback:  Box<VoiceCallbackWrapper<RenderCallback<S, R>>>,
+    shared:     Arc<Shared<S, R>>,
+}
+
+struct Shared<S, R> {
+    voice:      AtomicPtr<IXAudio2SourceVoice>, // null until created, and again once dropping
+    renderer:   Mutex<R>,
+    pool:       Arc<Mutex<Vec<Vec<S>>>>,
+}
+
+struct RenderCallback<S, R> { shared: Arc<Shared<S, R>> }
+
+/// Audio data that returns itself to the pool once XAudio2 is done with it.
+struct PooledBuffer<S: bytemuck::Pod> {
+    frames: Vec<S>,
+    pool:   Arc<Mutex<Vec<Vec<S>>>>,
+}
+
+impl<'xa2, S: bytemuck::Pod + Send + Sync, R: FnMut(&mut [S]) + Send + 'static> RenderSourceVoice<'xa2, S, R> {
+    pub(crate) fn new(
+        xaudio2:            &'xa2 XAudio2,
+        format:             &TypedSourceFormat<S>,
+        flags:              u32,
+        max_frequency_ratio:f32,
+        renderer:           R,
+        send_list:          Option<&[SendDescriptor]>,
+        effect_chain:       Option<&[EffectDescriptor]>,
+    ) -> Result<Self, HResultError> {
+        if size_of::<S>() == 0 || size_of::<S>() != usize::from(format.wave_format().nBlockAlign) { return Err(E::INVALIDARG) }
+        let shared = Arc::new(Shared {
+            voice:      AtomicPtr::new(null_mut()),
+            renderer:   Mutex::new(renderer),
+            pool:       Arc::new(Mutex::new(Vec::new())),
+        });
+        let callback = Box::new(VoiceCallbackWrapper::new(RenderCallback { shared: Arc::clone(&shared) }));
+        // SAFETY: `callback` is boxed (so its address is stable) and outlives `voice` (see field order / Drop.)
+        let callback_ref : &'xa2 IXAudio2VoiceCallback = unsafe { &*(&**callback as *const IXAudio2VoiceCallback) };
+        let voice = unsafe { xaudio2.create_source_voice_unchecked(format, flags, max_frequency_ratio, Some(callback_ref), send_list, effect_chain) }?;
+        let voice = unsafe { SourceVoiceDynamic::from_raw(xaudio2, voice.into_raw()) };
+        shared.voice.store(voice.as_raw() as *mut _, Ordering::Release);
+        Ok(Self { voice, _callback: callback, shared })
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-destroyvoice)\]
+    /// Destroys this voice, stopping it if necessary and removing it from the XAudio2 graph.
+    ///
+    /// (Dropping the voice also implicitly stops/removes it.)
+    pub fn destroy_voice(self) {}
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-start)\]
+    /// Makes this voice start consuming and processing audio, rendering it on demand.
+    pub fn start(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> { self.voice.start(0, operation_set) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-stop)\]
+    /// Makes this voice stop consuming audio.  Already rendered audio remains queued.
+    pub fn stop(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> { self.voice.stop(flags, operation_set) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-flushsourcebuffers)\]
+    /// Removes all already rendered audio from this voice's queue (e.g. to make a parameter change take effect sooner.)
+    pub fn flush_source_buffers(&self) -> Result<HResultSuccess, HResultError> { self.voice.flush_source_buffers() }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getstate)\]
+    /// Returns the number of rendered buffers currently queued on this voice, samples played, etc.
+    pub fn get_state(&self, flags: u32) -> VoiceState { self.voice.get_state(flags) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setfrequencyratio)\]
+    /// Sets this voice's frequency adjustment, i.e. its pitch.
+    pub fn set_frequency_ratio(&self, ratio: f32, operation_set: u32) -> Result<HResultSuccess, HResultError> { self.voice.set_frequency_ratio(ratio, operation_set) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getfrequencyratio)\]
+    /// Returns this voice's current frequency adjustment ratio.
+    pub fn get_frequency_ratio(&self) -> f32 { self.voice.get_frequency_ratio() }
+
+    /// Access the renderer (e.g. to change its parameters) between rendering passes.
+    ///
+    /// This blocks XAudio2's processing (if it's waiting to render) until `f` returns, so keep `f` quick.
+    pub fn with_renderer<T>(&self, f: impl FnOnce(&mut R) -> T) -> T { f(&mut lock(&self.shared.renderer)) }
+}
+
+impl<'xa2, S: bytemuck::Pod + Send + Sync, R: FnMut(&mut [S]) + Send + 'static> Deref for RenderSourceVoice<'xa2, S, R> { fn deref(&self) -> &Self::Target { &self.voice } type Target = Voice<'xa2>; }
+
+impl<'xa2, S: bytemuck::Pod + Send + Sync, R: FnMut(&mut [S]) + Send + 'static> Drop for RenderSourceVoice<'xa2, S, R> {
+    fn drop(&mut self) {
+        // Hold the renderer lock so any in-progress render/submit finishes before the voice is destroyed, and no more start.
+        let _renderer = lock(&self.shared.renderer);
+        self.shared.voice.store(null_mut(), Ordering::Release);
+    }
+}
+
+impl<S: bytemuck::Pod + Send + Sync, R: FnMut(&mut [S]) + Send + 'static> Shared<S, R> {
+    /// Render and submit `bytes_required` bytes (rounded up to whole frames.)
+    fn render(&self, bytes_required: u32) -> Result<(), HResultError> {
+        if bytes_required == 0 { return Ok(()) }
+        let mut renderer = lock(&self.renderer);
+        let voice = match NonNull::new(self.voice.load(Ordering::Acquire)) { Some(v) => v, None => return Ok(()) };
+        // SAFETY: `SourceVoiceDynamic` is a `#[repr(transparent)]` wrapper around the same `NonNull`, borrowed without taking ownership.
+        // `voice` remains valid while `renderer` is locked: `RenderSourceVoice::drop` nulls it (with `renderer` locked) before destroying the voice.
+        let voice : &SourceVoiceDynamic<()> = unsafe { transmute(&voice) };
+
+        let frames = (bytes_required as usize + size_of::<S>() - 1) / size_of::<S>();
+        let mut buffer = PooledBuffer { frames: lock(&self.pool).pop().unwrap_or_default(), pool: Arc::clone(&self.pool) };
+        buffer.frames.clear();
+        buffer.frames.resize(frames, S::zeroed());
+        (*renderer)(&mut buffer.frames[..]);
+        unsafe { voice.submit_source_buffer_blob_unchecked(0, Box::new(buffer), .., SampleRange::EMPTY, NO_LOOP_REGION, ()) }?;
+        Ok(())
+    }
+}
+
+impl<S: bytemuck::Pod> AsRef<[u8]> for PooledBuffer<S> { fn as_ref(&self) -> &[u8] { bytemuck::cast_slice(&self.frames[..]) } }
+
+impl<S: bytemuck::Pod> Drop for PooledBuffer<S> {
+    fn drop(&mut self) {
+        let mut pool = lock(&self.pool);
+        if pool.len() < MAX_QUEUED_BUFFERS as usize { pool.push(core::mem::take(&mut self.frames)) }
+    }
+}
+
+impl<S: bytemuck::Pod + Send + Sync, R: FnMut(&mut [S]) + Send + 'static> VoiceCallback for RenderCallback<S, R> {
+    type BufferContext = ();
+    fn on_voice_processing_pass_start(&self, bytes_required: u32) { let _ = self.shared.render(bytes_required); }
+    fn on_voice_error(&self, _buffer_context: &(), _error: HResult) {}
+}
+
+fn lock<T>(m: &Mutex<T>) -> MutexGuard<T> { m.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) }
+
+
+
+#[test] fn render_source_voice() {
+    use crate::mock;
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let _master = xaudio2.create_mastering_voice(1, 1000, 0, (), None, DEFAULT_AUDIO_CATEGORY).unwrap();
+    let renders = Arc::new(Mutex::new(Vec::new()));
+    let log     = Arc::clone(&renders);
+    let voice   = xaudio2.create_render_source_voice(&TypedSourceFormat::<[i16; 1]>::pcm(1000), 0, 1.0, move |frames: &mut [[i16; 1]]| {
+        lock(&log).push((frames.len(), frames.as_ptr() as usize));
+        frames.fill([1000]);
+    }, None, None).unwrap();
+    let id      = engine.voice_id(voice.as_raw()).unwrap();
+
+    engine.step();
+    assert!(lock(&renders).is_empty(), "not yet started");
+
+    voice.start(COMMIT_NOW).unwrap();
+    for _ in 0 .. 3 { engine.step() }
+    let renders = lock(&renders).clone();
+    assert_eq!(renders.iter().map(|r| r.0).collect::<Vec<_>>(), [10, 10, 10], "exactly bytes_required (10 frames per pass @ 1 kHz)");
+    assert!(renders.iter().all(|r| r.1 == renders[0].1), "the same sample buffer is recycled every pass");
+    let info = engine.voice(id).unwrap();
+    assert_eq!((info.buffers_queued, info.samples_played), (0, 30), "never starved, nothing left over");
+    assert_eq!(lock(&voice.shared.pool).len(), 1, "the buffer returned to the pool");
+    assert!(engine.warnings().is_empty());
+}
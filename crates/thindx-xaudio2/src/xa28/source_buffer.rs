@@ -12,4 +12,5 @@ use core::any::Any;
 pub(crate) struct SourceBuffer<Context: Send + Sync + Sized + 'static> {
     pub(crate) context:     Context,
     pub(crate) _audio_data: Box<dyn Any + Send>, // for keepalive only
+    pub(crate) _decoded_packet_cumulative_bytes: Option<Box<dyn Any + Send>>, // for keepalive only
 }
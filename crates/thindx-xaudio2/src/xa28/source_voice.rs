@@ -44,6 +44,8 @@ use core::ptr::*;
 /// | [`enable_effect`](Voice::enable_effect)                                           | Enables an effect in this voice’s effect chain.
 /// | [`disable_effect`](Voice::disable_effect)                                         | Disables an effect in this voice’s effect chain.
 /// | [`get_effect_state`](Voice::get_effect_state)                                     | Returns the running state of an effect.
+/// | [`set_effect_parameters`](Voice::set_effect_parameters)                           | Sets effect-specific parameters via an [`EffectHandle`].
+/// | [`get_effect_parameters`](Voice::get_effect_parameters)                           | Obtains the current effect-specific parameters via an [`EffectHandle`].
 /// | [`set_effect_parameters_raw`](Voice::set_effect_parameters_raw)                   | Sets effect-specific parameters.  Not type checked.
 /// | [`get_effect_parameters_raw`](Voice::get_effect_parameters_raw)                   | Obtains the current effect-specific parameters.  Not type checked.
 /// | [`set_filter_parameters`](Voice::set_filter_parameters)                           | Sets this voice’s [`FilterParameters`].
@@ -113,9 +115,12 @@ impl<'xa2, Sample: Send + Sync + Sized + 'static, Context: Send + Sync + Sized +
         b.pContext = Box::into_raw(Box::new(SourceBuffer::<Context> {
             context,
             _audio_data: Box::new(audio_data),
+            _decoded_packet_cumulative_bytes: None,
         })).cast();
 
-        unsafe { self.as_ref().SubmitSourceBuffer(&b, null()) }.succeeded()
+        let hr = unsafe { self.as_ref().SubmitSourceBuffer(&b, null()) }.succeeded();
+        if hr.is_err() { drop(unsafe { Box::from_raw(b.pContext.cast::<SourceBuffer<Context>>()) }) } // XAudio2 won't call OnBufferEnd for rejected buffers
+        hr
     }
 
     /// Create a voice wrapper from a raw pointer.
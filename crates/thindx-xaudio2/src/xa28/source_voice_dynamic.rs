@@ -20,6 +20,8 @@ use core::ptr::*;
 /// | ----------------------------------------------------------------------------------| -------------|
 /// | [`destroy_voice`](Self::destroy_voice)                                            | Destroys this voice, stopping it if necessary and removing it from the XAudio2 graph.
 /// | [`submit_source_buffer_blob_unchecked`](Self::submit_source_buffer_blob_unchecked)| Adds a new audio buffer to this voice's input queue.
+/// | [`submit_adpcm_buffer_unchecked`](Self::submit_adpcm_buffer_unchecked)            | Adds a new MS-ADPCM audio buffer to this voice's input queue, checking it's whole blocks.
+/// | [`submit_xwma_buffer_unchecked`](Self::submit_xwma_buffer_unchecked)              | Adds a new xWMA audio buffer (and its decoded packet cumulative bytes table) to this voice's input queue.
 ///
 /// ### Methods (via `SourceVoiceUntyped` after `Deref`)
 /// | Method                                                                            | Description  |
@@ -43,6 +45,8 @@ use core::ptr::*;
 /// | [`enable_effect`](Voice::enable_effect)                                           | Enables an effect in this voice’s effect chain.
 /// | [`disable_effect`](Voice::disable_effect)                                         | Disables an effect in this voice’s effect chain.
 /// | [`get_effect_state`](Voice::get_effect_state)                                     | Returns the running state of an effect.
+/// | [`set_effect_parameters`](Voice::set_effect_parameters)                           | Sets effect-specific parameters via an [`EffectHandle`].
+/// | [`get_effect_parameters`](Voice::get_effect_parameters)                           | Obtains the current effect-specific parameters via an [`EffectHandle`].
 /// | [`set_effect_parameters_raw`](Voice::set_effect_parameters_raw)                   | Sets effect-specific parameters.  Not type checked.
 /// | [`get_effect_parameters_raw`](Voice::get_effect_parameters_raw)                   | Obtains the current effect-specific parameters.  Not type checked.
 /// | [`set_filter_parameters`](Voice::set_filter_parameters)                           | Sets this voice’s [`FilterParameters`].
@@ -83,17 +87,33 @@ impl<'xa2, Context: Send + Sync + Sized + 'static> SourceVoiceDynamic<'xa2, Cont
         loop_count:     impl Into<xaudio2::LoopCount>,
         context:        Context,
     ) -> Result<HResultSuccess, HResultError> {
-        let play_range  = play_range.into();
-        let loop_range  = loop_range.into();
-        let loop_count  = loop_count.into();
-
-        let mut b = XAUDIO2_BUFFER {
+        let b = XAUDIO2_BUFFER {
             Flags:      flags,
             AudioBytes: size_of_val(&audio_data.as_ref().as_ref()[..]).try_into().map_err(|_| E::INVALIDARG)?,
             pAudioData: audio_data.as_ref().as_ref().as_ptr().cast(),
             .. Default::default()
         };
 
+        unsafe { self.submit(b, None, play_range.into(), loop_range.into(), loop_count.into(), SourceBuffer {
+            context,
+            _audio_data: audio_data,
+            _decoded_packet_cumulative_bytes: None,
+        })}
+    }
+
+    /// Fill in `b`'s regions and context from the rest of the arguments, then submit it.
+    ///
+    /// ### Safety
+    /// `b` (and `wma`, if any) must only reference memory kept alive by `buffer`.
+    unsafe fn submit(
+        &self,
+        mut b:          XAUDIO2_BUFFER,
+        wma:            Option<XAUDIO2_BUFFER_WMA>,
+        play_range:     xaudio2::SampleRange,
+        loop_range:     xaudio2::SampleRange,
+        loop_count:     xaudio2::LoopCount,
+        buffer:         SourceBuffer<Context>,
+    ) -> Result<HResultSuccess, HResultError> {
         match play_range.into_raw_xaudio2_begin_length() {
             None                    => return Ok(S::OK),
             Some((begin, length))   => {
@@ -110,12 +130,99 @@ impl<'xa2, Context: Send + Sync + Sized + 'static> SourceVoiceDynamic<'xa2, Cont
             }
         }
 
-        b.pContext = Box::into_raw(Box::new(SourceBuffer::<Context> {
+        b.pContext = Box::into_raw(Box::new(buffer)).cast();
+
+        let wma : *const XAUDIO2_BUFFER_WMA = match wma.as_ref() { Some(wma) => wma, None => null() };
+        let hr = unsafe { self.as_ref().SubmitSourceBuffer(&b, wma) }.succeeded();
+        if hr.is_err() { drop(unsafe { Box::from_raw(b.pContext.cast::<SourceBuffer<Context>>()) }) } // XAudio2 won't call OnBufferEnd for rejected buffers
+        hr
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-submitsourcebuffer)\]
+    /// Adds a new MS-ADPCM audio buffer to this voice's input queue.
+    ///
+    /// Unlike [submit_source_buffer_blob_unchecked](Self::submit_source_buffer_blob_unchecked), this validates the buffer against `format`:
+    /// *   `audio_data` must be a whole number of [block_align](xaudio2::AdpcmSourceFormat::block_align) byte blocks.
+    /// *   `play_range` and `loop_range` are measured in decoded samples, and must begin on a block boundary
+    ///     (and end on one too, unless they end at the end of the buffer.)
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG] if any of the above checks fail.
+    ///
+    /// ### Safety
+    /// It is up to the caller to ensure `format` matches the source voice's format.
+    pub unsafe fn submit_adpcm_buffer_unchecked<AudioData: AsRef<[u8]> + Send + Sized + 'static>(
+        &self,
+        format:         &xaudio2::AdpcmSourceFormat,
+        flags:          u32,
+        audio_data:     Box<AudioData>,
+        play_range:     impl Into<xaudio2::SampleRange>,
+        loop_range:     impl Into<xaudio2::SampleRange>,
+        loop_count:     impl Into<xaudio2::LoopCount>,
+        context:        Context,
+    ) -> Result<HResultSuccess, HResultError> {
+        let play_range  = play_range.into();
+        let loop_range  = loop_range.into();
+        let loop_count  = loop_count.into();
+
+        let samples = format.samples(audio_data.as_ref().as_ref().len())?;
+        if let Some((begin, length)) = play_range.into_raw_xaudio2_begin_length() { format.check_region(samples, begin, length)? }
+        if loop_count.0 != 0 {
+            if let Some((begin, length)) = loop_range.into_raw_xaudio2_begin_length() { format.check_region(samples, begin, length)? }
+        }
+
+        unsafe { self.submit_source_buffer_blob_unchecked(flags, audio_data, play_range, loop_range, loop_count, context) }
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-submitsourcebuffer)\]
+    /// Adds a new xWMA audio buffer to this voice's input queue.
+    ///
+    /// Both `audio_data` and `decoded_packet_cumulative_bytes` (the `.xwm` file's `dpds` table, see [XAUDIO2_BUFFER_WMA]) are kept alive until `on_buffer_end`.
+    /// They're validated against `format` as per [XwmaSourceFormat::packets](xaudio2::XwmaSourceFormat::packets).
+    ///
+    /// When splitting a sound across multiple buffers, each buffer's table must be rebased:
+    /// subtract the previous buffer's last entry from all of the next buffer's entries.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG] if `audio_data` isn't whole packets, or doesn't match `decoded_packet_cumulative_bytes`.
+    ///
+    /// ### Safety
+    /// It is up to the caller to ensure `format` matches the source voice's format.
+    pub unsafe fn submit_xwma_buffer_unchecked<
+        AudioData:  AsRef<[u8]>  + Send + Sized + 'static,
+        Dpds:       AsRef<[u32]> + Send + Sized + 'static,
+    >(
+        &self,
+        format:                             &xaudio2::XwmaSourceFormat,
+        flags:                              u32,
+        audio_data:                         Box<AudioData>,
+        decoded_packet_cumulative_bytes:    Box<Dpds>,
+        play_range:                         impl Into<xaudio2::SampleRange>,
+        loop_range:                         impl Into<xaudio2::SampleRange>,
+        loop_count:                         impl Into<xaudio2::LoopCount>,
+        context:                            Context,
+    ) -> Result<HResultSuccess, HResultError> {
+        let audio   = audio_data.as_ref().as_ref();
+        let dpds    = decoded_packet_cumulative_bytes.as_ref().as_ref();
+        let packets = format.packets(audio.len(), dpds)?;
+
+        let b = XAUDIO2_BUFFER {
+            Flags:      flags,
+            AudioBytes: size_of_val(audio).try_into().map_err(|_| E::INVALIDARG)?,
+            pAudioData: audio.as_ptr().cast(),
+            .. Default::default()
+        };
+
+        let wma = XAUDIO2_BUFFER_WMA {
+            pDecodedPacketCumulativeBytes:  dpds.as_ptr(),
+            PacketCount:                    packets,
+        };
+
+        unsafe { self.submit(b, Some(wma), play_range.into(), loop_range.into(), loop_count.into(), SourceBuffer {
             context,
             _audio_data: audio_data,
-        })).cast();
-
-        unsafe { self.as_ref().SubmitSourceBuffer(&b, null()) }.succeeded()
+            _decoded_packet_cumulative_bytes: Some(decoded_packet_cumulative_bytes),
+        })}
     }
 
     /// Create a voice wrapper from a raw pointer.
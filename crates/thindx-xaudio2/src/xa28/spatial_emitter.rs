@@ -0,0 +1,152 @@
+use super::xaudio2::*;
+#[cfg(doc)] use super::XAudio2;
+use crate::portable::x3daudio::*;
+
+use winresult::*;
+
+use alloc::vec::Vec;
+
+
+
+/// Drives a [SourceVoiceUntyped]'s output matrix, frequency ratio, and filters from an [Emitter], once per frame.
+///
+/// Each [update](Self::update) runs the 3D calculation (via any [Calculator]), then queues every resulting voice change into a single operation set,
+/// so a frame's worth of changes (for any number of emitters) can be applied atomically with one [XAudio2::commit_changes].
+///
+/// ### Voice setup
+/// *   `voice` must send to `output` (typically the mastering voice) and, if used, `reverb` (typically a submix voice with a reverb effect.)
+/// *   Those sends need [SEND_USEFILTER] if `flags` include [CALCULATE_LPF_DIRECT] / [CALCULATE_LPF_REVERB] respectively.
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// # let master = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+/// # let reverb = xaudio2.create_submix_voice(1, 48000, 0, 0, None, None).unwrap();
+/// # fn frame() -> Option<(x3daudio::Listener, x3daudio::Vector)> { None }
+/// let format = xaudio2::TypedSourceFormat::<[i16; 1]>::pcm(48000);
+/// let sends = [xaudio2::SendDescriptor::new(xaudio2::SEND_USEFILTER, master.as_ref()), xaudio2::SendDescriptor::new(xaudio2::SEND_USEFILTER, reverb.as_ref())];
+/// let voice = unsafe { xaudio2.create_source_voice_unchecked(&format, 0, xaudio2::DEFAULT_FREQ_RATIO, None, Some(&sends[..]), None) }.unwrap();
+///
+/// let x3d = x3daudio::X3DAudio::new(master.get_channel_mask().unwrap(), x3daudio::SPEED_OF_SOUND).unwrap();
+/// let mut spatial = xaudio2::SpatialEmitter::new(x3daudio::Emitter::default());
+/// const FRAME_OPERATION_SET : u32 = 1;
+/// while let Some((listener, position)) = frame() {
+///     spatial.emitter.position = position;
+///     spatial.update(&x3d, &listener, &voice, &master, Some(&*reverb), FRAME_OPERATION_SET).unwrap();
+///     // ...update other emitters with the same operation set...
+///     xaudio2.commit_changes(FRAME_OPERATION_SET).unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug)] pub struct SpatialEmitter {
+    /// The emitter to calculate for: update its position, velocity, and orientation between frames.
+    pub emitter:            Emitter,
+
+    /// `CALCULATE_*` flags: which [DspSettings] to calculate, and thus which voice settings to update.
+    ///
+    /// Defaults to [CALCULATE_MATRIX] | [CALCULATE_DOPPLER] | [CALCULATE_LPF_DIRECT] | [CALCULATE_LPF_REVERB] | [CALCULATE_REVERB].
+    pub flags:              u32,
+
+    /// The frequency ratio to use without any doppler shift (defaults to `1.0`.)
+    pub frequency_ratio:    f32,
+
+    settings:               DspSettings,
+    reverb_matrix:          Vec<f32>,
+}
+
+impl SpatialEmitter {
+    /// Drive a voice from `emitter`, with the default `flags` and a `frequency_ratio` of `1.0`.
+    pub fn new(emitter: Emitter) -> Self {
+        Self {
+            settings:           DspSettings::new(emitter.channel_count(), 0),
+            emitter,
+            flags:              CALCULATE_MATRIX | CALCULATE_DOPPLER | CALCULATE_LPF_DIRECT | CALCULATE_LPF_REVERB | CALCULATE_REVERB,
+            frequency_ratio:    1.0,
+            reverb_matrix:      Vec::new(),
+        }
+    }
+
+    /// The [DspSettings] calculated by the most recent [update](Self::update).
+    pub fn settings(&self) -> &DspSettings { &self.settings }
+
+    /// Calculate [DspSettings] for [emitter](Self::emitter) as heard by `listener`,
+    /// and queue the results as changes to `voice` in `operation_set`:
+    ///
+    /// | Flag                      | Change    |
+    /// | --------------------------| ----------|
+    /// | [CALCULATE_MATRIX]        | `voice.set_output_matrix(output, ...)`
+    /// | [CALCULATE_DOPPLER]       | `voice.set_frequency_ratio(frequency_ratio * doppler_factor, ...)`
+    /// | [CALCULATE_LPF_DIRECT]    | `voice.set_output_filter_parameters(output, ...)`
+    /// | [CALCULATE_REVERB]        | `voice.set_output_matrix(reverb, ...)` (`reverb_level` from every source channel to every `reverb` channel)
+    /// | [CALCULATE_LPF_REVERB]    | `voice.set_output_filter_parameters(reverb, ...)`
+    ///
+    /// Nothing takes effect until `xaudio2.commit_changes(operation_set)` (unless `operation_set` is [COMMIT_NOW], which applies each change individually.)
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]             - if `listener` or [emitter](Self::emitter) fail validation
+    /// *   [E::INVALIDARG]             - if `output`'s input channel count doesn't match `calculator`'s [dst_channel_count](Calculator::dst_channel_count)
+    /// *   [E_INVALID_CALL]            - if `voice` doesn't send to `output` / `reverb`, or lacks [SEND_USEFILTER] for a filter change
+    /// *   On error, changes queued before the failure remain queued in `operation_set`.
+    pub fn update(&mut self, calculator: &impl Calculator, listener: &Listener, voice: &SourceVoiceUntyped, output: &Voice, reverb: Option<&Voice>, operation_set: u32) -> Result<(), HResultError> {
+        if output.get_voice_details().InputChannels != calculator.dst_channel_count() { return Err(E::INVALIDARG) }
+        calculator.calculate_into(listener, &self.emitter, self.flags, &mut self.settings)?;
+        let s = &self.settings;
+
+        if self.flags & CALCULATE_MATRIX != 0 {
+            voice.set_output_matrix(output.as_ref(), s.src_channel_count, s.dst_channel_count, &s.matrix_coefficients, operation_set)?;
+        }
+        if self.flags & CALCULATE_DOPPLER != 0 {
+            voice.set_frequency_ratio(self.frequency_ratio * s.doppler_factor, operation_set)?;
+        }
+        if self.flags & CALCULATE_LPF_DIRECT != 0 {
+            voice.set_output_filter_parameters(output.as_ref(), &low_pass(s.lpf_direct_coefficient), operation_set)?;
+        }
+
+        if let Some(reverb) = reverb {
+            if self.flags & CALCULATE_REVERB != 0 {
+                let reverb_channels = reverb.get_voice_details().InputChannels;
+                self.reverb_matrix.clear();
+                self.reverb_matrix.resize(s.src_channel_count as usize * reverb_channels as usize, s.reverb_level);
+                voice.set_output_matrix(reverb.as_ref(), s.src_channel_count, reverb_channels, &self.reverb_matrix, operation_set)?;
+            }
+            if self.flags & CALCULATE_LPF_REVERB != 0 {
+                voice.set_output_filter_parameters(reverb.as_ref(), &low_pass(s.lpf_reverb_coefficient), operation_set)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert an X3DAudio LPF coefficient into filter parameters, as per the XAudio2Sound3D sample.
+fn low_pass(coefficient: f32) -> FilterParameters {
+    FilterParameters { Type: sys::LowPassFilter, Frequency: 2.0 * (PI / 6.0 * coefficient).sin(), OneOverQ: 1.0 }
+}
+
+
+
+#[test] fn spatial_emitter() {
+    use crate::mock;
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let master  = xaudio2.create_mastering_voice(2, 48000, 0, (), None, DEFAULT_AUDIO_CATEGORY).unwrap();
+    let reverb  = xaudio2.create_submix_voice(1, 48000, 0, 0, None, None).unwrap();
+    let sends   = [SendDescriptor::new(SEND_USEFILTER, master.as_ref()), SendDescriptor::new(SEND_USEFILTER, reverb.as_ref())];
+    let voice   = unsafe { xaudio2.create_source_voice_unchecked(&TypedSourceFormat::<[f32; 1]>::pcm(48000), 0, MAX_FREQ_RATIO, None, Some(&sends[..]), None) }.unwrap();
+    let id      = engine.voice_id(voice.as_raw()).unwrap();
+
+    let spatializer = Spatializer::new(crate::portable::SPEAKER_STEREO, SPEED_OF_SOUND).unwrap();
+    let mut spatial = SpatialEmitter::new(Emitter { position: Vector::new(1.0, 0.0, 0.0), velocity: Vector::new(-SPEED_OF_SOUND / 2.0, 0.0, 0.0), .. Default::default() });
+    spatial.update(&spatializer, &Listener::default(), &voice, &master, Some(&*reverb), 3).unwrap();
+    assert_eq!(spatial.settings().doppler_factor, 2.0);
+    assert_eq!(engine.voice(id).unwrap().frequency_ratio, 1.0, "not yet committed");
+
+    xaudio2.commit_changes(3).unwrap();
+    assert_eq!(engine.voice(id).unwrap().frequency_ratio, 2.0);
+    let mut matrix = [0.0; 2];
+    voice.get_output_matrix(master.as_ref(), 1, 2, &mut matrix);
+    assert_eq!(matrix, [0.0, 1.0]);
+
+    assert_eq!(spatial.update(&Spatializer::new(crate::portable::SPEAKER_5POINT1, SPEED_OF_SOUND).unwrap(), &Listener::default(), &voice, &master, None, 3).err(), Some(E::INVALIDARG));
+}
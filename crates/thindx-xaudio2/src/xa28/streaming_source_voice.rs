@@ -0,0 +1,364 @@
+use super::*;
+use super::xaudio2::*;
+use super::xaudio2::sys::*;
+
+use winresult::*;
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use core::mem::transmute;
+use core::ops::Deref;
+use core::ptr::{NonNull, null_mut};
+use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+
+use std::io::{ErrorKind, Read};
+use std::sync::{Mutex, MutexGuard};
+
+
+
+/// A source of audio data for a [StreamingSourceVoice].
+///
+/// [decode](Self::decode) is called by whichever thread needs more audio:
+/// [StreamingSourceVoice::start] primes the queue on the calling thread, and [VoiceCallback::on_buffer_end] refills it on an XAudio2 thread.
+/// Since the latter blocks XAudio2's processing, decoding should be quick - prefer chunks of a few hundred milliseconds or less.
+pub trait StreamDecoder : Send + 'static {
+    /// Append the next chunk of audio data (whole blocks, in the voice's format) to `chunk`.
+    ///
+    /// Returns `false` once the stream is exhausted (`chunk` may still contain the final data.)
+    /// Returning `true` with an empty `chunk` pauses refilling until the next buffer ends or [StreamingSourceVoice::start] is called.
+    fn decode(&mut self, chunk: &mut Vec<u8>) -> bool;
+}
+
+/// A [StreamDecoder] reading fixed size chunks from a [Read]er (e.g. a file of raw PCM data, positioned just past its `.wav` header.)
+///
+/// I/O errors are treated as the end of the stream.
+pub struct ReadDecoder<R: Read + Send + 'static> {
+    reader:         R,
+    block_align:    usize,
+    chunk_bytes:    usize,
+}
+
+impl<R: Read + Send + 'static> ReadDecoder<R> {
+    /// Read `chunk_bytes` at a time (rounded down to whole blocks of `block_align` bytes, but at least one block.)
+    pub fn new(reader: R, block_align: u16, chunk_bytes: usize) -> Self {
+        let block_align = usize::from(block_align).max(1);
+        Self { reader, block_align, chunk_bytes: (chunk_bytes - chunk_bytes % block_align).max(block_align) }
+    }
+
+    /// Reclaim the underlying reader.
+    pub fn into_inner(self) -> R { self.reader }
+}
+
+impl<R: Read + Send + 'static> StreamDecoder for ReadDecoder<R> {
+    fn decode(&mut self, chunk: &mut Vec<u8>) -> bool {
+        let start = chunk.len();
+        chunk.resize(start + self.chunk_bytes, 0);
+        let mut read = 0;
+        let more = loop {
+            match self.reader.read(&mut chunk[start + read ..]) {
+                Ok(0)                                           => break false,
+                Ok(n)                                           => { read += n; if read == self.chunk_bytes { break true } },
+                Err(err) if err.kind() == ErrorKind::Interrupted=> continue,
+                Err(_)                                          => break false,
+            }
+        };
+        chunk.truncate(start + read - read % self.block_align);
+        more
+    }
+}
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/how-to--stream-a-sound-from-disk)\]
+/// A source voice that keeps up to `max_queued_buffers` chunks of a [StreamDecoder]'s audio queued, refilling as buffers end.
+///
+/// The final chunk is submitted with [END_OF_STREAM] (or followed by a [discontinuity](SourceVoiceUntyped::discontinuity)
+/// if the decoder ends without data), after which [is_finished](Self::is_finished) returns `true` once playback completes.
+///
+/// Queue management methods ([start](Self::start), [stop](Self::stop), [flush_source_buffers](Self::flush_source_buffers))
+/// are wrapped to coordinate with refilling: in particular, flushing stops refilling until the next [start](Self::start),
+/// so the `on_buffer_end`s of flushed buffers don't immediately re-queue more audio.
+/// Other [Voice] methods (volume, filters, output matrices, ...) are available via [Deref].
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// # let _master = xaudio2.create_mastering_voice(xaudio2::DEFAULT_CHANNELS, xaudio2::DEFAULT_SAMPLERATE, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+/// let format  = xaudio2::TypedSourceFormat::<[i16; 2]>::pcm(44100);
+/// let file    = std::fs::File::open("music.pcm").unwrap();
+/// let decoder = xaudio2::ReadDecoder::new(file, format.wave_format().nBlockAlign, 44100); // 250ms chunks
+/// let music   = xaudio2.create_streaming_source_voice(&format, 0, xaudio2::DEFAULT_FREQ_RATIO, 3, decoder, None, None).unwrap();
+/// music.start(xaudio2::COMMIT_NOW).unwrap();
+/// while !music.is_finished() { std::thread::sleep(std::time::Duration::from_millis(100)) }
+/// ```
+pub struct StreamingSourceVoice<'xa2, D: StreamDecoder> {
+    voice:      SourceVoiceDynamic<'xa2, ()>,                   // N.B. declared first: must be destroyed before `_callback` is freed
+    _callback:  Box<VoiceCallbackWrapper<StreamCallback<D>>>,
+    shared:     Arc<Shared<D>>,
+}
+
+struct Shared<D> {
+    voice:      AtomicPtr<IXAudio2SourceVoice>, // null until created, and again once dropping
+    state:      Mutex<State<D>>,
+    finished:   AtomicBool,
+}
+
+struct State<D> {
+    decoder:            D,
+    queued:             usize,
+    max_queued_buffers: usize,
+    refill:             bool,
+    exhausted:          bool,
+    pending:            Option<(Arc<Vec<u8>>, bool)>,   // a decoded chunk (+ `more`) that failed to submit, retried before decoding more
+}
+
+struct StreamCallback<D> { shared: Arc<Shared<D>> }
+
+struct Chunk(Arc<Vec<u8>>);
+impl AsRef<[u8]> for Chunk { fn as_ref(&self) -> &[u8] { &self.0[..] } }
+
+impl<'xa2, D: StreamDecoder> StreamingSourceVoice<'xa2, D> {
+    pub(crate) fn new(
+        xaudio2:            &'xa2 XAudio2,
+        format:             &SourceFormat,
+        flags:              u32,
+        max_frequency_ratio:f32,
+        max_queued_buffers: usize,
+        decoder:            D,
+        send_list:          Option<&[SendDescriptor]>,
+        effect_chain:       Option<&[EffectDescriptor]>,
+    ) -> Result<Self, HResultError> {
+        if max_queued_buffers == 0 || max_queued_buffers > MAX_QUEUED_BUFFERS as usize { return Err(E::INVALIDARG) }
+        let shared = Arc::new(Shared {
+            voice:      AtomicPtr::new(null_mut()),
+            state:      Mutex::new(State { decoder, queued: 0, max_queued_buffers, refill: false, exhausted: false, pending: None }),
+            finished:   AtomicBool::new(false),
+        });
+        let callback = Box::new(VoiceCallbackWrapper::new(StreamCallback { shared: Arc::clone(&shared) }));
+        // SAFETY: `callback` is boxed (so its address is stable) and outlives `voice` (see field order / Drop.)
+        let callback_ref : &'xa2 IXAudio2VoiceCallback = unsafe { &*(&**callback as *const IXAudio2VoiceCallback) };
+        let voice = unsafe { xaudio2.create_source_voice_unchecked(format, flags, max_frequency_ratio, Some(callback_ref), send_list, effect_chain) }?;
+        let voice = unsafe { SourceVoiceDynamic::from_raw(xaudio2, voice.into_raw()) };
+        shared.voice.store(voice.as_raw() as *mut _, Ordering::Release);
+        Ok(Self { voice, _callback: callback, shared })
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-destroyvoice)\]
+    /// Destroys this voice, stopping it if necessary and removing it from the XAudio2 graph.
+    ///
+    /// (Dropping the voice also implicitly stops/removes it.)
+    pub fn destroy_voice(self) {}
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-start)\]
+    /// (Re)enables refilling, queues up to `max_queued_buffers` chunks, and starts consuming and processing audio.
+    pub fn start(&self, operation_set: u32) -> Result<HResultSuccess, HResultError> {
+        self.shared.lock().refill = true;
+        self.shared.refill(0)?;
+        self.voice.start(0, operation_set)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-stop)\]
+    /// Stops consuming audio.  Queued chunks are kept, so [start](Self::start) resumes where playback left off.
+    pub fn stop(&self, flags: u32, operation_set: u32) -> Result<HResultSuccess, HResultError> { self.voice.stop(flags, operation_set) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-flushsourcebuffers)\]
+    /// Stops refilling (until the next [start](Self::start)), then removes all pending chunks from this voice's queue.
+    pub fn flush_source_buffers(&self) -> Result<HResultSuccess, HResultError> {
+        self.shared.lock().refill = false;
+        self.voice.flush_source_buffers()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getstate)\]
+    /// Returns the number of chunks currently queued on this voice, samples played, etc.
+    pub fn get_state(&self, flags: u32) -> VoiceState { self.voice.get_state(flags) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-setfrequencyratio)\]
+    /// Sets this voice's frequency adjustment, i.e. its pitch.
+    pub fn set_frequency_ratio(&self, ratio: f32, operation_set: u32) -> Result<HResultSuccess, HResultError> { self.voice.set_frequency_ratio(ratio, operation_set) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-ixaudio2sourcevoice-getfrequencyratio)\]
+    /// Returns this voice's current frequency adjustment ratio.
+    pub fn get_frequency_ratio(&self) -> f32 { self.voice.get_frequency_ratio() }
+
+    /// Returns `true` once the decoder has been exhausted, and the final chunk has finished playing.
+    pub fn is_finished(&self) -> bool { self.shared.finished.load(Ordering::Acquire) }
+}
+
+impl<'xa2, D: StreamDecoder> Deref for StreamingSourceVoice<'xa2, D> { fn deref(&self) -> &Self::Target { &self.voice } type Target = Voice<'xa2>; }
+
+impl<'xa2, D: StreamDecoder> Drop for StreamingSourceVoice<'xa2, D> {
+    fn drop(&mut self) {
+        // Destroying the voice may still end buffers: don't refill them.
+        self.shared.lock().refill = false;
+        self.shared.voice.store(null_mut(), Ordering::Release);
+    }
+}
+
+impl<D: StreamDecoder> Shared<D> {
+    fn lock(&self) -> MutexGuard<State<D>> { self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) }
+
+    /// Account for `ended` buffers, then queue chunks until `max_queued_buffers` are queued.
+    ///
+    /// Decoding and submitting both happen with `state` locked, so chunks are queued in order even when
+    /// [StreamingSourceVoice::start] races an `on_buffer_end`.
+    fn refill(&self, ended: usize) -> Result<(), HResultError> {
+        let mut state = self.lock();
+        let state = &mut *state;
+        state.queued = state.queued.saturating_sub(ended);
+        if state.exhausted && state.queued == 0 { self.finished.store(true, Ordering::Release) }
+        if !state.refill { return Ok(()) }
+
+        let voice = match NonNull::new(self.voice.load(Ordering::Acquire)) { Some(v) => v, None => return Ok(()) };
+        // SAFETY: `SourceVoiceDynamic` is a `#[repr(transparent)]` wrapper around the same `NonNull`, borrowed without taking ownership.
+        // `voice` remains valid while `state.refill` is set: `StreamingSourceVoice::drop` clears it (with `state` locked) before destroying the voice.
+        let voice : &SourceVoiceDynamic<()> = unsafe { transmute(&voice) };
+
+        while !state.exhausted && state.queued < state.max_queued_buffers {
+            let (chunk, more) = match state.pending.take() {
+                Some(pending) => pending,
+                None => {
+                    let mut chunk = Vec::new();
+                    let more = state.decoder.decode(&mut chunk);
+                    (Arc::new(chunk), more)
+                },
+            };
+            if chunk.is_empty() {
+                state.exhausted = !more;
+                if !more && state.queued > 0 { voice.discontinuity()?; } // flags the last queued chunk as the end of the stream
+                if !more && state.queued == 0 { self.finished.store(true, Ordering::Release) }
+                break;
+            }
+            let flags = if more { 0 } else { END_OF_STREAM };
+            let result = unsafe { voice.submit_source_buffer_blob_unchecked(flags, Box::new(Chunk(Arc::clone(&chunk))), .., SampleRange::EMPTY, NO_LOOP_REGION, ()) };
+            if let Err(err) = result {
+                state.pending = Some((chunk, more)); // keep the chunk (and don't treat the stream as exhausted) until it's submitted
+                state.refill = false; // don't retry until the next start
+                return Err(err);
+            }
+            state.exhausted = !more;
+            state.queued += 1;
+        }
+        Ok(())
+    }
+}
+
+impl<D: StreamDecoder> VoiceCallback for StreamCallback<D> {
+    type BufferContext = ();
+    fn on_buffer_end(&self, _buffer_context: ()) { let _ = self.shared.refill(1); }
+    fn on_voice_error(&self, _buffer_context: &(), _error: HResult) {}
+}
+
+
+
+#[cfg(test)] fn submits(engine: &crate::mock::Engine) -> usize { engine.calls().iter().filter(|c| c.method == "SubmitSourceBuffer").count() }
+
+#[test] fn streaming_refill() {
+    use crate::mock;
+    use std::io::Cursor;
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let _master = xaudio2.create_mastering_voice(1, 1000, 0, (), None, DEFAULT_AUDIO_CATEGORY).unwrap();
+    let decoder = ReadDecoder::new(Cursor::new(alloc::vec![0x80u8; 35]), 1, 10); // 10 frame chunks: 10 + 10 + 10 + 5
+    let voice   = xaudio2.create_streaming_source_voice(&TypedSourceFormat::<[u8; 1]>::pcm(1000), 0, 1.0, 2, decoder, None, None).unwrap();
+    let id      = engine.voice_id(voice.as_raw()).unwrap();
+    assert_eq!(engine.voice(id).unwrap().buffers_queued, 0, "nothing is decoded until start");
+
+    voice.start(COMMIT_NOW).unwrap();
+    assert_eq!((submits(&engine), engine.voice(id).unwrap().buffers_queued), (2, 2));
+
+    engine.step(); // 10 frames per pass @ 1 kHz: chunk 1 ends, chunk 3 is queued
+    let info = engine.voice(id).unwrap();
+    assert_eq!((submits(&engine), info.buffers_queued, info.end_of_stream), (3, 2, false));
+
+    engine.step(); // chunk 2 ends, the final (short) chunk 4 is queued
+    let info = engine.voice(id).unwrap();
+    assert_eq!((submits(&engine), info.buffers_queued, info.end_of_stream), (4, 2, true));
+
+    engine.step();
+    assert!(!voice.is_finished());
+    engine.step();
+    assert!(voice.is_finished());
+    assert_eq!(submits(&engine), 4);
+    assert_eq!(engine.voice(id).unwrap().samples_played, 35);
+    assert!(engine.warnings().is_empty());
+}
+
+#[test] fn streaming_flush_then_start() {
+    use crate::mock;
+    use std::io::Cursor;
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let _master = xaudio2.create_mastering_voice(1, 1000, 0, (), None, DEFAULT_AUDIO_CATEGORY).unwrap();
+    let decoder = ReadDecoder::new(Cursor::new(alloc::vec![0x80u8; 1000]), 1, 10);
+    let voice   = xaudio2.create_streaming_source_voice(&TypedSourceFormat::<[u8; 1]>::pcm(1000), 0, 1.0, 3, decoder, None, None).unwrap();
+    let id      = engine.voice_id(voice.as_raw()).unwrap();
+
+    voice.start(COMMIT_NOW).unwrap();
+    engine.step(); // chunk 1 ends, chunk 4 is queued
+    assert_eq!(submits(&engine), 4);
+
+    voice.flush_source_buffers().unwrap();
+    engine.step(); // flushed chunks end without refilling
+    assert_eq!((submits(&engine), engine.voice(id).unwrap().buffers_queued), (4, 0));
+    assert!(!voice.is_finished());
+
+    voice.start(COMMIT_NOW).unwrap();
+    assert_eq!((submits(&engine), engine.voice(id).unwrap().buffers_queued), (7, 3));
+
+    voice.flush_source_buffers().unwrap();
+    voice.start(COMMIT_NOW).unwrap(); // before the flushed chunks have ended: they still count against `max_queued_buffers`...
+    assert_eq!((submits(&engine), engine.voice(id).unwrap().buffers_queued), (7, 0));
+    engine.step(); // ...until their `on_buffer_end`s refill the queue
+    assert_eq!((submits(&engine), engine.voice(id).unwrap().buffers_queued), (10, 3));
+    assert!(engine.warnings().is_empty());
+}
+
+#[test] fn streaming_submit_failure() {
+    use crate::mock;
+    use std::io::Cursor;
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let _master = xaudio2.create_mastering_voice(1, 1000, 0, (), None, DEFAULT_AUDIO_CATEGORY).unwrap();
+    let decoder = ReadDecoder::new(Cursor::new(alloc::vec![0x80u8; 15]), 1, 10);
+    let voice   = xaudio2.create_streaming_source_voice(&TypedSourceFormat::<[u8; 1]>::pcm(1000), 0, 1.0, 1, decoder, None, None).unwrap();
+    let id      = engine.voice_id(voice.as_raw()).unwrap();
+
+    voice.start(COMMIT_NOW).unwrap();
+    engine.fail_next("SubmitSourceBuffer", E::OUTOFMEMORY);
+    engine.step(); // chunk 1 ends, submitting the final chunk fails (stopping refills)
+    assert_eq!(engine.voice(id).unwrap().buffers_queued, 0);
+    assert!(!voice.is_finished(), "the final chunk hasn't played yet");
+
+    voice.start(COMMIT_NOW).unwrap(); // retries the final chunk
+    let info = engine.voice(id).unwrap();
+    assert_eq!((info.buffers_queued, info.end_of_stream), (1, true));
+    engine.step();
+    assert!(voice.is_finished());
+    assert_eq!(engine.voice(id).unwrap().samples_played, 15);
+}
+
+#[test] fn streaming_drop_during_callbacks() {
+    use crate::mock;
+    use std::io::Cursor;
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let _master = xaudio2.create_mastering_voice(1, 1000, 0, (), None, DEFAULT_AUDIO_CATEGORY).unwrap();
+    let decoder = ReadDecoder::new(Cursor::new(alloc::vec![0x80u8; 1000]), 1, 10);
+    let voice   = xaudio2.create_streaming_source_voice(&TypedSourceFormat::<[u8; 1]>::pcm(1000), 0, 1.0, 2, decoder, None, None).unwrap();
+    voice.start(COMMIT_NOW).unwrap();
+    engine.step();
+
+    let shared = Arc::clone(&voice.shared);
+    drop(voice); // N.B. real XAudio2 may still end queued buffers during DestroyVoice
+    let submitted = submits(&engine);
+    shared.refill(1).unwrap(); // ...which mustn't decode or touch the destroyed voice
+    assert_eq!(submits(&engine), submitted);
+    engine.step();
+}
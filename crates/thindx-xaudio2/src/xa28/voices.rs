@@ -63,7 +63,10 @@ macro_rules! voices {
             #[allow(dead_code)] pub(crate) fn as_ref(&self) -> &$ivoice { unsafe { self.voice.as_ref() } }
         }
 
-        impl<'xa2> Drop for $voice <'xa2> { fn drop(&mut self) { unsafe { (*self.voice.as_ptr()).DestroyVoice() } } }
+        impl<'xa2> Drop for $voice <'xa2> { fn drop(&mut self) {
+            super::forget_voice_effects(self.voice.as_ptr() as usize);
+            unsafe { (*self.voice.as_ptr()).DestroyVoice() }
+        }}
     )*};
 }
 
@@ -79,6 +82,8 @@ voices! {
     /// | [`enable_effect`](Voice::enable_effect)                                           | Enables an effect in this voice’s effect chain.
     /// | [`disable_effect`](Voice::disable_effect)                                         | Disables an effect in this voice’s effect chain.
     /// | [`get_effect_state`](Voice::get_effect_state)                                     | Returns the running state of an effect.
+    /// | [`set_effect_parameters`](Voice::set_effect_parameters)                           | Sets effect-specific parameters via an [`EffectHandle`].
+    /// | [`get_effect_parameters`](Voice::get_effect_parameters)                           | Obtains the current effect-specific parameters via an [`EffectHandle`].
     /// | [`set_effect_parameters_raw`](Voice::set_effect_parameters_raw)                   | Sets effect-specific parameters.  Not type checked.
     /// | [`get_effect_parameters_raw`](Voice::get_effect_parameters_raw)                   | Obtains the current effect-specific parameters.  Not type checked.
     /// | [`set_filter_parameters`](Voice::set_filter_parameters)                           | Sets this voice’s [`FilterParameters`].
@@ -109,6 +114,8 @@ voices! {
     /// | [`enable_effect`](Voice::enable_effect)                                           | Enables an effect in this voice’s effect chain.
     /// | [`disable_effect`](Voice::disable_effect)                                         | Disables an effect in this voice’s effect chain.
     /// | [`get_effect_state`](Voice::get_effect_state)                                     | Returns the running state of an effect.
+    /// | [`set_effect_parameters`](Voice::set_effect_parameters)                           | Sets effect-specific parameters via an [`EffectHandle`].
+    /// | [`get_effect_parameters`](Voice::get_effect_parameters)                           | Obtains the current effect-specific parameters via an [`EffectHandle`].
     /// | [`set_effect_parameters_raw`](Voice::set_effect_parameters_raw)                   | Sets effect-specific parameters.  Not type checked.
     /// | [`get_effect_parameters_raw`](Voice::get_effect_parameters_raw)                   | Obtains the current effect-specific parameters.  Not type checked.
     /// | [`set_filter_parameters`](Voice::set_filter_parameters)                           | Sets this voice’s [`FilterParameters`].
@@ -138,6 +145,8 @@ voices! {
     /// | [`enable_effect`](Voice::enable_effect)                                           | Enables an effect in this voice’s effect chain.
     /// | [`disable_effect`](Voice::disable_effect)                                         | Disables an effect in this voice’s effect chain.
     /// | [`get_effect_state`](Voice::get_effect_state)                                     | Returns the running state of an effect.
+    /// | [`set_effect_parameters`](Voice::set_effect_parameters)                           | Sets effect-specific parameters via an [`EffectHandle`].
+    /// | [`get_effect_parameters`](Voice::get_effect_parameters)                           | Obtains the current effect-specific parameters via an [`EffectHandle`].
     /// | [`set_effect_parameters_raw`](Voice::set_effect_parameters_raw)                   | Sets effect-specific parameters.  Not type checked.
     /// | [`get_effect_parameters_raw`](Voice::get_effect_parameters_raw)                   | Obtains the current effect-specific parameters.  Not type checked.
     /// | [`set_filter_parameters`](Voice::set_filter_parameters)                           | Sets this voice’s [`FilterParameters`].
@@ -176,6 +185,8 @@ voices! {
     /// | [`enable_effect`](Voice::enable_effect)                                           | Enables an effect in this voice’s effect chain.
     /// | [`disable_effect`](Voice::disable_effect)                                         | Disables an effect in this voice’s effect chain.
     /// | [`get_effect_state`](Voice::get_effect_state)                                     | Returns the running state of an effect.
+    /// | [`set_effect_parameters`](Voice::set_effect_parameters)                           | Sets effect-specific parameters via an [`EffectHandle`].
+    /// | [`get_effect_parameters`](Voice::get_effect_parameters)                           | Obtains the current effect-specific parameters via an [`EffectHandle`].
     /// | [`set_effect_parameters_raw`](Voice::set_effect_parameters_raw)                   | Sets effect-specific parameters.  Not type checked.
     /// | [`get_effect_parameters_raw`](Voice::get_effect_parameters_raw)                   | Obtains the current effect-specific parameters.  Not type checked.
     /// | [`set_filter_parameters`](Voice::set_filter_parameters)                           | Sets this voice’s [`FilterParameters`].
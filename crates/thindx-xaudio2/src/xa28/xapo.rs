@@ -0,0 +1,423 @@
+use crate::util::xaudio2_thread_guard;
+use crate::portable::{WaveFormatEx, WaveFormatExtensible, KSDATAFORMAT_SUBTYPE_IEEE_FLOAT, WAVE_FORMAT_EXTENSIBLE, WAVE_FORMAT_IEEE_FLOAT};
+use super::xaudio2::*;
+
+use thindx_xaudio2_sys::FromVtable;
+use thindx_xaudio2_sys::xapo::*;
+
+use abibool::bool32;
+use winapi::Interface;
+use winapi::shared::guiddef::{GUID, IsEqualGUID, REFIID};
+use winapi::shared::minwindef::ULONG;
+use winapi::shared::mmreg::WAVEFORMATEX;
+use winapi::shared::winerror::{E_NOINTERFACE, E_POINTER, HRESULT, S_OK};
+use winapi::um::combaseapi::CoTaskMemAlloc;
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winresult::*;
+
+use alloc::sync::Arc;
+use core::ffi::c_void;
+use core::mem::{ManuallyDrop, size_of};
+use std::sync::{Mutex, MutexGuard};
+
+
+
+/// The format of an [Xapo]'s input or output buffer: always 32-bit float, interleaved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)] pub struct XapoFormat {
+    /// Number of interleaved channels: `XAPO_MIN_CHANNELS ..= XAPO_MAX_CHANNELS` (`1 ..= 64`)
+    pub channels:       u32,
+
+    /// Frames per second: `XAPO_MIN_FRAMERATE ..= XAPO_MAX_FRAMERATE` (`1000 ..= 200000`)
+    pub sample_rate:    u32,
+}
+
+impl XapoFormat {
+    /// Read a 32-bit float `WAVEFORMATEX` / `WAVEFORMATEXTENSIBLE`, or [None] for anything else.
+    ///
+    /// ### Safety
+    /// *   `format` must be null, or point to a `WAVEFORMATEX` followed by `cbSize` bytes.
+    unsafe fn from_raw(format: *const WAVEFORMATEX) -> Option<Self> {
+        if format.is_null() { return None }
+        let ex = WaveFormatEx::from(unsafe { *format });
+        let float = match ex.wFormatTag {
+            WAVE_FORMAT_IEEE_FLOAT  => true,
+            WAVE_FORMAT_EXTENSIBLE  => ex.cbSize >= 22 && { let ext = unsafe { *format.cast::<WaveFormatExtensible>() }; let sub_format = ext.SubFormat; sub_format == KSDATAFORMAT_SUBTYPE_IEEE_FLOAT },
+            _                       => false,
+        };
+        let channels = u32::from(ex.nChannels);
+        let valid = float
+            && ex.wBitsPerSample == 32
+            && (XAPO_MIN_CHANNELS ..= XAPO_MAX_CHANNELS).contains(&channels)
+            && (XAPO_MIN_FRAMERATE ..= XAPO_MAX_FRAMERATE).contains(&{ex.nSamplesPerSec});
+        if valid { Some(Self { channels, sample_rate: ex.nSamplesPerSec }) } else { None }
+    }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/ns-xapo-xapo_registration_properties)\]
+/// XAPO_REGISTRATION_PROPERTIES: describes an [Xapo].
+#[derive(Clone, Copy, Debug)] pub struct XapoRegistrationProperties {
+    /// COM class ID of the effect (purely informational: XAudio2 doesn't create XAPOs by class ID)
+    pub clsid:          GUID,
+
+    /// Friendly name (truncated to 255 UTF-16 code units)
+    pub friendly_name:  &'static str,
+
+    /// Copyright information (truncated to 255 UTF-16 code units)
+    pub copyright_info: &'static str,
+
+    pub major_version:  u32,
+    pub minor_version:  u32,
+
+    /// `XAPO_FLAG_*_MUST_MATCH` flags.
+    ///
+    /// `XAPO_FLAG_FRAMERATE_MUST_MATCH`, `XAPO_FLAG_BITSPERSAMPLE_MUST_MATCH`, and `XAPO_FLAG_BUFFERCOUNT_MUST_MATCH` are always set:
+    /// [Xapo]s process one 32-bit float buffer into another of the same frame rate.
+    /// The `XAPO_FLAG_INPLACE_*` flags are always cleared: [Xapo::process] requires distinct input and output buffers.
+    pub flags:          u32,
+}
+
+impl Default for XapoRegistrationProperties {
+    fn default() -> Self {
+        Self {
+            clsid:          GUID { Data1: 0, Data2: 0, Data3: 0, Data4: [0; 8] },
+            friendly_name:  "",
+            copyright_info: "",
+            major_version:  1,
+            minor_version:  0,
+            flags:          XAPO_FLAG_CHANNELS_MUST_MATCH,
+        }
+    }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapo)\]
+/// [IXAPO] in trait form: a custom audio effect, processing 32-bit float audio.
+///
+/// All methods are called with exclusive access to `self` (the [wrap]ped effect is guarded by a mutex),
+/// and [process](Self::process) is called on XAudio2's realtime audio thread: avoid blocking or allocating there.
+/// For runtime parameters, share an `Arc<`[ParameterChannel]`>` with the effect, and [begin_process](ParameterChannel::begin_process) in [process](Self::process).
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// struct Gain(f32);
+/// impl xaudio2::Xapo for Gain {
+///     fn process(&mut self, input: &mut [f32], output: &mut [f32]) {
+///         for (o, i) in output.iter_mut().zip(input.iter()) { *o = *i * self.0; }
+///     }
+/// }
+///
+/// let mut chain = xaudio2::EffectChain::new();
+/// chain.push(xaudio2::Xapo::wrap(Gain(0.5)).descriptor(true, 2));
+/// let submix = xaudio2.create_submix_voice(2, 48000, 0, 0, None, Some(chain.as_slice())).unwrap();
+/// ```
+///
+/// [wrap]: Self::wrap
+pub trait Xapo : Send + Sized + 'static {
+    /// Convert `self` into an [IXAPO] implementation suitable for an [EffectChain].
+    fn wrap(self) -> TypedEffect<Self> { XapoWrapper::new(self) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-getregistrationproperties)\]
+    /// Describes this effect.
+    fn registration_properties(&self) -> XapoRegistrationProperties { Default::default() }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-isinputformatsupported)\]
+    /// Returns `true` if this effect can process `input` into `output` (which always share a `sample_rate`.)
+    ///
+    /// Defaults to requiring identical formats.  Effects that change channel counts should override this and clear `XAPO_FLAG_CHANNELS_MUST_MATCH`.
+    fn is_format_supported(&self, input: XapoFormat, output: XapoFormat) -> bool { input == output }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-initialize)\]
+    /// Performs any effect-specific initialization, with data passed to [IXAPO::Initialize] (XAudio2 itself never calls this.)
+    fn initialize(&mut self, data: &[u8]) -> Result<(), HResultError> { let _ = data; Ok(()) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-reset)\]
+    /// Resets frame history (e.g. delay lines), without affecting allocations or parameters.
+    fn reset(&mut self) {}
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-lockforprocess)\]
+    /// Called once the formats have been negotiated (via [is_format_supported](Self::is_format_supported)), before any [process](Self::process)ing.
+    /// Allocate buffers here for at most `max_frames` frames per [process](Self::process) call.
+    fn lock_for_process(&mut self, input: XapoFormat, output: XapoFormat, max_frames: u32) -> Result<(), HResultError> { let _ = (input, output, max_frames); Ok(()) }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-unlockforprocess)\]
+    /// Deallocates anything allocated by [lock_for_process](Self::lock_for_process).
+    fn unlock_for_process(&mut self) {}
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapo/nf-xapo-ixapo-process)\]
+    /// Process interleaved `input` frames into the same number of interleaved `output` frames, on XAudio2's realtime audio thread.
+    ///
+    /// *   `input` is scratch space: it may be modified.  Silent input buffers are zeroed before this is called.
+    /// *   While the effect is disabled, input is copied to output instead of calling this, unless their channel counts differ.
+    fn process(&mut self, input: &mut [f32], output: &mut [f32]);
+}
+
+
+
+/// The object behind a wrapped [Xapo]'s [IXAPO] pointer.  Reference counted via [Arc].
+#[repr(C)] struct XapoWrapper<X: Xapo> {
+    interface:  IXAPO, // must be first: `this` pointers are cast back to `XapoWrapper`
+    state:      Mutex<XapoState<X>>,
+}
+
+struct XapoState<X: Xapo> {
+    xapo:   X,
+    locked: Option<(XapoFormat, XapoFormat)>,
+}
+
+// SAFETY: `interface` only references a `'static` vtable, and all mutable state lives behind `state`'s lock.
+unsafe impl<X: Xapo> Send for XapoWrapper<X> {}
+unsafe impl<X: Xapo> Sync for XapoWrapper<X> {}
+
+impl<X: Xapo> XapoWrapper<X> {
+    fn new(xapo: X) -> TypedEffect<X> {
+        let this = Arc::new(Self { interface: unsafe { IXAPO::from_vtable(&Self::VTBL) }, state: Mutex::new(XapoState { xapo, locked: None }) });
+        let unknown = unsafe { mcom::Rc::from_raw(Arc::into_raw(this) as *mut IUnknown) };
+        // SAFETY: `X` has no parameter accessors (the wrapper doesn't implement IXAPOParameters.)
+        unsafe { TypedEffect::from_unknown(unknown) }
+    }
+
+    const VTBL : IXAPOVtbl = IXAPOVtbl {
+        base: IUnknownVtbl {
+            QueryInterface:         Self::query_interface,
+            AddRef:                 Self::add_ref,
+            Release:                Self::release,
+        },
+        GetRegistrationProperties:  Self::get_registration_properties,
+        IsInputFormatSupported:     Self::is_input_format_supported,
+        IsOutputFormatSupported:    Self::is_output_format_supported,
+        Initialize:                 Self::initialize,
+        Reset:                      Self::reset,
+        LockForProcess:             Self::lock_for_process,
+        UnlockForProcess:           Self::unlock_for_process,
+        Process:                    Self::process,
+        CalcInputFrames:            Self::calc_frames,
+        CalcOutputFrames:           Self::calc_frames,
+    };
+
+    unsafe fn from_this<'a, T>(this: *const T) -> &'a Self { unsafe { &*this.cast() } }
+
+    /// Lock the effect.  Poisoning is ignored: panics abort via [xaudio2_thread_guard] anyways.
+    fn lock(&self) -> MutexGuard<XapoState<X>> { self.state.lock().unwrap_or_else(|poison| poison.into_inner()) }
+
+    unsafe extern "system" fn query_interface(this: *mut IUnknown, riid: REFIID, ppv: *mut *mut c_void) -> HRESULT {
+        xaudio2_thread_guard(||{
+            if ppv.is_null() || riid.is_null() { return E_POINTER }
+            let riid = unsafe { &*riid };
+            if IsEqualGUID(riid, &IUnknown::uuidof()) || IsEqualGUID(riid, &IXAPO::uuidof()) {
+                unsafe { Self::add_ref(this) };
+                unsafe { *ppv = this.cast() };
+                S_OK
+            } else {
+                unsafe { *ppv = core::ptr::null_mut() };
+                E_NOINTERFACE
+            }
+        })
+    }
+
+    unsafe extern "system" fn add_ref(this: *mut IUnknown) -> ULONG {
+        let this = this as *const Self;
+        unsafe { Arc::increment_strong_count(this) };
+        let arc = ManuallyDrop::new(unsafe { Arc::from_raw(this) });
+        Arc::strong_count(&arc) as ULONG
+    }
+
+    unsafe extern "system" fn release(this: *mut IUnknown) -> ULONG {
+        xaudio2_thread_guard(||{
+            let arc = unsafe { Arc::from_raw(this as *const Self) };
+            let remaining = Arc::strong_count(&arc) - 1;
+            drop(arc);
+            remaining as ULONG
+        })
+    }
+
+    unsafe extern "system" fn get_registration_properties(this: *const IXAPO, pp_registration_properties: *mut *mut XAPO_REGISTRATION_PROPERTIES) -> HResult {
+        xaudio2_thread_guard(||{
+            if pp_registration_properties.is_null() { return E::POINTER.into() }
+            let props = unsafe { Self::from_this(this) }.lock().xapo.registration_properties();
+
+            let mut friendly_name   = [0; XAPO_REGISTRATION_STRING_LENGTH];
+            let mut copyright_info  = [0; XAPO_REGISTRATION_STRING_LENGTH];
+            for (dst, src) in friendly_name [.. XAPO_REGISTRATION_STRING_LENGTH-1].iter_mut().zip(props.friendly_name .encode_utf16()) { *dst = src }
+            for (dst, src) in copyright_info[.. XAPO_REGISTRATION_STRING_LENGTH-1].iter_mut().zip(props.copyright_info.encode_utf16()) { *dst = src }
+
+            let raw = XAPO_REGISTRATION_PROPERTIES {
+                clsid:                  props.clsid,
+                FriendlyName:           friendly_name,
+                CopyrightInfo:          copyright_info,
+                MajorVersion:           props.major_version,
+                MinorVersion:           props.minor_version,
+                Flags:                  (props.flags | XAPO_FLAG_FRAMERATE_MUST_MATCH | XAPO_FLAG_BITSPERSAMPLE_MUST_MATCH | XAPO_FLAG_BUFFERCOUNT_MUST_MATCH) & !(XAPO_FLAG_INPLACE_REQUIRED | XAPO_FLAG_INPLACE_SUPPORTED),
+                MinInputBufferCount:    1,
+                MaxInputBufferCount:    1,
+                MinOutputBufferCount:   1,
+                MaxOutputBufferCount:   1,
+            };
+            let alloc = unsafe { CoTaskMemAlloc(size_of::<XAPO_REGISTRATION_PROPERTIES>()) }.cast::<XAPO_REGISTRATION_PROPERTIES>(); // XAPOAlloc
+            if alloc.is_null() { return E::OUTOFMEMORY.into() }
+            unsafe { alloc.write_unaligned(raw) };
+            unsafe { *pp_registration_properties = alloc };
+            S::OK.into()
+        })
+    }
+
+    /// Shared implementation of `Is{Input,Output}FormatSupported`: `other` is the fixed format, `requested` the queried one.
+    unsafe fn is_format_supported(this: *const IXAPO, other: *const WAVEFORMATEX, requested: *const WAVEFORMATEX, pp_supported: *mut *mut WAVEFORMATEX, requested_is_input: bool) -> HResult {
+        xaudio2_thread_guard(||{
+            if other.is_null() || requested.is_null() { return E::INVALIDARG.into() }
+            let supported = match unsafe { (XapoFormat::from_raw(other), XapoFormat::from_raw(requested)) } {
+                (Some(other), Some(requested)) if other.sample_rate == requested.sample_rate => {
+                    let xapo = &unsafe { Self::from_this(this) }.lock().xapo;
+                    if requested_is_input { xapo.is_format_supported(requested, other) } else { xapo.is_format_supported(other, requested) }
+                },
+                _ => false,
+            };
+            if supported { return S::OK.into() }
+
+            // Suggest `other`'s format as the nearest supported format: identical formats are supported by default.
+            if !pp_supported.is_null() {
+                let bytes = size_of::<WaveFormatEx>() + usize::from(unsafe { (*other).cbSize });
+                let alloc = unsafe { CoTaskMemAlloc(bytes) }.cast::<WAVEFORMATEX>(); // XAPOAlloc
+                if !alloc.is_null() { unsafe { core::ptr::copy_nonoverlapping(other.cast::<u8>(), alloc.cast::<u8>(), bytes) } }
+                unsafe { *pp_supported = alloc };
+            }
+            XAPO_E_FORMAT_UNSUPPORTED
+        })
+    }
+
+    unsafe extern "system" fn is_input_format_supported(this: *const IXAPO, output_format: *const WAVEFORMATEX, requested_input_format: *const WAVEFORMATEX, pp_supported_input_format: *mut *mut WAVEFORMATEX) -> HResult {
+        unsafe { Self::is_format_supported(this, output_format, requested_input_format, pp_supported_input_format, true) }
+    }
+
+    unsafe extern "system" fn is_output_format_supported(this: *const IXAPO, input_format: *const WAVEFORMATEX, requested_output_format: *const WAVEFORMATEX, pp_supported_output_format: *mut *mut WAVEFORMATEX) -> HResult {
+        unsafe { Self::is_format_supported(this, input_format, requested_output_format, pp_supported_output_format, false) }
+    }
+
+    unsafe extern "system" fn initialize(this: *const IXAPO, data: *const c_void, data_byte_size: u32) -> HResult {
+        xaudio2_thread_guard(||{
+            let data = if data_byte_size == 0 { &[][..] } else if data.is_null() { return E::INVALIDARG.into() } else { unsafe { core::slice::from_raw_parts(data.cast::<u8>(), data_byte_size as usize) } };
+            match unsafe { Self::from_this(this) }.lock().xapo.initialize(data) {
+                Ok(())  => S::OK.into(),
+                Err(e)  => e.into(),
+            }
+        })
+    }
+
+    unsafe extern "system" fn reset(this: *const IXAPO) {
+        xaudio2_thread_guard(||{
+            unsafe { Self::from_this(this) }.lock().xapo.reset()
+        })
+    }
+
+    unsafe extern "system" fn lock_for_process(this: *const IXAPO, input_count: u32, inputs: *const XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS, output_count: u32, outputs: *const XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS) -> HResult {
+        xaudio2_thread_guard(||{
+            if input_count != 1 || output_count != 1 || inputs.is_null() || outputs.is_null() { return E::INVALIDARG.into() }
+            let (input, output) = unsafe { (*inputs, *outputs) };
+            let (input_format, output_format) = match unsafe { (XapoFormat::from_raw(input.pFormat), XapoFormat::from_raw(output.pFormat)) } {
+                (Some(i), Some(o)) if i.sample_rate == o.sample_rate => (i, o),
+                _ => return XAPO_E_FORMAT_UNSUPPORTED,
+            };
+            let max_frames = input.MaxFrameCount;
+            if max_frames != { output.MaxFrameCount } { return E::INVALIDARG.into() }
+
+            let mut state = unsafe { Self::from_this(this) }.lock();
+            if state.locked.is_some() { return E_INVALID_CALL }
+            if !state.xapo.is_format_supported(input_format, output_format) { return XAPO_E_FORMAT_UNSUPPORTED }
+            if let Err(e) = state.xapo.lock_for_process(input_format, output_format, max_frames) { return e.into() }
+            state.locked = Some((input_format, output_format));
+            S::OK.into()
+        })
+    }
+
+    unsafe extern "system" fn unlock_for_process(this: *const IXAPO) {
+        xaudio2_thread_guard(||{
+            let mut state = unsafe { Self::from_this(this) }.lock();
+            if state.locked.take().is_some() { state.xapo.unlock_for_process() }
+        })
+    }
+
+    unsafe extern "system" fn process(this: *const IXAPO, input_count: u32, inputs: *const XAPO_PROCESS_BUFFER_PARAMETERS, output_count: u32, outputs: *mut XAPO_PROCESS_BUFFER_PARAMETERS, is_enabled: bool32) {
+        xaudio2_thread_guard(||{
+            if input_count != 1 || output_count != 1 || inputs.is_null() || outputs.is_null() { return }
+            let mut state = unsafe { Self::from_this(this) }.lock();
+            let (input_format, output_format) = match state.locked { Some(locked) => locked, None => return };
+
+            let input = unsafe { *inputs };
+            let frames = input.ValidFrameCount as usize;
+            let input_buffer  = unsafe { core::slice::from_raw_parts_mut(input.pBuffer.cast::<f32>(), frames * input_format.channels as usize) };
+            let output_buffer = unsafe { core::slice::from_raw_parts_mut((*outputs).pBuffer.cast::<f32>(), frames * output_format.channels as usize) };
+            if { input.BufferFlags } == XAPO_BUFFER_SILENT { input_buffer.fill(0.0) }
+
+            if !bool::from(is_enabled) && input_format.channels == output_format.channels {
+                output_buffer.copy_from_slice(input_buffer);
+            } else {
+                state.xapo.process(input_buffer, output_buffer);
+            }
+
+            unsafe { (*outputs).BufferFlags = XAPO_BUFFER_VALID };
+            unsafe { (*outputs).ValidFrameCount = input.ValidFrameCount };
+        })
+    }
+
+    /// `CalcInputFrames` / `CalcOutputFrames`: [Xapo]s always process one input frame per output frame.
+    unsafe extern "system" fn calc_frames(_this: *const IXAPO, frames: u32) -> u32 { frames }
+}
+
+
+
+#[test] fn xapo() {
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use core::ptr::{null, null_mut};
+
+    struct Gain { gain: f32, dropped: Arc<AtomicBool> }
+    impl Drop for Gain { fn drop(&mut self) { self.dropped.store(true, Ordering::Relaxed) } }
+    impl Xapo for Gain {
+        fn registration_properties(&self) -> XapoRegistrationProperties { XapoRegistrationProperties { friendly_name: "Gain", .. Default::default() } }
+        fn process(&mut self, input: &mut [f32], output: &mut [f32]) { for (o, i) in output.iter_mut().zip(input.iter()) { *o = *i * self.gain } }
+    }
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let effect  = Gain { gain: 0.5, dropped: dropped.clone() }.wrap();
+    let xapo    = effect.as_unknown().try_cast::<IXAPO>().unwrap();
+
+    unsafe {
+        let mut props = null_mut();
+        assert_eq!(xapo.GetRegistrationProperties(&mut props), HResult::from(S::OK));
+        let p = props.read_unaligned();
+        winapi::um::combaseapi::CoTaskMemFree(props.cast());
+        assert_eq!({ p.FriendlyName }[..5], [b'G' as u16, b'a' as u16, b'i' as u16, b'n' as u16, 0]);
+        assert_eq!({ p.Flags } & XAPO_FLAG_INPLACE_SUPPORTED, 0);
+
+        let stereo  = WAVEFORMATEX::from(WaveFormatEx { wFormatTag: WAVE_FORMAT_IEEE_FLOAT, nChannels: 2, nSamplesPerSec: 48000, nAvgBytesPerSec: 48000 * 8, nBlockAlign: 8, wBitsPerSample: 32, cbSize: 0 });
+        let mono    = WAVEFORMATEX::from(WaveFormatEx { nChannels: 1, nAvgBytesPerSec: 48000 * 4, nBlockAlign: 4, .. WaveFormatEx::from(stereo) });
+        assert_eq!(xapo.IsInputFormatSupported(&stereo, &stereo, null_mut()), HResult::from(S::OK));
+        let mut nearest = null_mut();
+        assert_eq!(xapo.IsInputFormatSupported(&stereo, &mono, &mut nearest), XAPO_E_FORMAT_UNSUPPORTED);
+        assert_eq!({ (*nearest).nChannels }, 2);
+        winapi::um::combaseapi::CoTaskMemFree(nearest.cast());
+
+        let lock = XAPO_LOCKFORPROCESS_BUFFER_PARAMETERS { pFormat: &stereo, MaxFrameCount: 2 };
+        assert_eq!(xapo.LockForProcess(1, &lock, 1, &lock), HResult::from(S::OK));
+        assert_eq!(xapo.LockForProcess(1, &lock, 1, &lock), E_INVALID_CALL);
+
+        let mut input   = [1.0, 2.0, 3.0, 4.0];
+        let mut output  = [0.0; 4];
+        let inputs      = XAPO_PROCESS_BUFFER_PARAMETERS { pBuffer: input .as_mut_ptr().cast(), BufferFlags: XAPO_BUFFER_VALID,  ValidFrameCount: 2 };
+        let mut outputs = XAPO_PROCESS_BUFFER_PARAMETERS { pBuffer: output.as_mut_ptr().cast(), BufferFlags: XAPO_BUFFER_SILENT, ValidFrameCount: 0 };
+        xapo.Process(1, &inputs, 1, &mut outputs, true.into());
+        assert_eq!(output, [0.5, 1.0, 1.5, 2.0]);
+        assert!({ outputs.BufferFlags } == XAPO_BUFFER_VALID && { outputs.ValidFrameCount } == 2);
+
+        xapo.Process(1, &inputs, 1, &mut outputs, false.into());
+        assert_eq!(output, [1.0, 2.0, 3.0, 4.0], "disabled: passthrough");
+        xapo.UnlockForProcess();
+
+        assert_eq!(xapo.Initialize(null(), 0), HResult::from(S::OK));
+        assert_eq!(xapo.CalcInputFrames(480), 480);
+    }
+
+    drop(xapo);
+    assert!(!dropped.load(Ordering::Relaxed));
+    drop(effect);
+    assert!(dropped.load(Ordering::Relaxed));
+}
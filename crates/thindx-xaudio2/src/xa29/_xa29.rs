@@ -8,8 +8,14 @@
 
 use super::xaudio2_8 as prev;
 
+mod builder;
 mod exports;
+mod hrtf;
 mod ixaudio2extension_ext;
+mod reverb;
+mod volume_meter;
+mod xapofx;
+pub mod x3daudio;
 
 // Might not remain pub
 #[doc(hidden)] pub use xaudio2::sys::{
@@ -38,37 +44,219 @@ pub mod xaudio2 {
     // Re-exports
     #[doc(no_inline)] pub use winresult::{HResult, HResultError};
     pub use super::ixaudio2extension_ext::XAudio2Extension as Extension;
-    use winresult::{ERROR, ErrorCode};
+    use winresult::ERROR;
     #[cfg(doc)] use winresult::CO;
 
     pub use exports::*;
+    pub use builder::*;
     #[doc(inline)] pub use prev::xaudio2::{
         // Structs
+        AdpcmSourceFormat,
         Context,
         DebugConfiguration,
+        EffectChain,
         EffectDescriptor,
+        EffectHandle,
         EngineCallbackWrapper,
         FilterParameters,
         LoopCount,
         MasteringVoice,
+        Owned,
+        OwnedMasteringVoice,
+        OwnedRenderSourceVoice,
+        OwnedSourceVoice,
+        OwnedSourceVoiceDynamic,
+        OwnedSourceVoiceUntyped,
+        OwnedStreamingSourceVoice,
+        OwnedSubmixVoice,
+        ParameterChannel,
         PerformanceData,
+        Processing,
+        ReadDecoder,
+        RenderSourceVoice,
         SampleRange,
         SendDescriptor,
         SourceFormat,
         SourceVoice,
         SourceVoiceDynamic,
         SourceVoiceUntyped,
+        SpatialEmitter,
+        StreamingSourceVoice,
+        TypedEffect,
+        TypedEffectDescriptor,
         SubmixVoice,
         TypedSourceFormat,
         Voice,
         VoiceCallbackWrapper,
         VoiceDetails,
         VoiceState,
+        XapoFormat,
+        XapoRegistrationProperties,
+        XwmaSourceFormat,
 
         // Traits
+        EffectParameters,
         EngineCallback,
         HasPcmWaveFormat,
+        StreamDecoder,
         VoiceCallback,
+        Xapo,
+    };
+
+    pub use super::hrtf::*;
+    pub use super::reverb::*;
+    pub use super::volume_meter::*;
+    pub use super::xapofx::*;
+    #[doc(inline)] pub use crate::portable::{
+        // Structs
+        I3dl2ReverbParameters,
+        ReverbParameters,
+
+        // Functions
+        reverb_convert_i3dl2_to_native,
+
+        // Constants
+        REVERB_MIN_WET_DRY_MIX,
+        REVERB_MIN_REFLECTIONS_DELAY,
+        REVERB_MIN_REVERB_DELAY,
+        REVERB_MIN_REAR_DELAY,
+        REVERB_MIN_7POINT1_REAR_DELAY,
+        REVERB_MIN_7POINT1_SIDE_DELAY,
+        REVERB_MIN_POSITION,
+        REVERB_MIN_DIFFUSION,
+        REVERB_MIN_LOW_EQ_GAIN,
+        REVERB_MIN_LOW_EQ_CUTOFF,
+        REVERB_MIN_HIGH_EQ_GAIN,
+        REVERB_MIN_HIGH_EQ_CUTOFF,
+        REVERB_MIN_ROOM_FILTER_FREQ,
+        REVERB_MIN_ROOM_FILTER_MAIN,
+        REVERB_MIN_ROOM_FILTER_HF,
+        REVERB_MIN_REFLECTIONS_GAIN,
+        REVERB_MIN_REVERB_GAIN,
+        REVERB_MIN_DECAY_TIME,
+        REVERB_MIN_DENSITY,
+        REVERB_MIN_ROOM_SIZE,
+        REVERB_MAX_WET_DRY_MIX,
+        REVERB_MAX_REFLECTIONS_DELAY,
+        REVERB_MAX_REVERB_DELAY,
+        REVERB_MAX_REAR_DELAY,
+        REVERB_MAX_7POINT1_REAR_DELAY,
+        REVERB_MAX_7POINT1_SIDE_DELAY,
+        REVERB_MAX_POSITION,
+        REVERB_MAX_DIFFUSION,
+        REVERB_MAX_LOW_EQ_GAIN,
+        REVERB_MAX_LOW_EQ_CUTOFF,
+        REVERB_MAX_HIGH_EQ_GAIN,
+        REVERB_MAX_HIGH_EQ_CUTOFF,
+        REVERB_MAX_ROOM_FILTER_FREQ,
+        REVERB_MAX_ROOM_FILTER_MAIN,
+        REVERB_MAX_ROOM_FILTER_HF,
+        REVERB_MAX_REFLECTIONS_GAIN,
+        REVERB_MAX_REVERB_GAIN,
+        REVERB_MAX_DENSITY,
+        REVERB_MAX_ROOM_SIZE,
+        REVERB_DEFAULT_WET_DRY_MIX,
+        REVERB_DEFAULT_REFLECTIONS_DELAY,
+        REVERB_DEFAULT_REVERB_DELAY,
+        REVERB_DEFAULT_REAR_DELAY,
+        REVERB_DEFAULT_7POINT1_REAR_DELAY,
+        REVERB_DEFAULT_7POINT1_SIDE_DELAY,
+        REVERB_DEFAULT_POSITION,
+        REVERB_DEFAULT_POSITION_MATRIX,
+        REVERB_DEFAULT_EARLY_DIFFUSION,
+        REVERB_DEFAULT_LATE_DIFFUSION,
+        REVERB_DEFAULT_LOW_EQ_GAIN,
+        REVERB_DEFAULT_LOW_EQ_CUTOFF,
+        REVERB_DEFAULT_HIGH_EQ_GAIN,
+        REVERB_DEFAULT_HIGH_EQ_CUTOFF,
+        REVERB_DEFAULT_ROOM_FILTER_FREQ,
+        REVERB_DEFAULT_ROOM_FILTER_MAIN,
+        REVERB_DEFAULT_ROOM_FILTER_HF,
+        REVERB_DEFAULT_REFLECTIONS_GAIN,
+        REVERB_DEFAULT_REVERB_GAIN,
+        REVERB_DEFAULT_DECAY_TIME,
+        REVERB_DEFAULT_DENSITY,
+        REVERB_DEFAULT_ROOM_SIZE,
+        I3DL2_PRESET_DEFAULT,
+        I3DL2_PRESET_GENERIC,
+        I3DL2_PRESET_PADDEDCELL,
+        I3DL2_PRESET_ROOM,
+        I3DL2_PRESET_BATHROOM,
+        I3DL2_PRESET_LIVINGROOM,
+        I3DL2_PRESET_STONEROOM,
+        I3DL2_PRESET_AUDITORIUM,
+        I3DL2_PRESET_CONCERTHALL,
+        I3DL2_PRESET_CAVE,
+        I3DL2_PRESET_ARENA,
+        I3DL2_PRESET_HANGAR,
+        I3DL2_PRESET_CARPETEDHALLWAY,
+        I3DL2_PRESET_HALLWAY,
+        I3DL2_PRESET_STONECORRIDOR,
+        I3DL2_PRESET_ALLEY,
+        I3DL2_PRESET_FOREST,
+        I3DL2_PRESET_CITY,
+        I3DL2_PRESET_MOUNTAINS,
+        I3DL2_PRESET_QUARRY,
+        I3DL2_PRESET_PLAIN,
+        I3DL2_PRESET_PARKINGLOT,
+        I3DL2_PRESET_SEWERPIPE,
+        I3DL2_PRESET_UNDERWATER,
+        I3DL2_PRESET_SMALLROOM,
+        I3DL2_PRESET_MEDIUMROOM,
+        I3DL2_PRESET_LARGEROOM,
+        I3DL2_PRESET_MEDIUMHALL,
+        I3DL2_PRESET_LARGEHALL,
+        I3DL2_PRESET_PLATE,
+    };
+
+    #[doc(inline)] pub use crate::portable::{
+        // Structs
+        FxEchoInitData,
+        FxEchoParameters,
+        FxEqParameters,
+        FxMasteringLimiterParameters,
+        FxReverbParameters,
+
+        // Constants
+        CLSID_FXEQ,
+        CLSID_FXMASTERINGLIMITER,
+        CLSID_FXREVERB,
+        CLSID_FXECHO,
+        FXEQ_MIN_FRAMERATE,
+        FXEQ_MAX_FRAMERATE,
+        FXEQ_MIN_FREQUENCY_CENTER,
+        FXEQ_MAX_FREQUENCY_CENTER,
+        FXEQ_DEFAULT_FREQUENCY_CENTER_0,
+        FXEQ_DEFAULT_FREQUENCY_CENTER_1,
+        FXEQ_DEFAULT_FREQUENCY_CENTER_2,
+        FXEQ_DEFAULT_FREQUENCY_CENTER_3,
+        FXEQ_MIN_GAIN,
+        FXEQ_MAX_GAIN,
+        FXEQ_DEFAULT_GAIN,
+        FXEQ_MIN_BANDWIDTH,
+        FXEQ_MAX_BANDWIDTH,
+        FXEQ_DEFAULT_BANDWIDTH,
+        FXMASTERINGLIMITER_MIN_RELEASE,
+        FXMASTERINGLIMITER_MAX_RELEASE,
+        FXMASTERINGLIMITER_DEFAULT_RELEASE,
+        FXMASTERINGLIMITER_MIN_LOUDNESS,
+        FXMASTERINGLIMITER_MAX_LOUDNESS,
+        FXMASTERINGLIMITER_DEFAULT_LOUDNESS,
+        FXREVERB_MIN_DIFFUSION,
+        FXREVERB_MAX_DIFFUSION,
+        FXREVERB_DEFAULT_DIFFUSION,
+        FXREVERB_MIN_ROOMSIZE,
+        FXREVERB_MAX_ROOMSIZE,
+        FXREVERB_DEFAULT_ROOMSIZE,
+        FXECHO_MIN_WETDRYMIX,
+        FXECHO_MAX_WETDRYMIX,
+        FXECHO_DEFAULT_WETDRYMIX,
+        FXECHO_MIN_FEEDBACK,
+        FXECHO_MAX_FEEDBACK,
+        FXECHO_DEFAULT_FEEDBACK,
+        FXECHO_MIN_DELAY,
+        FXECHO_MAX_DELAY,
+        FXECHO_DEFAULT_DELAY,
     };
 
     /// Raw low level FFI bindings
@@ -163,6 +351,13 @@ pub mod xaudio2 {
         Processor31,
         Processor32,
     };
+    pub use sys::{
+        XAUDIO2_DEBUG_ENGINE            as DEBUG_ENGINE,
+        XAUDIO2_STOP_ENGINE_WHEN_IDLE   as STOP_ENGINE_WHEN_IDLE,
+        XAUDIO2_1024_QUANTUM            as QUANTUM_1024,
+        XAUDIO2_NO_VIRTUAL_AUDIO_CLIENT as NO_VIRTUAL_AUDIO_CLIENT,
+    };
+
     pub use sys::{
         XAUDIO2_ANY_PROCESSOR           as ANY_PROCESSOR,
         XAUDIO2_USE_DEFAULT_PROCESSOR   as USE_DEFAULT_PROCESSOR,
@@ -212,7 +407,7 @@ pub mod xaudio2 {
     /// Worst case scenario, I'll close it as an out-of-scope xaudio2_9.dll bug.
     ///
     /// ### Arguments
-    /// *   `flags`     - Must be [None]
+    /// *   `flags`     - Must be [None] (see [XAudio2Builder] for [DEBUG_ENGINE], [STOP_ENGINE_WHEN_IDLE], [QUANTUM_1024], explicit [Exports], etc.)
     /// *   `processor` - The processor(s) to run XAudio2's worker thread(s) on.
     ///
     ///     | Value                     | Description   |
@@ -248,42 +443,73 @@ pub mod xaudio2 {
     ///
     /// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
     pub unsafe fn create(flags: Option<core::convert::Infallible>, processor: impl Into<Option<Processor>>) -> Result<XAudio2, HResultError> {
+        let _ = flags;
+        unsafe { create_with_exports(Exports::from_default_path_cached_hresult()?, 0, processor.into(), NTDDI_VERSION) }
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/gaming/gdk/_content/gc/reference/audio/xaudio2xbox/functions/xaudio2createwithsharedcontexts)\] XAudio2CreateWithSharedContexts:
+    /// Creates a new [IXAudio2] instance which shares `shared`'s audio processing context(s) (worker threads) instead of spawning its own.
+    ///
+    /// This lets e.g. middleware own a separate engine (with its own voice graph, mastering voice, and engine callbacks)
+    /// without doubling XAudio2's thread and CPU overhead.
+    /// Voices still belong to a single engine: you can't send from one engine's voices to the other's.
+    ///
+    /// ### Safety
+    /// All of [create]'s caveats apply, plus:
+    /// *   `shared` must have been created by the default [DLL] (e.g. via [create], not a redist or [D_DLL] loaded via [XAudio2Builder].)
+    /// *   `shared` should outlive the returned engine, since its processing contexts are what run the returned engine.
+    ///     Dropping `shared` first isn't documented as being supported.
+    /// *   The signature of `XAudio2CreateWithSharedContexts` is taken from the GDK docs - no desktop Windows SDK header declares it.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use thindx_xaudio2::xaudio2_9::*;
+    /// mcom::init::mta().unwrap();
+    /// let main = unsafe { xaudio2::create(None, None) }.unwrap();
+    /// let middleware = unsafe { xaudio2::create_with_shared_contexts(&main) }.unwrap();
+    /// drop(middleware);
+    /// drop(main);
+    /// ```
+    ///
+    /// ### Errors
+    /// *   [HResultError::from_win32]\([ERROR::MOD_NOT_FOUND])     - if `XAudio2_9.dll` could not be found
+    /// *   [HResultError::from_win32]\([ERROR::PROC_NOT_FOUND])    - if `XAudio2_9.dll` doesn't export `XAudio2CreateWithSharedContexts` (e.g. older builds of Windows 10)
+    /// *   [HResultError::from_win32]\([ERROR::NOINTERFACE])       - if [IXAudio2] was null despite the function "succeeding" (thindx specific)
+    ///
+    /// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
+    pub unsafe fn create_with_shared_contexts(shared: &XAudio2) -> Result<XAudio2, HResultError> {
         #![allow(non_snake_case)]
 
-        let exports = match Exports::from_default_path_cached() {
-            Ok(e) => e,
-            Err(err) => {
-                let code = if let Some(code) = err.raw_os_error() {
-                    let code = code as u32;
-                    if let Ok(code) = u16::try_from(code) {
-                        ErrorCode::from(code)
-                    } else {
-                        return Err(HResultError::from(code));
-                    }
-                } else {
-                    ERROR::INVALID_LIBRARY
-                };
-                return Err(HResultError::from_win32(code));
-            }
-        };
+        let exports = Exports::from_default_path_cached_hresult()?;
+        let XAudio2CreateWithSharedContexts = exports.XAudio2CreateWithSharedContexts.ok_or(HResultError::from_win32(ERROR::PROC_NOT_FOUND))?;
 
-        let _ = flags;
-        return match processor.into() {
-            Some(processor) => imp(exports, processor),
+        let mut xaudio2 = None;
+        unsafe { XAudio2CreateWithSharedContexts(shared, &mut xaudio2) }.succeeded()?;
+        let xaudio2 = xaudio2.ok_or(HResultError::from_win32(ERROR::NOINTERFACE))?; // XAudio2CreateWithSharedContexts "succeeded" but gave us a null ptr?
+        Ok(xaudio2)
+    }
+
+    /// The `ntddiVersion` passed to `XAudio2CreateWithVersionInfo` by default: `NTDDI_WIN10_NI` - see `C:\Program Files (x86)\Windows Kits\10\Include\10.0.22621.0\shared\sdkddkver.h`
+    pub const NTDDI_VERSION : u32 = 0x0A00000C;
+
+    /// [create], but using an explicit set of [Exports] (e.g. loaded from `xaudio2_9redist.dll`), flags, and `ntddiVersion`.
+    pub(crate) unsafe fn create_with_exports(exports: &Exports, flags: u32, processor: Option<Processor>, ntddi_version: u32) -> Result<XAudio2, HResultError> {
+        #![allow(non_snake_case)]
+
+        return match processor {
+            Some(processor) => imp(exports, flags, processor, ntddi_version),
             None => {
-                let xaudio2 = imp(exports, USE_DEFAULT_PROCESSOR);
-                let xaudio2 = xaudio2.or_else(|_| imp(exports, DEFAULT_PROCESSOR));
+                let xaudio2 = imp(exports, flags, USE_DEFAULT_PROCESSOR, ntddi_version);
+                let xaudio2 = xaudio2.or_else(|_| imp(exports, flags, DEFAULT_PROCESSOR, ntddi_version));
                 xaudio2
             },
         };
 
-        fn imp(exports: &Exports, processor: Processor) -> Result<XAudio2, HResultError> {
+        fn imp(exports: &Exports, flags: u32, processor: Processor, ntddi_version: u32) -> Result<XAudio2, HResultError> {
             let mut xaudio2 = None;
-            let flags = 0;
-            const NTDDI_VERSION : u32 = 0x0A00000C; // NTDDI_WIN10_NI - see C:\Program Files (x86)\Windows Kits\10\Include\10.0.22621.0\shared\sdkddkver.h
 
             let hr = if let Some(XAudio2CreateWithVersionInfo) = exports.XAudio2CreateWithVersionInfo {
-                unsafe { XAudio2CreateWithVersionInfo(&mut xaudio2, flags, processor, NTDDI_VERSION) }
+                unsafe { XAudio2CreateWithVersionInfo(&mut xaudio2, flags, processor, ntddi_version) }
             } else if let Some(XAudio2Create) = exports.XAudio2Create {
                 unsafe { XAudio2Create(&mut xaudio2, flags, processor) }
             } else {
@@ -0,0 +1,175 @@
+use super::{XAudio2, xaudio2::*};
+
+use winresult::{E, ERROR, HResultError};
+
+use core::fmt::{self, Display, Formatter};
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-xaudio2create)\]
+/// XAudio2Create, with control over engine flags, processor, `ntddiVersion`, and which DLL's [Exports] are used.
+///
+/// | Setting                                               | Default   |
+/// | ----------------------------------------------------- | --------- |
+/// | [exports](Self::exports)                              | [Exports::from_default_path_cached] ([DLL])
+/// | [debug_engine](Self::debug_engine)                    | `false`
+/// | [stop_engine_when_idle](Self::stop_engine_when_idle)  | `false`
+/// | [quantum_1024](Self::quantum_1024)                    | `false`
+/// | [processor](Self::processor)                          | [None] (as per [create])
+/// | [ntddi_version](Self::ntddi_version)                  | [NTDDI_VERSION]
+///
+/// ### Example
+/// ```
+/// use thindx_xaudio2::xaudio2_9::*;
+///
+/// let xaudio2 = unsafe { xaudio2::XAudio2Builder::new().stop_engine_when_idle(true).build() }.unwrap();
+///
+/// // Use the redist instead of the OS's XAudio2:
+/// if let Ok(redist) = xaudio2::Exports::from_path("xaudio2_9redist.dll") {
+///     let xaudio2 = unsafe { xaudio2::XAudio2Builder::new().exports(&redist).build() }.unwrap();
+/// }
+/// ```
+#[derive(Clone, Copy)] pub struct XAudio2Builder<'e> {
+    exports:        Option<&'e Exports>,
+    flags:          u32,
+    processor:      Option<Processor>,
+    ntddi_version:  Option<u32>,
+}
+
+impl XAudio2Builder<'static> {
+    /// A builder matching <code>[create]\(None, None\)</code>.
+    pub fn new() -> Self { Self { exports: None, flags: 0, processor: None, ntddi_version: None } }
+}
+
+impl Default for XAudio2Builder<'static> { fn default() -> Self { Self::new() } }
+
+impl<'e> XAudio2Builder<'e> {
+    /// Create XAudio2 via `exports` (e.g. from <code>[Exports::from_path]\([D_DLL]\)</code>, or the redist's `xaudio2_9redist.dll`) instead of the default [DLL]'s.
+    pub fn exports<'e2>(self, exports: &'e2 Exports) -> XAudio2Builder<'e2> {
+        XAudio2Builder { exports: Some(exports), flags: self.flags, processor: self.processor, ntddi_version: self.ntddi_version }
+    }
+
+    /// [DEBUG_ENGINE]: Use the debug engine.
+    /// Requires <code>[exports](Self::exports)\([Exports::from_path]\([D_DLL]\)\)</code>, which is only installed with the Windows SDK / Graphics Tools.
+    pub fn debug_engine(self, enable: bool) -> Self { self.flag(DEBUG_ENGINE, enable) }
+
+    /// [STOP_ENGINE_WHEN_IDLE]: Stop the engine when no source voices are started, and restart it when one is.
+    pub fn stop_engine_when_idle(self, enable: bool) -> Self { self.flag(STOP_ENGINE_WHEN_IDLE, enable) }
+
+    /// [QUANTUM_1024]: Use a nondefault processing quantum of 21.33 ms (1024 samples at 48 kHz.)
+    pub fn quantum_1024(self, enable: bool) -> Self { self.flag(QUANTUM_1024, enable) }
+
+    /// The processor(s) to run XAudio2's worker thread(s) on.
+    /// [None] tries [USE_DEFAULT_PROCESSOR] first, then [DEFAULT_PROCESSOR] (as per [create].)
+    pub fn processor(mut self, processor: impl Into<Option<Processor>>) -> Self { self.processor = processor.into(); self }
+
+    /// The `ntddiVersion` passed to `XAudio2CreateWithVersionInfo` (e.g. `0x0A000006` for `NTDDI_WIN10_RS5`.)
+    ///
+    /// If this is set, [build](Self::build) fails with [BuildError::VersionInfoUnsupported] when [Exports::XAudio2CreateWithVersionInfo] is unavailable,
+    /// instead of silently falling back on `XAudio2Create`.
+    pub fn ntddi_version(mut self, ntddi_version: u32) -> Self { self.ntddi_version = Some(ntddi_version); self }
+
+    /// The `Flags` that will be passed to `XAudio2Create*`.
+    pub fn flags(&self) -> u32 { self.flags }
+
+    fn flag(mut self, flag: u32, enable: bool) -> Self {
+        if enable { self.flags |= flag } else { self.flags &= !flag }
+        self
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-xaudio2create)\]
+    /// Creates a new [IXAudio2](super::IXAudio2) instance.
+    ///
+    /// Flags known to be unsupported by [exports](Self::exports) are rejected before XAudio2 is called.
+    /// XAudio2 itself doesn't distinguish bad flags from other invalid arguments, so any other failure is a [BuildError::Create]:
+    /// retry without some [flags](Self::flags) if you want to fall back on e.g. the default quantum.
+    ///
+    /// ### Safety
+    /// See [create].
+    ///
+    /// ### Errors
+    /// *   [BuildError::Create]                    - if the default [DLL] couldn't be loaded, or XAudio2 creation failed (see [create]'s errors)
+    /// *   [BuildError::UnsupportedFlags]          - if [debug_engine](Self::debug_engine) was set, but [exports](Self::exports) aren't [D_DLL]'s
+    /// *   [BuildError::VersionInfoUnsupported]    - if [ntddi_version](Self::ntddi_version) was set, but `XAudio2CreateWithVersionInfo` isn't exported
+    pub unsafe fn build(&self) -> Result<XAudio2, BuildError> {
+        let exports = match self.exports {
+            Some(exports)   => exports,
+            None            => Exports::from_default_path_cached_hresult().map_err(BuildError::Create)?,
+        };
+
+        if self.flags & DEBUG_ENGINE != 0 && !exports.debug { return Err(BuildError::UnsupportedFlags { flags: DEBUG_ENGINE, error: E::INVALIDARG.into() }) }
+        if self.ntddi_version.is_some() && exports.XAudio2CreateWithVersionInfo.is_none() { return Err(BuildError::VersionInfoUnsupported) }
+        let ntddi_version = self.ntddi_version.unwrap_or(NTDDI_VERSION);
+
+        unsafe { create_with_exports(exports, self.flags, self.processor, ntddi_version) }.map_err(BuildError::Create)
+    }
+}
+
+
+
+/// An error returned by [XAudio2Builder::build].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive] pub enum BuildError {
+    /// The DLL couldn't be loaded, or XAudio2 creation failed (possibly due to the builder's flags - XAudio2 doesn't say.)
+    Create(HResultError),
+    /// `flags` are known to be unsupported by the builder's [Exports] (e.g. [DEBUG_ENGINE] without [D_DLL]), and were rejected without calling XAudio2.
+    UnsupportedFlags { flags: u32, error: HResultError },
+    /// [XAudio2Builder::ntddi_version] was set, but [Exports::XAudio2CreateWithVersionInfo] is unavailable (XAudio 2.9 prior to `NTDDI_WIN10_RS5`?)
+    VersionInfoUnsupported,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            BuildError::Create(error)                       => write!(fmt, "unable to create XAudio2: {error:?}"),
+            BuildError::UnsupportedFlags { flags, error }   => write!(fmt, "unable to create XAudio2: flags 0x{flags:X} unsupported: {error:?}"),
+            BuildError::VersionInfoUnsupported              => write!(fmt, "unable to create XAudio2: ntddi_version specified, but XAudio2CreateWithVersionInfo isn't exported"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl From<BuildError> for HResultError {
+    fn from(err: BuildError) -> Self {
+        match err {
+            BuildError::Create(error)                   => error,
+            BuildError::UnsupportedFlags { error, .. }  => error,
+            BuildError::VersionInfoUnsupported          => HResultError::from_win32(ERROR::PROC_NOT_FOUND),
+        }
+    }
+}
+
+
+
+#[test] fn builder() {
+    let xaudio2 = unsafe { XAudio2Builder::new().stop_engine_when_idle(true).build() }.unwrap();
+    drop(xaudio2);
+
+    let exports = Exports::from_default_path().unwrap();
+    let builder = XAudio2Builder::new().exports(&exports).stop_engine_when_idle(true).stop_engine_when_idle(false);
+    assert_eq!(builder.flags(), 0);
+    let _xaudio2 = unsafe { builder.build() }.unwrap();
+}
+
+#[test] fn builder_errors() {
+    let none = Exports {
+        CreateAudioReverb:                  None,
+        CreateAudioReverbV2_8:              None,
+        CreateAudioVolumeMeter:             None,
+        CreateFX:                           None,
+        X3DAudioCalculate:                  None,
+        X3DAudioInitialize:                 None,
+        XAudio2Create:                      None,
+        XAudio2CreateV2_9:                  None,
+        XAudio2CreateWithSharedContexts:    None,
+        XAudio2CreateWithVersionInfo:       None,
+        debug:                              false,
+    };
+    let debug = Exports { debug: true, ..none };
+
+    let unsupported = BuildError::UnsupportedFlags { flags: DEBUG_ENGINE, error: E::INVALIDARG.into() };
+    assert_eq!(unsafe { XAudio2Builder::new().exports(&none ).debug_engine(true).build() }.err(), Some(unsupported));
+    assert_eq!(unsafe { XAudio2Builder::new().exports(&none ).ntddi_version(NTDDI_VERSION).build() }.err(), Some(BuildError::VersionInfoUnsupported));
+    assert_eq!(unsafe { XAudio2Builder::new().exports(&debug).debug_engine(true).ntddi_version(NTDDI_VERSION).build() }.err(), Some(BuildError::VersionInfoUnsupported));
+}
@@ -1,20 +1,14 @@
 use super::{XAudio2, xaudio2::sys::*};
 use winapi::shared::guiddef::REFCLSID;
 use winapi::um::unknwnbase::IUnknown;
-use winresult::HResult;
+use thindx_xaudio2_sys::x3daudio::{X3DAudioCalculateFn, X3DAudioInitializeFn};
+use super::prev::io_error_to_hresult;
+use winresult::{HResult, HResultError};
+#[cfg(doc)] use winresult::ERROR;
 use core::ffi::c_void;
 
 
 
-const X3DAUDIO_HANDLE_BYTESIZE : usize = 20; // TODO: offload to -sys ?
-#[allow(non_camel_case_types)] type X3DAUDIO_HANDLE = [u8; X3DAUDIO_HANDLE_BYTESIZE]; // TODO: offload to -sys ?
-
-#[allow(non_camel_case_types)] type X3DAUDIO_LISTENER      = c_void;    // TODO: remove
-#[allow(non_camel_case_types)] type X3DAUDIO_EMITTER       = c_void;    // TODO: remove
-#[allow(non_camel_case_types)] type X3DAUDIO_DSP_SETTINGS  = c_void;    // TODO: remove
-
-
-
 /// `XAudio2_9.dll` exports.
 ///
 /// Based off of `dumpbin /exports C:\Windows\System32\XAudio2_9.dll` on Windows 10.0.19043.2006:
@@ -50,8 +44,18 @@ const X3DAUDIO_HANDLE_BYTESIZE : usize = 20; // TODO: offload to -sys ?
 
 
     /// \[<strike>microsoft.com</strike>\]
-    /// Probably has the same signautre as CreateAudioReverb, but neither CreateAudioReverb nor CreateAudioReverbV2_8 alias each other, so I can't confirm.
-    #[allow(dead_code)] CreateAudioReverbV2_8: Option<Option<&'static ()>>,
+    /// [CreateAudioReverb](Self::CreateAudioReverb), but taking XAudio 2.8's `XAUDIO2FX_REVERB_PARAMETERS` (no `DisableLateField`.)<br>
+    /// Equivalent to [xaudio2_8::xaudio2::Exports::CreateAudioReverb](crate::xaudio2_8::xaudio2::Exports::CreateAudioReverb).
+    ///
+    /// | Argument          | Description   |
+    /// | ----------------- | ------------- |
+    /// | ppApo             | Output audio effect.  Can be [QueryInterface]d or [mcom::Rc::try_cast]ed to [IXAPO] or [IXAPOParameters].
+    ///
+    /// [QueryInterface]:       https://learn.microsoft.com/en-us/cpp/atl/queryinterface?view=msvc-170
+    /// [IXAPO]:                https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapo
+    /// [IXAPOParameters]:      https://learn.microsoft.com/en-us/windows/win32/api/xapo/nn-xapo-ixapoparameters
+    pub CreateAudioReverbV2_8:  Option<unsafe extern "stdcall" fn(ppApo: *mut *mut IUnknown) -> HResult>,
+    // Neither CreateAudioReverb nor CreateAudioReverbV2_8 alias each other, but the 2.8 reverb has the same signature.
 
 
     /// \[<strike>microsoft.com</strike>\]
@@ -99,8 +103,7 @@ const X3DAUDIO_HANDLE_BYTESIZE : usize = 20; // TODO: offload to -sys ?
     /// | pDSPSettings      | Calculation results
     ///
     /// [X3DAudioInitialize]:   https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudioinitialize
-    pub X3DAudioCalculate:      Option<unsafe extern "cdecl" fn(Instance: &X3DAUDIO_HANDLE, pListener: *const X3DAUDIO_LISTENER, pEmitter: *const X3DAUDIO_EMITTER, Flags: u32, pDSPSettings: *mut X3DAUDIO_DSP_SETTINGS)>,
-    // X3DAUDIO_API_ -> STDAPIVCALLTYPE -> __cdecl
+    pub X3DAudioCalculate:      Option<X3DAudioCalculateFn>,
 
 
     /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudioinitialize)\]
@@ -110,8 +113,7 @@ const X3DAUDIO_HANDLE_BYTESIZE : usize = 20; // TODO: offload to -sys ?
     /// | --------------------- | ------------- |
     /// | SpeakerChannelMask    | Assignment of channels to speaker positions. This value must not be zero. The only permissible value on Xbox 360 is `SPEAKER_XBOX`.
     /// | SpeedOfSound          | Speed of sound, in user-defined world units per second. Use this value only for doppler calculations. It must be greater than or equal to [f32::MIN_POSITIVE].
-    pub X3DAudioInitialize:     Option<unsafe extern "cdecl" fn(SpeakerChannelMask: u32, SpeedOfSound: f32, Instance: &mut X3DAUDIO_HANDLE) -> HResult>,
-    // X3DAUDIO_API_ -> STDAPIVCALLTYPE -> __cdecl
+    pub X3DAudioInitialize:     Option<X3DAudioInitializeFn>,
 
 
     /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2/nf-xaudio2-xaudio2create)\]
@@ -142,8 +144,14 @@ const X3DAUDIO_HANDLE_BYTESIZE : usize = 20; // TODO: offload to -sys ?
 
 
     /// \[[microsoft.com](https://learn.microsoft.com/en-us/gaming/gdk/_content/gc/reference/audio/xaudio2xbox/functions/xaudio2createwithsharedcontexts)\]
-    /// NDAed function requiring ID@Xbox access?
-    #[allow(dead_code)] XAudio2CreateWithSharedContexts: Option<Option<&'static ()>>, // type unknown
+    /// Creates a new XAudio2 object that shares the audio processing context(s) (worker threads) of an existing one.
+    ///
+    /// | Argument              | Description   |
+    /// | --------------------- | ------------- |
+    /// | sharedContextXAudio2  | Existing [IXAudio2] (created by this same DLL) whose processing contexts should be shared
+    /// | ppXAudio2             | Returned [IXAudio2]
+    pub XAudio2CreateWithSharedContexts: Option<unsafe extern "system" fn(sharedContextXAudio2: &IXAudio2, ppXAudio2: &mut Option<XAudio2>) -> HResult>,
+    // Documented for the GDK's xaudio2xbox.h, which isn't part of the desktop Windows SDK - the signature above is per the GDK docs, not a header.
 
 
     /// \[<strike>microsoft.com</strike>\]
@@ -156,6 +164,9 @@ const X3DAUDIO_HANDLE_BYTESIZE : usize = 20; // TODO: offload to -sys ?
     /// | XAudio2Processor  | [XAUDIO2_DEFAULT_PROCESSOR]   | What processor(s) to run XAudio2 on.
     /// | ntddiVersion      | `NTDDI_VERSION`               | Highest OS version supported by the WDK being built with.
     pub XAudio2CreateWithVersionInfo: Option<unsafe extern "system" fn(ppXAudio2: &mut Option<XAudio2>, Flags: u32, XAudio2Processor: XAUDIO2_PROCESSOR, ntddiVersion: u32) -> HResult>,
+
+    /// Loaded via <code>[from_path](Self::from_path)\([D_DLL](super::xaudio2::D_DLL)\)</code>?  [XAudio2Builder::debug_engine](super::xaudio2::XAudio2Builder::debug_engine) requires this.
+    pub(crate) debug: bool,
 }
 
 impl Exports {
@@ -164,13 +175,20 @@ impl Exports {
         &*EXPORTS
     }
 
+    /// [from_default_path_cached](Self::from_default_path_cached), with I/O errors converted to [HResultError]s (e.g. [ERROR::MOD_NOT_FOUND].)
+    pub(crate) fn from_default_path_cached_hresult() -> Result<&'static Self, HResultError> {
+        Self::from_default_path_cached().as_ref().map_err(io_error_to_hresult)
+    }
+
     pub fn from_default_path() -> std::io::Result<Self> {
         Self::from_path(super::xaudio2::DLL)
     }
 
     pub fn from_path(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let debug = path.file_name().map_or(false, |name| name.eq_ignore_ascii_case(super::xaudio2::D_DLL));
         let lib = minidl::Library::load(path)?; // TODO: LOAD_LIBRARY_SEARCH_SYSTEM32: https://github.com/MaulingMonkey/minidl/issues/2
-        Self::from_minidl_0_1(lib)
+        Ok(Self { debug, ..Self::from_minidl_0_1(lib)? })
     }
 
     pub fn from_minidl_0_1(lib: minidl::Library) -> std::io::Result<Self> {
@@ -185,10 +203,29 @@ impl Exports {
             XAudio2CreateV2_9:                  unsafe { lib.sym_opt("XAudio2CreateV2_9\0") },
             XAudio2CreateWithSharedContexts:    unsafe { lib.sym_opt("XAudio2CreateWithSharedContexts\0") },
             XAudio2CreateWithVersionInfo:       unsafe { lib.sym_opt("XAudio2CreateWithVersionInfo\0") },
+            debug:                              false,
         })
     }
 }
 
 #[test] fn exports() {
-    let _exports = Exports::from_default_path().unwrap();
+    let exports = Exports::from_default_path().unwrap();
+    assert!(exports.XAudio2Create.is_some());
+}
+
+#[test] fn exports_resolve_by_name() {
+    // N.B. only checks symbol resolution: XAudio2CreateWithSharedContexts's signature comes from the GDK docs, not a desktop SDK header, so it's never called here.
+    // Older builds of Windows 10 don't export it at all, so compare against the raw export table instead of asserting is_some().
+    let lib = minidl::Library::load(super::xaudio2::DLL).unwrap();
+    let raw = |name: &str| -> Option<usize> { unsafe { lib.sym_opt::<unsafe extern "system" fn()>(name) }.map(|f| f as usize) };
+    let expected = [
+        raw("XAudio2Create\0"),
+        raw("XAudio2CreateWithSharedContexts\0"),
+        raw("XAudio2CreateWithVersionInfo\0"),
+    ];
+
+    let exports = Exports::from_minidl_0_1(lib).unwrap();
+    assert_eq!(exports.XAudio2Create                  .map(|f| f as usize), expected[0]);
+    assert_eq!(exports.XAudio2CreateWithSharedContexts.map(|f| f as usize), expected[1]);
+    assert_eq!(exports.XAudio2CreateWithVersionInfo   .map(|f| f as usize), expected[2]);
 }
@@ -0,0 +1,270 @@
+use super::xaudio2::*;
+use super::prev::io_error_to_hresult;
+
+use thindx_xaudio2_sys::hrtfapo::{self as hrtf, CreateHrtfApoFn, IXAPOHrtfParameters};
+pub use thindx_xaudio2_sys::hrtfapo::{HrtfPosition, HrtfOrientation};
+
+use winapi::um::unknwnbase::IUnknown;
+use winresult::*;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ne-hrtfapoapi-hrtfdirectivitytype)\]
+/// The shape of a sound source's emission, for [HrtfEmitter::new].
+///
+/// `scaling` in `[0, 1]` blends between the pattern (`1.0`) and omnidirectional (`0.0`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)] pub enum HrtfDirectivity {
+    /// The sound emission is the same in all directions.
+    #[default] OmniDirectional,
+
+    /// The sound emission is cardioid shaped, with `order` in `(0, 32]` controlling the shape.
+    Cardioid { scaling: f32, order: f32 },
+
+    /// The sound emission is cone shaped, with angles in radians: `0 ≤ inner_angle ≤ outer_angle ≤ 2π`.
+    Cone { scaling: f32, inner_angle: f32, outer_angle: f32 },
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ns-hrtfapoapi-hrtfdistancedecay)\]
+/// How a sound source's gain decays with distance, for [HrtfEmitter::new].
+#[derive(Clone, Copy, Debug, PartialEq)] pub enum HrtfDistanceDecay {
+    /// Simulates natural decay with distance, with gains in dB and distances in meters:
+    /// *   `HRTF_MIN_GAIN_LIMIT (-96) ≤ min_gain ≤ max_gain ≤ HRTF_MAX_GAIN_LIMIT (12)`
+    /// *   `HRTF_MIN_UNITY_GAIN_DISTANCE (0.05) ≤ unity_gain_distance ≤ cutoff_distance`
+    Natural { max_gain: f32, min_gain: f32, unity_gain_distance: f32, cutoff_distance: f32 },
+
+    /// No decay: attenuate via [HrtfEmitter::set_source_gain] yourself.
+    Custom,
+}
+
+impl HrtfDirectivity {
+    fn is_valid(&self) -> bool {
+        let scaling = |scaling: f32| (0.0 ..= 1.0).contains(&scaling);
+        match *self {
+            Self::OmniDirectional                               => true,
+            Self::Cardioid { scaling: s, order }                => scaling(s) && order > 0.0 && order <= 32.0,
+            Self::Cone { scaling: s, inner_angle, outer_angle } => scaling(s) && 0.0 <= inner_angle && inner_angle <= outer_angle && outer_angle <= core::f32::consts::TAU,
+        }
+    }
+}
+
+impl HrtfDistanceDecay {
+    fn is_valid(&self) -> bool {
+        match *self {
+            Self::Natural { max_gain, min_gain, unity_gain_distance, cutoff_distance } => {
+                hrtf::HRTF_MIN_GAIN_LIMIT <= min_gain && min_gain <= max_gain && max_gain <= hrtf::HRTF_MAX_GAIN_LIMIT &&
+                hrtf::HRTF_MIN_UNITY_GAIN_DISTANCE <= unity_gain_distance && unity_gain_distance <= cutoff_distance
+            },
+            Self::Custom => true,
+        }
+    }
+}
+
+impl Default for HrtfDistanceDecay {
+    /// [HrtfDistanceDecay::Natural] with the `hrtfapoapi.h` defaults (`12` dB max, `-96` dB min, unity gain at `1` meter, no cutoff.)
+    fn default() -> Self {
+        Self::Natural {
+            max_gain:               hrtf::HRTF_MAX_GAIN_LIMIT,
+            min_gain:               hrtf::HRTF_MIN_GAIN_LIMIT,
+            unity_gain_distance:    hrtf::HRTF_DEFAULT_UNITY_GAIN_DISTANCE,
+            cutoff_distance:        hrtf::HRTF_DEFAULT_CUTOFF_DISTANCE,
+        }
+    }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/ne-hrtfapoapi-hrtfenvironment)\]
+/// The acoustic environment simulated by an [HrtfEmitter].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)] pub enum HrtfEnvironment {
+    #[default] Small,
+    Medium,
+    Large,
+    Outdoors,
+}
+
+impl From<HrtfEnvironment> for hrtf::HrtfEnvironment {
+    fn from(value: HrtfEnvironment) -> Self {
+        match value {
+            HrtfEnvironment::Small      => hrtf::Small,
+            HrtfEnvironment::Medium     => hrtf::Medium,
+            HrtfEnvironment::Large      => hrtf::Large,
+            HrtfEnvironment::Outdoors   => hrtf::Outdoors,
+        }
+    }
+}
+
+
+
+/// An HRTF (head-related transfer function) effect spatializing a mono source voice for headphones,
+/// created by [CreateHrtfApo](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-createhrtfapo).
+///
+/// ### Requirements
+/// *   Windows 10+ (`hrtfapo.dll`)
+/// *   The XAudio2 engine must be created with [XAUDIO2_1024_QUANTUM](sys::XAUDIO2_1024_QUANTUM)
+/// *   The voice must be a 48 kHz mono source voice (the effect outputs stereo, so its output voices should be too)
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # fn frame() -> Option<(xaudio2::HrtfPosition, xaudio2::HrtfOrientation)> { None }
+/// let xaudio2 = unsafe { xaudio2::XAudio2Builder::new().quantum_1024(true).build() }.unwrap();
+/// let _master = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+/// let format = xaudio2::TypedSourceFormat::<[f32; 1]>::pcm(48000);
+/// let voice = unsafe { xaudio2.create_source_voice_unchecked(&format, 0, xaudio2::DEFAULT_FREQ_RATIO, None, None, None) }.unwrap();
+/// let hrtf = xaudio2::HrtfEmitter::new(&voice, Default::default(), Default::default()).unwrap();
+/// hrtf.set_environment(xaudio2::HrtfEnvironment::Medium).unwrap();
+/// while let Some((position, orientation)) = frame() {
+///     hrtf.update(&position, &orientation).unwrap();
+/// }
+/// ```
+#[derive(Clone)] pub struct HrtfEmitter {
+    parameters: mcom::Rc<IXAPOHrtfParameters>,
+}
+
+impl HrtfEmitter {
+    /// Create an HRTF effect, and make it `voice`'s entire effect chain (replacing any existing effects.)
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]                                         - if `voice` isn't mono
+    /// *   [E::INVALIDARG]                                         - if `directivity` or `decay` are out of range
+    /// *   [HResultError::from_win32]\([ERROR::MOD_NOT_FOUND])     - if `hrtfapo.dll` could not be found (e.g. prior to Windows 10)
+    /// *   [HResultError::from_win32]\([ERROR::PROC_NOT_FOUND])    - if `hrtfapo.dll` failed to export `CreateHrtfApo`
+    /// *   [E::NOINTERFACE]                                        - if the effect doesn't implement [IXAPOHrtfParameters]
+    /// *   [E_INVALID_CALL]                                        - if `voice` isn't 48 kHz, or the engine wasn't created with [XAUDIO2_1024_QUANTUM](sys::XAUDIO2_1024_QUANTUM)
+    ///
+    /// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
+    pub fn new(voice: &Voice, directivity: HrtfDirectivity, decay: HrtfDistanceDecay) -> Result<Self, HResultError> {
+        if voice.get_voice_details().InputChannels != 1 { return Err(E::INVALIDARG) }
+        if !directivity.is_valid() || !decay.is_valid() { return Err(E::INVALIDARG) }
+
+        let mut decay = match decay {
+            HrtfDistanceDecay::Natural { max_gain, min_gain, unity_gain_distance, cutoff_distance } => hrtf::HrtfDistanceDecay {
+                r#type: hrtf::NaturalDecay, maxGain: max_gain, minGain: min_gain, unityGainDistance: unity_gain_distance, cutoffDistance: cutoff_distance,
+            },
+            HrtfDistanceDecay::Custom => hrtf::HrtfDistanceDecay {
+                r#type: hrtf::CustomDecay, maxGain: hrtf::HRTF_MAX_GAIN_LIMIT, minGain: hrtf::HRTF_MIN_GAIN_LIMIT,
+                unityGainDistance: hrtf::HRTF_DEFAULT_UNITY_GAIN_DISTANCE, cutoffDistance: hrtf::HRTF_DEFAULT_CUTOFF_DISTANCE,
+            },
+        };
+        let mut omni = hrtf::HrtfDirectivity { r#type: hrtf::OmniDirectional, scaling: 0.0 };
+        let mut cardioid;
+        let mut cone;
+        let directivity : *mut hrtf::HrtfDirectivity = match directivity {
+            HrtfDirectivity::OmniDirectional => &mut omni as *mut hrtf::HrtfDirectivity,
+            HrtfDirectivity::Cardioid { scaling, order } => {
+                cardioid = hrtf::HrtfDirectivityCardioid { directivity: hrtf::HrtfDirectivity { r#type: hrtf::Cardioid, scaling }, order };
+                (&mut cardioid as *mut hrtf::HrtfDirectivityCardioid).cast()
+            },
+            HrtfDirectivity::Cone { scaling, inner_angle, outer_angle } => {
+                cone = hrtf::HrtfDirectivityCone { directivity: hrtf::HrtfDirectivity { r#type: hrtf::Cone, scaling }, innerAngle: inner_angle, outerAngle: outer_angle };
+                (&mut cone as *mut hrtf::HrtfDirectivityCone).cast()
+            },
+        };
+        let init = hrtf::HrtfApoInit { distanceDecay: &mut decay, directivity };
+
+        let create = HrtfApoExports::cached()?.CreateHrtfApo.ok_or(HResultError::from_win32(ERROR::PROC_NOT_FOUND))?;
+        let mut effect : Option<mcom::Rc<IUnknown>> = None; // N.B. same layout as a nullable `*mut IUnknown`
+        // SAFETY: `init` points to a valid decay, and a directivity struct matching its `type` (`directivity` is the first member of cardioid/cone.)
+        unsafe { create(&init, &mut effect as *mut Option<mcom::Rc<IUnknown>> as *mut *mut IUnknown) }.succeeded()?;
+        let effect = effect.ok_or(HResultError::from_win32(ERROR::NOINTERFACE))?;
+        let parameters = effect.try_cast::<IXAPOHrtfParameters>().ok_or(E::NOINTERFACE)?;
+
+        voice.set_effect_chain(Some(&[EffectDescriptor::new(effect, true, 2)]))?;
+        Ok(Self { parameters })
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-ixapohrtfparameters-setsourceposition)\]
+    /// Sets the position of the source, in meters, relative to the listener (+x right, +y up, -z forward.)
+    pub fn set_source_position(&self, position: &HrtfPosition) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.parameters.SetSourcePosition(position) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-ixapohrtfparameters-setsourceorientation)\]
+    /// Sets the orientation of the source (a row-major 3x3 rotation matrix), relative to the listener.
+    pub fn set_source_orientation(&self, orientation: &HrtfOrientation) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.parameters.SetSourceOrientation(orientation) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-ixapohrtfparameters-setsourcegain)\]
+    /// Sets the custom gain of the source, in dB: `[-96, 12]` (typically used with [HrtfDistanceDecay::Custom].)
+    pub fn set_source_gain(&self, gain: f32) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.parameters.SetSourceGain(gain) }.succeeded()
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/hrtfapoapi/nf-hrtfapoapi-ixapohrtfparameters-setenvironment)\]
+    /// Selects the acoustic environment to simulate.
+    pub fn set_environment(&self, environment: HrtfEnvironment) -> Result<HResultSuccess, HResultError> {
+        unsafe { self.parameters.SetEnvironment(environment.into()) }.succeeded()
+    }
+
+    /// Sets both the position and orientation of the source, relative to the listener (once per frame.)
+    pub fn update(&self, position: &HrtfPosition, orientation: &HrtfOrientation) -> Result<(), HResultError> {
+        self.set_source_position(position)?;
+        self.set_source_orientation(orientation)?;
+        Ok(())
+    }
+
+    /// The underlying [IXAPOHrtfParameters] interface.
+    pub fn parameters(&self) -> &mcom::Rc<IXAPOHrtfParameters> { &self.parameters }
+}
+
+
+
+/// `hrtfapo.dll` exports.
+#[allow(non_snake_case)] // match original naming instead
+struct HrtfApoExports {
+    CreateHrtfApo: Option<CreateHrtfApoFn>,
+}
+
+impl HrtfApoExports {
+    fn cached() -> Result<&'static Self, HResultError> {
+        lazy_static::lazy_static! { static ref EXPORTS : std::io::Result<HrtfApoExports> = HrtfApoExports::load(); }
+        EXPORTS.as_ref().map_err(io_error_to_hresult)
+    }
+
+    fn load() -> std::io::Result<Self> {
+        let lib = minidl::Library::load(hrtf::HRTFAPO_DLL)?;
+        Ok(Self { CreateHrtfApo: unsafe { lib.sym_opt("CreateHrtfApo\0") } })
+    }
+}
+
+
+
+#[test] fn hrtf_emitter_requires_mono() {
+    use crate::mock;
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let voice   = unsafe { xaudio2.create_source_voice_unchecked(&TypedSourceFormat::<[f32; 2]>::pcm(48000), 0, MAX_FREQ_RATIO, None, None, None) }.unwrap();
+    assert_eq!(HrtfEmitter::new(&voice, Default::default(), Default::default()).err(), Some(E::INVALIDARG));
+}
+
+#[test] fn hrtf_emitter_validates_parameters() {
+    use crate::mock;
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let voice   = unsafe { xaudio2.create_source_voice_unchecked(&TypedSourceFormat::<[f32; 1]>::pcm(48000), 0, MAX_FREQ_RATIO, None, None, None) }.unwrap();
+    let natural = |max_gain, min_gain, unity_gain_distance, cutoff_distance| HrtfDistanceDecay::Natural { max_gain, min_gain, unity_gain_distance, cutoff_distance };
+
+    for directivity in [
+        HrtfDirectivity::Cardioid { scaling: 1.5, order: 1.0 },
+        HrtfDirectivity::Cardioid { scaling: 1.0, order: 0.0 },
+        HrtfDirectivity::Cardioid { scaling: 1.0, order: 33.0 },
+        HrtfDirectivity::Cone { scaling: -0.5, inner_angle: 0.0, outer_angle: 1.0 },
+        HrtfDirectivity::Cone { scaling: 1.0, inner_angle: 2.0, outer_angle: 1.0 },
+        HrtfDirectivity::Cone { scaling: 1.0, inner_angle: 0.0, outer_angle: 7.0 },
+        HrtfDirectivity::Cone { scaling: 1.0, inner_angle: f32::NAN, outer_angle: 1.0 },
+    ] { assert_eq!(HrtfEmitter::new(&voice, directivity, Default::default()).err(), Some(E::INVALIDARG), "{directivity:?}") }
+
+    for decay in [
+        natural(13.0, 0.0, 1.0, 10.0),
+        natural(0.0, -97.0, 1.0, 10.0),
+        natural(-10.0, 0.0, 1.0, 10.0),
+        natural(0.0, -10.0, 0.01, 10.0),
+        natural(0.0, -10.0, 10.0, 1.0),
+    ] { assert_eq!(HrtfEmitter::new(&voice, Default::default(), decay).err(), Some(E::INVALIDARG), "{decay:?}") }
+
+    assert!(HrtfDirectivity::Cone { scaling: 1.0, inner_angle: 0.0, outer_angle: core::f32::consts::TAU }.is_valid());
+    assert!(HrtfDirectivity::Cardioid { scaling: 0.0, order: 32.0 }.is_valid());
+    assert!(HrtfDistanceDecay::default().is_valid());
+    assert!(HrtfDistanceDecay::Custom.is_valid());
+}
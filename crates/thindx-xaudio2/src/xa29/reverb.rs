@@ -0,0 +1,71 @@
+use super::xaudio2::*;
+
+use winapi::um::unknwnbase::IUnknown;
+use winresult::*;
+
+
+
+impl EffectParameters for ReverbParameters { fn validate(&self) -> Result<(), HResultError> { ReverbParameters::validate(self) } }
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2fx/nf-xaudio2fx-xaudio2createreverb)\]
+/// XAudio2CreateReverb: creates the built-in reverb effect.
+///
+/// The reverb processes float32 audio: mono or stereo input, with output either matching the input, or 5.1 / 7.1 surround.
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// let mut chain = xaudio2::EffectChain::new();
+/// let reverb = chain.push(xaudio2::create_reverb().unwrap().descriptor(true, 1));
+/// let submix = xaudio2.create_submix_voice(1, 48000, 0, 0, None, Some(chain.as_slice())).unwrap();
+/// let parameters = xaudio2::reverb_convert_i3dl2_to_native(&xaudio2::I3DL2_PRESET_CONCERTHALL, true);
+/// submix.set_effect_parameters(&reverb, &parameters, xaudio2::COMMIT_NOW).unwrap();
+/// ```
+///
+/// ### Errors
+/// *   [HResultError::from_win32]\([ERROR::MOD_NOT_FOUND])     - if `XAudio2_9.dll` could not be found
+/// *   [HResultError::from_win32]\([ERROR::PROC_NOT_FOUND])    - if `XAudio2_9.dll` failed to export `CreateAudioReverb`
+/// *   [HResultError::from_win32]\([ERROR::NOINTERFACE])       - if the effect was null despite the function "succeeding" (thindx specific)
+///
+/// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
+pub fn create_reverb() -> Result<TypedEffect<ReverbParameters>, HResultError> {
+    let exports = Exports::from_default_path_cached_hresult()?;
+    let create = exports.CreateAudioReverb.ok_or(HResultError::from_win32(ERROR::PROC_NOT_FOUND))?;
+    let mut effect : Option<mcom::Rc<IUnknown>> = None; // N.B. same layout as a nullable `*mut IUnknown`
+    unsafe { create(&mut effect as *mut Option<mcom::Rc<IUnknown>> as *mut *mut IUnknown) }.succeeded()?;
+    let effect = effect.ok_or(HResultError::from_win32(ERROR::NOINTERFACE))?;
+    Ok(unsafe { TypedEffect::from_unknown(effect) })
+}
+
+
+
+#[test] fn create_reverb_descriptor() {
+    let reverb = create_reverb().unwrap();
+    let mut chain = EffectChain::new();
+    let handle = chain.push(reverb.descriptor(true, 2));
+    assert_eq!(handle.index(), 0);
+    assert_eq!(chain.len(), 1);
+}
+
+
+
+#[test] fn set_reverb_parameters_validates() {
+    use crate::mock;
+
+    struct Passthrough;
+    impl Xapo for Passthrough { fn process(&mut self, input: &mut [f32], output: &mut [f32]) { output.copy_from_slice(input) } }
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let effect  = unsafe { TypedEffect::<ReverbParameters>::from_unknown(Passthrough.wrap().into_unknown()) }; // the mock only stores parameters
+    let mut chain = EffectChain::new();
+    let reverb  = chain.push(effect.descriptor(true, 1));
+    let _master = xaudio2.create_mastering_voice(1, 48000, 0, (), None, DEFAULT_AUDIO_CATEGORY).unwrap();
+    let submix  = xaudio2.create_submix_voice(1, 48000, 0, 0, None, Some(chain.as_slice())).unwrap();
+
+    let parameters = reverb_convert_i3dl2_to_native(&I3DL2_PRESET_CONCERTHALL, false);
+    assert!(submix.set_effect_parameters(&reverb, &parameters, COMMIT_NOW).is_ok());
+    assert_eq!(submix.set_effect_parameters(&reverb, &ReverbParameters { DecayTime: 0.0, .. parameters }, COMMIT_NOW).err(), Some(E::INVALIDARG));
+    assert_eq!(submix.get_effect_parameters(&reverb).unwrap(), parameters);
+}
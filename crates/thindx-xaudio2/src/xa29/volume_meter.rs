@@ -0,0 +1,144 @@
+use super::xaudio2::*;
+
+use winapi::um::unknwnbase::IUnknown;
+use winresult::*;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2fx/ns-xaudio2fx-xaudio2fx_volumemeter_levels)\]
+/// XAUDIO2FX_VOLUMEMETER_LEVELS: owned per-channel levels read back from a [create_volume_meter] effect.
+///
+/// Read via [Voice::get_volume_meter_levels] (the volume meter has no settable parameters.)
+#[derive(Clone, Debug, Default, PartialEq)] pub struct VolumeMeterLevels {
+    /// Maximum absolute level of each channel during the most recent processing pass.
+    pub peak_levels:    Vec<f32>,
+
+    /// Root mean square level of each channel during the most recent processing pass.
+    pub rms_levels:     Vec<f32>,
+}
+
+/// XAUDIO2FX_VOLUMEMETER_LEVELS
+#[allow(non_snake_case)] // match original naming instead
+#[repr(C, packed(1))] struct RawLevels {
+    pPeakLevels:    *mut f32,
+    pRMSLevels:     *mut f32,
+    ChannelCount:   u32,
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xaudio2fx/nf-xaudio2fx-xaudio2createvolumemeter)\]
+/// XAudio2CreateVolumeMeter: creates the built-in volume meter effect.
+///
+/// The meter passes audio through unmodified: its [descriptor](TypedEffect::descriptor)'s `output_channels` should match its input.
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// let master = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+/// let mut chain = xaudio2::EffectChain::new();
+/// let meter = chain.push(xaudio2::create_volume_meter().unwrap().descriptor(true, 2));
+/// master.set_effect_chain(Some(chain.as_slice())).unwrap();
+/// // ...
+/// let levels = master.get_volume_meter_levels(&meter).unwrap();
+/// println!("peak: {:?} rms: {:?}", levels.peak_levels, levels.rms_levels);
+/// ```
+///
+/// ### Errors
+/// *   [HResultError::from_win32]\([ERROR::MOD_NOT_FOUND])     - if `XAudio2_9.dll` could not be found
+/// *   [HResultError::from_win32]\([ERROR::PROC_NOT_FOUND])    - if `XAudio2_9.dll` failed to export `CreateAudioVolumeMeter`
+/// *   [HResultError::from_win32]\([ERROR::NOINTERFACE])       - if the effect was null despite the function "succeeding" (thindx specific)
+///
+/// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
+pub fn create_volume_meter() -> Result<TypedEffect<VolumeMeterLevels>, HResultError> {
+    let exports = Exports::from_default_path_cached_hresult()?;
+    let create = exports.CreateAudioVolumeMeter.ok_or(HResultError::from_win32(ERROR::PROC_NOT_FOUND))?;
+    let mut effect : Option<mcom::Rc<IUnknown>> = None; // N.B. same layout as a nullable `*mut IUnknown`
+    unsafe { create(&mut effect as *mut Option<mcom::Rc<IUnknown>> as *mut *mut IUnknown) }.succeeded()?;
+    let effect = effect.ok_or(HResultError::from_win32(ERROR::NOINTERFACE))?;
+    // SAFETY: `VolumeMeterLevels` is only read via `get_volume_meter_levels`, which matches XAUDIO2FX_VOLUMEMETER_LEVELS.
+    Ok(unsafe { TypedEffect::from_unknown(effect) })
+}
+
+impl Voice<'_> {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/desktop/api/xaudio2/nf-xaudio2-ixaudio2voice-geteffectparameters)\]
+    /// Reads the levels measured by the volume meter identified by `meter` (from this voice's [EffectChain].)
+    ///
+    /// The meter passes audio through unmodified, so `meter` must have been [described](TypedEffect::descriptor) as outputting
+    /// this voice's [`InputChannels`](VoiceDetails::InputChannels) - place meters before any effect that changes the channel count.
+    /// The level buffers are sized for [MAX_AUDIO_CHANNELS] regardless, then truncated to the channel count the meter reports,
+    /// so even a misbehaving meter can't write out of bounds.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `meter`'s `output_channels` doesn't match [get_voice_details](Self::get_voice_details)`().InputChannels`.
+    /// *   [E::INVALIDARG]     - if `meter` isn't in this voice's current effect chain.
+    pub fn get_volume_meter_levels(&self, meter: &EffectHandle<VolumeMeterLevels>) -> Result<VolumeMeterLevels, HResultError> {
+        let index = meter.index_in(self)?;
+        let channels = meter.output_channels();
+        if channels != self.get_voice_details().InputChannels || channels > MAX_AUDIO_CHANNELS { return Err(E::INVALIDARG) }
+        let mut levels = VolumeMeterLevels { peak_levels: vec![0.0; MAX_AUDIO_CHANNELS as usize], rms_levels: vec![0.0; MAX_AUDIO_CHANNELS as usize] };
+        let mut raw = RawLevels { pPeakLevels: levels.peak_levels.as_mut_ptr(), pRMSLevels: levels.rms_levels.as_mut_ptr(), ChannelCount: channels };
+        let raw_bytes = core::mem::size_of_val(&raw) as u32;
+        // SAFETY: `raw` points to two `MAX_AUDIO_CHANNELS` long arrays, and no voice (thus no meter) has more channels than that.
+        unsafe { self.as_ref().GetEffectParameters(index, (&mut raw as *mut RawLevels).cast(), raw_bytes) }.succeeded()?;
+        let reported = raw.ChannelCount.min(MAX_AUDIO_CHANNELS) as usize;
+        levels.peak_levels.truncate(reported);
+        levels.rms_levels.truncate(reported);
+        Ok(levels)
+    }
+}
+
+
+
+#[test] fn create_volume_meter_descriptor() {
+    let meter = create_volume_meter().unwrap();
+    let mut chain = EffectChain::new();
+    let _unknown = chain.push_descriptor(meter.descriptor(true, 1).into_descriptor());
+    let handle = chain.push(meter.descriptor(true, 1));
+    assert_eq!(handle.index(), 1);
+    assert_eq!(handle.output_channels(), 1);
+}
+
+#[test] fn get_volume_meter_levels_channels() {
+    use crate::mock;
+
+    struct Passthrough;
+    impl Xapo for Passthrough { fn process(&mut self, input: &mut [f32], output: &mut [f32]) { output.copy_from_slice(input) } }
+
+    let engine  = mock::Engine::new();
+    let xaudio2 = engine.xaudio2();
+    let meter   = || unsafe { TypedEffect::<VolumeMeterLevels>::from_unknown(Passthrough.wrap().into_unknown()) }; // never actually read by the mock
+    let mut chain = EffectChain::new();
+    let stereo  = chain.push(meter().descriptor(true, 2));
+    let mono    = chain.push(meter().descriptor(true, 1));
+    let master  = xaudio2.create_mastering_voice(2, 48000, 0, (), Some(chain.as_slice()), DEFAULT_AUDIO_CATEGORY).unwrap();
+
+    engine.clear_calls();
+    assert_eq!(master.get_volume_meter_levels(&mono).err(), Some(E::INVALIDARG.into()));
+    assert!(engine.calls().iter().all(|call| call.method != "GetEffectParameters"), "mismatched channel counts are rejected before XAudio2 is called");
+    assert!(master.get_volume_meter_levels(&stereo).is_ok());
+}
+
+#[test] #[ignore = "requires an audio device, and takes a few hundred milliseconds of real time"] fn get_volume_meter_levels() {
+    mcom::init::mta().expect("mcom::init::mta");
+    let xaudio2 = unsafe { create(None, None) }.expect("create");
+    let mut chain = EffectChain::new();
+    let meter = chain.push(create_volume_meter().unwrap().descriptor(true, 2));
+    let master = xaudio2.create_mastering_voice(2, 48000, 0, (), Some(chain.as_slice()), DEFAULT_AUDIO_CATEGORY).expect("create_mastering_voice");
+
+    let format = TypedSourceFormat::<[f32; 2]>::pcm(48000);
+    let tone = xaudio2.create_render_source_voice(&format, 0, DEFAULT_FREQ_RATIO, |frames: &mut [[f32; 2]]| frames.fill([0.5, 0.25]), None, None).unwrap();
+    tone.start(COMMIT_NOW).unwrap();
+    std::thread::sleep(core::time::Duration::from_millis(200));
+
+    let levels = master.get_volume_meter_levels(&meter).unwrap();
+    assert_eq!(levels.peak_levels.len(), 2);
+    assert_eq!(levels.rms_levels.len(), 2);
+    assert!((levels.peak_levels[0] - 0.5 ).abs() < 0.01, "{levels:?}");
+    assert!((levels.peak_levels[1] - 0.25).abs() < 0.01, "{levels:?}");
+
+    drop(tone);
+    drop(master);
+}
@@ -0,0 +1,232 @@
+//! `X3DAudio*` & `X3DAUDIO_*`
+//!
+//! [X3DAudio] calculates [DspSettings] for an [Emitter] heard by a [Listener], via the `X3DAudio*` functions exported by `XAudio2_9.dll`.
+//! [Spatializer] is a pure Rust alternative with the same interface ([Calculator].)
+//! [SpatialEmitter](crate::xaudio2_9::xaudio2::SpatialEmitter) applies the results to a voice each frame.
+//!
+//! ### Example
+//! ```no_run
+//! # use thindx_xaudio2::xaudio2_9::*;
+//! # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+//! # let master = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+//! # let format = xaudio2::TypedSourceFormat::<[i16; 1]>::pcm(48000);
+//! # let voice = unsafe { xaudio2.create_source_voice_unchecked(&format, 0, xaudio2::DEFAULT_FREQ_RATIO, None, None, None) }.unwrap();
+//! let x3d = x3daudio::X3DAudio::new(master.get_channel_mask().unwrap(), x3daudio::SPEED_OF_SOUND).unwrap();
+//! let listener = x3daudio::Listener::default();
+//! let emitter = x3daudio::Emitter { position: x3daudio::Vector::new(10.0, 0.0, 5.0), .. Default::default() };
+//! let dsp = x3d.calculate(&listener, &emitter, x3daudio::CALCULATE_MATRIX | x3daudio::CALCULATE_DOPPLER).unwrap();
+//! voice.set_output_matrix(unsafe { &*master.as_raw() }, dsp.src_channel_count, dsp.dst_channel_count, &dsp.matrix_coefficients, xaudio2::COMMIT_NOW).unwrap();
+//! voice.set_frequency_ratio(dsp.doppler_factor, xaudio2::COMMIT_NOW).unwrap();
+//! ```
+
+use crate::xaudio2_9::xaudio2::Exports;
+use sys::*;
+
+use winresult::*;
+
+use core::fmt::{self, Debug, Formatter};
+
+#[doc(inline)] pub use crate::portable::x3daudio::*;
+#[doc(inline)] pub use crate::portable::{
+    // Speaker positions (as also defined by `x3daudio.h`)
+    SPEAKER_FRONT_LEFT,
+    SPEAKER_FRONT_RIGHT,
+    SPEAKER_FRONT_CENTER,
+    SPEAKER_LOW_FREQUENCY,
+    SPEAKER_BACK_LEFT,
+    SPEAKER_BACK_RIGHT,
+    SPEAKER_FRONT_LEFT_OF_CENTER,
+    SPEAKER_FRONT_RIGHT_OF_CENTER,
+    SPEAKER_BACK_CENTER,
+    SPEAKER_SIDE_LEFT,
+    SPEAKER_SIDE_RIGHT,
+    SPEAKER_TOP_CENTER,
+    SPEAKER_TOP_FRONT_LEFT,
+    SPEAKER_TOP_FRONT_CENTER,
+    SPEAKER_TOP_FRONT_RIGHT,
+    SPEAKER_TOP_BACK_LEFT,
+    SPEAKER_TOP_BACK_CENTER,
+    SPEAKER_TOP_BACK_RIGHT,
+
+    // Common speaker layouts
+    SPEAKER_MONO,
+    SPEAKER_STEREO,
+    SPEAKER_2POINT1,
+    SPEAKER_SURROUND,
+    SPEAKER_QUAD,
+    SPEAKER_4POINT1,
+    SPEAKER_5POINT1,
+    SPEAKER_7POINT1,
+    SPEAKER_5POINT1_SURROUND,
+    SPEAKER_7POINT1_SURROUND,
+};
+
+/// Raw low level FFI bindings
+///
+pub use thindx_xaudio2_sys::x3daudio as sys;
+
+
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/xaudio2/x3daudio)\]
+/// An initialized X3DAUDIO_HANDLE for a specific speaker configuration and speed of sound.
+///
+/// Plain data: cheap to [Clone], and safe to share between threads.
+#[derive(Clone)] pub struct X3DAudio {
+    handle:                 X3DAUDIO_HANDLE,
+    calculate:              X3DAudioCalculateFn,
+    speaker_channel_mask:   u32,
+}
+
+impl Debug for X3DAudio {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("X3DAudio").field("speaker_channel_mask", &self.speaker_channel_mask).finish_non_exhaustive()
+    }
+}
+
+impl X3DAudio {
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudioinitialize)\]
+    /// X3DAudioInitialize: prepare to calculate [DspSettings] for `speaker_channel_mask` (e.g. [MasteringVoice::get_channel_mask](crate::xaudio2_9::xaudio2::MasteringVoice::get_channel_mask)),
+    /// with doppler using `speed_of_sound` (in world units per second, e.g. [SPEED_OF_SOUND] for meters.)
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]                                 - if `speaker_channel_mask` is `0`, or `speed_of_sound < f32::MIN_POSITIVE` (or NaN)
+    /// *   [HResultError::from_win32]\([ERROR::MOD_NOT_FOUND])     - if `XAudio2_9.dll` could not be found
+    /// *   [HResultError::from_win32]\([ERROR::PROC_NOT_FOUND])    - if `XAudio2_9.dll` failed to export `X3DAudioInitialize` or `X3DAudioCalculate`
+    ///
+    /// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
+    pub fn new(speaker_channel_mask: u32, speed_of_sound: f32) -> Result<Self, HResultError> {
+        if speaker_channel_mask == 0 || !(f32::MIN_POSITIVE ..= f32::MAX).contains(&speed_of_sound) { return Err(E::INVALIDARG) }
+        let exports = Exports::from_default_path_cached_hresult()?;
+        let initialize  = exports.X3DAudioInitialize.ok_or(HResultError::from_win32(ERROR::PROC_NOT_FOUND))?;
+        let calculate   = exports.X3DAudioCalculate .ok_or(HResultError::from_win32(ERROR::PROC_NOT_FOUND))?;
+        let mut handle = [0u8; X3DAUDIO_HANDLE_BYTESIZE];
+        unsafe { initialize(speaker_channel_mask, speed_of_sound, &mut handle) }.succeeded()?;
+        Ok(Self { handle, calculate, speaker_channel_mask })
+    }
+
+    /// The speaker channel mask this was initialized with.
+    pub fn speaker_channel_mask(&self) -> u32 { self.speaker_channel_mask }
+
+    /// The number of channels in the final mix ([DspSettings::dst_channel_count].)
+    pub fn dst_channel_count(&self) -> u32 { self.speaker_channel_mask.count_ones() }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudiocalculate)\]
+    /// X3DAudioCalculate: calculate the `flags` (`CALCULATE_*`) parts of [DspSettings] for `emitter` as heard by `listener`.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `listener` or `emitter` fail validation ([Listener::validate], [Emitter::validate])
+    pub fn calculate(&self, listener: &Listener, emitter: &Emitter, flags: u32) -> Result<DspSettings, HResultError> {
+        let mut settings = DspSettings::new(emitter.channel_count(), self.dst_channel_count());
+        self.calculate_into(listener, emitter, flags, &mut settings)?;
+        Ok(settings)
+    }
+
+    /// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/x3daudio/nf-x3daudio-x3daudiocalculate)\]
+    /// X3DAudioCalculate: like [calculate](Self::calculate), but reuses `settings`' buffers (resizing them as needed) to avoid per-frame allocations.
+    ///
+    /// ### Errors
+    /// *   [E::INVALIDARG]     - if `listener` or `emitter` fail validation ([Listener::validate], [Emitter::validate])
+    pub fn calculate_into(&self, listener: &Listener, emitter: &Emitter, flags: u32, settings: &mut DspSettings) -> Result<(), HResultError> {
+        listener.validate()?;
+        emitter.validate()?;
+        settings.resize(emitter.channel_count(), self.dst_channel_count());
+
+        let mut listener_cone   = listener.cone.map(X3DAUDIO_CONE::from);
+        let mut emitter_cone    = emitter.cone.map(X3DAUDIO_CONE::from);
+        let mut volume_curve    = curve(emitter.volume_curve.as_deref());
+        let mut lfe_curve       = curve(emitter.lfe_curve.as_deref());
+        let mut lpf_direct      = curve(emitter.lpf_direct_curve.as_deref());
+        let mut lpf_reverb      = curve(emitter.lpf_reverb_curve.as_deref());
+        let mut reverb_curve    = curve(emitter.reverb_curve.as_deref());
+
+        let raw_listener = X3DAUDIO_LISTENER {
+            OrientFront:            listener.orient_front.into(),
+            OrientTop:              listener.orient_top.into(),
+            Position:               listener.position.into(),
+            Velocity:               listener.velocity.into(),
+            pCone:                  opt_ptr(&mut listener_cone),
+        };
+
+        let raw_emitter = X3DAUDIO_EMITTER {
+            pCone:                  opt_ptr(&mut emitter_cone),
+            OrientFront:            emitter.orient_front.into(),
+            OrientTop:              emitter.orient_top.into(),
+            Position:               emitter.position.into(),
+            Velocity:               emitter.velocity.into(),
+            InnerRadius:            emitter.inner_radius,
+            InnerRadiusAngle:       emitter.inner_radius_angle,
+            ChannelCount:           emitter.channel_count(),
+            ChannelRadius:          emitter.channel_radius,
+            pChannelAzimuths:       emitter.channel_azimuths.as_ptr() as *mut f32, // N.B. only read by X3DAudio
+            pVolumeCurve:           opt_ptr(&mut volume_curve),
+            pLFECurve:              opt_ptr(&mut lfe_curve),
+            pLPFDirectCurve:        opt_ptr(&mut lpf_direct),
+            pLPFReverbCurve:        opt_ptr(&mut lpf_reverb),
+            pReverbCurve:           opt_ptr(&mut reverb_curve),
+            CurveDistanceScaler:    emitter.curve_distance_scaler,
+            DopplerScaler:          emitter.doppler_scaler,
+        };
+
+        let mut raw_settings = X3DAUDIO_DSP_SETTINGS {
+            pMatrixCoefficients:        settings.matrix_coefficients.as_mut_ptr(),
+            pDelayTimes:                settings.delay_times.as_mut_ptr(),
+            SrcChannelCount:            settings.src_channel_count,
+            DstChannelCount:            settings.dst_channel_count,
+            LPFDirectCoefficient:       settings.lpf_direct_coefficient,
+            LPFReverbCoefficient:       settings.lpf_reverb_coefficient,
+            ReverbLevel:                settings.reverb_level,
+            DopplerFactor:              settings.doppler_factor,
+            EmitterToListenerAngle:     settings.emitter_to_listener_angle,
+            EmitterToListenerDistance:  settings.emitter_to_listener_distance,
+            EmitterVelocityComponent:   settings.emitter_velocity_component,
+            ListenerVelocityComponent:  settings.listener_velocity_component,
+        };
+
+        // SAFETY:
+        //  * `raw_emitter` / `raw_listener` pointers reference locals or `emitter` fields which outlive the call, with counts matching their lengths
+        //  * `X3DAUDIO_EMITTER`'s `*mut` pointers are only read through, despite the lack of const
+        //  * `raw_settings` buffers were sized by `settings.resize` to `SrcChannelCount * DstChannelCount` and `DstChannelCount`
+        //  * `self.handle` was initialized by `X3DAudioInitialize` from the same DLL as `self.calculate`
+        unsafe { (self.calculate)(&self.handle, &raw_listener, &raw_emitter, flags, &mut raw_settings) };
+
+        settings.lpf_direct_coefficient         = raw_settings.LPFDirectCoefficient;
+        settings.lpf_reverb_coefficient         = raw_settings.LPFReverbCoefficient;
+        settings.reverb_level                   = raw_settings.ReverbLevel;
+        settings.doppler_factor                 = raw_settings.DopplerFactor;
+        settings.emitter_to_listener_angle      = raw_settings.EmitterToListenerAngle;
+        settings.emitter_to_listener_distance   = raw_settings.EmitterToListenerDistance;
+        settings.emitter_velocity_component     = raw_settings.EmitterVelocityComponent;
+        settings.listener_velocity_component    = raw_settings.ListenerVelocityComponent;
+        Ok(())
+    }
+}
+
+impl Calculator for X3DAudio {
+    fn dst_channel_count(&self) -> u32 { X3DAudio::dst_channel_count(self) }
+    fn calculate_into(&self, listener: &Listener, emitter: &Emitter, flags: u32, settings: &mut DspSettings) -> Result<(), HResultError> {
+        X3DAudio::calculate_into(self, listener, emitter, flags, settings)
+    }
+}
+
+const _ : () = assert!(core::mem::size_of::<DistanceCurvePoint>() == core::mem::size_of::<X3DAUDIO_DISTANCE_CURVE_POINT>());
+
+/// Borrow `points` as an X3DAUDIO_DISTANCE_CURVE (if any.)  The result must not outlive `points`.
+fn curve(points: Option<&[DistanceCurvePoint]>) -> Option<X3DAUDIO_DISTANCE_CURVE> {
+    points.map(|points| X3DAUDIO_DISTANCE_CURVE { pPoints: points.as_ptr() as *mut X3DAUDIO_DISTANCE_CURVE_POINT, PointCount: points.len() as u32 })
+}
+
+fn opt_ptr<T>(value: &mut Option<T>) -> *mut T { value.as_mut().map_or(core::ptr::null_mut(), |v| v) }
+
+
+
+#[test] fn calculate_mono_stereo() {
+    let x3d = X3DAudio::new(SPEAKER_STEREO, SPEED_OF_SOUND).unwrap();
+    assert_eq!(x3d.dst_channel_count(), 2);
+    let listener = Listener::default();
+    let emitter = Emitter { position: Vector::new(1.0, 0.0, 0.0), .. Default::default() };
+    let dsp = x3d.calculate(&listener, &emitter, CALCULATE_MATRIX | CALCULATE_DOPPLER).unwrap();
+    assert_eq!(dsp.matrix_coefficients.len(), 2);
+    assert!(dsp.matrix_coefficients[1] > dsp.matrix_coefficients[0], "emitter to the right should be louder on the right: {:?}", dsp.matrix_coefficients);
+    assert_eq!(dsp.doppler_factor, 1.0);
+    assert_eq!(x3d.calculate(&listener, &Emitter { channel_azimuths: alloc::vec::Vec::new(), .. Default::default() }, CALCULATE_MATRIX).err(), Some(E::INVALIDARG));
+}
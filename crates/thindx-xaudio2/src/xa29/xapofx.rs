@@ -0,0 +1,115 @@
+use super::xaudio2::*;
+use crate::portable::Guid;
+
+use winapi::shared::guiddef::GUID;
+use winapi::um::unknwnbase::IUnknown;
+use winresult::*;
+
+use core::ffi::c_void;
+use core::ptr::null;
+
+
+
+impl EffectParameters for FxEqParameters                 { fn validate(&self) -> Result<(), HResultError> { FxEqParameters::validate(self) } }
+impl EffectParameters for FxMasteringLimiterParameters   { fn validate(&self) -> Result<(), HResultError> { FxMasteringLimiterParameters::validate(self) } }
+impl EffectParameters for FxReverbParameters             { fn validate(&self) -> Result<(), HResultError> { FxReverbParameters::validate(self) } }
+impl EffectParameters for FxEchoParameters               { fn validate(&self) -> Result<(), HResultError> { FxEchoParameters::validate(self) } }
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/nf-xapofx-createfx)\]
+/// CreateFX(CLSID_FXEQ): creates a four band equalizer.
+///
+/// Processes float32 audio at [FXEQ_MIN_FRAMERATE] ..= [FXEQ_MAX_FRAMERATE] Hz, with output channels matching input channels.
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// let mut chain = xaudio2::EffectChain::new();
+/// let eq = chain.push(xaudio2::create_fx_eq().unwrap().descriptor(true, 2));
+/// let submix = xaudio2.create_submix_voice(2, 48000, 0, 0, None, Some(chain.as_slice())).unwrap();
+/// let parameters = xaudio2::FxEqParameters { Gain0: xaudio2::FXEQ_MAX_GAIN, .. Default::default() };
+/// submix.set_effect_parameters(&eq, &parameters, xaudio2::COMMIT_NOW).unwrap();
+/// ```
+///
+/// ### Errors
+/// *   [HResultError::from_win32]\([ERROR::MOD_NOT_FOUND])     - if `XAudio2_9.dll` could not be found
+/// *   [HResultError::from_win32]\([ERROR::PROC_NOT_FOUND])    - if `XAudio2_9.dll` failed to export `CreateFX`
+/// *   [HResultError::from_win32]\([ERROR::NOINTERFACE])       - if the effect was null despite the function "succeeding" (thindx specific)
+///
+/// [HResultError::from_win32]: https://docs.rs/winresult/latest/winresult/struct.HResultError.html#method.from_win32
+pub fn create_fx_eq() -> Result<TypedEffect<FxEqParameters>, HResultError> {
+    unsafe { create_fx(&CLSID_FXEQ, &[]) }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/nf-xapofx-createfx)\]
+/// CreateFX(CLSID_FXMasteringLimiter): creates a limiter, typically for the end of a [MasteringVoice]'s effect chain.
+///
+/// Processes float32 audio, with output channels matching input channels.
+///
+/// ### Example
+/// ```no_run
+/// # use thindx_xaudio2::xaudio2_9::*;
+/// # let xaudio2 = unsafe { xaudio2::create(None, None) }.unwrap();
+/// let master = xaudio2.create_mastering_voice(2, 48000, 0, (), None, xaudio2::DEFAULT_AUDIO_CATEGORY).unwrap();
+/// let mut chain = xaudio2::EffectChain::new();
+/// let limiter = chain.push(xaudio2::create_fx_mastering_limiter().unwrap().descriptor(true, 2));
+/// master.set_effect_chain(Some(chain.as_slice())).unwrap();
+/// let parameters = xaudio2::FxMasteringLimiterParameters { Loudness: 1500, .. Default::default() };
+/// master.set_effect_parameters(&limiter, &parameters, xaudio2::COMMIT_NOW).unwrap();
+/// ```
+///
+/// ### Errors
+/// *   As per [create_fx_eq]
+pub fn create_fx_mastering_limiter() -> Result<TypedEffect<FxMasteringLimiterParameters>, HResultError> {
+    unsafe { create_fx(&CLSID_FXMASTERINGLIMITER, &[]) }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/nf-xapofx-createfx)\]
+/// CreateFX(CLSID_FXReverb): creates a simple reverb (cheaper, but less configurable, than [create_reverb].)
+///
+/// Processes float32 audio, with output channels matching input channels.
+///
+/// ### Errors
+/// *   As per [create_fx_eq]
+pub fn create_fx_reverb() -> Result<TypedEffect<FxReverbParameters>, HResultError> {
+    unsafe { create_fx(&CLSID_FXREVERB, &[]) }
+}
+
+/// \[[microsoft.com](https://learn.microsoft.com/en-us/windows/win32/api/xapofx/nf-xapofx-createfx)\]
+/// CreateFX(CLSID_FXEcho): creates an echo, able to delay by up to `init_data.MaxDelay` milliseconds.
+///
+/// Processes float32 audio, with output channels matching input channels.
+///
+/// ### Errors
+/// *   [E::INVALIDARG]     - if `init_data` fails [FxEchoInitData::validate]
+/// *   As per [create_fx_eq]
+pub fn create_fx_echo(init_data: &FxEchoInitData) -> Result<TypedEffect<FxEchoParameters>, HResultError> {
+    init_data.validate()?;
+    unsafe { create_fx(&CLSID_FXECHO, bytemuck::bytes_of(init_data)) }
+}
+
+/// ### Safety
+/// *   `clsid` must be an XAPOFX effect whose parameters are `P`, and which accepts `init_data`.
+unsafe fn create_fx<P>(clsid: &Guid, init_data: &[u8]) -> Result<TypedEffect<P>, HResultError> {
+    let exports = Exports::from_default_path_cached_hresult()?;
+    let create = exports.CreateFX.ok_or(HResultError::from_win32(ERROR::PROC_NOT_FOUND))?;
+    let clsid = GUID::from(*clsid);
+    let init_ptr : *const c_void = if init_data.is_empty() { null() } else { init_data.as_ptr().cast() };
+    let init_bytes = u32::try_from(init_data.len()).map_err(|_| E::INVALIDARG)?;
+    let mut effect : Option<mcom::Rc<IUnknown>> = None; // N.B. same layout as a nullable `*mut IUnknown`
+    unsafe { create(&clsid, &mut effect as *mut Option<mcom::Rc<IUnknown>> as *mut *mut IUnknown, init_ptr, init_bytes) }.succeeded()?;
+    let effect = effect.ok_or(HResultError::from_win32(ERROR::NOINTERFACE))?;
+    Ok(unsafe { TypedEffect::from_unknown(effect) })
+}
+
+
+
+#[test] fn create_fx_descriptors() {
+    let mut chain = EffectChain::new();
+    let eq      = chain.push(create_fx_eq().unwrap().descriptor(true, 2));
+    let limiter = chain.push(create_fx_mastering_limiter().unwrap().descriptor(true, 2));
+    let reverb  = chain.push(create_fx_reverb().unwrap().descriptor(true, 2));
+    let echo    = chain.push(create_fx_echo(&FxEchoInitData::default()).unwrap().descriptor(true, 2));
+    assert_eq!([eq.index(), limiter.index(), reverb.index(), echo.index()], [0, 1, 2, 3]);
+    assert_eq!(create_fx_echo(&FxEchoInitData { MaxDelay: 0.0 }).err(), Some(E::INVALIDARG));
+}